@@ -0,0 +1,32 @@
+//! iRacing Telemetry File Example
+//!
+//! This example lists the laps of an `.ibt` file written by iRacing. It reads
+//! the file directly, so it runs on any platform.
+//!
+//! Usage: cargo run --example ibt_laps -- session.ibt
+
+use race_sdk::iracing::IbtFile;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let Some(path) = std::env::args().nth(1) else {
+        println!("Usage: ibt_laps <file.ibt>");
+        return Ok(());
+    };
+
+    let mut ibt = IbtFile::open(&path)?;
+    if let Some(info) = ibt.session_info() {
+        let car = info.drivers.player().map(|driver| driver.car_screen_name.as_str()).unwrap_or("unknown car");
+        println!("{} at {} {}", car, info.weekend.track_display_name, info.weekend.track_config_name);
+    }
+    println!("Records: {} at {} Hz", ibt.record_count(), ibt.header().tick_rate);
+
+    for lap in ibt.laps()? {
+        let mut top_speed = 0.0f32;
+        for sample in ibt.lap_samples(&lap)? {
+            top_speed = top_speed.max(sample?.get::<f32>("Speed").unwrap_or_default());
+        }
+        let status = if lap.complete { "" } else { " (partial)" };
+        println!("Lap {:3}: {:8.3}s, top speed {:5.1} km/h{}", lap.lap, lap.duration(), top_speed * 3.6, status);
+    }
+    Ok(())
+}
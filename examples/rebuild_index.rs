@@ -0,0 +1,36 @@
+//! Recording Index Rebuild Example
+//!
+//! This example restores the index of a recording that was cut off mid-write,
+//! e.g. because the recording process crashed.
+//!
+//! Usage: cargo run --example rebuild_index -- session.rsdk
+
+use race_sdk::recording::{self, Recording};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let Some(path) = std::env::args().nth(1) else {
+        println!("Usage: rebuild_index <recording>");
+        return Ok(());
+    };
+
+    let report = recording::rebuild_index(&path)?;
+    if report.truncated {
+        println!("{} was cut off, removed {} bytes of partial records", path, report.removed_bytes);
+    } else {
+        println!("{} was complete, replaced its index", path);
+    }
+    if report.skipped_records > 0 {
+        println!("Skipped {} undecodable records", report.skipped_records);
+    }
+    println!("Frames:      {}", report.frames);
+    println!("Blocks:      {}", report.blocks);
+    println!("Checkpoints: {}", report.checkpoints);
+
+    let mut recording = Recording::open(&path)?;
+    if let Some(index) = recording.index()? {
+        for lap in &index.laps {
+            println!("Session {} lap {}: {:?} - {:?}", lap.session, lap.lap, lap.start, lap.end);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,210 @@
+use crate::ac::{Coordinates, Graphics, Physics, StaticInfo};
+use crate::page_layout;
+use crate::telemetry::storage;
+
+const STATUS: &[(i32, &str)] = &[(0, "Off"), (1, "Replay"), (2, "Live"), (3, "Pause")];
+
+const SESSION_TYPE: &[(i32, &str)] = &[
+    (-1, "Unknown"),
+    (0, "Practice"),
+    (1, "Qualify"),
+    (2, "Race"),
+    (3, "Hotlap"),
+    (4, "TimeAttack"),
+    (5, "Drift"),
+    (6, "Drag"),
+];
+
+const FLAG_TYPE: &[(i32, &str)] = &[
+    (0, "NoFlag"),
+    (1, "BlueFlag"),
+    (2, "YellowFlag"),
+    (3, "BlackFlag"),
+    (4, "WhiteFlag"),
+    (5, "CheckeredFlag"),
+    (6, "PenaltyFlag"),
+];
+
+// Three `f32`s, laid out as an `F32[Axis]` field.
+unsafe impl storage::Elements<storage::F32> for Coordinates {}
+
+page_layout!(unsafe Physics {
+    #[discrete]
+    packet_id: I32,
+    gas: F32,
+    brake: F32,
+    fuel: F32 in "l",
+    #[discrete]
+    gear: I32,
+    rpms: I32 in "rpm",
+    steer_angle: F32,
+    speed_kmh: F32 in "km/h",
+    velocity: F32[Axis] in "m/s",
+    acc_g: F32[Axis] in "G",
+    wheel_slip: F32[Wheel],
+    wheel_load: F32[Wheel] in "N",
+    wheels_pressure: F32[Wheel] in "psi",
+    wheel_angular_speed: F32[Wheel] in "rad/s",
+    tyre_wear: F32[Wheel],
+    tyre_dirty_level: F32[Wheel],
+    tyre_core_temperature: F32[Wheel] in "C",
+    camber_rad: F32[Wheel] in "rad",
+    suspension_travel: F32[Wheel] in "m",
+    drs: F32,
+    tc: F32,
+    heading: F32 in "rad",
+    pitch: F32 in "rad",
+    roll: F32 in "rad",
+    cg_height: F32 in "m",
+    car_damage: F32[Index(5)],
+    #[discrete]
+    number_of_tyres_out: I32,
+    #[discrete]
+    pit_limiter_on: I32,
+    abs: F32,
+    kers_charge: F32,
+    kers_input: F32,
+    #[discrete]
+    auto_shifter_on: I32,
+    ride_height: F32[Axle] in "m",
+    turbo_boost: F32,
+    ballast: F32 in "kg",
+    air_density: F32,
+    air_temp: F32 in "C",
+    road_temp: F32 in "C",
+    local_angular_velocity: F32[Axis] in "rad/s",
+    final_ff: F32,
+    performance_meter: F32,
+    #[discrete]
+    engine_brake: I32,
+    #[discrete]
+    ers_recovery_level: I32,
+    #[discrete]
+    ers_power_level: I32,
+    #[discrete]
+    ers_heat_charging: I32,
+    #[discrete]
+    ers_is_charging: I32,
+    kers_current_kj: F32 in "kJ",
+    #[discrete]
+    drs_available: I32,
+    #[discrete]
+    drs_enabled: I32,
+    brake_temp: F32[Wheel] in "C",
+    clutch: F32,
+    tyre_temp_i: F32[Wheel] in "C",
+    tyre_temp_m: F32[Wheel] in "C",
+    tyre_temp_o: F32[Wheel] in "C",
+    #[discrete]
+    is_ai_controlled: I32,
+    tyre_contact_point: F32[Wheel, Axis] in "m",
+    tyre_contact_normal: F32[Wheel, Axis],
+    tyre_contact_heading: F32[Wheel, Axis],
+    brake_bias: F32,
+    local_velocity: F32[Axis] in "m/s",
+});
+
+page_layout!(unsafe Graphics {
+    #[discrete]
+    packet_id: I32,
+    status: Enum(STATUS),
+    session: Enum(SESSION_TYPE),
+    current_time: Text(15),
+    last_time: Text(15),
+    best_time: Text(15),
+    split: Text(15),
+    #[discrete]
+    completed_laps: I32,
+    #[discrete]
+    position: I32,
+    i_current_time: I32 in "ms",
+    #[discrete]
+    i_last_time: I32 in "ms",
+    #[discrete]
+    i_best_time: I32 in "ms",
+    session_time_left: F32 in "ms",
+    distance_traveled: F32 in "m",
+    #[discrete]
+    is_in_pit: I32,
+    #[discrete]
+    current_sector_index: I32,
+    #[discrete]
+    last_sector_time: I32 in "ms",
+    #[discrete]
+    number_of_laps: I32,
+    tyre_compound: Text(33),
+    replay_time_multiplier: F32,
+    normalized_car_position: F32,
+    car_coordinates: F32[Axis] in "m",
+    penalty_time: F32 in "s",
+    flag: Enum(FLAG_TYPE),
+    #[discrete]
+    ideal_line_on: I32,
+    #[discrete]
+    is_in_pit_lane: I32,
+    surface_grip: F32,
+    #[discrete]
+    mandatory_pit_done: I32,
+});
+
+page_layout!(unsafe StaticInfo {
+    sm_version: Text(15),
+    ac_version: Text(15),
+    #[discrete]
+    number_of_sessions: I32,
+    #[discrete]
+    num_cars: I32,
+    car_model: Text(33),
+    track: Text(33),
+    player_name: Text(33),
+    player_surname: Text(33),
+    player_nick: Text(33),
+    #[discrete]
+    sector_count: I32,
+    max_torque: F32 in "Nm",
+    max_power: F32 in "W",
+    max_rpm: I32 in "rpm",
+    max_fuel: F32 in "l",
+    suspension_max_travel: F32[Wheel] in "m",
+    tyre_radius: F32[Wheel] in "m",
+    max_turbo_boost: F32,
+    deprecated1: F32,
+    deprecated2: F32,
+    #[discrete]
+    penalties_enabled: I32,
+    aid_fuel_rate: F32,
+    aid_tire_rate: F32,
+    aid_mechanical_damage: F32,
+    #[discrete]
+    aid_allow_tyre_blankets: I32,
+    aid_stability: F32,
+    #[discrete]
+    aid_auto_clutch: I32,
+    #[discrete]
+    aid_auto_blip: I32,
+    #[discrete]
+    has_drs: I32,
+    #[discrete]
+    has_ers: I32,
+    #[discrete]
+    has_kers: I32,
+    kers_max_joules: F32 in "J",
+    #[discrete]
+    engine_brake_settings_count: I32,
+    #[discrete]
+    ers_power_controller_count: I32,
+    track_spline_length: F32 in "m",
+    track_configuration: Text(15),
+    ers_max_j: F32 in "J",
+    #[discrete]
+    is_timed_race: I32,
+    #[discrete]
+    has_extra_lap: I32,
+    car_skin: Text(33),
+    #[discrete]
+    reversed_grid_positions: I32,
+    #[discrete]
+    pit_window_start: I32,
+    #[discrete]
+    pit_window_end: I32,
+});
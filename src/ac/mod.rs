@@ -3,6 +3,10 @@ pub mod types;
 pub use client::*;
 pub use types::*;
 
+mod layout;
+
+use crate::SharedMemory;
+use crate::telemetry::{Game, Page, PageLayout, Sampler, TelemetrySource, Value};
 use std::fmt;
 
 #[derive(Debug)]
@@ -43,6 +47,14 @@ impl AssettoCorsa {
         }
     }
 
+    /// Creates a facade reading from custom memory sources, see
+    /// [`AssettoCorsaClient::from_shared_memory`].
+    pub fn from_shared_memory(physics: Box<dyn SharedMemory>, graphics: Box<dyn SharedMemory>, static_info: Box<dyn SharedMemory>) -> Self {
+        Self {
+            client: AssettoCorsaClient::from_shared_memory(physics, graphics, static_info),
+        }
+    }
+
     pub fn connect(&mut self) -> Result<()> {
         self.client.connect()
     }
@@ -70,6 +82,45 @@ impl AssettoCorsa {
     pub fn get_static_info(&self) -> Option<StaticInfo> {
         self.client.get_static_info()
     }
+
+    /// Registers an additional shared-memory page, see [`AssettoCorsaClient::register_page`].
+    pub fn register_page<T: Page>(&mut self, name: &str) {
+        self.client.register_page::<T>(name)
+    }
+
+    pub fn register_page_layout(&mut self, name: &str, layout: PageLayout) {
+        self.client.register_page_layout(name, layout)
+    }
+
+    pub fn unregister_page(&mut self, name: &str) {
+        self.client.unregister_page(name)
+    }
+
+    pub fn is_page_connected(&self, name: &str) -> bool {
+        self.client.is_page_connected(name)
+    }
+
+    pub fn get_page<T: Page>(&self, name: &str) -> Option<T> {
+        self.client.get_page(name)
+    }
+
+    pub fn get_page_bytes(&self, name: &str) -> Option<Vec<u8>> {
+        self.client.get_page_bytes(name)
+    }
+
+    pub fn get_page_value(&self, name: &str, path: &str) -> Option<Value> {
+        self.client.get_page_value(name, path)
+    }
+
+    /// Returns a sampler that emits every new physics frame with its capture time.
+    pub fn physics_sampler(&self) -> Sampler<Physics, impl FnMut() -> Option<Physics> + '_> {
+        Sampler::new(|| self.client.get_physics())
+    }
+
+    /// Returns a sampler that emits every new graphics frame with its capture time.
+    pub fn graphics_sampler(&self) -> Sampler<Graphics, impl FnMut() -> Option<Graphics> + '_> {
+        Sampler::new(|| self.client.get_graphics())
+    }
 }
 
 impl Default for AssettoCorsa {
@@ -77,3 +128,41 @@ impl Default for AssettoCorsa {
         Self::new()
     }
 }
+
+impl TelemetrySource for AssettoCorsa {
+    const GAME: Game = Game::AssettoCorsa;
+    type Physics = Physics;
+    type Graphics = Graphics;
+    type StaticInfo = StaticInfo;
+
+    fn get_physics(&self) -> Option<Physics> {
+        AssettoCorsa::get_physics(self)
+    }
+
+    fn get_graphics(&self) -> Option<Graphics> {
+        AssettoCorsa::get_graphics(self)
+    }
+
+    fn get_static_info(&self) -> Option<StaticInfo> {
+        AssettoCorsa::get_static_info(self)
+    }
+}
+
+impl TelemetrySource for AssettoCorsaClient {
+    const GAME: Game = Game::AssettoCorsa;
+    type Physics = Physics;
+    type Graphics = Graphics;
+    type StaticInfo = StaticInfo;
+
+    fn get_physics(&self) -> Option<Physics> {
+        AssettoCorsaClient::get_physics(self)
+    }
+
+    fn get_graphics(&self) -> Option<Graphics> {
+        AssettoCorsaClient::get_graphics(self)
+    }
+
+    fn get_static_info(&self) -> Option<StaticInfo> {
+        AssettoCorsaClient::get_static_info(self)
+    }
+}
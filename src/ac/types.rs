@@ -1,3 +1,4 @@
+use crate::telemetry::{Packet, RawEnum, raw_enum};
 use std::fmt;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -9,6 +10,8 @@ pub enum AcStatus {
     Pause = 3,
 }
 
+raw_enum!(AcStatus { Off, Replay, Live, Pause });
+
 impl fmt::Display for AcStatus {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -33,6 +36,17 @@ pub enum AcSessionType {
     Drag = 6,
 }
 
+raw_enum!(AcSessionType {
+    Unknown,
+    Practice,
+    Qualify,
+    Race,
+    Hotlap,
+    TimeAttack,
+    Drift,
+    Drag
+});
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[repr(u32)]
 pub enum AcFlagType {
@@ -45,7 +59,18 @@ pub enum AcFlagType {
     PenaltyFlag = 6,
 }
 
+raw_enum!(AcFlagType {
+    NoFlag,
+    BlueFlag,
+    YellowFlag,
+    BlackFlag,
+    WhiteFlag,
+    CheckeredFlag,
+    PenaltyFlag
+});
+
 #[derive(Debug, Clone, Copy)]
+#[repr(C)]
 pub struct Coordinates {
     pub x: f32,
     pub y: f32,
@@ -121,8 +146,8 @@ pub struct Physics {
 #[repr(C)]
 pub struct Graphics {
     pub packet_id: i32,
-    pub status: AcStatus,
-    pub session: AcSessionType,
+    pub status: RawEnum<AcStatus>,
+    pub session: RawEnum<AcSessionType>,
     pub current_time: [u16; 15], // UTF-16 string
     pub last_time: [u16; 15],    // UTF-16 string
     pub best_time: [u16; 15],    // UTF-16 string
@@ -143,7 +168,7 @@ pub struct Graphics {
     pub normalized_car_position: f32,
     pub car_coordinates: [f32; 3],
     pub penalty_time: f32,
-    pub flag: AcFlagType,
+    pub flag: RawEnum<AcFlagType>,
     pub ideal_line_on: i32,
     pub is_in_pit_lane: i32,
     pub surface_grip: f32,
@@ -257,6 +282,18 @@ impl StaticInfo {
     }
 }
 
+impl Packet for Physics {
+    fn packet_id(&self) -> i32 {
+        self.packet_id
+    }
+}
+
+impl Packet for Graphics {
+    fn packet_id(&self) -> i32 {
+        self.packet_id
+    }
+}
+
 pub fn utf16_to_string(utf16: &[u16]) -> String {
     let end = utf16.iter().position(|&c| c == 0).unwrap_or(utf16.len());
     String::from_utf16_lossy(&utf16[..end])
@@ -334,8 +371,8 @@ impl Default for Graphics {
     fn default() -> Self {
         Self {
             packet_id: 0,
-            status: AcStatus::Off,
-            session: AcSessionType::Unknown,
+            status: AcStatus::Off.into(),
+            session: AcSessionType::Unknown.into(),
             current_time: [0; 15],
             last_time: [0; 15],
             best_time: [0; 15],
@@ -356,7 +393,7 @@ impl Default for Graphics {
             normalized_car_position: 0.0,
             car_coordinates: [0.0; 3],
             penalty_time: 0.0,
-            flag: AcFlagType::NoFlag,
+            flag: AcFlagType::NoFlag.into(),
             ideal_line_on: 0,
             is_in_pit_lane: 0,
             surface_grip: 0.0,
@@ -0,0 +1,355 @@
+use crate::acc::{Coordinates, Graphics, Physics, StaticInfo};
+use crate::page_layout;
+use crate::telemetry::storage;
+
+const STATUS: &[(i32, &str)] = &[(0, "Off"), (1, "Replay"), (2, "Live"), (3, "Pause")];
+
+const SESSION_TYPE: &[(i32, &str)] = &[
+    (-1, "Unknown"),
+    (0, "Practice"),
+    (1, "Qualify"),
+    (2, "Race"),
+    (3, "Hotlap"),
+    (4, "TimeAttack"),
+    (5, "Superpole"),
+    (6, "Hotstint"),
+    (7, "HotlapSuperpole"),
+];
+
+const FLAG_TYPE: &[(i32, &str)] = &[
+    (0, "NoFlag"),
+    (1, "BlueFlag"),
+    (2, "YellowFlag"),
+    (3, "BlackFlag"),
+    (4, "WhiteFlag"),
+    (5, "CheckeredFlag"),
+    (6, "PenaltyFlag"),
+    (7, "GreenFlag"),
+];
+
+const PENALTY_TYPE: &[(i32, &str)] = &[(0, "None"), (1, "DriveThrough"), (2, "StopAndGo"), (3, "TimePenalty"), (4, "PitLane")];
+
+const TRACK_GRIP_STATUS: &[(i32, &str)] = &[(0, "Green"), (1, "Fast"), (2, "Optimum"), (3, "Greasy"), (4, "Wet"), (5, "Flooded")];
+
+const RAIN_INTENSITY: &[(i32, &str)] = &[
+    (0, "NoRain"),
+    (1, "Drizzle"),
+    (2, "LightRain"),
+    (3, "MediumRain"),
+    (4, "HeavyRain"),
+    (5, "Thunderstorm"),
+];
+
+// Three `f32`s, laid out as an `F32[Axis]` field.
+unsafe impl storage::Elements<storage::F32> for Coordinates {}
+
+page_layout!(unsafe Physics {
+    #[discrete]
+    packet_id: I32,
+    gas: F32,
+    brake: F32,
+    fuel: F32 in "l",
+    #[discrete]
+    gear: I32,
+    rpms: I32 in "rpm",
+    steer_angle: F32,
+    speed_kmh: F32 in "km/h",
+    velocity: F32[Axis] in "m/s",
+    acc_g: F32[Axis] in "G",
+    wheel_slip: F32[Wheel],
+    wheel_load: F32[Wheel] in "N",
+    wheels_pressure: F32[Wheel] in "psi",
+    wheel_angular_speed: F32[Wheel] in "rad/s",
+    tyre_wear: F32[Wheel],
+    tyre_dirty_level: F32[Wheel],
+    tyre_core_temperature: F32[Wheel] in "C",
+    camber_rad: F32[Wheel] in "rad",
+    suspension_travel: F32[Wheel] in "m",
+    drs: F32,
+    tc: F32,
+    heading: F32 in "rad",
+    pitch: F32 in "rad",
+    roll: F32 in "rad",
+    cg_height: F32 in "m",
+    car_damage: F32[Index(5)],
+    #[discrete]
+    number_of_tyres_out: I32,
+    #[discrete]
+    pit_limiter_on: I32,
+    abs: F32,
+    kers_charge: F32,
+    kers_input: F32,
+    #[discrete]
+    auto_shifter_on: I32,
+    ride_height: F32[Axle] in "m",
+    turbo_boost: F32,
+    ballast: F32 in "kg",
+    air_density: F32,
+    air_temp: F32 in "C",
+    road_temp: F32 in "C",
+    local_angular_velocity: F32[Axis] in "rad/s",
+    final_ff: F32,
+    performance_meter: F32,
+    #[discrete]
+    engine_brake: I32,
+    #[discrete]
+    ers_recovery_level: I32,
+    #[discrete]
+    ers_power_level: I32,
+    #[discrete]
+    ers_heat_charging: I32,
+    #[discrete]
+    ers_is_charging: I32,
+    kers_current_kj: F32 in "kJ",
+    #[discrete]
+    drs_available: I32,
+    #[discrete]
+    drs_enabled: I32,
+    brake_temp: F32[Wheel] in "C",
+    clutch: F32,
+    tyre_temp_i: F32[Wheel] in "C",
+    tyre_temp_m: F32[Wheel] in "C",
+    tyre_temp_o: F32[Wheel] in "C",
+    #[discrete]
+    is_ai_controlled: I32,
+    tyre_contact_point: F32[Wheel, Axis] in "m",
+    tyre_contact_normal: F32[Wheel, Axis],
+    tyre_contact_heading: F32[Wheel, Axis],
+    brake_bias: F32,
+    local_velocity: F32[Axis] in "m/s",
+    // ACC specific fields
+    #[discrete]
+    p2p_activation: I32,
+    #[discrete]
+    p2p_status: I32,
+    current_max_rpm: F32 in "rpm",
+    mz: F32[Wheel] in "Nm",
+    fx: F32[Wheel] in "N",
+    fy: F32[Wheel] in "N",
+    slip_ratio: F32[Wheel],
+    slip_angle: F32[Wheel] in "rad",
+    #[discrete]
+    tc_in_action: I32,
+    #[discrete]
+    abs_in_action: I32,
+    suspension_damage: F32[Wheel],
+    tyre_temp: F32[Wheel] in "C",
+    water_temp: F32 in "C",
+    brake_pressure: F32[Wheel],
+    #[discrete]
+    front_brake_compound: I32,
+    #[discrete]
+    rear_brake_compound: I32,
+    pad_life: F32[Wheel] in "mm",
+    disc_life: F32[Wheel] in "mm",
+    #[discrete]
+    ignition_on: I32,
+    #[discrete]
+    starter_engine_on: I32,
+    #[discrete]
+    is_engine_running: I32,
+    kerb_vibration: F32,
+    slip_vibrations: F32,
+    g_vibrations: F32,
+    abs_vibrations: F32,
+});
+
+page_layout!(unsafe Graphics {
+    #[discrete]
+    packet_id: I32,
+    status: Enum(STATUS),
+    session: Enum(SESSION_TYPE),
+    current_time: Text(15),
+    last_time: Text(15),
+    best_time: Text(15),
+    split: Text(15),
+    #[discrete]
+    completed_laps: I32,
+    #[discrete]
+    position: I32,
+    i_current_time: I32 in "ms",
+    #[discrete]
+    i_last_time: I32 in "ms",
+    #[discrete]
+    i_best_time: I32 in "ms",
+    session_time_left: F32 in "ms",
+    distance_traveled: F32 in "m",
+    #[discrete]
+    is_in_pit: I32,
+    #[discrete]
+    current_sector_index: I32,
+    #[discrete]
+    last_sector_time: I32 in "ms",
+    #[discrete]
+    number_of_laps: I32,
+    tyre_compound: Text(34),
+    replay_time_multiplier: F32,
+    normalized_car_position: F32,
+    #[discrete]
+    active_cars: I32,
+    car_coordinates: F32[Index(60), Axis] in "m",
+    #[discrete]
+    car_id: I32[Index(60)],
+    #[discrete]
+    player_car_id: I32,
+    penalty_time: F32 in "s",
+    flag: Enum(FLAG_TYPE),
+    penalty: Enum(PENALTY_TYPE),
+    #[discrete]
+    ideal_line_on: I32,
+    #[discrete]
+    is_in_pit_lane: I32,
+    surface_grip: F32,
+    #[discrete]
+    mandatory_pit_done: I32,
+    wind_speed: F32 in "m/s",
+    wind_direction: F32 in "rad",
+    #[discrete]
+    is_setup_menu_visible: I32,
+    #[discrete]
+    main_display_index: I32,
+    #[discrete]
+    secondary_display_index: I32,
+    #[discrete]
+    tc: I32,
+    #[discrete]
+    tc_cut: I32,
+    #[discrete]
+    engine_map: I32,
+    #[discrete]
+    abs: I32,
+    fuel_x_lap: F32 in "l",
+    #[discrete]
+    rain_lights: I32,
+    #[discrete]
+    flashing_lights: I32,
+    #[discrete]
+    lights_stage: I32,
+    exhaust_temperature: F32 in "C",
+    #[discrete]
+    wiper_lv: I32,
+    driver_stint_total_time_left: I32 in "ms",
+    driver_stint_time_left: I32 in "ms",
+    #[discrete]
+    rain_tyres: I32,
+    #[discrete]
+    session_index: I32,
+    used_fuel: F32 in "l",
+    delta_lap_time: Text(16),
+    i_delta_lap_time: I32 in "ms",
+    estimated_lap_time: Text(16),
+    i_estimated_lap_time: I32 in "ms",
+    #[discrete]
+    is_delta_positive: I32,
+    #[discrete]
+    i_split: I32 in "ms",
+    #[discrete]
+    is_valid_lap: I32,
+    fuel_estimated_laps: F32,
+    track_status: Text(34),
+    #[discrete]
+    missing_mandatory_pits: I32,
+    clock: F32 in "s",
+    #[discrete]
+    direction_lights_left: I32,
+    #[discrete]
+    direction_lights_right: I32,
+    #[discrete]
+    global_yellow: I32,
+    #[discrete]
+    global_yellow1: I32,
+    #[discrete]
+    global_yellow2: I32,
+    #[discrete]
+    global_yellow3: I32,
+    #[discrete]
+    global_white: I32,
+    #[discrete]
+    global_green: I32,
+    #[discrete]
+    global_chequered: I32,
+    #[discrete]
+    global_red: I32,
+    #[discrete]
+    mfd_tyre_set: I32,
+    mfd_fuel_to_add: F32 in "l",
+    mfd_tyre_pressure_lf: F32 in "psi",
+    mfd_tyre_pressure_rf: F32 in "psi",
+    mfd_tyre_pressure_lr: F32 in "psi",
+    mfd_tyre_pressure_rr: F32 in "psi",
+    track_grip_status: Enum(TRACK_GRIP_STATUS),
+    rain_intensity: Enum(RAIN_INTENSITY),
+    rain_intensity_in_10min: Enum(RAIN_INTENSITY),
+    rain_intensity_in_30min: Enum(RAIN_INTENSITY),
+    #[discrete]
+    current_tyre_set: I32,
+    #[discrete]
+    strategy_tyre_set: I32,
+});
+
+page_layout!(unsafe StaticInfo {
+    sm_version: Text(15),
+    acc_version: Text(15),
+    #[discrete]
+    number_of_sessions: I32,
+    #[discrete]
+    num_cars: I32,
+    car_model: Text(33),
+    track: Text(33),
+    player_name: Text(33),
+    player_surname: Text(33),
+    player_nick: Text(34),
+    #[discrete]
+    sector_count: I32,
+    max_torque: F32 in "Nm",
+    max_power: F32 in "W",
+    max_rpm: I32 in "rpm",
+    max_fuel: F32 in "l",
+    suspension_max_travel: F32[Wheel] in "m",
+    tyre_radius: F32[Wheel] in "m",
+    max_turbo_boost: F32,
+    deprecated1: F32,
+    deprecated2: F32,
+    #[discrete]
+    penalties_enabled: I32,
+    aid_fuel_rate: F32,
+    aid_tire_rate: F32,
+    aid_mechanical_damage: F32,
+    #[discrete]
+    aid_allow_tyre_blankets: I32,
+    #[discrete]
+    aid_stability: I32,
+    #[discrete]
+    aid_auto_clutch: I32,
+    #[discrete]
+    aid_auto_blip: I32,
+    #[discrete]
+    has_drs: I32,
+    #[discrete]
+    has_ers: I32,
+    #[discrete]
+    has_kers: I32,
+    kers_max_joules: F32 in "J",
+    #[discrete]
+    engine_brake_settings_count: I32,
+    #[discrete]
+    ers_power_controller_count: I32,
+    track_spline_length: F32 in "m",
+    track_configuration: Text(34),
+    ers_max_j: F32 in "J",
+    #[discrete]
+    is_timed_race: I32,
+    #[discrete]
+    has_extra_lap: I32,
+    car_skin: Text(34),
+    #[discrete]
+    reversed_grid_positions: I32,
+    #[discrete]
+    pit_window_start: I32,
+    #[discrete]
+    pit_window_end: I32,
+    #[discrete]
+    is_online: I32,
+    dry_tyres_name: Text(33),
+    wet_tyres_name: Text(33),
+});
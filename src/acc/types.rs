@@ -1,3 +1,4 @@
+use crate::telemetry::{Packet, RawEnum, raw_enum};
 use std::fmt;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -9,6 +10,8 @@ pub enum AccStatus {
     Pause = 3,
 }
 
+raw_enum!(AccStatus { Off, Replay, Live, Pause });
+
 impl fmt::Display for AccStatus {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -34,6 +37,18 @@ pub enum AccSessionType {
     HotlapSuperpole = 7,
 }
 
+raw_enum!(AccSessionType {
+    Unknown,
+    Practice,
+    Qualify,
+    Race,
+    Hotlap,
+    TimeAttack,
+    Superpole,
+    Hotstint,
+    HotlapSuperpole
+});
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[repr(u32)]
 pub enum AccFlagType {
@@ -47,6 +62,17 @@ pub enum AccFlagType {
     GreenFlag = 7,
 }
 
+raw_enum!(AccFlagType {
+    NoFlag,
+    BlueFlag,
+    YellowFlag,
+    BlackFlag,
+    WhiteFlag,
+    CheckeredFlag,
+    PenaltyFlag,
+    GreenFlag
+});
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[repr(u32)]
 pub enum AccPenaltyType {
@@ -57,6 +83,14 @@ pub enum AccPenaltyType {
     PitLane = 4,
 }
 
+raw_enum!(AccPenaltyType {
+    None,
+    DriveThrough,
+    StopAndGo,
+    TimePenalty,
+    PitLane
+});
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[repr(u32)]
 pub enum AccTrackGripStatus {
@@ -68,6 +102,15 @@ pub enum AccTrackGripStatus {
     Flooded = 5,
 }
 
+raw_enum!(AccTrackGripStatus {
+    Green,
+    Fast,
+    Optimum,
+    Greasy,
+    Wet,
+    Flooded
+});
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[repr(u32)]
 pub enum AccRainIntensity {
@@ -79,7 +122,17 @@ pub enum AccRainIntensity {
     Thunderstorm = 5,
 }
 
+raw_enum!(AccRainIntensity {
+    NoRain,
+    Drizzle,
+    LightRain,
+    MediumRain,
+    HeavyRain,
+    Thunderstorm
+});
+
 #[derive(Debug, Clone, Copy)]
+#[repr(C)]
 pub struct Coordinates {
     pub x: f32,
     pub y: f32,
@@ -181,8 +234,8 @@ pub struct Physics {
 #[repr(C)]
 pub struct Graphics {
     pub packet_id: i32,
-    pub status: AccStatus,
-    pub session: AccSessionType,
+    pub status: RawEnum<AccStatus>,
+    pub session: RawEnum<AccSessionType>,
     pub current_time: [u16; 15], // UTF-16 string
     pub last_time: [u16; 15],    // UTF-16 string
     pub best_time: [u16; 15],    // UTF-16 string
@@ -206,8 +259,8 @@ pub struct Graphics {
     pub car_id: [i32; 60],
     pub player_car_id: i32,
     pub penalty_time: f32,
-    pub flag: AccFlagType,
-    pub penalty: AccPenaltyType,
+    pub flag: RawEnum<AccFlagType>,
+    pub penalty: RawEnum<AccPenaltyType>,
     pub ideal_line_on: i32,
     pub is_in_pit_lane: i32,
     pub surface_grip: f32,
@@ -259,10 +312,10 @@ pub struct Graphics {
     pub mfd_tyre_pressure_rf: f32,
     pub mfd_tyre_pressure_lr: f32,
     pub mfd_tyre_pressure_rr: f32,
-    pub track_grip_status: AccTrackGripStatus,
-    pub rain_intensity: AccRainIntensity,
-    pub rain_intensity_in_10min: AccRainIntensity,
-    pub rain_intensity_in_30min: AccRainIntensity,
+    pub track_grip_status: RawEnum<AccTrackGripStatus>,
+    pub rain_intensity: RawEnum<AccRainIntensity>,
+    pub rain_intensity_in_10min: RawEnum<AccRainIntensity>,
+    pub rain_intensity_in_30min: RawEnum<AccRainIntensity>,
     pub current_tyre_set: i32,
     pub strategy_tyre_set: i32,
 }
@@ -377,6 +430,18 @@ impl StaticInfo {
     }
 }
 
+impl Packet for Physics {
+    fn packet_id(&self) -> i32 {
+        self.packet_id
+    }
+}
+
+impl Packet for Graphics {
+    fn packet_id(&self) -> i32 {
+        self.packet_id
+    }
+}
+
 pub fn utf16_to_string(utf16: &[u16]) -> String {
     let end = utf16.iter().position(|&c| c == 0).unwrap_or(utf16.len());
     String::from_utf16_lossy(&utf16[..end])
@@ -479,8 +544,8 @@ impl Default for Graphics {
     fn default() -> Self {
         Self {
             packet_id: 0,
-            status: AccStatus::Off,
-            session: AccSessionType::Unknown,
+            status: AccStatus::Off.into(),
+            session: AccSessionType::Unknown.into(),
             current_time: [0; 15],
             last_time: [0; 15],
             best_time: [0; 15],
@@ -504,8 +569,8 @@ impl Default for Graphics {
             car_id: [0; 60],
             player_car_id: 0,
             penalty_time: 0.0,
-            flag: AccFlagType::NoFlag,
-            penalty: AccPenaltyType::None,
+            flag: AccFlagType::NoFlag.into(),
+            penalty: AccPenaltyType::None.into(),
             ideal_line_on: 0,
             is_in_pit_lane: 0,
             surface_grip: 0.0,
@@ -557,10 +622,10 @@ impl Default for Graphics {
             mfd_tyre_pressure_rf: 0.0,
             mfd_tyre_pressure_lr: 0.0,
             mfd_tyre_pressure_rr: 0.0,
-            track_grip_status: AccTrackGripStatus::Green,
-            rain_intensity: AccRainIntensity::NoRain,
-            rain_intensity_in_10min: AccRainIntensity::NoRain,
-            rain_intensity_in_30min: AccRainIntensity::NoRain,
+            track_grip_status: AccTrackGripStatus::Green.into(),
+            rain_intensity: AccRainIntensity::NoRain.into(),
+            rain_intensity_in_10min: AccRainIntensity::NoRain.into(),
+            rain_intensity_in_30min: AccRainIntensity::NoRain.into(),
             current_tyre_set: 0,
             strategy_tyre_set: 0,
         }
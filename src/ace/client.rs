@@ -1,12 +1,13 @@
 use crate::ace::{AssettoCorsaEvoError, Graphics, Physics, StaticInfo};
-use crate::utils::{open_shared_memory, SharedMemory};
+use crate::telemetry::{Page, PageLayout, Value};
+use crate::utils::{ExtraPages, SharedMemory, open_shared_memory, read_page};
 use std::mem;
-use std::ptr;
 
 pub struct AssettoCorsaEvoClient {
     physics_memory: Option<Box<dyn SharedMemory>>,
     graphics_memory: Option<Box<dyn SharedMemory>>,
     static_memory: Option<Box<dyn SharedMemory>>,
+    extra_pages: ExtraPages,
 }
 
 impl AssettoCorsaEvoClient {
@@ -15,6 +16,18 @@ impl AssettoCorsaEvoClient {
             physics_memory: None,
             graphics_memory: None,
             static_memory: None,
+            extra_pages: ExtraPages::new(),
+        }
+    }
+
+    /// Creates a client reading from custom memory sources instead of the
+    /// game, e.g. a [`Replay`](crate::recording::Replay) of a recording.
+    pub fn from_shared_memory(physics: Box<dyn SharedMemory>, graphics: Box<dyn SharedMemory>, static_info: Box<dyn SharedMemory>) -> Self {
+        Self {
+            physics_memory: Some(physics),
+            graphics_memory: Some(graphics),
+            static_memory: Some(static_info),
+            extra_pages: ExtraPages::new(),
         }
     }
 
@@ -28,12 +41,14 @@ impl AssettoCorsaEvoClient {
                 self.physics_memory = Some(Box::new(physics));
                 self.graphics_memory = Some(Box::new(graphics));
                 self.static_memory = Some(Box::new(static_mem));
+                self.extra_pages.open_all();
                 Ok(())
             }
             (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => {
                 self.physics_memory = None;
                 self.graphics_memory = None;
                 self.static_memory = None;
+                self.extra_pages.close_all();
                 Err(map_shared_memory_error(e))
             }
         }
@@ -43,31 +58,64 @@ impl AssettoCorsaEvoClient {
         self.physics_memory.is_some() && self.graphics_memory.is_some() && self.static_memory.is_some()
     }
 
+    /// Reads a copy of the page, `None` when not connected.
     pub fn get_physics(&self) -> Option<Physics> {
-        if let Some(ref memory) = self.physics_memory {
-            let physics: Physics = unsafe { ptr::read(memory.as_slice().as_ptr() as *const Physics) };
-            Some(physics)
-        } else {
-            None
-        }
+        read_page(self.physics_memory.as_deref()?)
     }
 
+    /// Reads a copy of the page, see [`get_physics`](Self::get_physics).
     pub fn get_graphics(&self) -> Option<Graphics> {
-        if let Some(ref memory) = self.graphics_memory {
-            let graphics: Graphics = unsafe { ptr::read(memory.as_slice().as_ptr() as *const Graphics) };
-            Some(graphics)
-        } else {
-            None
-        }
+        read_page(self.graphics_memory.as_deref()?)
     }
 
+    /// Reads a copy of the page, see [`get_physics`](Self::get_physics).
     pub fn get_static_info(&self) -> Option<StaticInfo> {
-        if let Some(ref memory) = self.static_memory {
-            let static_info: StaticInfo = unsafe { ptr::read(memory.as_slice().as_ptr() as *const StaticInfo) };
-            Some(static_info)
-        } else {
-            None
-        }
+        read_page(self.static_memory.as_deref()?)
+    }
+
+    /// Registers an additional shared-memory page read through a typed page.
+    ///
+    /// Extra pages are opened together with the built-in ones, a page that
+    /// does not exist is simply unavailable and does not fail `connect`.
+    pub fn register_page<T: Page>(&mut self, name: &str) {
+        self.register_page_layout(name, PageLayout::of::<T>());
+    }
+
+    /// Registers an additional shared-memory page described by a byte layout.
+    pub fn register_page_layout(&mut self, name: &str, layout: PageLayout) {
+        let connected = self.is_connected();
+        self.extra_pages.register(name, layout, connected);
+    }
+
+    pub fn unregister_page(&mut self, name: &str) {
+        self.extra_pages.unregister(name);
+    }
+
+    pub fn registered_pages(&self) -> Vec<&str> {
+        self.extra_pages.names()
+    }
+
+    pub fn is_page_connected(&self, name: &str) -> bool {
+        self.extra_pages.is_open(name)
+    }
+
+    pub fn get_page<T: Page>(&self, name: &str) -> Option<T> {
+        self.extra_pages.get(name)
+    }
+
+    pub fn get_page_bytes(&self, name: &str) -> Option<Vec<u8>> {
+        self.extra_pages.bytes(name)
+    }
+
+    /// Reads one element of an extra page by path, e.g. `tyre_core[0]`.
+    pub fn get_page_value(&self, name: &str, path: &str) -> Option<Value> {
+        self.extra_pages.value(name, path)
+    }
+}
+
+impl Default for AssettoCorsaEvoClient {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
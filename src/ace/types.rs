@@ -1,3 +1,4 @@
+use crate::telemetry::{Packet, RawEnum, raw_enum};
 use std::fmt;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -9,6 +10,8 @@ pub enum AceStatus {
     Pause = 3,
 }
 
+raw_enum!(AceStatus { Off, Replay, Live, Pause });
+
 impl fmt::Display for AceStatus {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -34,6 +37,18 @@ pub enum AceSessionType {
     HotlapSuperpole = 7,
 }
 
+raw_enum!(AceSessionType {
+    Unknown,
+    Practice,
+    Qualify,
+    Race,
+    Hotlap,
+    TimeAttack,
+    Superpole,
+    Hotstint,
+    HotlapSuperpole
+});
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[repr(u32)]
 pub enum AceFlagType {
@@ -47,6 +62,17 @@ pub enum AceFlagType {
     GreenFlag = 7,
 }
 
+raw_enum!(AceFlagType {
+    NoFlag,
+    BlueFlag,
+    YellowFlag,
+    BlackFlag,
+    WhiteFlag,
+    CheckeredFlag,
+    PenaltyFlag,
+    GreenFlag
+});
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[repr(u32)]
 pub enum AcePenaltyType {
@@ -57,6 +83,14 @@ pub enum AcePenaltyType {
     PitLane = 4,
 }
 
+raw_enum!(AcePenaltyType {
+    None,
+    DriveThrough,
+    StopAndGo,
+    TimePenalty,
+    PitLane
+});
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[repr(u32)]
 pub enum AceTrackGripStatus {
@@ -68,6 +102,15 @@ pub enum AceTrackGripStatus {
     Flooded = 5,
 }
 
+raw_enum!(AceTrackGripStatus {
+    Green,
+    Fast,
+    Optimum,
+    Greasy,
+    Wet,
+    Flooded
+});
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[repr(u32)]
 pub enum AceRainIntensity {
@@ -79,7 +122,17 @@ pub enum AceRainIntensity {
     Thunderstorm = 5,
 }
 
+raw_enum!(AceRainIntensity {
+    NoRain,
+    Drizzle,
+    LightRain,
+    MediumRain,
+    HeavyRain,
+    Thunderstorm
+});
+
 #[derive(Debug, Clone, Copy)]
+#[repr(C)]
 pub struct Coordinates {
     pub x: f32,
     pub y: f32,
@@ -181,8 +234,8 @@ pub struct Physics {
 #[repr(C)]
 pub struct Graphics {
     pub packet_id: i32,
-    pub status: AceStatus,
-    pub session: AceSessionType,
+    pub status: RawEnum<AceStatus>,
+    pub session: RawEnum<AceSessionType>,
     pub current_time: [u16; 15], // UTF-16 string
     pub last_time: [u16; 15],    // UTF-16 string
     pub best_time: [u16; 15],    // UTF-16 string
@@ -206,8 +259,8 @@ pub struct Graphics {
     pub car_id: [i32; 60],
     pub player_car_id: i32,
     pub penalty_time: f32,
-    pub flag: AceFlagType,
-    pub penalty: AcePenaltyType,
+    pub flag: RawEnum<AceFlagType>,
+    pub penalty: RawEnum<AcePenaltyType>,
     pub ideal_line_on: i32,
     pub is_in_pit_lane: i32,
     pub surface_grip: f32,
@@ -259,10 +312,10 @@ pub struct Graphics {
     pub mfd_tyre_pressure_rf: f32,
     pub mfd_tyre_pressure_lr: f32,
     pub mfd_tyre_pressure_rr: f32,
-    pub track_grip_status: AceTrackGripStatus,
-    pub rain_intensity: AceRainIntensity,
-    pub rain_intensity_in_10min: AceRainIntensity,
-    pub rain_intensity_in_30min: AceRainIntensity,
+    pub track_grip_status: RawEnum<AceTrackGripStatus>,
+    pub rain_intensity: RawEnum<AceRainIntensity>,
+    pub rain_intensity_in_10min: RawEnum<AceRainIntensity>,
+    pub rain_intensity_in_30min: RawEnum<AceRainIntensity>,
     pub current_tyre_set: i32,
     pub strategy_tyre_set: i32,
 }
@@ -377,6 +430,18 @@ impl StaticInfo {
     }
 }
 
+impl Packet for Physics {
+    fn packet_id(&self) -> i32 {
+        self.packet_id
+    }
+}
+
+impl Packet for Graphics {
+    fn packet_id(&self) -> i32 {
+        self.packet_id
+    }
+}
+
 pub fn utf16_to_string(utf16: &[u16]) -> String {
     let end = utf16.iter().position(|&c| c == 0).unwrap_or(utf16.len());
     String::from_utf16_lossy(&utf16[..end])
@@ -479,8 +544,8 @@ impl Default for Graphics {
     fn default() -> Self {
         Self {
             packet_id: 0,
-            status: AceStatus::Off,
-            session: AceSessionType::Unknown,
+            status: AceStatus::Off.into(),
+            session: AceSessionType::Unknown.into(),
             current_time: [0; 15],
             last_time: [0; 15],
             best_time: [0; 15],
@@ -504,8 +569,8 @@ impl Default for Graphics {
             car_id: [0; 60],
             player_car_id: 0,
             penalty_time: 0.0,
-            flag: AceFlagType::NoFlag,
-            penalty: AcePenaltyType::None,
+            flag: AceFlagType::NoFlag.into(),
+            penalty: AcePenaltyType::None.into(),
             ideal_line_on: 0,
             is_in_pit_lane: 0,
             surface_grip: 0.0,
@@ -557,10 +622,10 @@ impl Default for Graphics {
             mfd_tyre_pressure_rf: 0.0,
             mfd_tyre_pressure_lr: 0.0,
             mfd_tyre_pressure_rr: 0.0,
-            track_grip_status: AceTrackGripStatus::Green,
-            rain_intensity: AceRainIntensity::NoRain,
-            rain_intensity_in_10min: AceRainIntensity::NoRain,
-            rain_intensity_in_30min: AceRainIntensity::NoRain,
+            track_grip_status: AceTrackGripStatus::Green.into(),
+            rain_intensity: AceRainIntensity::NoRain.into(),
+            rain_intensity_in_10min: AceRainIntensity::NoRain.into(),
+            rain_intensity_in_30min: AceRainIntensity::NoRain.into(),
             current_tyre_set: 0,
             strategy_tyre_set: 0,
         }
@@ -0,0 +1,81 @@
+use crate::am2::{Automobilista2Error, SHARED_MEMORY_VERSION, Shared};
+use crate::utils::{SharedMemory, open_shared_memory};
+use std::hint;
+use std::mem;
+use std::sync::atomic::{Ordering, fence};
+
+/// Reads attempted by [`Automobilista2Client::get_shared`] before giving up.
+pub const MAX_READ_ATTEMPTS: usize = 64;
+
+pub struct Automobilista2Client {
+    memory: Option<Box<dyn SharedMemory>>,
+}
+
+impl Automobilista2Client {
+    pub fn new() -> Self {
+        Self { memory: None }
+    }
+
+    /// Creates a client reading from a custom memory source instead of the
+    /// game.
+    pub fn from_shared_memory(memory: Box<dyn SharedMemory>) -> Self {
+        Self { memory: Some(memory) }
+    }
+
+    /// Opens `$pcars2$`, which the game only creates with shared memory set
+    /// to "Project CARS 2" in its options.
+    pub fn connect(&mut self) -> Result<(), Automobilista2Error> {
+        self.memory = None;
+        let memory = open_shared_memory("$pcars2$", mem::size_of::<Shared>()).map_err(map_shared_memory_error)?;
+        let version = Shared::version_of(memory.as_slice()).unwrap_or_default();
+        if version < SHARED_MEMORY_VERSION {
+            return Err(Automobilista2Error::VersionMismatch {
+                expected: SHARED_MEMORY_VERSION,
+                found: version,
+            });
+        }
+        self.memory = Some(Box::new(memory));
+        Ok(())
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.memory.is_some()
+    }
+
+    /// Reads a consistent copy of the page.
+    ///
+    /// The game makes the sequence number odd while it writes the page, a
+    /// copy is only returned when the number was even and unchanged across
+    /// it. Returns `None` when not connected or the game kept writing for
+    /// [`MAX_READ_ATTEMPTS`] reads.
+    pub fn get_shared(&self) -> Option<Shared> {
+        let bytes = self.memory.as_ref()?.as_slice();
+        for _ in 0..MAX_READ_ATTEMPTS {
+            let before = Shared::sequence_number_of(bytes)?;
+            if before % 2 == 0 {
+                // Keep the copy between the two reads of the sequence number.
+                fence(Ordering::Acquire);
+                let shared = Shared::from_bytes(bytes)?;
+                fence(Ordering::Acquire);
+                if Shared::sequence_number_of(bytes)? == before && shared.sequence_number == before {
+                    return Some(shared);
+                }
+            }
+            hint::spin_loop();
+        }
+        None
+    }
+}
+
+impl Default for Automobilista2Client {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn map_shared_memory_error(err: crate::utils::SharedMemoryError) -> Automobilista2Error {
+    match err {
+        crate::utils::SharedMemoryError::SharedMemoryNotFound(msg) => Automobilista2Error::SharedMemoryNotFound(msg),
+        crate::utils::SharedMemoryError::ConnectionFailed(msg) => Automobilista2Error::ConnectionFailed(msg),
+    }
+}
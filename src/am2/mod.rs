@@ -0,0 +1,124 @@
+pub mod client;
+pub mod types;
+pub mod udp;
+pub use client::*;
+pub use types::*;
+pub use udp::*;
+
+use crate::SharedMemory;
+use crate::telemetry::Sampler;
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum Automobilista2Error {
+    SharedMemoryNotFound(String),
+    ConnectionFailed(String),
+    InvalidData(String),
+    VersionMismatch { expected: u32, found: u32 },
+    Io(io::Error),
+}
+
+impl fmt::Display for Automobilista2Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Automobilista2Error::SharedMemoryNotFound(msg) => write!(f, "Shared memory not found: {}", msg),
+            Automobilista2Error::ConnectionFailed(msg) => write!(f, "Connection failed: {}", msg),
+            Automobilista2Error::InvalidData(msg) => write!(f, "Invalid data format: {}", msg),
+            Automobilista2Error::VersionMismatch { expected, found } => {
+                write!(f, "Shared memory version {} is older than the supported {}", found, expected)
+            }
+            Automobilista2Error::Io(err) => write!(f, "I/O error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for Automobilista2Error {}
+
+impl From<io::Error> for Automobilista2Error {
+    fn from(err: io::Error) -> Self {
+        Automobilista2Error::Io(err)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Automobilista2Error>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConnectionStatus {
+    Disconnected,
+    Connected,
+}
+
+/// Automobilista 2, read through its Project CARS 2 compatible `$pcars2$`
+/// shared memory.
+///
+/// ```no_run
+/// use race_sdk::am2::Automobilista2;
+///
+/// let mut ams2 = Automobilista2::new();
+/// ams2.connect().unwrap();
+///
+/// if let Some(shared) = ams2.get_shared() {
+///     println!("{} at {}, {:.0} km/h", shared.car_name_str(), shared.track_location_str(), shared.speed_kmh());
+///     println!("rain {:.0}%, track {:.1} °C", shared.rain_density * 100.0, shared.track_temperature);
+///     for participant in shared.participants() {
+///         println!("P{} {} ({})", participant.race_position, participant.name, participant.car_name);
+///     }
+/// }
+/// ```
+pub struct Automobilista2 {
+    client: Automobilista2Client,
+}
+
+impl Automobilista2 {
+    pub fn new() -> Self {
+        Self {
+            client: Automobilista2Client::new(),
+        }
+    }
+
+    /// Creates a facade reading from a custom memory source, see
+    /// [`Automobilista2Client::from_shared_memory`].
+    pub fn from_shared_memory(memory: Box<dyn SharedMemory>) -> Self {
+        Self {
+            client: Automobilista2Client::from_shared_memory(memory),
+        }
+    }
+
+    pub fn connect(&mut self) -> Result<()> {
+        self.client.connect()
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.client.is_connected()
+    }
+
+    pub fn status(&self) -> ConnectionStatus {
+        if self.is_connected() {
+            ConnectionStatus::Connected
+        } else {
+            ConnectionStatus::Disconnected
+        }
+    }
+
+    /// Reads a consistent copy of the page, see [`Automobilista2Client::get_shared`].
+    pub fn get_shared(&self) -> Option<Shared> {
+        self.client.get_shared()
+    }
+
+    /// The active participants, see [`Shared::participants`].
+    pub fn get_participants(&self) -> Vec<Participant> {
+        self.client.get_shared().map(|shared| shared.participants()).unwrap_or_default()
+    }
+
+    /// Returns a sampler that emits every new consistent page.
+    pub fn sampler(&self) -> Sampler<Shared, impl FnMut() -> Option<Shared> + '_> {
+        Sampler::new(|| self.client.get_shared())
+    }
+}
+
+impl Default for Automobilista2 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
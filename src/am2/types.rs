@@ -0,0 +1,575 @@
+use crate::telemetry::Packet;
+use std::fmt;
+use std::mem;
+use std::ptr;
+
+/// Oldest `mVersion` of the `$pcars2$` layout these types describe. Later
+/// versions only append fields.
+pub const SHARED_MEMORY_VERSION: u32 = 13;
+
+pub const STORED_PARTICIPANTS_MAX: usize = 64;
+pub const STRING_LENGTH_MAX: usize = 64;
+pub const TYRE_COMPOUND_NAME_LENGTH_MAX: usize = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u32)]
+pub enum GameState {
+    Exited = 0,
+    FrontEnd = 1,
+    InGamePlaying = 2,
+    InGamePaused = 3,
+    InGameInMenuTimeTicking = 4,
+    InGameRestarting = 5,
+    InGameReplay = 6,
+    FrontEndReplay = 7,
+}
+
+impl GameState {
+    pub fn from_raw(value: u32) -> Option<Self> {
+        Some(match value {
+            0 => GameState::Exited,
+            1 => GameState::FrontEnd,
+            2 => GameState::InGamePlaying,
+            3 => GameState::InGamePaused,
+            4 => GameState::InGameInMenuTimeTicking,
+            5 => GameState::InGameRestarting,
+            6 => GameState::InGameReplay,
+            7 => GameState::FrontEndReplay,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u32)]
+pub enum SessionState {
+    Invalid = 0,
+    Practice = 1,
+    Test = 2,
+    Qualify = 3,
+    FormationLap = 4,
+    Race = 5,
+    TimeAttack = 6,
+}
+
+impl SessionState {
+    pub fn from_raw(value: u32) -> Option<Self> {
+        Some(match value {
+            0 => SessionState::Invalid,
+            1 => SessionState::Practice,
+            2 => SessionState::Test,
+            3 => SessionState::Qualify,
+            4 => SessionState::FormationLap,
+            5 => SessionState::Race,
+            6 => SessionState::TimeAttack,
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for SessionState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SessionState::Invalid => write!(f, "Invalid"),
+            SessionState::Practice => write!(f, "Practice"),
+            SessionState::Test => write!(f, "Test"),
+            SessionState::Qualify => write!(f, "Qualify"),
+            SessionState::FormationLap => write!(f, "FormationLap"),
+            SessionState::Race => write!(f, "Race"),
+            SessionState::TimeAttack => write!(f, "TimeAttack"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u32)]
+pub enum RaceState {
+    Invalid = 0,
+    NotStarted = 1,
+    Racing = 2,
+    Finished = 3,
+    Disqualified = 4,
+    Retired = 5,
+    Dnf = 6,
+}
+
+impl RaceState {
+    pub fn from_raw(value: u32) -> Option<Self> {
+        Some(match value {
+            0 => RaceState::Invalid,
+            1 => RaceState::NotStarted,
+            2 => RaceState::Racing,
+            3 => RaceState::Finished,
+            4 => RaceState::Disqualified,
+            5 => RaceState::Retired,
+            6 => RaceState::Dnf,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u32)]
+pub enum FlagColour {
+    None = 0,
+    Green = 1,
+    Blue = 2,
+    WhiteSlowCar = 3,
+    WhiteFinalLap = 4,
+    Red = 5,
+    Yellow = 6,
+    DoubleYellow = 7,
+    BlackAndWhite = 8,
+    BlackOrangeCircle = 9,
+    Black = 10,
+    Chequered = 11,
+}
+
+impl FlagColour {
+    pub fn from_raw(value: u32) -> Option<Self> {
+        Some(match value {
+            0 => FlagColour::None,
+            1 => FlagColour::Green,
+            2 => FlagColour::Blue,
+            3 => FlagColour::WhiteSlowCar,
+            4 => FlagColour::WhiteFinalLap,
+            5 => FlagColour::Red,
+            6 => FlagColour::Yellow,
+            7 => FlagColour::DoubleYellow,
+            8 => FlagColour::BlackAndWhite,
+            9 => FlagColour::BlackOrangeCircle,
+            10 => FlagColour::Black,
+            11 => FlagColour::Chequered,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u32)]
+pub enum PitMode {
+    None = 0,
+    DrivingIntoPits = 1,
+    InPit = 2,
+    DrivingOutOfPits = 3,
+    InGarage = 4,
+    DrivingOutOfGarage = 5,
+}
+
+impl PitMode {
+    pub fn from_raw(value: u32) -> Option<Self> {
+        Some(match value {
+            0 => PitMode::None,
+            1 => PitMode::DrivingIntoPits,
+            2 => PitMode::InPit,
+            3 => PitMode::DrivingOutOfPits,
+            4 => PitMode::InGarage,
+            5 => PitMode::DrivingOutOfGarage,
+            _ => return None,
+        })
+    }
+}
+
+/// Position and progress of one participant.
+///
+/// Booleans are stored as bytes, any non-zero value is `true`.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct ParticipantInfo {
+    pub is_active: u8,
+    /// UTF-8, see [`decode_name`].
+    pub name: [u8; STRING_LENGTH_MAX],
+    pub world_position: [f32; 3],
+    pub current_lap_distance: f32,
+    pub race_position: u32,
+    pub laps_completed: u32,
+    pub current_lap: u32,
+    pub current_sector: i32,
+}
+
+impl ParticipantInfo {
+    pub fn name_str(&self) -> String {
+        decode_name(&self.name)
+    }
+}
+
+/// The `$pcars2$` shared-memory page written by Automobilista 2.
+///
+/// Per-wheel arrays are ordered front left, front right, rear left, rear
+/// right. Times are in seconds and negative when not set, speeds in m/s,
+/// temperatures in °C. Booleans are stored as bytes.
+#[derive(Debug, Clone)]
+#[repr(C)]
+pub struct Shared {
+    pub version: u32,
+    pub build_version_number: u32,
+
+    pub game_state: u32,
+    pub session_state: u32,
+    pub race_state: u32,
+
+    pub viewed_participant_index: i32,
+    pub num_participants: i32,
+    pub participant_info: [ParticipantInfo; STORED_PARTICIPANTS_MAX],
+
+    pub unfiltered_throttle: f32,
+    pub unfiltered_brake: f32,
+    pub unfiltered_steering: f32,
+    pub unfiltered_clutch: f32,
+
+    pub car_name: [u8; STRING_LENGTH_MAX],
+    pub car_class_name: [u8; STRING_LENGTH_MAX],
+
+    pub laps_in_event: u32,
+    pub track_location: [u8; STRING_LENGTH_MAX],
+    pub track_variation: [u8; STRING_LENGTH_MAX],
+    /// In metres.
+    pub track_length: f32,
+
+    pub num_sectors: i32,
+    pub lap_invalidated: u8,
+    pub best_lap_time: f32,
+    pub last_lap_time: f32,
+    pub current_time: f32,
+    pub split_time_ahead: f32,
+    pub split_time_behind: f32,
+    pub split_time: f32,
+    pub event_time_remaining: f32,
+    pub personal_fastest_lap_time: f32,
+    pub world_fastest_lap_time: f32,
+    pub current_sector1_time: f32,
+    pub current_sector2_time: f32,
+    pub current_sector3_time: f32,
+    pub fastest_sector1_time: f32,
+    pub fastest_sector2_time: f32,
+    pub fastest_sector3_time: f32,
+    pub personal_fastest_sector1_time: f32,
+    pub personal_fastest_sector2_time: f32,
+    pub personal_fastest_sector3_time: f32,
+    pub world_fastest_sector1_time: f32,
+    pub world_fastest_sector2_time: f32,
+    pub world_fastest_sector3_time: f32,
+
+    pub highest_flag_colour: u32,
+    pub highest_flag_reason: u32,
+
+    pub pit_mode: u32,
+    pub pit_schedule: u32,
+
+    pub car_flags: u32,
+    pub oil_temp_celsius: f32,
+    pub oil_pressure_kpa: f32,
+    pub water_temp_celsius: f32,
+    pub water_pressure_kpa: f32,
+    pub fuel_pressure_kpa: f32,
+    /// Fraction of `fuel_capacity`.
+    pub fuel_level: f32,
+    /// In litres.
+    pub fuel_capacity: f32,
+    pub speed: f32,
+    pub rpm: f32,
+    pub max_rpm: f32,
+    pub brake: f32,
+    pub throttle: f32,
+    pub clutch: f32,
+    pub steering: f32,
+    /// `-1` reverse, `0` neutral.
+    pub gear: i32,
+    pub num_gears: i32,
+    pub odometer_km: f32,
+    pub anti_lock_active: u8,
+    pub last_opponent_collision_index: i32,
+    pub last_opponent_collision_magnitude: f32,
+    pub boost_active: u8,
+    pub boost_amount: f32,
+
+    pub orientation: [f32; 3],
+    pub local_velocity: [f32; 3],
+    pub world_velocity: [f32; 3],
+    pub angular_velocity: [f32; 3],
+    pub local_acceleration: [f32; 3],
+    pub world_acceleration: [f32; 3],
+    pub extents_centre: [f32; 3],
+
+    pub tyre_flags: [u32; 4],
+    pub terrain: [u32; 4],
+    pub tyre_y: [f32; 4],
+    pub tyre_rps: [f32; 4],
+    pub tyre_slip_speed: [f32; 4],
+    pub tyre_temp: [f32; 4],
+    pub tyre_grip: [f32; 4],
+    pub tyre_height_above_ground: [f32; 4],
+    pub tyre_lateral_stiffness: [f32; 4],
+    pub tyre_wear: [f32; 4],
+    pub brake_damage: [f32; 4],
+    pub suspension_damage: [f32; 4],
+    pub brake_temp_celsius: [f32; 4],
+    /// In Kelvin, like the other layer temperatures.
+    pub tyre_tread_temp: [f32; 4],
+    pub tyre_layer_temp: [f32; 4],
+    pub tyre_carcass_temp: [f32; 4],
+    pub tyre_rim_temp: [f32; 4],
+    pub tyre_internal_air_temp: [f32; 4],
+
+    pub crash_state: u32,
+    pub aero_damage: f32,
+    pub engine_damage: f32,
+
+    pub ambient_temperature: f32,
+    pub track_temperature: f32,
+    pub rain_density: f32,
+    pub wind_speed: f32,
+    pub wind_direction_x: f32,
+    pub wind_direction_y: f32,
+    pub cloud_brightness: f32,
+
+    /// Even when the page is consistent, odd while the game writes it.
+    pub sequence_number: u32,
+
+    pub wheel_local_position_y: [f32; 4],
+    pub suspension_travel: [f32; 4],
+    pub suspension_velocity: [f32; 4],
+    /// In kPa.
+    pub air_pressure: [f32; 4],
+    pub engine_speed: f32,
+    pub engine_torque: f32,
+    pub wings: [f32; 2],
+    pub hand_brake: f32,
+
+    pub current_sector1_times: [f32; STORED_PARTICIPANTS_MAX],
+    pub current_sector2_times: [f32; STORED_PARTICIPANTS_MAX],
+    pub current_sector3_times: [f32; STORED_PARTICIPANTS_MAX],
+    pub fastest_sector1_times: [f32; STORED_PARTICIPANTS_MAX],
+    pub fastest_sector2_times: [f32; STORED_PARTICIPANTS_MAX],
+    pub fastest_sector3_times: [f32; STORED_PARTICIPANTS_MAX],
+    pub fastest_lap_times: [f32; STORED_PARTICIPANTS_MAX],
+    pub last_lap_times: [f32; STORED_PARTICIPANTS_MAX],
+    pub laps_invalidated: [u8; STORED_PARTICIPANTS_MAX],
+    pub race_states: [u32; STORED_PARTICIPANTS_MAX],
+    pub pit_modes: [u32; STORED_PARTICIPANTS_MAX],
+    pub orientations: [[f32; 3]; STORED_PARTICIPANTS_MAX],
+    pub speeds: [f32; STORED_PARTICIPANTS_MAX],
+    pub car_names: [[u8; STRING_LENGTH_MAX]; STORED_PARTICIPANTS_MAX],
+    pub car_class_names: [[u8; STRING_LENGTH_MAX]; STORED_PARTICIPANTS_MAX],
+
+    pub enforced_pit_stop_lap: i32,
+    pub translated_track_location: [u8; STRING_LENGTH_MAX],
+    pub translated_track_variation: [u8; STRING_LENGTH_MAX],
+    pub brake_bias: f32,
+    pub turbo_boost_pressure: f32,
+    pub tyre_compound: [[u8; TYRE_COMPOUND_NAME_LENGTH_MAX]; 4],
+    pub pit_schedules: [u32; STORED_PARTICIPANTS_MAX],
+    pub highest_flag_colours: [u32; STORED_PARTICIPANTS_MAX],
+    pub highest_flag_reasons: [u32; STORED_PARTICIPANTS_MAX],
+    pub nationalities: [u32; STORED_PARTICIPANTS_MAX],
+    pub snow_density: f32,
+
+    pub session_duration: f32,
+    pub session_additional_laps: i32,
+    /// Surface temperature across the tread.
+    pub tyre_temp_left: [f32; 4],
+    pub tyre_temp_center: [f32; 4],
+    pub tyre_temp_right: [f32; 4],
+    pub drs_state: u32,
+    pub ride_height: [f32; 4],
+    pub joy_pad0: u32,
+    pub d_pad: u32,
+    pub anti_lock_setting: i32,
+    pub traction_control_setting: i32,
+    pub ers_deployment_mode: i32,
+    pub ers_auto_mode_enabled: u8,
+    pub clutch_temp: f32,
+    pub clutch_wear: f32,
+    pub clutch_overheated: u8,
+    pub clutch_slipping: u8,
+    pub yellow_flag_state: i32,
+    pub session_is_private: u8,
+    pub launch_stage: i32,
+}
+
+/// One participant with the entries of all per-participant arrays of
+/// [`Shared`], see [`Shared::participants`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Participant {
+    /// Index into the participant arrays.
+    pub index: usize,
+    pub name: String,
+    pub car_name: String,
+    pub car_class_name: String,
+    pub world_position: [f32; 3],
+    pub orientation: [f32; 3],
+    pub speed: f32,
+    pub current_lap_distance: f32,
+    pub race_position: u32,
+    pub laps_completed: u32,
+    pub current_lap: u32,
+    pub current_sector: i32,
+    pub current_sector_times: [f32; 3],
+    pub fastest_sector_times: [f32; 3],
+    pub fastest_lap_time: f32,
+    pub last_lap_time: f32,
+    pub lap_invalidated: bool,
+    pub race_state: Option<RaceState>,
+    pub pit_mode: Option<PitMode>,
+    pub highest_flag_colour: Option<FlagColour>,
+    pub nationality: u32,
+}
+
+impl Shared {
+    /// Copies the page out of raw shared memory without checking the
+    /// sequence number, `None` if `bytes` is too short.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < mem::size_of::<Self>() {
+            return None;
+        }
+        // Booleans are declared as bytes, so any bit pattern is valid.
+        Some(unsafe { ptr::read_unaligned(bytes.as_ptr() as *const Self) })
+    }
+
+    /// Reads `mVersion` from raw shared memory.
+    pub fn version_of(bytes: &[u8]) -> Option<u32> {
+        Some(u32::from_le_bytes(bytes.get(0..4)?.try_into().ok()?))
+    }
+
+    /// Reads `mSequenceNumber` from raw shared memory while the game may be
+    /// writing it.
+    pub fn sequence_number_of(bytes: &[u8]) -> Option<u32> {
+        let offset = mem::offset_of!(Shared, sequence_number);
+        let word = bytes.get(offset..offset + 4)?;
+        Some(unsafe { ptr::read_volatile(word.as_ptr() as *const u32) })
+    }
+
+    pub fn game_state(&self) -> Option<GameState> {
+        GameState::from_raw(self.game_state)
+    }
+
+    pub fn session_state(&self) -> Option<SessionState> {
+        SessionState::from_raw(self.session_state)
+    }
+
+    pub fn race_state(&self) -> Option<RaceState> {
+        RaceState::from_raw(self.race_state)
+    }
+
+    pub fn pit_mode(&self) -> Option<PitMode> {
+        PitMode::from_raw(self.pit_mode)
+    }
+
+    pub fn highest_flag_colour(&self) -> Option<FlagColour> {
+        FlagColour::from_raw(self.highest_flag_colour)
+    }
+
+    pub fn car_name_str(&self) -> String {
+        decode_name(&self.car_name)
+    }
+
+    pub fn car_class_name_str(&self) -> String {
+        decode_name(&self.car_class_name)
+    }
+
+    pub fn track_location_str(&self) -> String {
+        decode_name(&self.track_location)
+    }
+
+    pub fn track_variation_str(&self) -> String {
+        decode_name(&self.track_variation)
+    }
+
+    pub fn tyre_compound_str(&self, wheel: usize) -> String {
+        decode_name(&self.tyre_compound[wheel])
+    }
+
+    /// Speed in km/h.
+    pub fn speed_kmh(&self) -> f32 {
+        self.speed * 3.6
+    }
+
+    /// Fuel left in litres.
+    pub fn fuel_litres(&self) -> f32 {
+        self.fuel_level * self.fuel_capacity
+    }
+
+    /// The participant whose car the player is watching, usually their own.
+    pub fn viewed_participant(&self) -> Option<Participant> {
+        let index = usize::try_from(self.viewed_participant_index).ok()?;
+        self.participant(index)
+    }
+
+    pub fn participant(&self, index: usize) -> Option<Participant> {
+        let info = self.participant_info.get(index)?;
+        Some(Participant {
+            index,
+            name: info.name_str(),
+            car_name: decode_name(&self.car_names[index]),
+            car_class_name: decode_name(&self.car_class_names[index]),
+            world_position: info.world_position,
+            orientation: self.orientations[index],
+            speed: self.speeds[index],
+            current_lap_distance: info.current_lap_distance,
+            race_position: info.race_position,
+            laps_completed: info.laps_completed,
+            current_lap: info.current_lap,
+            current_sector: info.current_sector,
+            current_sector_times: [
+                self.current_sector1_times[index],
+                self.current_sector2_times[index],
+                self.current_sector3_times[index],
+            ],
+            fastest_sector_times: [
+                self.fastest_sector1_times[index],
+                self.fastest_sector2_times[index],
+                self.fastest_sector3_times[index],
+            ],
+            fastest_lap_time: self.fastest_lap_times[index],
+            last_lap_time: self.last_lap_times[index],
+            lap_invalidated: self.laps_invalidated[index] != 0,
+            race_state: RaceState::from_raw(self.race_states[index]),
+            pit_mode: PitMode::from_raw(self.pit_modes[index]),
+            highest_flag_colour: FlagColour::from_raw(self.highest_flag_colours[index]),
+            nationality: self.nationalities[index],
+        })
+    }
+
+    /// The active participants among the first `num_participants` entries.
+    pub fn participants(&self) -> Vec<Participant> {
+        let count = self.num_participants.clamp(0, STORED_PARTICIPANTS_MAX as i32) as usize;
+        (0..count)
+            .filter(|&index| self.participant_info[index].is_active != 0)
+            .filter_map(|index| self.participant(index))
+            .collect()
+    }
+}
+
+impl Packet for Shared {
+    /// Half the sequence number, which advances by two per update.
+    fn packet_id(&self) -> i32 {
+        (self.sequence_number / 2) as i32
+    }
+}
+
+/// Decodes a fixed-size, null-terminated UTF-8 string.
+///
+/// The game cuts long names at the buffer size, possibly inside a
+/// multi-byte character, such a partial character is dropped.
+pub fn decode_name(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&c| c == 0).unwrap_or(bytes.len());
+    let bytes = &bytes[..end];
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(err) if err.error_len().is_none() => String::from_utf8_lossy(&bytes[..err.valid_up_to()]).into_owned(),
+        Err(_) => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+impl Default for ParticipantInfo {
+    fn default() -> Self {
+        // All fields are numbers or bytes, zero is a valid value for each.
+        unsafe { mem::zeroed() }
+    }
+}
+
+impl Default for Shared {
+    fn default() -> Self {
+        unsafe { mem::zeroed() }
+    }
+}
@@ -0,0 +1,998 @@
+use crate::am2::{Automobilista2Error, FlagColour, GameState, PitMode, RaceState, Result, SessionState, decode_name};
+use std::collections::HashMap;
+use std::f32::consts::PI;
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::time::Duration;
+
+/// Port the game sends to by default.
+pub const UDP_PORT: u16 = 5606;
+/// Largest datagram of the protocol, the class names packet.
+pub const UDP_PACKET_SIZE_MAX: usize = 1500;
+
+pub const UDP_PARTICIPANTS_MAX: usize = 32;
+pub const UDP_PARTICIPANTS_PER_PACKET: usize = 16;
+pub const UDP_VEHICLES_PER_PACKET: usize = 16;
+pub const UDP_CLASSES_PER_PACKET: usize = 60;
+
+const HEADER_SIZE: usize = 12;
+const TELEMETRY_SIZE: usize = 559;
+const RACE_DEFINITION_SIZE: usize = 308;
+const PARTICIPANTS_SIZE: usize = 1136;
+const TIMINGS_SIZE: usize = 1063;
+/// The packet is declared as 24 bytes, the last two are padding.
+const GAME_STATE_SIZE: usize = 22;
+const TIME_STATS_SIZE: usize = 1040;
+const VEHICLE_NAMES_SIZE: usize = 1164;
+const CLASS_NAMES_SIZE: usize = 1452;
+
+const TRACK_NAME_LENGTH_MAX: usize = 64;
+const PARTICIPANT_NAME_LENGTH_MAX: usize = 64;
+const VEHICLE_NAME_LENGTH_MAX: usize = 64;
+const CLASS_NAME_LENGTH_MAX: usize = 20;
+const TYRE_NAME_LENGTH_MAX: usize = 40;
+
+/// The `mPacketType` of the protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum PacketType {
+    CarPhysics = 0,
+    RaceDefinition = 1,
+    Participants = 2,
+    Timings = 3,
+    GameState = 4,
+    /// Declared but not sent by the game.
+    WeatherState = 5,
+    /// Declared but not sent by the game.
+    VehicleNames = 6,
+    TimeStats = 7,
+    /// Vehicle names and class names, told apart by their size.
+    ParticipantVehicleNames = 8,
+}
+
+impl PacketType {
+    pub fn from_raw(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => PacketType::CarPhysics,
+            1 => PacketType::RaceDefinition,
+            2 => PacketType::Participants,
+            3 => PacketType::Timings,
+            4 => PacketType::GameState,
+            5 => PacketType::WeatherState,
+            6 => PacketType::VehicleNames,
+            7 => PacketType::TimeStats,
+            8 => PacketType::ParticipantVehicleNames,
+            _ => return None,
+        })
+    }
+}
+
+/// The `PacketBase` every datagram starts with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PacketHeader {
+    /// Counts all packets sent since the game started.
+    pub packet_number: u32,
+    /// Counts the messages of this type, the parts of a split message
+    /// share it.
+    pub category_packet_number: u32,
+    /// Starts at 1 for messages split over several packets.
+    pub partial_packet_index: u8,
+    pub partial_packet_number: u8,
+    pub packet_type: u8,
+    pub packet_version: u8,
+}
+
+impl PacketHeader {
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < HEADER_SIZE {
+            return None;
+        }
+        let mut cursor = Cursor::new(bytes);
+        Some(Self {
+            packet_number: cursor.u32(),
+            category_packet_number: cursor.u32(),
+            partial_packet_index: cursor.u8(),
+            partial_packet_number: cursor.u8(),
+            packet_type: cursor.u8(),
+            packet_version: cursor.u8(),
+        })
+    }
+
+    pub fn kind(&self) -> Option<PacketType> {
+        PacketType::from_raw(self.packet_type)
+    }
+}
+
+/// Telemetry of the viewed car (`sTelemetryData`).
+///
+/// Per-wheel arrays are ordered front left, front right, rear left, rear
+/// right. Pedals, damage and wear are scaled to 0..1, steering to -1..1.
+/// Speeds are in m/s, tyre layer temperatures in Kelvin and the other
+/// temperatures in °C.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CarTelemetry {
+    pub viewed_participant_index: i8,
+    pub unfiltered_throttle: f32,
+    pub unfiltered_brake: f32,
+    pub unfiltered_steering: f32,
+    pub unfiltered_clutch: f32,
+    pub car_flags: u8,
+    pub oil_temp_celsius: i16,
+    pub oil_pressure_kpa: u16,
+    pub water_temp_celsius: i16,
+    pub water_pressure_kpa: u16,
+    pub fuel_pressure_kpa: u16,
+    /// In litres.
+    pub fuel_capacity: u8,
+    pub brake: f32,
+    pub throttle: f32,
+    pub clutch: f32,
+    /// Fraction of the capacity.
+    pub fuel_level: f32,
+    pub speed: f32,
+    pub rpm: u16,
+    pub max_rpm: u16,
+    pub steering: f32,
+    /// 0 is neutral and -1 reverse.
+    pub gear: i8,
+    pub num_gears: u8,
+    pub boost_amount: u8,
+    pub crash_state: u8,
+    pub odometer_km: f32,
+    pub orientation: [f32; 3],
+    pub local_velocity: [f32; 3],
+    pub world_velocity: [f32; 3],
+    pub angular_velocity: [f32; 3],
+    pub local_acceleration: [f32; 3],
+    pub world_acceleration: [f32; 3],
+    pub extents_centre: [f32; 3],
+    pub tyre_flags: [u8; 4],
+    pub terrain: [u8; 4],
+    pub tyre_y: [f32; 4],
+    pub tyre_rps: [f32; 4],
+    pub tyre_temp: [u8; 4],
+    pub tyre_height_above_ground: [f32; 4],
+    pub tyre_wear: [f32; 4],
+    pub brake_damage: [f32; 4],
+    pub suspension_damage: [f32; 4],
+    pub brake_temp_celsius: [i16; 4],
+    pub tyre_tread_temp: [u16; 4],
+    pub tyre_layer_temp: [u16; 4],
+    pub tyre_carcass_temp: [u16; 4],
+    pub tyre_rim_temp: [u16; 4],
+    pub tyre_internal_air_temp: [u16; 4],
+    pub tyre_temp_left: [u16; 4],
+    pub tyre_temp_center: [u16; 4],
+    pub tyre_temp_right: [u16; 4],
+    pub wheel_local_position_y: [f32; 4],
+    pub ride_height: [f32; 4],
+    pub suspension_travel: [f32; 4],
+    pub suspension_velocity: [f32; 4],
+    pub suspension_ride_height: [u16; 4],
+    pub air_pressure: [u16; 4],
+    pub engine_speed: f32,
+    pub engine_torque: f32,
+    pub wings: [u8; 2],
+    pub hand_brake: f32,
+    pub aero_damage: f32,
+    pub engine_damage: f32,
+    pub joy_pad: u32,
+    pub d_pad: u8,
+    pub tyre_compound: [String; 4],
+    pub turbo_boost_pressure: f32,
+    pub full_position: [f32; 3],
+    /// Front share of the brake force, 0..1.
+    pub brake_bias: f32,
+    pub tick_count: u32,
+}
+
+/// Track and session information (`sRaceData`). Times are in seconds and
+/// negative when not set.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RaceDefinition {
+    pub world_fastest_lap_time: f32,
+    pub personal_fastest_lap_time: f32,
+    pub personal_fastest_sector_times: [f32; 3],
+    pub world_fastest_sector_times: [f32; 3],
+    /// In metres.
+    pub track_length: f32,
+    pub track_location: String,
+    pub track_variation: String,
+    pub translated_track_location: String,
+    pub translated_track_variation: String,
+    /// Whether the session runs for a time rather than a number of laps.
+    pub timed_session: bool,
+    /// The laps of the session, or its duration in units of 5 minutes.
+    pub laps_time_in_event: u16,
+    /// Negative when there is no mandatory stop.
+    pub enforced_pit_stop_lap: i8,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParticipantName {
+    /// Index into the timing and stats arrays.
+    pub slot: usize,
+    pub name: String,
+    pub nationality: u32,
+    /// Unique within a multiplayer session.
+    pub mp_index: u16,
+}
+
+/// Names of the participants (`sParticipantsData`), sent as one packet per
+/// 16 participants.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParticipantNames {
+    /// Changes whenever participants join or leave, see
+    /// [`Timings::participants_changed_timestamp`].
+    pub participants_changed_timestamp: u32,
+    pub participants: Vec<ParticipantName>,
+}
+
+/// Position and progress of one participant.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParticipantTiming {
+    pub slot: usize,
+    /// In metres, with quarter metre precision.
+    pub world_position: [f32; 3],
+    /// Heading, pitch and bank in radians.
+    pub orientation: [f32; 3],
+    pub current_lap_distance: f32,
+    pub race_position: u8,
+    pub is_active: bool,
+    /// 0 based.
+    pub sector: u8,
+    pub highest_flag: Option<FlagColour>,
+    pub flag_reason: u8,
+    pub pit_mode: Option<PitMode>,
+    pub pit_schedule: u8,
+    /// Index into the vehicle names.
+    pub car_index: u16,
+    /// Whether a local or remote human drives the car.
+    pub is_human: bool,
+    pub race_state: Option<RaceState>,
+    pub lap_invalidated: bool,
+    pub current_lap: u8,
+    pub current_time: f32,
+    pub current_sector_time: f32,
+    pub mp_index: u16,
+}
+
+/// Timing of all participants (`sTimingsData`). Times are in seconds.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Timings {
+    pub participants_changed_timestamp: u32,
+    pub event_time_remaining: f32,
+    pub split_time_ahead: f32,
+    pub split_time_behind: f32,
+    pub split_time: f32,
+    /// The first `sNumParticipants` entries.
+    pub participants: Vec<ParticipantTiming>,
+    pub local_participant_index: u16,
+    pub tick_count: u32,
+}
+
+/// Game and weather state (`sGameStateData`).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GameStateData {
+    pub build_version: u16,
+    pub game_state: Option<GameState>,
+    pub session_state: Option<SessionState>,
+    /// In °C.
+    pub ambient_temperature: i8,
+    pub track_temperature: i8,
+    /// 0..1.
+    pub rain_density: f32,
+    pub snow_density: f32,
+    pub wind_speed: i8,
+    pub wind_direction: [i8; 2],
+}
+
+/// Lap records of one participant. Times are in seconds and negative when
+/// not set.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParticipantStats {
+    pub slot: usize,
+    pub fastest_lap_time: f32,
+    pub last_lap_time: f32,
+    pub last_sector_time: f32,
+    pub fastest_sector_times: [f32; 3],
+    pub online_rep: u32,
+    pub mp_index: u16,
+}
+
+/// Lap records of all participants (`sTimeStatsData`).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TimeStats {
+    pub participants_changed_timestamp: u32,
+    pub participants: Vec<ParticipantStats>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VehicleName {
+    /// Matches [`ParticipantTiming::car_index`].
+    pub index: u16,
+    pub class: u32,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClassName {
+    /// Matches [`VehicleName::class`].
+    pub index: u32,
+    pub name: String,
+}
+
+/// One decoded message, possibly reassembled from several packets.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UdpMessage {
+    Telemetry(Box<CarTelemetry>),
+    RaceDefinition(RaceDefinition),
+    Participants(ParticipantNames),
+    Timings(Timings),
+    GameState(GameStateData),
+    TimeStats(TimeStats),
+    /// Entries with an empty name are left out.
+    VehicleNames(Vec<VehicleName>),
+    /// Entries with an empty name are left out.
+    ClassNames(Vec<ClassName>),
+    /// A packet type the decoder does not know.
+    Unknown(PacketHeader),
+}
+
+impl UdpMessage {
+    /// Decodes a single datagram without reassembly; the entries of a
+    /// partial packet carry the slots of its part.
+    pub fn decode(bytes: &[u8]) -> Result<(PacketHeader, UdpMessage)> {
+        let header = PacketHeader::parse(bytes).ok_or_else(|| invalid("packet shorter than its header"))?;
+        let Some(kind) = header.kind() else {
+            return Ok((header, UdpMessage::Unknown(header)));
+        };
+        let expect = |size: usize| {
+            if bytes.len() < size {
+                Err(invalid(&format!("{:?} packet of {} bytes, expected {}", kind, bytes.len(), size)))
+            } else {
+                Ok(Cursor::at(bytes, HEADER_SIZE))
+            }
+        };
+        let part = usize::from(header.partial_packet_index.max(1) - 1);
+
+        let message = match kind {
+            PacketType::CarPhysics => UdpMessage::Telemetry(Box::new(decode_telemetry(expect(TELEMETRY_SIZE)?))),
+            PacketType::RaceDefinition => UdpMessage::RaceDefinition(decode_race_definition(expect(RACE_DEFINITION_SIZE)?)),
+            PacketType::Participants => UdpMessage::Participants(decode_participants(expect(PARTICIPANTS_SIZE)?, part)),
+            PacketType::Timings => UdpMessage::Timings(decode_timings(expect(TIMINGS_SIZE)?)),
+            PacketType::GameState => UdpMessage::GameState(decode_game_state(expect(GAME_STATE_SIZE)?)),
+            PacketType::TimeStats => UdpMessage::TimeStats(decode_time_stats(expect(TIME_STATS_SIZE)?)),
+            PacketType::ParticipantVehicleNames if bytes.len() >= CLASS_NAMES_SIZE => UdpMessage::ClassNames(decode_class_names(expect(CLASS_NAMES_SIZE)?)),
+            PacketType::ParticipantVehicleNames => UdpMessage::VehicleNames(decode_vehicle_names(expect(VEHICLE_NAMES_SIZE)?)),
+            PacketType::WeatherState | PacketType::VehicleNames => UdpMessage::Unknown(header),
+        };
+        Ok((header, message))
+    }
+
+    /// Appends the entries of a later part of the same message.
+    fn merge(&mut self, part: UdpMessage) {
+        match (self, part) {
+            (UdpMessage::Participants(names), UdpMessage::Participants(part)) => names.participants.extend(part.participants),
+            (UdpMessage::VehicleNames(vehicles), UdpMessage::VehicleNames(part)) => vehicles.extend(part),
+            (UdpMessage::ClassNames(classes), UdpMessage::ClassNames(part)) => classes.extend(part),
+            (message, part) => *message = part,
+        }
+    }
+}
+
+/// Collects the parts of messages the game splits over several packets.
+///
+/// Parts are kept per message kind until every index from 1 to
+/// `mPartialPacketNumber` has arrived; a repeated index, a different part
+/// count or a different `mCategoryPacketNumber` starts over, so a lost
+/// packet only drops its own message.
+#[derive(Debug, Default)]
+pub struct PacketAssembler {
+    pending: HashMap<(u8, bool), Pending>,
+}
+
+#[derive(Debug)]
+struct Pending {
+    category: u32,
+    count: u8,
+    parts: Vec<Option<UdpMessage>>,
+}
+
+impl PacketAssembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decodes a datagram, returning the message once all of its parts
+    /// have arrived.
+    pub fn push(&mut self, bytes: &[u8]) -> Result<Option<UdpMessage>> {
+        let (header, message) = UdpMessage::decode(bytes)?;
+        let count = header.partial_packet_number;
+        let index = header.partial_packet_index;
+        if count <= 1 || index == 0 || index > count || matches!(message, UdpMessage::Unknown(_)) {
+            return Ok(Some(message));
+        }
+
+        let key = (header.packet_type, matches!(message, UdpMessage::ClassNames(_)));
+        let category = header.category_packet_number;
+        let start = || Pending {
+            category,
+            count,
+            parts: Vec::new(),
+        };
+        let pending = self.pending.entry(key).or_insert_with(start);
+        let slot = usize::from(index - 1);
+        if pending.category != category || pending.count != count || pending.parts.get(slot).is_some_and(Option::is_some) {
+            *pending = start();
+        }
+        pending.parts.resize(usize::from(count), None);
+        pending.parts[slot] = Some(message);
+        if pending.parts.iter().any(Option::is_none) {
+            return Ok(None);
+        }
+
+        let mut parts = self.pending.remove(&key).into_iter().flat_map(|pending| pending.parts).flatten();
+        let mut message = parts.next().expect("all parts present");
+        for part in parts {
+            message.merge(part);
+        }
+        Ok(Some(message))
+    }
+}
+
+/// A participant with everything the packets tell about it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionParticipant {
+    pub name: String,
+    pub nationality: u32,
+    pub vehicle: String,
+    pub class: String,
+    pub timing: ParticipantTiming,
+    pub stats: Option<ParticipantStats>,
+}
+
+/// The latest message of every kind, merged into one view of the session.
+#[derive(Debug, Clone, Default)]
+pub struct UdpSession {
+    pub telemetry: Option<CarTelemetry>,
+    pub race: Option<RaceDefinition>,
+    pub game_state: Option<GameStateData>,
+    pub names: Option<ParticipantNames>,
+    pub timings: Option<Timings>,
+    pub stats: Option<TimeStats>,
+    pub vehicles: HashMap<u16, VehicleName>,
+    pub classes: HashMap<u32, String>,
+}
+
+impl UdpSession {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn apply(&mut self, message: &UdpMessage) {
+        match message {
+            UdpMessage::Telemetry(telemetry) => self.telemetry = Some(telemetry.as_ref().clone()),
+            UdpMessage::RaceDefinition(race) => self.race = Some(race.clone()),
+            UdpMessage::Participants(names) => self.names = Some(names.clone()),
+            UdpMessage::Timings(timings) => self.timings = Some(timings.clone()),
+            UdpMessage::GameState(state) => self.game_state = Some(state.clone()),
+            UdpMessage::TimeStats(stats) => self.stats = Some(stats.clone()),
+            UdpMessage::VehicleNames(vehicles) => {
+                self.vehicles.extend(vehicles.iter().map(|vehicle| (vehicle.index, vehicle.clone())));
+            }
+            UdpMessage::ClassNames(classes) => {
+                self.classes.extend(classes.iter().map(|class| (class.index, class.name.clone())));
+            }
+            UdpMessage::Unknown(_) => {}
+        }
+    }
+
+    /// The participants of the latest timings, in slot order.
+    ///
+    /// Names and stats are only used while their participant timestamp
+    /// matches the timings, after participants joined or left they stay
+    /// empty until the game resends them.
+    pub fn participants(&self) -> Vec<SessionParticipant> {
+        let Some(timings) = &self.timings else {
+            return Vec::new();
+        };
+        let current = timings.participants_changed_timestamp;
+        let names = self.names.as_ref().filter(|names| names.participants_changed_timestamp == current);
+        let stats = self.stats.as_ref().filter(|stats| stats.participants_changed_timestamp == current);
+
+        timings
+            .participants
+            .iter()
+            .map(|timing| {
+                let name = names.and_then(|names| names.participants.iter().find(|name| name.slot == timing.slot));
+                let vehicle = self.vehicles.get(&timing.car_index);
+                SessionParticipant {
+                    name: name.map(|name| name.name.clone()).unwrap_or_default(),
+                    nationality: name.map_or(0, |name| name.nationality),
+                    vehicle: vehicle.map(|vehicle| vehicle.name.clone()).unwrap_or_default(),
+                    class: vehicle.and_then(|vehicle| self.classes.get(&vehicle.class)).cloned().unwrap_or_default(),
+                    timing: timing.clone(),
+                    stats: stats
+                        .and_then(|stats| stats.participants.iter().find(|entry| entry.slot == timing.slot))
+                        .cloned(),
+                }
+            })
+            .collect()
+    }
+
+    /// The participant on this machine's screen.
+    pub fn viewed_participant(&self) -> Option<SessionParticipant> {
+        let index = usize::try_from(self.telemetry.as_ref()?.viewed_participant_index).ok()?;
+        self.participants().into_iter().find(|participant| participant.timing.slot == index)
+    }
+}
+
+/// Receives the pCars2 UDP v2 protocol Automobilista 2 broadcasts, so a
+/// session can be read from another machine.
+///
+/// Enable it in the game under Options > System > UDP Frequency, with the
+/// protocol version set to Project CARS 2.
+///
+/// ```no_run
+/// use race_sdk::am2::{Automobilista2Udp, UdpMessage};
+///
+/// let mut udp = Automobilista2Udp::bind(race_sdk::am2::UDP_PORT).unwrap();
+/// loop {
+///     if let UdpMessage::Timings(_) = udp.recv().unwrap() {
+///         for participant in udp.session().participants() {
+///             println!("P{} {} ({})", participant.timing.race_position, participant.name, participant.vehicle);
+///         }
+///     }
+/// }
+/// ```
+pub struct Automobilista2Udp {
+    socket: UdpSocket,
+    assembler: PacketAssembler,
+    session: UdpSession,
+    buffer: Vec<u8>,
+    malformed: u32,
+}
+
+impl Automobilista2Udp {
+    /// Listens on all interfaces.
+    pub fn bind(port: u16) -> Result<Self> {
+        Self::bind_addr(("0.0.0.0", port))
+    }
+
+    pub fn bind_addr(addr: impl ToSocketAddrs) -> Result<Self> {
+        Ok(Self::from_socket(UdpSocket::bind(addr)?))
+    }
+
+    pub fn from_socket(socket: UdpSocket) -> Self {
+        Self {
+            socket,
+            assembler: PacketAssembler::new(),
+            session: UdpSession::new(),
+            buffer: vec![0; UDP_PACKET_SIZE_MAX],
+            malformed: 0,
+        }
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.socket.local_addr()?)
+    }
+
+    /// Limits how long [`recv`](Self::recv) waits for a packet, `None`
+    /// waits forever.
+    pub fn set_timeout(&self, timeout: Option<Duration>) -> Result<()> {
+        Ok(self.socket.set_read_timeout(timeout)?)
+    }
+
+    /// Datagrams skipped as malformed so far.
+    pub fn malformed(&self) -> u32 {
+        self.malformed
+    }
+
+    /// Waits for the next complete message and merges it into the session.
+    ///
+    /// Malformed packets are skipped.
+    pub fn recv(&mut self) -> Result<UdpMessage> {
+        loop {
+            let len = self.socket.recv(&mut self.buffer)?;
+            if let Some(message) = self.push(len) {
+                return Ok(message);
+            }
+        }
+    }
+
+    /// Reads all packets already received without waiting and returns the
+    /// completed messages.
+    ///
+    /// Malformed packets are skipped.
+    pub fn poll(&mut self) -> Result<Vec<UdpMessage>> {
+        self.socket.set_nonblocking(true)?;
+        let mut messages = Vec::new();
+        let result = loop {
+            match self.socket.recv(&mut self.buffer) {
+                Ok(len) => messages.extend(self.push(len)),
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => break Ok(messages),
+                Err(err) => break Err(err.into()),
+            }
+        };
+        self.socket.set_nonblocking(false)?;
+        result
+    }
+
+    pub fn session(&self) -> &UdpSession {
+        &self.session
+    }
+
+    fn push(&mut self, len: usize) -> Option<UdpMessage> {
+        match self.assembler.push(&self.buffer[..len]) {
+            Ok(message) => {
+                let message = message?;
+                self.session.apply(&message);
+                Some(message)
+            }
+            Err(_) => {
+                self.malformed = self.malformed.wrapping_add(1);
+                None
+            }
+        }
+    }
+}
+
+fn decode_telemetry(mut c: Cursor) -> CarTelemetry {
+    let viewed_participant_index = c.i8();
+    let unfiltered_throttle = c.unit();
+    let unfiltered_brake = c.unit();
+    let unfiltered_steering = c.signed_unit();
+    let unfiltered_clutch = c.unit();
+    let car_flags = c.u8();
+    let oil_temp_celsius = c.i16();
+    let oil_pressure_kpa = c.u16();
+    let water_temp_celsius = c.i16();
+    let water_pressure_kpa = c.u16();
+    let fuel_pressure_kpa = c.u16();
+    let fuel_capacity = c.u8();
+    let brake = c.unit();
+    let throttle = c.unit();
+    let clutch = c.unit();
+    let fuel_level = c.f32();
+    let speed = c.f32();
+    let rpm = c.u16();
+    let max_rpm = c.u16();
+    let steering = c.signed_unit();
+    let gears = c.u8();
+    CarTelemetry {
+        viewed_participant_index,
+        unfiltered_throttle,
+        unfiltered_brake,
+        unfiltered_steering,
+        unfiltered_clutch,
+        car_flags,
+        oil_temp_celsius,
+        oil_pressure_kpa,
+        water_temp_celsius,
+        water_pressure_kpa,
+        fuel_pressure_kpa,
+        fuel_capacity,
+        brake,
+        throttle,
+        clutch,
+        fuel_level,
+        speed,
+        rpm,
+        max_rpm,
+        steering,
+        gear: if gears & 0x0f == 0x0f { -1 } else { (gears & 0x0f) as i8 },
+        num_gears: gears >> 4,
+        boost_amount: c.u8(),
+        crash_state: c.u8(),
+        odometer_km: c.f32(),
+        orientation: c.f32s(),
+        local_velocity: c.f32s(),
+        world_velocity: c.f32s(),
+        angular_velocity: c.f32s(),
+        local_acceleration: c.f32s(),
+        world_acceleration: c.f32s(),
+        extents_centre: c.f32s(),
+        tyre_flags: c.bytes(),
+        terrain: c.bytes(),
+        tyre_y: c.f32s(),
+        tyre_rps: c.f32s(),
+        tyre_temp: c.bytes(),
+        tyre_height_above_ground: c.f32s(),
+        tyre_wear: c.units(),
+        brake_damage: c.units(),
+        suspension_damage: c.units(),
+        brake_temp_celsius: [c.i16(), c.i16(), c.i16(), c.i16()],
+        tyre_tread_temp: c.u16s(),
+        tyre_layer_temp: c.u16s(),
+        tyre_carcass_temp: c.u16s(),
+        tyre_rim_temp: c.u16s(),
+        tyre_internal_air_temp: c.u16s(),
+        tyre_temp_left: c.u16s(),
+        tyre_temp_center: c.u16s(),
+        tyre_temp_right: c.u16s(),
+        wheel_local_position_y: c.f32s(),
+        ride_height: c.f32s(),
+        suspension_travel: c.f32s(),
+        suspension_velocity: c.f32s(),
+        suspension_ride_height: c.u16s(),
+        air_pressure: c.u16s(),
+        engine_speed: c.f32(),
+        engine_torque: c.f32(),
+        wings: c.bytes(),
+        hand_brake: c.unit(),
+        aero_damage: c.unit(),
+        engine_damage: c.unit(),
+        joy_pad: c.u32(),
+        d_pad: c.u8(),
+        tyre_compound: [0; 4].map(|_| c.text(TYRE_NAME_LENGTH_MAX)),
+        turbo_boost_pressure: c.f32(),
+        full_position: c.f32s(),
+        brake_bias: c.unit(),
+        tick_count: c.u32(),
+    }
+}
+
+fn decode_race_definition(mut c: Cursor) -> RaceDefinition {
+    let world_fastest_lap_time = c.f32();
+    let personal_fastest_lap_time = c.f32();
+    let personal_fastest_sector_times = c.f32s();
+    let world_fastest_sector_times = c.f32s();
+    let track_length = c.f32();
+    let track_location = c.text(TRACK_NAME_LENGTH_MAX);
+    let track_variation = c.text(TRACK_NAME_LENGTH_MAX);
+    let translated_track_location = c.text(TRACK_NAME_LENGTH_MAX);
+    let translated_track_variation = c.text(TRACK_NAME_LENGTH_MAX);
+    let laps_time = c.u16();
+    RaceDefinition {
+        world_fastest_lap_time,
+        personal_fastest_lap_time,
+        personal_fastest_sector_times,
+        world_fastest_sector_times,
+        track_length,
+        track_location,
+        track_variation,
+        translated_track_location,
+        translated_track_variation,
+        timed_session: laps_time & 0x8000 != 0,
+        laps_time_in_event: laps_time & 0x7fff,
+        enforced_pit_stop_lap: c.i8(),
+    }
+}
+
+fn decode_participants(mut c: Cursor, part: usize) -> ParticipantNames {
+    let participants_changed_timestamp = c.u32();
+    let names: Vec<String> = (0..UDP_PARTICIPANTS_PER_PACKET).map(|_| c.text(PARTICIPANT_NAME_LENGTH_MAX)).collect();
+    let nationalities: Vec<u32> = (0..UDP_PARTICIPANTS_PER_PACKET).map(|_| c.u32()).collect();
+    let participants = names
+        .into_iter()
+        .zip(nationalities)
+        .enumerate()
+        .map(|(i, (name, nationality))| ParticipantName {
+            slot: part * UDP_PARTICIPANTS_PER_PACKET + i,
+            name,
+            nationality,
+            mp_index: c.u16(),
+        })
+        .collect();
+    ParticipantNames {
+        participants_changed_timestamp,
+        participants,
+    }
+}
+
+fn decode_timings(mut c: Cursor) -> Timings {
+    let count = usize::try_from(c.i8()).unwrap_or(0).min(UDP_PARTICIPANTS_MAX);
+    let participants_changed_timestamp = c.u32();
+    let event_time_remaining = c.f32();
+    let split_time_ahead = c.f32();
+    let split_time_behind = c.f32();
+    let split_time = c.f32();
+    let mut participants: Vec<ParticipantTiming> = (0..UDP_PARTICIPANTS_MAX).map(|slot| decode_participant_timing(&mut c, slot)).collect();
+    participants.truncate(count);
+    Timings {
+        participants_changed_timestamp,
+        event_time_remaining,
+        split_time_ahead,
+        split_time_behind,
+        split_time,
+        participants,
+        local_participant_index: c.u16(),
+        tick_count: c.u32(),
+    }
+}
+
+fn decode_participant_timing(c: &mut Cursor, slot: usize) -> ParticipantTiming {
+    let position = [c.i16(), c.i16(), c.i16()];
+    let orientation = [c.i16(), c.i16(), c.i16()];
+    let current_lap_distance = f32::from(c.u16());
+    let race_position = c.u8();
+    // The upper bits of the sector add two bits of precision to x and z.
+    let sector = c.u8();
+    let flag = c.u8();
+    let pit = c.u8();
+    let car_index = c.u16();
+    let race_state = c.u8();
+    ParticipantTiming {
+        slot,
+        world_position: [
+            f32::from(position[0]) + f32::from((sector >> 3) & 3) / 4.0,
+            f32::from(position[1]),
+            f32::from(position[2]) + f32::from((sector >> 5) & 3) / 4.0,
+        ],
+        orientation: [
+            f32::from(orientation[0]) / 32768.0 * PI,
+            f32::from(orientation[1]) / 32768.0 * PI / 2.0,
+            f32::from(orientation[2]) / 32768.0 * PI,
+        ],
+        current_lap_distance,
+        race_position: race_position & 0x7f,
+        is_active: race_position & 0x80 != 0,
+        sector: sector & 7,
+        highest_flag: FlagColour::from_raw(u32::from(flag & 0x0f)),
+        flag_reason: flag >> 4,
+        pit_mode: PitMode::from_raw(u32::from(pit & 7)),
+        pit_schedule: pit >> 3,
+        car_index: car_index & 0x7fff,
+        is_human: car_index & 0x8000 != 0,
+        race_state: RaceState::from_raw(u32::from(race_state & 7)),
+        lap_invalidated: race_state & 0x08 != 0,
+        current_lap: c.u8(),
+        current_time: c.f32(),
+        current_sector_time: c.f32(),
+        mp_index: c.u16(),
+    }
+}
+
+fn decode_game_state(mut c: Cursor) -> GameStateData {
+    let build_version = c.u16();
+    let state = c.u8();
+    GameStateData {
+        build_version,
+        game_state: GameState::from_raw(u32::from(state & 7)),
+        session_state: SessionState::from_raw(u32::from((state >> 3) & 7)),
+        ambient_temperature: c.i8(),
+        track_temperature: c.i8(),
+        rain_density: c.unit(),
+        snow_density: c.unit(),
+        wind_speed: c.i8(),
+        wind_direction: [c.i8(), c.i8()],
+    }
+}
+
+fn decode_time_stats(mut c: Cursor) -> TimeStats {
+    let participants_changed_timestamp = c.u32();
+    let participants = (0..UDP_PARTICIPANTS_MAX)
+        .map(|slot| {
+            let stats = ParticipantStats {
+                slot,
+                fastest_lap_time: c.f32(),
+                last_lap_time: c.f32(),
+                last_sector_time: c.f32(),
+                fastest_sector_times: c.f32s(),
+                online_rep: c.u32(),
+                mp_index: c.u16(),
+            };
+            // Entries are padded to 32 bytes.
+            c.skip(2);
+            stats
+        })
+        .collect();
+    TimeStats {
+        participants_changed_timestamp,
+        participants,
+    }
+}
+
+fn decode_vehicle_names(mut c: Cursor) -> Vec<VehicleName> {
+    (0..UDP_VEHICLES_PER_PACKET)
+        .map(|_| {
+            let vehicle = VehicleName {
+                index: c.u16(),
+                class: c.u32(),
+                name: c.text(VEHICLE_NAME_LENGTH_MAX),
+            };
+            // Entries are padded to 72 bytes.
+            c.skip(2);
+            vehicle
+        })
+        .filter(|vehicle| !vehicle.name.is_empty())
+        .collect()
+}
+
+fn decode_class_names(mut c: Cursor) -> Vec<ClassName> {
+    (0..UDP_CLASSES_PER_PACKET)
+        .map(|_| ClassName {
+            index: c.u32(),
+            name: c.text(CLASS_NAME_LENGTH_MAX),
+        })
+        .filter(|class| !class.name.is_empty())
+        .collect()
+}
+
+fn invalid(msg: &str) -> Automobilista2Error {
+    Automobilista2Error::InvalidData(msg.to_string())
+}
+
+/// Reads the little-endian, byte-packed fields of a packet. The packet size
+/// is checked before decoding, reads past the end return zeros.
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self::at(bytes, 0)
+    }
+
+    fn at(bytes: &'a [u8], pos: usize) -> Self {
+        Self { bytes, pos }
+    }
+
+    fn bytes<const N: usize>(&mut self) -> [u8; N] {
+        let mut out = [0; N];
+        if let Some(bytes) = self.bytes.get(self.pos..self.pos + N) {
+            out.copy_from_slice(bytes);
+        }
+        self.pos += N;
+        out
+    }
+
+    fn skip(&mut self, len: usize) {
+        self.pos += len;
+    }
+
+    fn u8(&mut self) -> u8 {
+        self.bytes::<1>()[0]
+    }
+
+    fn i8(&mut self) -> i8 {
+        self.u8() as i8
+    }
+
+    fn u16(&mut self) -> u16 {
+        u16::from_le_bytes(self.bytes())
+    }
+
+    fn i16(&mut self) -> i16 {
+        i16::from_le_bytes(self.bytes())
+    }
+
+    fn u32(&mut self) -> u32 {
+        u32::from_le_bytes(self.bytes())
+    }
+
+    fn f32(&mut self) -> f32 {
+        f32::from_le_bytes(self.bytes())
+    }
+
+    /// A byte scaled to 0..1.
+    fn unit(&mut self) -> f32 {
+        f32::from(self.u8()) / 255.0
+    }
+
+    /// A signed byte scaled to -1..1.
+    fn signed_unit(&mut self) -> f32 {
+        f32::from(self.i8()) / 127.0
+    }
+
+    fn f32s<const N: usize>(&mut self) -> [f32; N] {
+        [0; N].map(|_| self.f32())
+    }
+
+    fn u16s<const N: usize>(&mut self) -> [u16; N] {
+        [0; N].map(|_| self.u16())
+    }
+
+    fn units<const N: usize>(&mut self) -> [f32; N] {
+        [0; N].map(|_| self.unit())
+    }
+
+    fn text(&mut self, len: usize) -> String {
+        let start = self.pos.min(self.bytes.len());
+        let end = (self.pos + len).min(self.bytes.len());
+        self.pos += len;
+        decode_name(&self.bytes[start..end])
+    }
+}
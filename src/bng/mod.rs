@@ -0,0 +1,150 @@
+pub mod types;
+pub mod udp;
+pub use types::*;
+pub use udp::*;
+
+use crate::telemetry::Sampler;
+use std::cell::RefCell;
+use std::fmt;
+use std::io;
+use std::net::{Ipv4Addr, SocketAddr, UdpSocket};
+
+#[derive(Debug)]
+pub enum BeamNgError {
+    ConnectionFailed(String),
+    InvalidData(String),
+    Io(io::Error),
+}
+
+impl fmt::Display for BeamNgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BeamNgError::ConnectionFailed(msg) => write!(f, "Connection failed: {}", msg),
+            BeamNgError::InvalidData(msg) => write!(f, "Invalid data format: {}", msg),
+            BeamNgError::Io(err) => write!(f, "I/O error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for BeamNgError {}
+
+impl From<io::Error> for BeamNgError {
+    fn from(err: io::Error) -> Self {
+        BeamNgError::Io(err)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, BeamNgError>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConnectionStatus {
+    Disconnected,
+    Connected,
+}
+
+/// BeamNG.drive, read through the OutGauge packets it sends over UDP.
+///
+/// The game only sends, so connecting binds the socket the packets
+/// arrive on and the latest packet is what the getters return.
+///
+/// ```no_run
+/// use race_sdk::bng::{BeamNg, DashLights};
+///
+/// let mut beamng = BeamNg::new();
+/// beamng.connect().unwrap();
+///
+/// for sample in beamng.sampler() {
+///     let packet = sample.frame;
+///     println!("{:.0} km/h {:.0} rpm gear {}, fuel {:.0}%", packet.speed_kmh(), packet.rpm, packet.gear(), packet.fuel * 100.0);
+///     if packet.is_lit(DashLights::ABS) {
+///         println!("ABS active");
+///     }
+/// }
+/// ```
+pub struct BeamNg {
+    address: SocketAddr,
+    id: Option<i32>,
+    receiver: RefCell<Option<OutGaugeReceiver>>,
+    latest: RefCell<Option<OutGauge>>,
+}
+
+impl BeamNg {
+    /// Listens on all interfaces on [`OUTGAUGE_PORT`] once connected.
+    pub fn new() -> Self {
+        Self {
+            address: SocketAddr::from((Ipv4Addr::UNSPECIFIED, OUTGAUGE_PORT)),
+            id: None,
+            receiver: RefCell::new(None),
+            latest: RefCell::new(None),
+        }
+    }
+
+    /// Creates a facade receiving on an already bound socket.
+    pub fn from_socket(socket: UdpSocket) -> Self {
+        let beamng = Self::new();
+        *beamng.receiver.borrow_mut() = Some(OutGaugeReceiver::from_socket(socket));
+        beamng
+    }
+
+    /// The address to listen on, matching the IP and port set in the game.
+    pub fn with_address(mut self, address: SocketAddr) -> Self {
+        self.address = address;
+        self
+    }
+
+    /// Keeps only the packets sent with `id`, see [`OutGaugeReceiver::with_id`].
+    pub fn with_id(mut self, id: i32) -> Self {
+        self.id = Some(id);
+        let receiver = self.receiver.get_mut().take();
+        *self.receiver.get_mut() = receiver.map(|receiver| receiver.with_id(id));
+        self
+    }
+
+    pub fn connect(&mut self) -> Result<()> {
+        if self.is_connected() {
+            return Ok(());
+        }
+        let receiver = OutGaugeReceiver::bind_addr(self.address).map_err(|err| BeamNgError::ConnectionFailed(format!("{}: {}", self.address, err)))?;
+        *self.receiver.get_mut() = Some(match self.id {
+            Some(id) => receiver.with_id(id),
+            None => receiver,
+        });
+        Ok(())
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.receiver.borrow().is_some()
+    }
+
+    pub fn status(&self) -> ConnectionStatus {
+        if self.is_connected() {
+            ConnectionStatus::Connected
+        } else {
+            ConnectionStatus::Disconnected
+        }
+    }
+
+    /// The latest packet, after reading those received since the last
+    /// call without waiting. `None` until the first packet arrives.
+    pub fn get_outgauge(&self) -> Option<OutGauge> {
+        if let Some(receiver) = self.receiver.borrow_mut().as_mut()
+            && let Ok(packets) = receiver.poll()
+            && let Some(packet) = packets.into_iter().last()
+        {
+            *self.latest.borrow_mut() = Some(packet);
+        }
+        self.latest.borrow().clone()
+    }
+
+    /// Returns a sampler that emits every new packet, told apart by
+    /// [`OutGauge::sequence`].
+    pub fn sampler(&self) -> Sampler<OutGauge, impl FnMut() -> Option<OutGauge> + '_> {
+        Sampler::new(|| self.get_outgauge())
+    }
+}
+
+impl Default for BeamNg {
+    fn default() -> Self {
+        Self::new()
+    }
+}
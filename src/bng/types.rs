@@ -0,0 +1,279 @@
+use crate::bng::{BeamNgError, Result};
+use crate::telemetry::Packet;
+use crate::utils::flags::bit_flags;
+
+/// Size of a packet without the optional id.
+pub const OUTGAUGE_SIZE: usize = 92;
+/// Size of a packet carrying the id set in the game's OutGauge options.
+pub const OUTGAUGE_SIZE_WITH_ID: usize = 96;
+
+bit_flags! {
+    /// The `OG_x` flags of `OutGaugePack::Flags`.
+    pub struct OutGaugeFlags(u16) {
+        /// Shift key held.
+        const SHIFT = 1 << 0;
+        /// Ctrl key held.
+        const CTRL = 1 << 1;
+        /// The car has a turbo, `turbo` is meaningful.
+        const TURBO = 1 << 13;
+        /// The user prefers km/h over mph.
+        const KM = 1 << 14;
+        /// The user prefers bar over psi.
+        const BAR = 1 << 15;
+    }
+}
+
+bit_flags! {
+    /// The `DL_x` dashboard lights, as the car provides them in
+    /// `dash_lights` and as they are lit in `show_lights`.
+    pub struct DashLights(u32) {
+        const SHIFT = 1 << 0;
+        const FULL_BEAM = 1 << 1;
+        const HANDBRAKE = 1 << 2;
+        const PIT_SPEED = 1 << 3;
+        const TC = 1 << 4;
+        const SIGNAL_L = 1 << 5;
+        const SIGNAL_R = 1 << 6;
+        /// Shared light for both turn signals.
+        const SIGNAL_ANY = 1 << 7;
+        const OIL_WARN = 1 << 8;
+        const BATTERY = 1 << 9;
+        const ABS = 1 << 10;
+        const SPARE = 1 << 11;
+    }
+}
+
+/// One OutGauge packet (`OutGaugePack`), the LFS dashboard protocol
+/// BeamNG.drive sends for the vehicle the player drives.
+///
+/// Speeds are in m/s, temperatures in °C and pressures in bar, whatever
+/// units the `KM` and `BAR` flags prefer for display.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct OutGauge {
+    /// Time in ms, left at 0 by BeamNG.
+    pub time: u32,
+    /// Short car name, `beam` for BeamNG.
+    pub car: String,
+    pub flags: OutGaugeFlags,
+    /// 0 is reverse, 1 neutral, 2 first gear, see [`OutGauge::gear`].
+    pub raw_gear: u8,
+    /// Unique id of the player in LFS, 0 in BeamNG.
+    pub plid: u8,
+    pub speed: f32,
+    pub rpm: f32,
+    /// Boost in bar.
+    pub turbo: f32,
+    pub eng_temp: f32,
+    /// Fuel left, 0.0 to 1.0.
+    pub fuel: f32,
+    pub oil_pressure: f32,
+    pub oil_temp: f32,
+    /// The lights the car has.
+    pub dash_lights: DashLights,
+    /// The lights currently lit.
+    pub show_lights: DashLights,
+    /// Pedals, 0.0 to 1.0.
+    pub throttle: f32,
+    pub brake: f32,
+    pub clutch: f32,
+    /// The usual display texts, fuel and settings.
+    pub display1: String,
+    pub display2: String,
+    /// The id set in the game's OutGauge options, `None` when the packet
+    /// was sent without one.
+    pub id: Option<i32>,
+    /// Packets the receiver decoded before this one, not part of the
+    /// protocol. Tells packets apart as BeamNG leaves `time` at 0.
+    pub sequence: u32,
+}
+
+impl OutGauge {
+    /// Decodes a packet of [`OUTGAUGE_SIZE`] or [`OUTGAUGE_SIZE_WITH_ID`]
+    /// bytes.
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != OUTGAUGE_SIZE && bytes.len() != OUTGAUGE_SIZE_WITH_ID {
+            return Err(BeamNgError::InvalidData(format!(
+                "OutGauge packet of {} bytes, expected {} or {}",
+                bytes.len(),
+                OUTGAUGE_SIZE,
+                OUTGAUGE_SIZE_WITH_ID
+            )));
+        }
+        let u32_at = |offset: usize| u32::from_le_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]]);
+        let f32_at = |offset: usize| f32::from_bits(u32_at(offset));
+        Ok(Self {
+            time: u32_at(0),
+            car: decode_text(&bytes[4..8]),
+            flags: OutGaugeFlags(u16::from_le_bytes([bytes[8], bytes[9]])),
+            raw_gear: bytes[10],
+            plid: bytes[11],
+            speed: f32_at(12),
+            rpm: f32_at(16),
+            turbo: f32_at(20),
+            eng_temp: f32_at(24),
+            fuel: f32_at(28),
+            oil_pressure: f32_at(32),
+            oil_temp: f32_at(36),
+            dash_lights: DashLights(u32_at(40)),
+            show_lights: DashLights(u32_at(44)),
+            throttle: f32_at(48),
+            brake: f32_at(52),
+            clutch: f32_at(56),
+            display1: decode_text(&bytes[60..76]),
+            display2: decode_text(&bytes[76..92]),
+            id: (bytes.len() == OUTGAUGE_SIZE_WITH_ID).then(|| u32_at(92) as i32),
+            sequence: 0,
+        })
+    }
+
+    /// Encodes the packet, with the id when it has one.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(OUTGAUGE_SIZE_WITH_ID);
+        out.extend_from_slice(&self.time.to_le_bytes());
+        encode_text(&mut out, &self.car, 4);
+        out.extend_from_slice(&self.flags.bits().to_le_bytes());
+        out.push(self.raw_gear);
+        out.push(self.plid);
+        for value in [self.speed, self.rpm, self.turbo, self.eng_temp, self.fuel, self.oil_pressure, self.oil_temp] {
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+        out.extend_from_slice(&self.dash_lights.bits().to_le_bytes());
+        out.extend_from_slice(&self.show_lights.bits().to_le_bytes());
+        for value in [self.throttle, self.brake, self.clutch] {
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+        encode_text(&mut out, &self.display1, 16);
+        encode_text(&mut out, &self.display2, 16);
+        if let Some(id) = self.id {
+            out.extend_from_slice(&id.to_le_bytes());
+        }
+        out
+    }
+
+    /// -1 for reverse, 0 for neutral, then the forward gears from 1.
+    pub fn gear(&self) -> i32 {
+        self.raw_gear as i32 - 1
+    }
+
+    pub fn speed_kmh(&self) -> f32 {
+        self.speed * 3.6
+    }
+
+    /// Whether the car has `light` and it is lit.
+    pub fn is_lit(&self, light: DashLights) -> bool {
+        self.dash_lights.contains(light) && self.show_lights.contains(light)
+    }
+}
+
+impl Packet for OutGauge {
+    fn packet_id(&self) -> i32 {
+        self.sequence as i32
+    }
+}
+
+/// Text up to the first NUL, as Latin-1.
+fn decode_text(bytes: &[u8]) -> String {
+    bytes.iter().take_while(|&&byte| byte != 0).map(|&byte| byte as char).collect()
+}
+
+/// Latin-1 text cut or padded with NULs to `len`.
+fn encode_text(out: &mut Vec<u8>, text: &str, len: usize) {
+    let start = out.len();
+    out.extend(text.chars().take(len).map(|c| u8::try_from(c).unwrap_or(b'?')));
+    out.resize(start + len, 0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packet() -> OutGauge {
+        OutGauge {
+            time: 1234,
+            car: "beam".into(),
+            flags: OutGaugeFlags::TURBO | OutGaugeFlags::KM,
+            raw_gear: 3,
+            plid: 7,
+            speed: 27.5,
+            rpm: 6200.0,
+            turbo: 0.8,
+            eng_temp: 92.0,
+            fuel: 0.45,
+            oil_pressure: 4.1,
+            oil_temp: 105.0,
+            dash_lights: DashLights::ABS | DashLights::TC | DashLights::SHIFT,
+            show_lights: DashLights::ABS,
+            throttle: 1.0,
+            brake: 0.25,
+            clutch: 0.0,
+            display1: "Fuel 45%".into(),
+            display2: "Brake bias 56%".into(),
+            id: None,
+            sequence: 0,
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let packet = packet();
+        let bytes = packet.encode();
+        assert_eq!(bytes.len(), OUTGAUGE_SIZE);
+        assert_eq!(OutGauge::decode(&bytes).unwrap(), packet);
+
+        let packet = OutGauge { id: Some(-5), ..packet };
+        let bytes = packet.encode();
+        assert_eq!(bytes.len(), OUTGAUGE_SIZE_WITH_ID);
+        assert_eq!(OutGauge::decode(&bytes).unwrap(), packet);
+    }
+
+    #[test]
+    fn layout() {
+        let mut bytes = vec![0u8; OUTGAUGE_SIZE_WITH_ID];
+        bytes[4..8].copy_from_slice(b"beam");
+        bytes[8..10].copy_from_slice(&(1u16 << 13).to_le_bytes());
+        bytes[10] = 0;
+        bytes[12..16].copy_from_slice(&10.0f32.to_le_bytes());
+        bytes[40..44].copy_from_slice(&(1u32 << 10).to_le_bytes());
+        bytes[44..48].copy_from_slice(&(1u32 << 10).to_le_bytes());
+        bytes[52..56].copy_from_slice(&0.5f32.to_le_bytes());
+        bytes[60..63].copy_from_slice(b"abc");
+        bytes[92..96].copy_from_slice(&42i32.to_le_bytes());
+
+        let packet = OutGauge::decode(&bytes).unwrap();
+        assert_eq!(packet.car, "beam");
+        assert_eq!(packet.flags, OutGaugeFlags::TURBO);
+        assert_eq!(packet.gear(), -1);
+        assert_eq!(packet.speed_kmh(), 36.0);
+        assert!(packet.is_lit(DashLights::ABS));
+        assert!(!packet.is_lit(DashLights::TC));
+        assert_eq!(packet.brake, 0.5);
+        assert_eq!(packet.display1, "abc");
+        assert_eq!(packet.display2, "");
+        assert_eq!(packet.id, Some(42));
+        assert_eq!(packet.encode(), bytes);
+    }
+
+    #[test]
+    fn text_is_cut_to_its_field() {
+        let packet = OutGauge {
+            car: "beamng".into(),
+            display1: "é€".into(),
+            ..packet()
+        };
+        let decoded = OutGauge::decode(&packet.encode()).unwrap();
+        assert_eq!(decoded.car, "beam");
+        // Latin-1 passes through, anything else becomes '?'.
+        assert_eq!(decoded.display1, "é?");
+    }
+
+    #[test]
+    fn wrong_sizes() {
+        let bytes = OutGauge { id: Some(1), ..packet() }.encode();
+        for len in [0, 4, OUTGAUGE_SIZE - 1, OUTGAUGE_SIZE + 1, OUTGAUGE_SIZE_WITH_ID - 1] {
+            assert!(matches!(OutGauge::decode(&bytes[..len]), Err(BeamNgError::InvalidData(_))), "{} bytes", len);
+        }
+        let mut long = bytes.clone();
+        long.push(0);
+        assert!(OutGauge::decode(&long).is_err());
+    }
+}
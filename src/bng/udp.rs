@@ -0,0 +1,125 @@
+use crate::bng::{OUTGAUGE_SIZE_WITH_ID, OutGauge, Result};
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::time::Duration;
+
+/// Port BeamNG.drive sends OutGauge to by default.
+pub const OUTGAUGE_PORT: u16 = 4444;
+
+/// Receives the OutGauge packets BeamNG.drive sends, locally or from
+/// another machine.
+///
+/// Enable it in the game under Options > Other > OutGauge support, with
+/// the IP and port the receiver listens on.
+///
+/// ```no_run
+/// use race_sdk::bng::{DashLights, OutGaugeReceiver};
+///
+/// let mut receiver = OutGaugeReceiver::bind(race_sdk::bng::OUTGAUGE_PORT).unwrap();
+/// loop {
+///     let packet = receiver.recv().unwrap();
+///     println!("{:.0} km/h {:.0} rpm gear {}", packet.speed_kmh(), packet.rpm, packet.gear());
+///     if packet.is_lit(DashLights::HANDBRAKE) {
+///         println!("handbrake on");
+///     }
+/// }
+/// ```
+pub struct OutGaugeReceiver {
+    socket: UdpSocket,
+    /// Only packets with this id are kept, when set.
+    id: Option<i32>,
+    received: u32,
+    malformed: u32,
+    buffer: [u8; OUTGAUGE_SIZE_WITH_ID + 1],
+}
+
+impl OutGaugeReceiver {
+    /// Listens on all interfaces.
+    pub fn bind(port: u16) -> Result<Self> {
+        Self::bind_addr(("0.0.0.0", port))
+    }
+
+    pub fn bind_addr(addr: impl ToSocketAddrs) -> Result<Self> {
+        Ok(Self::from_socket(UdpSocket::bind(addr)?))
+    }
+
+    pub fn from_socket(socket: UdpSocket) -> Self {
+        Self {
+            socket,
+            id: None,
+            received: 0,
+            malformed: 0,
+            buffer: [0; OUTGAUGE_SIZE_WITH_ID + 1],
+        }
+    }
+
+    /// Keeps only the packets sent with `id`, to tell several senders
+    /// on the same port apart.
+    pub fn with_id(mut self, id: i32) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.socket.local_addr()?)
+    }
+
+    /// Limits how long [`recv`](Self::recv) waits for a packet, `None`
+    /// waits forever.
+    pub fn set_timeout(&self, timeout: Option<Duration>) -> Result<()> {
+        Ok(self.socket.set_read_timeout(timeout)?)
+    }
+
+    /// Packets decoded so far.
+    pub fn received(&self) -> u32 {
+        self.received
+    }
+
+    /// Datagrams skipped as malformed so far.
+    pub fn malformed(&self) -> u32 {
+        self.malformed
+    }
+
+    /// Waits for the next packet.
+    ///
+    /// Malformed packets and those with another id than
+    /// [`with_id`](Self::with_id) are skipped.
+    pub fn recv(&mut self) -> Result<OutGauge> {
+        loop {
+            let len = self.socket.recv(&mut self.buffer)?;
+            if let Some(packet) = self.decode(len) {
+                return Ok(packet);
+            }
+        }
+    }
+
+    /// Reads all packets already received without waiting.
+    ///
+    /// Malformed packets are skipped.
+    pub fn poll(&mut self) -> Result<Vec<OutGauge>> {
+        self.socket.set_nonblocking(true)?;
+        let mut packets = Vec::new();
+        let result = loop {
+            match self.socket.recv(&mut self.buffer) {
+                Ok(len) => packets.extend(self.decode(len)),
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => break Ok(packets),
+                Err(err) => break Err(err.into()),
+            }
+        };
+        self.socket.set_nonblocking(false)?;
+        result
+    }
+
+    fn decode(&mut self, len: usize) -> Option<OutGauge> {
+        let Ok(mut packet) = OutGauge::decode(&self.buffer[..len]) else {
+            self.malformed = self.malformed.wrapping_add(1);
+            return None;
+        };
+        if self.id.is_some_and(|id| packet.id != Some(id)) {
+            return None;
+        }
+        packet.sequence = self.received;
+        self.received = self.received.wrapping_add(1);
+        Some(packet)
+    }
+}
@@ -0,0 +1,432 @@
+use crate::export::{Column, Result, SessionTracker, select_columns};
+use crate::recording::{Frame, PageKind, Recording};
+use crate::telemetry::{FieldType, Packet, Page, TelemetrySource, Value};
+use arrow_array::types::Int32Type;
+use arrow_array::{ArrayRef, DictionaryArray, Float32Array, Float64Array, Int32Array, RecordBatch, StringArray};
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::marker::PhantomData;
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Rows per record batch unless configured otherwise.
+pub const DEFAULT_BATCH_ROWS: usize = 65_536;
+
+/// Converts telemetry of a Kunos game into Arrow record batches and Parquet
+/// files. Requires the `arrow` feature.
+///
+/// Like the [`CsvExporter`](crate::export::CsvExporter), every physics frame
+/// becomes a row holding the latest graphics and static info. The schema
+/// starts with `time` (seconds), `session` and `lap`, followed by the
+/// selected columns. It only depends on the
+/// game and the selection, so files of different sessions can be read as one
+/// dataset. Each column carries its `page`, `path` and, when known, `unit` as
+/// field metadata. Enums are dictionary encoded strings.
+///
+/// ```no_run
+/// use race_sdk::acc::AssettoCorsaCompetizione;
+/// use race_sdk::export::ArrowExporter;
+/// use race_sdk::recording::Recording;
+///
+/// let exporter = ArrowExporter::<AssettoCorsaCompetizione>::new().select("*");
+/// let recording = Recording::open("session.rsdk").unwrap();
+/// for file in exporter.write_parquet(recording, "telemetry").unwrap() {
+///     println!("{}", file.display());
+/// }
+/// ```
+///
+/// The files are laid out as `telemetry/session=0/lap=1/part-<id>-3.parquet`,
+/// which Polars and DuckDB read as hive partitions. The id is the time the
+/// recording was created, so several recordings can share a directory.
+pub struct ArrowExporter<S: TelemetrySource> {
+    patterns: Vec<String>,
+    excludes: Vec<String>,
+    batch_rows: usize,
+    source: PhantomData<fn() -> S>,
+}
+
+impl<S: TelemetrySource> Default for ArrowExporter<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: TelemetrySource> ArrowExporter<S> {
+    /// An exporter of all physics columns.
+    pub fn new() -> Self {
+        Self {
+            patterns: Vec::new(),
+            excludes: Vec::new(),
+            batch_rows: DEFAULT_BATCH_ROWS,
+            source: PhantomData,
+        }
+    }
+
+    /// Adds the columns matching `pattern`, see
+    /// [`select_columns`](crate::export::select_columns) for the syntax.
+    pub fn select(mut self, pattern: &str) -> Self {
+        self.patterns.push(pattern.to_string());
+        self
+    }
+
+    /// Drops the columns matching `pattern` from the selection.
+    pub fn exclude(mut self, pattern: &str) -> Self {
+        self.excludes.push(pattern.to_string());
+        self
+    }
+
+    /// Caps the number of rows per record batch.
+    pub fn with_batch_rows(mut self, rows: usize) -> Self {
+        self.batch_rows = rows.max(1);
+        self
+    }
+
+    /// The selected columns, qualified with their page where they would
+    /// clash with `time`, `session` or `lap`.
+    pub fn columns(&self) -> Result<Vec<Column>> {
+        let mut columns = select_columns::<S>(&self.patterns, &self.excludes)?;
+        for column in &mut columns {
+            if ["time", "session", "lap"].contains(&column.name.as_str()) {
+                column.name = format!("{}.{}", column.page.name(), column.name);
+            }
+        }
+        Ok(columns)
+    }
+
+    pub fn schema(&self) -> Result<SchemaRef> {
+        Ok(schema::<S>(&self.columns()?))
+    }
+
+    /// Starts converting a stream of frames into record batches.
+    pub fn batcher(&self) -> Result<Batcher<S>> {
+        let columns = self.columns()?;
+        Ok(Batcher {
+            schema: schema::<S>(&columns),
+            data: columns.iter().map(|column| ColumnData::new(&column.field.ty)).collect(),
+            columns,
+            batch_rows: self.batch_rows,
+            time: Vec::new(),
+            sessions: Vec::new(),
+            laps: Vec::new(),
+            graphics: None,
+            static_info: None,
+            tracker: SessionTracker::new(),
+            last_physics: None,
+            last_graphics: None,
+            epoch: Instant::now(),
+            source: PhantomData,
+        })
+    }
+
+    /// Converts all frames of a recording into record batches.
+    pub fn batches<R: Read>(&self, recording: Recording<R>) -> Result<Vec<RecordBatch>> {
+        let mut batcher = self.batcher()?;
+        let mut batches = Vec::new();
+        for frame in recording.frames::<S>() {
+            let frame = frame?;
+            batches.extend(batcher.push_frame(frame.time, &frame.frame)?);
+        }
+        batches.extend(batcher.flush()?);
+        Ok(batches)
+    }
+
+    /// Writes a recording as Parquet files partitioned by session and lap
+    /// below `dir`, returns the files written.
+    pub fn write_parquet<R: Read>(&self, recording: Recording<R>, dir: impl AsRef<Path>) -> Result<Vec<PathBuf>> {
+        let mut batcher = self.batcher()?;
+        let mut writer = ParquetWriter::new(dir, batcher.schema());
+        if let Some(started) = recording.header().started {
+            writer = writer.with_id(&recording_id(started));
+        }
+        for frame in recording.frames::<S>() {
+            let frame = frame?;
+            if let Some(batch) = batcher.push_frame(frame.time, &frame.frame)? {
+                writer.write(&batch)?;
+            }
+        }
+        if let Some(batch) = batcher.flush()? {
+            writer.write(&batch)?;
+        }
+        writer.finish()
+    }
+}
+
+/// Builds the schema for a column selection of `S`.
+pub fn schema<S: TelemetrySource>(columns: &[Column]) -> SchemaRef {
+    let mut fields = vec![
+        Field::new("time", DataType::Float64, false).with_metadata(HashMap::from([("unit".to_string(), "s".to_string())])),
+        Field::new("session", DataType::Int32, false),
+        Field::new("lap", DataType::Int32, false),
+    ];
+    for column in columns {
+        let mut metadata = HashMap::from([
+            ("page".to_string(), column.page.name().to_string()),
+            ("path".to_string(), column.field.element_path(column.index)),
+        ]);
+        if let Some(unit) = column.field.unit {
+            metadata.insert("unit".to_string(), unit.to_string());
+        }
+        let nullable = column.page != PageKind::Physics;
+        fields.push(Field::new(&column.name, data_type(&column.field.ty), nullable).with_metadata(metadata));
+    }
+    let metadata = HashMap::from([("game".to_string(), S::GAME.code().to_string())]);
+    Arc::new(Schema::new_with_metadata(fields, metadata))
+}
+
+fn data_type(ty: &FieldType) -> DataType {
+    match ty {
+        FieldType::I32 => DataType::Int32,
+        FieldType::F32 => DataType::Float32,
+        FieldType::Text(_) => DataType::Utf8,
+        FieldType::Enum(_) => DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
+    }
+}
+
+enum ColumnData {
+    Int(Vec<Option<i32>>),
+    Float(Vec<Option<f32>>),
+    Text(Vec<Option<String>>),
+    Enum(Vec<Option<String>>),
+}
+
+impl ColumnData {
+    fn new(ty: &FieldType) -> Self {
+        match ty {
+            FieldType::I32 => ColumnData::Int(Vec::new()),
+            FieldType::F32 => ColumnData::Float(Vec::new()),
+            FieldType::Text(_) => ColumnData::Text(Vec::new()),
+            FieldType::Enum(_) => ColumnData::Enum(Vec::new()),
+        }
+    }
+
+    fn push(&mut self, value: Option<Value>) {
+        match (self, value) {
+            (ColumnData::Int(values), Some(Value::Int(value))) => values.push(Some(value)),
+            (ColumnData::Float(values), Some(Value::Float(value))) => values.push(Some(value)),
+            (ColumnData::Text(values), Some(Value::Text(value))) => values.push(Some(value)),
+            (ColumnData::Enum(values), Some(value @ Value::Enum(..))) => values.push(Some(value.to_string())),
+            (ColumnData::Int(values), _) => values.push(None),
+            (ColumnData::Float(values), _) => values.push(None),
+            (ColumnData::Text(values), _) | (ColumnData::Enum(values), _) => values.push(None),
+        }
+    }
+
+    fn take(&mut self) -> ArrayRef {
+        match self {
+            ColumnData::Int(values) => Arc::new(Int32Array::from(mem::take(values))),
+            ColumnData::Float(values) => Arc::new(Float32Array::from(mem::take(values))),
+            ColumnData::Text(values) => Arc::new(StringArray::from(mem::take(values))),
+            ColumnData::Enum(values) => {
+                let array: DictionaryArray<Int32Type> = values.iter().map(|value| value.as_deref()).collect();
+                values.clear();
+                Arc::new(array)
+            }
+        }
+    }
+}
+
+/// Collects rows into record batches, see [`ArrowExporter::batcher`].
+///
+/// A batch is emitted when it is full and whenever the session or lap
+/// changes, so every batch belongs to exactly one session and lap.
+pub struct Batcher<S: TelemetrySource> {
+    schema: SchemaRef,
+    columns: Vec<Column>,
+    data: Vec<ColumnData>,
+    batch_rows: usize,
+    time: Vec<f64>,
+    sessions: Vec<i32>,
+    laps: Vec<i32>,
+    graphics: Option<Vec<u8>>,
+    static_info: Option<Vec<u8>>,
+    tracker: SessionTracker,
+    last_physics: Option<i32>,
+    last_graphics: Option<i32>,
+    epoch: Instant,
+    source: PhantomData<fn() -> S>,
+}
+
+impl<S: TelemetrySource> Batcher<S> {
+    pub fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    /// Rows collected for the next batch.
+    pub fn pending_rows(&self) -> usize {
+        self.time.len()
+    }
+
+    pub fn session(&self) -> i32 {
+        self.tracker.session()
+    }
+
+    pub fn lap(&self) -> i32 {
+        self.tracker.lap()
+    }
+
+    pub fn push_frame(&mut self, time: Duration, frame: &Frame<S>) -> Result<Option<RecordBatch>> {
+        match frame {
+            Frame::Physics(physics) => self.push_physics(time, physics),
+            Frame::Graphics(graphics) => self.set_graphics(graphics),
+            Frame::StaticInfo(static_info) => {
+                self.set_static_info(static_info);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Adds a row for `physics`, returns a batch when it filled one up.
+    pub fn push_physics(&mut self, time: Duration, physics: &S::Physics) -> Result<Option<RecordBatch>> {
+        self.last_physics = Some(physics.packet_id());
+        let physics = physics.to_bytes();
+        self.time.push(time.as_secs_f64());
+        self.sessions.push(self.tracker.session());
+        self.laps.push(self.tracker.lap());
+        for (column, data) in self.columns.iter().zip(&mut self.data) {
+            let bytes = match column.page {
+                PageKind::Physics => Some(&physics),
+                PageKind::Graphics => self.graphics.as_ref(),
+                PageKind::StaticInfo => self.static_info.as_ref(),
+            };
+            data.push(bytes.map(|bytes| column.read(bytes)));
+        }
+        if self.time.len() >= self.batch_rows { self.flush() } else { Ok(None) }
+    }
+
+    /// Updates the graphics columns, returns the pending rows as a batch
+    /// when a new session or lap starts.
+    pub fn set_graphics(&mut self, graphics: &S::Graphics) -> Result<Option<RecordBatch>> {
+        self.last_graphics = Some(graphics.packet_id());
+        let batch = if self.tracker.update(graphics) { self.flush()? } else { None };
+        self.graphics = Some(graphics.to_bytes());
+        Ok(batch)
+    }
+
+    pub fn set_static_info(&mut self, static_info: &S::StaticInfo) {
+        self.static_info = Some(static_info.to_bytes());
+    }
+
+    /// Reads all pages from a live `source` and adds a row if there is a new
+    /// physics frame, returns the batches completed by it. Times are
+    /// measured from the creation of the batcher.
+    pub fn record(&mut self, source: &S) -> Result<Vec<RecordBatch>> {
+        let time = self.epoch.elapsed();
+        if let Some(static_info) = source.get_static_info() {
+            self.set_static_info(&static_info);
+        }
+        let mut batches = Vec::new();
+        if let Some(graphics) = source.get_graphics()
+            && self.last_graphics != Some(graphics.packet_id())
+        {
+            batches.extend(self.set_graphics(&graphics)?);
+        }
+        if let Some(physics) = source.get_physics()
+            && self.last_physics != Some(physics.packet_id())
+        {
+            batches.extend(self.push_physics(time, &physics)?);
+        }
+        Ok(batches)
+    }
+
+    /// Returns the pending rows as a batch, `None` if there are none.
+    pub fn flush(&mut self) -> Result<Option<RecordBatch>> {
+        if self.time.is_empty() {
+            return Ok(None);
+        }
+        let mut arrays: Vec<ArrayRef> = vec![
+            Arc::new(Float64Array::from(mem::take(&mut self.time))),
+            Arc::new(Int32Array::from(mem::take(&mut self.sessions))),
+            Arc::new(Int32Array::from(mem::take(&mut self.laps))),
+        ];
+        arrays.extend(self.data.iter_mut().map(ColumnData::take));
+        Ok(Some(RecordBatch::try_new(self.schema.clone(), arrays)?))
+    }
+}
+
+/// Writes record batches into Parquet files partitioned by session and lap.
+///
+/// Batches must not span sessions or laps, which holds for those of a
+/// [`Batcher`]. A new file is started whenever the partition changes. File
+/// names carry an id of the export, existing files are never overwritten.
+pub struct ParquetWriter {
+    dir: PathBuf,
+    id: String,
+    schema: SchemaRef,
+    current: Option<((i32, i32), ArrowWriter<File>)>,
+    files: Vec<PathBuf>,
+}
+
+impl ParquetWriter {
+    /// A writer whose files are named after the current time.
+    pub fn new(dir: impl AsRef<Path>, schema: SchemaRef) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            id: recording_id(SystemTime::now()),
+            schema,
+            current: None,
+            files: Vec::new(),
+        }
+    }
+
+    /// Names the files `part-<id>-<n>.parquet`, `id` should be unique among
+    /// the exports into the same directory.
+    pub fn with_id(mut self, id: &str) -> Self {
+        self.id = id.to_string();
+        self
+    }
+
+    /// Writes a batch, fails if the file it starts already exists.
+    pub fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        if batch.num_rows() == 0 {
+            return Ok(());
+        }
+        let partition = (first_int(batch, "session"), first_int(batch, "lap"));
+        if self.current.as_ref().map(|(current, _)| *current) != Some(partition) {
+            self.close()?;
+            let dir = self.dir.join(format!("session={}", partition.0)).join(format!("lap={}", partition.1));
+            fs::create_dir_all(&dir)?;
+            let path = dir.join(format!("part-{}-{}.parquet", self.id, self.files.len()));
+            let properties = WriterProperties::builder().set_compression(Compression::SNAPPY).build();
+            let writer = ArrowWriter::try_new(File::create_new(&path)?, self.schema.clone(), Some(properties))?;
+            self.files.push(path);
+            self.current = Some((partition, writer));
+        }
+        if let Some((_, writer)) = &mut self.current {
+            writer.write(batch)?;
+        }
+        Ok(())
+    }
+
+    /// Closes the last file and returns all files written.
+    pub fn finish(mut self) -> Result<Vec<PathBuf>> {
+        self.close()?;
+        Ok(self.files)
+    }
+
+    fn close(&mut self) -> Result<()> {
+        if let Some((_, writer)) = self.current.take() {
+            writer.close()?;
+        }
+        Ok(())
+    }
+}
+
+/// Milliseconds since the Unix epoch.
+fn recording_id(time: SystemTime) -> String {
+    time.duration_since(UNIX_EPOCH).map_or(0, |since| since.as_millis()).to_string()
+}
+
+fn first_int(batch: &RecordBatch, column: &str) -> i32 {
+    batch
+        .column_by_name(column)
+        .and_then(|array| array.as_any().downcast_ref::<Int32Array>())
+        .filter(|array| !array.is_empty())
+        .map_or(0, |array| array.value(0))
+}
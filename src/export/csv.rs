@@ -0,0 +1,364 @@
+use crate::export::{Column, Result, SessionTracker, select_columns};
+use crate::recording::{Frame, PageKind, Recording};
+use crate::telemetry::{Packet, Page, TelemetrySource};
+use std::io::{self, Read, Write};
+use std::marker::PhantomData;
+use std::time::{Duration, Instant};
+
+/// Exports telemetry of a Kunos game as CSV.
+///
+/// Every physics frame becomes one row, graphics and static info columns
+/// hold the latest values seen. The first column is the time in seconds.
+/// Array fields are flattened into one column per element, UTF-16 strings
+/// and enums are written as text.
+///
+/// ```no_run
+/// use race_sdk::acc::AssettoCorsaCompetizione;
+/// use race_sdk::export::CsvExporter;
+/// use race_sdk::recording::Recording;
+/// use std::fs::File;
+///
+/// let exporter = CsvExporter::<AssettoCorsaCompetizione>::new()
+///     .select("speed_kmh")
+///     .select("gas")
+///     .select("brake")
+///     .select("tyre_core_temperature_*")
+///     .select("graphics.completed_laps");
+///
+/// let recording = Recording::open("session.rsdk").unwrap();
+/// exporter
+///     .export_laps(recording, |session, lap| File::create(format!("session_{}_lap_{}.csv", session, lap)))
+///     .unwrap();
+/// ```
+pub struct CsvExporter<S: TelemetrySource> {
+    patterns: Vec<String>,
+    excludes: Vec<String>,
+    delimiter: u8,
+    source: PhantomData<fn() -> S>,
+}
+
+impl<S: TelemetrySource> Default for CsvExporter<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: TelemetrySource> CsvExporter<S> {
+    /// An exporter of all physics columns.
+    pub fn new() -> Self {
+        Self {
+            patterns: Vec::new(),
+            excludes: Vec::new(),
+            delimiter: b',',
+            source: PhantomData,
+        }
+    }
+
+    /// Adds the columns matching `pattern`, see
+    /// [`select_columns`](crate::export::select_columns) for the syntax.
+    pub fn select(mut self, pattern: &str) -> Self {
+        self.patterns.push(pattern.to_string());
+        self
+    }
+
+    /// Drops the columns matching `pattern` from the selection.
+    pub fn exclude(mut self, pattern: &str) -> Self {
+        self.excludes.push(pattern.to_string());
+        self
+    }
+
+    /// Uses another delimiter than `,`, e.g. `b';'` or `b'\t'`.
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    pub fn columns(&self) -> Result<Vec<Column>> {
+        select_columns::<S>(&self.patterns, &self.excludes)
+    }
+
+    /// Starts a CSV file on `writer`, writing the header row.
+    pub fn writer<W: Write>(&self, writer: W) -> Result<CsvWriter<S, W>> {
+        CsvWriter::new(writer, self.columns()?, self.delimiter)
+    }
+
+    /// Starts one CSV file per lap, created by `create` with the session and
+    /// lap number.
+    pub fn lap_writer<W: Write, F: FnMut(i32, i32) -> io::Result<W>>(&self, create: F) -> Result<CsvLapWriter<S, W, F>> {
+        Ok(CsvLapWriter {
+            columns: self.columns()?,
+            delimiter: self.delimiter,
+            create,
+            current: None,
+            finished: Vec::new(),
+            tracker: SessionTracker::new(),
+            graphics: None,
+            static_info: None,
+            last_physics: None,
+            last_graphics: None,
+            epoch: Instant::now(),
+        })
+    }
+
+    /// Writes all frames of a recording into one CSV file.
+    pub fn export_recording<R: Read, W: Write>(&self, recording: Recording<R>, writer: W) -> Result<W> {
+        let mut writer = self.writer(writer)?;
+        for frame in recording.frames::<S>() {
+            let frame = frame?;
+            writer.write_frame(frame.time, &frame.frame)?;
+        }
+        writer.finish()
+    }
+
+    /// Writes a recording into one CSV file per lap, see [`CsvLapWriter`].
+    pub fn export_laps<R: Read, W: Write, F: FnMut(i32, i32) -> io::Result<W>>(&self, recording: Recording<R>, create: F) -> Result<Vec<(i32, i32, W)>> {
+        let mut writer = self.lap_writer(create)?;
+        for frame in recording.frames::<S>() {
+            let frame = frame?;
+            writer.write_frame(frame.time, &frame.frame)?;
+        }
+        writer.finish()
+    }
+}
+
+/// A CSV file being written, see [`CsvExporter::writer`].
+///
+/// Feed it frames from a recording with [`write_frame`](CsvWriter::write_frame)
+/// or poll a live client with [`record`](CsvWriter::record).
+pub struct CsvWriter<S: TelemetrySource, W: Write> {
+    writer: W,
+    columns: Vec<Column>,
+    delimiter: u8,
+    graphics: Option<Vec<u8>>,
+    static_info: Option<Vec<u8>>,
+    last_physics: Option<i32>,
+    last_graphics: Option<i32>,
+    epoch: Instant,
+    rows: u64,
+    source: PhantomData<fn() -> S>,
+}
+
+impl<S: TelemetrySource, W: Write> CsvWriter<S, W> {
+    fn new(mut writer: W, columns: Vec<Column>, delimiter: u8) -> Result<Self> {
+        let mut line = String::from("time");
+        for column in &columns {
+            line.push(delimiter as char);
+            push_cell(&mut line, &column.name, delimiter);
+        }
+        line.push('\n');
+        writer.write_all(line.as_bytes())?;
+        Ok(Self {
+            writer,
+            columns,
+            delimiter,
+            graphics: None,
+            static_info: None,
+            last_physics: None,
+            last_graphics: None,
+            epoch: Instant::now(),
+            rows: 0,
+            source: PhantomData,
+        })
+    }
+
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    /// Number of rows written, excluding the header.
+    pub fn rows(&self) -> u64 {
+        self.rows
+    }
+
+    pub fn write_frame(&mut self, time: Duration, frame: &Frame<S>) -> Result<()> {
+        match frame {
+            Frame::Physics(physics) => self.write_physics(time, physics),
+            Frame::Graphics(graphics) => {
+                self.set_graphics(graphics);
+                Ok(())
+            }
+            Frame::StaticInfo(static_info) => {
+                self.set_static_info(static_info);
+                Ok(())
+            }
+        }
+    }
+
+    /// Writes a row for `physics` with the latest graphics and static info.
+    pub fn write_physics(&mut self, time: Duration, physics: &S::Physics) -> Result<()> {
+        self.last_physics = Some(physics.packet_id());
+        let physics = physics.to_bytes();
+        let mut line = format!("{:.6}", time.as_secs_f64());
+        for column in &self.columns {
+            line.push(self.delimiter as char);
+            let bytes = match column.page {
+                PageKind::Physics => Some(&physics),
+                PageKind::Graphics => self.graphics.as_ref(),
+                PageKind::StaticInfo => self.static_info.as_ref(),
+            };
+            if let Some(bytes) = bytes {
+                push_cell(&mut line, &column.read(bytes).to_string(), self.delimiter);
+            }
+        }
+        line.push('\n');
+        self.writer.write_all(line.as_bytes())?;
+        self.rows += 1;
+        Ok(())
+    }
+
+    pub fn set_graphics(&mut self, graphics: &S::Graphics) {
+        self.last_graphics = Some(graphics.packet_id());
+        self.graphics = Some(graphics.to_bytes());
+    }
+
+    pub fn set_static_info(&mut self, static_info: &S::StaticInfo) {
+        self.static_info = Some(static_info.to_bytes());
+    }
+
+    /// Reads all pages from a live `source` and writes a row if there is a
+    /// new physics frame. Times are measured from the creation of the writer.
+    pub fn record(&mut self, source: &S) -> Result<bool> {
+        let time = self.epoch.elapsed();
+        if let Some(static_info) = source.get_static_info() {
+            self.set_static_info(&static_info);
+        }
+        if let Some(graphics) = source.get_graphics()
+            && self.last_graphics != Some(graphics.packet_id())
+        {
+            self.set_graphics(&graphics);
+        }
+        match source.get_physics() {
+            Some(physics) if self.last_physics != Some(physics.packet_id()) => {
+                self.write_physics(time, &physics)?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    pub fn finish(mut self) -> Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Writes one CSV file per lap, see [`CsvExporter::lap_writer`].
+///
+/// Sessions and laps are numbered by [`SessionTracker`], so lap `0` of each
+/// session is the out lap. Files are created when the first row of a lap
+/// is written.
+pub struct CsvLapWriter<S: TelemetrySource, W: Write, F: FnMut(i32, i32) -> io::Result<W>> {
+    columns: Vec<Column>,
+    delimiter: u8,
+    create: F,
+    current: Option<CsvWriter<S, W>>,
+    finished: Vec<(i32, i32, W)>,
+    tracker: SessionTracker,
+    graphics: Option<S::Graphics>,
+    static_info: Option<S::StaticInfo>,
+    last_physics: Option<i32>,
+    last_graphics: Option<i32>,
+    epoch: Instant,
+}
+
+impl<S: TelemetrySource, W: Write, F: FnMut(i32, i32) -> io::Result<W>> CsvLapWriter<S, W, F> {
+    /// The session the next row belongs to.
+    pub fn session(&self) -> i32 {
+        self.tracker.session()
+    }
+
+    /// The lap the next row belongs to.
+    pub fn lap(&self) -> i32 {
+        self.tracker.lap()
+    }
+
+    pub fn write_frame(&mut self, time: Duration, frame: &Frame<S>) -> Result<()> {
+        match frame {
+            Frame::Physics(physics) => self.write_physics(time, physics),
+            Frame::Graphics(graphics) => self.set_graphics(graphics),
+            Frame::StaticInfo(static_info) => {
+                self.set_static_info(static_info);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn write_physics(&mut self, time: Duration, physics: &S::Physics) -> Result<()> {
+        self.last_physics = Some(physics.packet_id());
+        if self.current.is_none() {
+            let mut writer = CsvWriter::new((self.create)(self.tracker.session(), self.tracker.lap())?, self.columns.clone(), self.delimiter)?;
+            if let Some(graphics) = &self.graphics {
+                writer.set_graphics(graphics);
+            }
+            if let Some(static_info) = &self.static_info {
+                writer.set_static_info(static_info);
+            }
+            self.current = Some(writer);
+        }
+        self.current.as_mut().unwrap().write_physics(time, physics)
+    }
+
+    /// Updates the graphics columns, finishing the current file when a new
+    /// session or lap starts.
+    pub fn set_graphics(&mut self, graphics: &S::Graphics) -> Result<()> {
+        self.last_graphics = Some(graphics.packet_id());
+        let (session, lap) = (self.tracker.session(), self.tracker.lap());
+        if self.tracker.update(graphics)
+            && let Some(writer) = self.current.take()
+        {
+            self.finished.push((session, lap, writer.finish()?));
+        }
+        if let Some(writer) = &mut self.current {
+            writer.set_graphics(graphics);
+        }
+        self.graphics = Some(graphics.clone());
+        Ok(())
+    }
+
+    pub fn set_static_info(&mut self, static_info: &S::StaticInfo) {
+        if let Some(writer) = &mut self.current {
+            writer.set_static_info(static_info);
+        }
+        self.static_info = Some(static_info.clone());
+    }
+
+    /// Like [`CsvWriter::record`], splitting files at lap changes.
+    pub fn record(&mut self, source: &S) -> Result<bool> {
+        let time = self.epoch.elapsed();
+        if let Some(static_info) = source.get_static_info() {
+            self.set_static_info(&static_info);
+        }
+        if let Some(graphics) = source.get_graphics()
+            && self.last_graphics != Some(graphics.packet_id())
+        {
+            self.set_graphics(&graphics)?;
+        }
+        match source.get_physics() {
+            Some(physics) if self.last_physics != Some(physics.packet_id()) => {
+                self.write_physics(time, &physics)?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Finishes the last file and returns all writers with their session and
+    /// lap numbers.
+    pub fn finish(mut self) -> Result<Vec<(i32, i32, W)>> {
+        if let Some(writer) = self.current.take() {
+            self.finished.push((self.tracker.session(), self.tracker.lap(), writer.finish()?));
+        }
+        Ok(self.finished)
+    }
+}
+
+/// Appends a cell, quoting it when it contains the delimiter, quotes or line breaks.
+fn push_cell(line: &mut String, text: &str, delimiter: u8) {
+    if text.contains([delimiter as char, '"', '\n', '\r']) {
+        line.push('"');
+        line.push_str(&text.replace('"', "\"\""));
+        line.push('"');
+    } else {
+        line.push_str(text);
+    }
+}
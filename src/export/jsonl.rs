@@ -0,0 +1,387 @@
+use crate::export::{ExportError, Result};
+use crate::recording::{Frame, PageKind, Recording};
+use crate::telemetry::{FieldDescriptor, FieldType, Game, Packet, Page, TelemetrySource, Timestamped, Value};
+use crate::utils::json::{self, Json};
+use std::io::{BufRead, Read, Write};
+use std::marker::PhantomData;
+use std::time::{Duration, Instant};
+
+/// Writes telemetry of a Kunos game as JSON Lines, one page per line.
+///
+/// Every line is an object with a `type` (`physics`, `graphics` or
+/// `static`), the `game` code and the `time` in seconds, followed by all
+/// fields of the page under their own name. Arrays are nested like their
+/// dimensions, enums are written as their variant name when known, strings
+/// are decoded and non-finite floats become `null`.
+///
+/// ```text
+/// {"type":"physics","game":"acc","time":12.345678,"packet_id":4711,"gas":0.82,...}
+/// ```
+///
+/// Tail a live client by calling [`record`](JsonlWriter::record) in a loop,
+/// every call flushes so that each line is complete as soon as it is written.
+///
+/// ```no_run
+/// use race_sdk::acc::AssettoCorsaCompetizione;
+/// use race_sdk::export::JsonlWriter;
+/// use std::io;
+///
+/// let acc = AssettoCorsaCompetizione::new();
+/// let mut writer = JsonlWriter::<AssettoCorsaCompetizione, _>::new(io::stdout().lock());
+/// loop {
+///     writer.record(&acc).unwrap();
+///     std::thread::sleep(std::time::Duration::from_millis(2));
+/// }
+/// ```
+pub struct JsonlWriter<S: TelemetrySource, W: Write> {
+    writer: W,
+    last_physics: Option<i32>,
+    last_graphics: Option<i32>,
+    last_static: Option<Vec<u8>>,
+    epoch: Instant,
+    lines: u64,
+    source: PhantomData<fn() -> S>,
+}
+
+impl<S: TelemetrySource, W: Write> JsonlWriter<S, W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            last_physics: None,
+            last_graphics: None,
+            last_static: None,
+            epoch: Instant::now(),
+            lines: 0,
+            source: PhantomData,
+        }
+    }
+
+    /// Number of lines written.
+    pub fn lines(&self) -> u64 {
+        self.lines
+    }
+
+    pub fn write_frame(&mut self, time: Duration, frame: &Frame<S>) -> Result<()> {
+        match frame {
+            Frame::Physics(physics) => self.write_physics(time, physics),
+            Frame::Graphics(graphics) => self.write_graphics(time, graphics),
+            Frame::StaticInfo(static_info) => self.write_static_info(time, static_info),
+        }
+    }
+
+    pub fn write_physics(&mut self, time: Duration, physics: &S::Physics) -> Result<()> {
+        self.last_physics = Some(physics.packet_id());
+        self.write_page(PageKind::Physics, time, physics)
+    }
+
+    pub fn write_graphics(&mut self, time: Duration, graphics: &S::Graphics) -> Result<()> {
+        self.last_graphics = Some(graphics.packet_id());
+        self.write_page(PageKind::Graphics, time, graphics)
+    }
+
+    pub fn write_static_info(&mut self, time: Duration, static_info: &S::StaticInfo) -> Result<()> {
+        self.last_static = Some(static_info.to_bytes());
+        self.write_page(PageKind::StaticInfo, time, static_info)
+    }
+
+    /// Writes all frames of a recording.
+    pub fn write_recording<R: Read>(&mut self, recording: Recording<R>) -> Result<()> {
+        for frame in recording.frames::<S>() {
+            let frame = frame?;
+            self.write_frame(frame.time, &frame.frame)?;
+        }
+        Ok(())
+    }
+
+    /// Reads all pages from a live `source` and writes the ones that changed,
+    /// returns the number of lines written. Times are measured from the
+    /// creation of the writer.
+    pub fn record(&mut self, source: &S) -> Result<usize> {
+        let time = self.epoch.elapsed();
+        let lines = self.lines;
+        if let Some(static_info) = source.get_static_info()
+            && self.last_static.as_deref() != Some(&static_info.to_bytes())
+        {
+            self.write_static_info(time, &static_info)?;
+        }
+        if let Some(graphics) = source.get_graphics()
+            && self.last_graphics != Some(graphics.packet_id())
+        {
+            self.write_graphics(time, &graphics)?;
+        }
+        if let Some(physics) = source.get_physics()
+            && self.last_physics != Some(physics.packet_id())
+        {
+            self.write_physics(time, &physics)?;
+        }
+        self.writer.flush()?;
+        Ok((self.lines - lines) as usize)
+    }
+
+    pub fn finish(mut self) -> Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn write_page<P: Page>(&mut self, page: PageKind, time: Duration, value: &P) -> Result<()> {
+        let bytes = value.to_bytes();
+        let mut line = format!(
+            "{{\"type\":\"{}\",\"game\":\"{}\",\"time\":{:.6}",
+            page.name(),
+            S::GAME.code(),
+            time.as_secs_f64()
+        );
+        for field in P::fields() {
+            line.push_str(",\"");
+            line.push_str(field.name);
+            line.push_str("\":");
+            if field.is_array() {
+                push_array(&mut line, field, &bytes, 0, 0);
+            } else {
+                push_value(&mut line, &field.read(&bytes, 0));
+            }
+        }
+        line.push_str("}\n");
+        self.writer.write_all(line.as_bytes())?;
+        self.lines += 1;
+        Ok(())
+    }
+}
+
+/// Writes the elements of `field` below dimension `depth`, starting at the
+/// flat element `index`.
+fn push_array(line: &mut String, field: &FieldDescriptor, bytes: &[u8], depth: usize, index: usize) {
+    let count = field.dims[depth].count();
+    let stride: usize = field.dims[depth + 1..].iter().map(|dim| dim.count()).product();
+    line.push('[');
+    for i in 0..count {
+        if i > 0 {
+            line.push(',');
+        }
+        if depth + 1 < field.dims.len() {
+            push_array(line, field, bytes, depth + 1, index + i * stride);
+        } else {
+            push_value(line, &field.read(bytes, index + i));
+        }
+    }
+    line.push(']');
+}
+
+fn push_value(line: &mut String, value: &Value) {
+    match value {
+        Value::Int(value) | Value::Enum(value, None) => line.push_str(&value.to_string()),
+        Value::Float(value) if value.is_finite() => line.push_str(&value.to_string()),
+        Value::Float(_) => line.push_str("null"),
+        Value::Text(text) => push_string(line, text),
+        Value::Enum(_, Some(name)) => push_string(line, name),
+    }
+}
+
+fn push_string(line: &mut String, text: &str) {
+    line.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => line.push_str("\\\""),
+            '\\' => line.push_str("\\\\"),
+            '\n' => line.push_str("\\n"),
+            '\r' => line.push_str("\\r"),
+            '\t' => line.push_str("\\t"),
+            c if (c as u32) < 0x20 => line.push_str(&format!("\\u{:04x}", c as u32)),
+            c => line.push(c),
+        }
+    }
+    line.push('"');
+}
+
+/// Reads the lines of a [`JsonlWriter`] back into frames.
+///
+/// Fields missing from a line keep their default, unknown keys are ignored
+/// and `null` floats read as NaN. Empty lines are skipped, lines of another
+/// game are an error.
+///
+/// ```no_run
+/// use race_sdk::acc::AssettoCorsaCompetizione;
+/// use race_sdk::export::JsonlReader;
+/// use race_sdk::recording::Frame;
+/// use std::fs::File;
+/// use std::io::BufReader;
+///
+/// let file = BufReader::new(File::open("session.jsonl").unwrap());
+/// for frame in JsonlReader::<AssettoCorsaCompetizione, _>::new(file) {
+///     let frame = frame.unwrap();
+///     if let Frame::Physics(physics) = frame.frame {
+///         println!("{:?} {}", frame.time, physics.speed_kmh);
+///     }
+/// }
+/// ```
+pub struct JsonlReader<S: TelemetrySource, R: BufRead> {
+    reader: R,
+    line: usize,
+    source: PhantomData<fn() -> S>,
+}
+
+impl<S: TelemetrySource, R: BufRead> JsonlReader<S, R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            line: 0,
+            source: PhantomData,
+        }
+    }
+
+    /// Parses a single line.
+    pub fn parse_line(line: &str) -> std::result::Result<Timestamped<Frame<S>>, String> {
+        let Json::Object(members) = json::parse(line)? else {
+            return Err("not an object".to_string());
+        };
+        let get = |key: &str| members.iter().find(|(name, _)| name == key).map(|(_, value)| value);
+        match get("game") {
+            Some(Json::String(code)) if code == S::GAME.code() => {}
+            Some(Json::String(code)) => {
+                return Err(match Game::from_code(code) {
+                    Some(game) => format!("frame of {}, expected {}", game, S::GAME),
+                    None => format!("unknown game: {}", code),
+                });
+            }
+            _ => return Err("missing game".to_string()),
+        }
+        let time = match get("time") {
+            Some(Json::Number(time)) if *time >= 0.0 && time.is_finite() => Duration::from_secs_f64(*time),
+            _ => Duration::ZERO,
+        };
+        let frame = match get("type") {
+            Some(Json::String(name)) => match PageKind::from_name(name) {
+                Some(PageKind::Physics) => Frame::Physics(read_page(&members)?),
+                Some(PageKind::Graphics) => Frame::Graphics(read_page(&members)?),
+                Some(PageKind::StaticInfo) => Frame::StaticInfo(read_page(&members)?),
+                None => return Err(format!("unknown type: {}", name)),
+            },
+            _ => return Err("missing type".to_string()),
+        };
+        Ok(Timestamped { time, frame })
+    }
+}
+
+impl<S: TelemetrySource, R: BufRead> Iterator for JsonlReader<S, R> {
+    type Item = Result<Timestamped<Frame<S>>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut line = String::new();
+        loop {
+            line.clear();
+            self.line += 1;
+            match self.reader.read_line(&mut line) {
+                Ok(0) => return None,
+                Ok(_) if line.trim().is_empty() => continue,
+                Ok(_) => return Some(Self::parse_line(&line).map_err(|message| ExportError::InvalidLine(self.line, message))),
+                Err(err) => return Some(Err(err.into())),
+            }
+        }
+    }
+}
+
+fn read_page<P: Page>(members: &[(String, Json)]) -> std::result::Result<P, String> {
+    let mut bytes = P::default().to_bytes();
+    for (name, json) in members {
+        if let Some(field) = P::field(name) {
+            let mut elements = Vec::with_capacity(field.count());
+            flatten(json, &mut elements);
+            for (index, json) in elements.into_iter().take(field.count()).enumerate() {
+                let value = match (field.ty, json) {
+                    (_, Json::Null) if field.ty == FieldType::F32 => Value::Float(f32::NAN),
+                    (FieldType::F32, Json::Number(value)) => Value::Float(*value as f32),
+                    (_, Json::Number(value)) => Value::Int(*value as i32),
+                    (_, Json::Bool(value)) => Value::Int(*value as i32),
+                    (_, Json::String(text)) => Value::Text(text.clone()),
+                    _ => return Err(format!("invalid value for {}", field.element_path(index))),
+                };
+                field.write(&mut bytes, index, &value).map_err(|err| err.to_string())?;
+            }
+        }
+    }
+    P::from_bytes(&bytes).ok_or_else(|| "invalid page".to_string())
+}
+
+fn flatten<'a>(json: &'a Json, out: &mut Vec<&'a Json>) {
+    match json {
+        Json::Array(items) => items.iter().for_each(|item| flatten(item, out)),
+        json => out.push(json),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::acc::{AccStatus, AssettoCorsaCompetizione, Graphics, Physics, StaticInfo};
+    use crate::telemetry::RawEnum;
+
+    fn utf16<const N: usize>(text: &str) -> [u16; N] {
+        let mut out = [0; N];
+        for (slot, unit) in out.iter_mut().zip(text.encode_utf16()) {
+            *slot = unit;
+        }
+        out
+    }
+
+    #[test]
+    fn frames_round_trip() {
+        let mut physics = Physics {
+            packet_id: 7,
+            gas: 0.825,
+            gear: -1,
+            speed_kmh: f32::NAN,
+            ..Physics::default()
+        };
+        physics.wheel_slip = [0.1, -2.5e-7, 3.0e10, f32::MIN_POSITIVE];
+        let graphics = Graphics {
+            packet_id: 3,
+            status: AccStatus::Live.into(),
+            // A value a newer game version might write.
+            penalty: RawEnum::from_raw(99),
+            tyre_compound: utf16("dry \"A\" \\ ü\t€"),
+            ..Graphics::default()
+        };
+        let static_info = StaticInfo {
+            car_model: utf16("ferrari_296_gt3"),
+            ..StaticInfo::default()
+        };
+        let frames = [
+            (Duration::ZERO, Frame::StaticInfo(static_info)),
+            (Duration::from_millis(1500), Frame::Graphics(graphics)),
+            (Duration::from_micros(1_500_001), Frame::Physics(physics)),
+        ];
+
+        let mut writer = JsonlWriter::<AssettoCorsaCompetizione, _>::new(Vec::new());
+        for (time, frame) in &frames {
+            writer.write_frame(*time, frame).unwrap();
+        }
+        let bytes = writer.finish().unwrap();
+        let read: Vec<_> = JsonlReader::<AssettoCorsaCompetizione, _>::new(bytes.as_slice())
+            .collect::<Result<_>>()
+            .unwrap();
+
+        assert_eq!(read.len(), frames.len());
+        for ((time, frame), read) in frames.iter().zip(&read) {
+            assert_eq!(read.time, *time);
+            let bytes = |frame: &Frame<AssettoCorsaCompetizione>| match frame {
+                Frame::Physics(page) => page.to_bytes(),
+                Frame::Graphics(page) => page.to_bytes(),
+                Frame::StaticInfo(page) => page.to_bytes(),
+            };
+            assert_eq!(bytes(&read.frame), bytes(frame));
+        }
+    }
+
+    #[test]
+    fn invalid_lines() {
+        type Reader<'a> = JsonlReader<AssettoCorsaCompetizione, &'a [u8]>;
+        assert!(Reader::parse_line("[]").is_err());
+        assert!(Reader::parse_line(r#"{"type":"physics","game":"ac","time":1}"#).is_err());
+        assert!(Reader::parse_line(r#"{"type":"tyres","game":"acc","time":1}"#).is_err());
+        assert!(Reader::parse_line(r#"{"type":"physics","game":"acc","gas":"full"}"#).is_err());
+
+        let lines = "\n{\"type\":\"graphics\",\"game\":\"acc\",\"time\":2}\n{\"type\":\n";
+        let read: Vec<_> = Reader::new(lines.as_bytes()).collect();
+        assert!(matches!(&read[..], [Ok(_), Err(ExportError::InvalidLine(3, _))]));
+    }
+}
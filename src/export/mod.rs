@@ -0,0 +1,239 @@
+#[cfg(feature = "arrow")]
+pub mod arrow;
+pub mod csv;
+pub mod jsonl;
+#[cfg(feature = "arrow")]
+pub use arrow::*;
+pub use csv::*;
+pub use jsonl::*;
+
+use crate::recording::{PageKind, RecordingError};
+use crate::telemetry::{FieldDescriptor, Page, TelemetrySource, Value};
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum ExportError {
+    Io(io::Error),
+    Recording(RecordingError),
+    UnknownColumn(String),
+    /// A line that could not be parsed, with its 1-based number.
+    InvalidLine(usize, String),
+    #[cfg(feature = "arrow")]
+    Arrow(arrow_schema::ArrowError),
+    #[cfg(feature = "arrow")]
+    Parquet(parquet::errors::ParquetError),
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::Io(err) => write!(f, "I/O error: {}", err),
+            ExportError::Recording(err) => write!(f, "Recording error: {}", err),
+            ExportError::UnknownColumn(pattern) => write!(f, "No column matches: {}", pattern),
+            ExportError::InvalidLine(line, message) => write!(f, "Invalid line {}: {}", line, message),
+            #[cfg(feature = "arrow")]
+            ExportError::Arrow(err) => write!(f, "Arrow error: {}", err),
+            #[cfg(feature = "arrow")]
+            ExportError::Parquet(err) => write!(f, "Parquet error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+impl From<io::Error> for ExportError {
+    fn from(err: io::Error) -> Self {
+        ExportError::Io(err)
+    }
+}
+
+impl From<RecordingError> for ExportError {
+    fn from(err: RecordingError) -> Self {
+        ExportError::Recording(err)
+    }
+}
+
+#[cfg(feature = "arrow")]
+impl From<arrow_schema::ArrowError> for ExportError {
+    fn from(err: arrow_schema::ArrowError) -> Self {
+        ExportError::Arrow(err)
+    }
+}
+
+#[cfg(feature = "arrow")]
+impl From<parquet::errors::ParquetError> for ExportError {
+    fn from(err: parquet::errors::ParquetError) -> Self {
+        ExportError::Parquet(err)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, ExportError>;
+
+/// Numbers sessions and laps from a stream of graphics frames.
+///
+/// A new session starts when the session type or index changes, or when the
+/// completed lap count drops. Lap `n` is the part of a session during which
+/// `n` laps were completed, so lap `0` is the out lap.
+#[derive(Debug, Clone, Default)]
+pub struct SessionTracker {
+    session: i32,
+    lap: i32,
+    last: Option<(Option<i32>, Option<i32>)>,
+}
+
+impl SessionTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn session(&self) -> i32 {
+        self.session
+    }
+
+    pub fn lap(&self) -> i32 {
+        self.lap
+    }
+
+    /// Updates the numbers, returns whether the session or lap changed.
+    pub fn update<G: Page>(&mut self, graphics: &G) -> bool {
+        let word = |name| match graphics.get(name) {
+            Some(Value::Int(value) | Value::Enum(value, _)) => Some(value),
+            _ => None,
+        };
+        self.update_words((word("session"), word("session_index")), word("completed_laps"))
+    }
+
+    /// [`update`](Self::update) from the raw `session`, `session_index` and
+    /// `completed_laps` words of a graphics page.
+    pub(crate) fn update_words(&mut self, key: (Option<i32>, Option<i32>), completed_laps: Option<i32>) -> bool {
+        let lap = completed_laps.unwrap_or(self.lap);
+        let (session, lap) = match self.last {
+            None => (self.session, lap),
+            Some(last) if last != key || lap < self.lap => (self.session + 1, lap),
+            Some(_) => (self.session, lap),
+        };
+        self.last = Some(key);
+        let changed = (session, lap) != (self.session, self.lap);
+        self.session = session;
+        self.lap = lap;
+        changed
+    }
+}
+
+/// One flattened element of a page, e.g. `tyre_temp_i_fl` or `car_coordinates_3_x`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    pub page: PageKind,
+    pub field: &'static FieldDescriptor,
+    pub index: usize,
+    /// The column name, qualified with the page (`graphics.packet_id`) when
+    /// the element name alone is ambiguous.
+    pub name: String,
+}
+
+impl Column {
+    pub fn read(&self, bytes: &[u8]) -> Value {
+        self.field.read(bytes, self.index)
+    }
+}
+
+/// Resolves column patterns against the pages of `S`.
+///
+/// A pattern is a glob (`*` and `?`) matched against field names and
+/// flattened element names, optionally prefixed with a page:
+/// `speed_kmh`, `tyre_temp_*`, `car_coordinates`, `graphics.*`. Columns are
+/// returned in pattern order, each once; every pattern must match something.
+/// Without patterns all physics columns are selected.
+pub fn select_columns<S: TelemetrySource>(patterns: &[String], excludes: &[String]) -> Result<Vec<Column>> {
+    let all = all_columns::<S>();
+    let mut selected: Vec<&Column> = Vec::new();
+    if patterns.is_empty() {
+        selected.extend(all.iter().filter(|column| column.page == PageKind::Physics));
+    }
+    for pattern in patterns {
+        let matches: Vec<&Column> = all.iter().filter(|column| column_matches(column, pattern)).collect();
+        if matches.is_empty() {
+            return Err(ExportError::UnknownColumn(pattern.clone()));
+        }
+        for column in matches {
+            if !selected.contains(&column) {
+                selected.push(column);
+            }
+        }
+    }
+    selected.retain(|column| !excludes.iter().any(|pattern| column_matches(column, pattern)));
+
+    let mut columns: Vec<Column> = selected.into_iter().cloned().collect();
+    let names: Vec<String> = columns.iter().map(|column| column.name.clone()).collect();
+    for column in &mut columns {
+        if names.iter().filter(|name| **name == column.name).count() > 1 {
+            column.name = format!("{}.{}", column.page.name(), column.name);
+        }
+    }
+    Ok(columns)
+}
+
+fn all_columns<S: TelemetrySource>() -> Vec<Column> {
+    let mut columns = Vec::new();
+    for (page, fields) in [
+        (PageKind::Physics, S::Physics::fields()),
+        (PageKind::Graphics, S::Graphics::fields()),
+        (PageKind::StaticInfo, S::StaticInfo::fields()),
+    ] {
+        for field in fields {
+            for index in 0..field.count() {
+                columns.push(Column {
+                    page,
+                    field,
+                    index,
+                    name: field.element_name(index),
+                });
+            }
+        }
+    }
+    columns
+}
+
+fn column_matches(column: &Column, pattern: &str) -> bool {
+    let pattern = match pattern.split_once('.') {
+        Some((page, rest)) if PageKind::from_name(page).is_some() => {
+            if PageKind::from_name(page) != Some(column.page) {
+                return false;
+            }
+            rest
+        }
+        _ => pattern,
+    };
+    glob_match(pattern, column.field.name) || glob_match(pattern, &column.name)
+}
+
+/// Matches `text` against a pattern where `*` stands for any run of
+/// characters and `?` for a single one.
+pub(crate) fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
@@ -0,0 +1,167 @@
+use crate::iracing::{HEADER_SIZE, Header, IRSDK_VERSION, IRacingError, MEM_MAP_FILE_NAME, SessionInfo, Telemetry, VarTable, decode_text};
+use crate::utils::{SharedMemory, open_shared_memory};
+use std::cell::RefCell;
+use std::hint;
+use std::sync::Arc;
+
+/// Reads attempted by [`IRacingClient::get_telemetry`] before giving up.
+pub const MAX_READ_ATTEMPTS: usize = 64;
+
+/// The header fields the variable headers depend on.
+type VarTableKey = (i32, i32, i32);
+
+pub struct IRacingClient {
+    memory: Option<Box<dyn SharedMemory>>,
+    vars: RefCell<Option<(VarTableKey, Arc<VarTable>)>>,
+    /// The session info parsed at an update count, `None` when it failed to
+    /// parse.
+    session_info: RefCell<Option<(i32, Option<Arc<SessionInfo>>)>>,
+}
+
+impl IRacingClient {
+    pub fn new() -> Self {
+        Self {
+            memory: None,
+            vars: RefCell::new(None),
+            session_info: RefCell::new(None),
+        }
+    }
+
+    /// Creates a client reading from a custom memory source instead of the
+    /// simulator, holding a complete copy of the file.
+    pub fn from_shared_memory(memory: Box<dyn SharedMemory>) -> Self {
+        Self {
+            memory: Some(memory),
+            vars: RefCell::new(None),
+            session_info: RefCell::new(None),
+        }
+    }
+
+    /// Opens the memory-mapped file, first its header and then the size
+    /// the header asks for.
+    pub fn connect(&mut self) -> Result<(), IRacingError> {
+        self.memory = None;
+        self.vars.replace(None);
+        self.session_info.replace(None);
+        let probe = open_shared_memory(MEM_MAP_FILE_NAME, HEADER_SIZE).map_err(map_shared_memory_error)?;
+        let header = Header::parse(probe.as_slice()).ok_or_else(|| IRacingError::InvalidData("header too short".to_string()))?;
+        if header.version != IRSDK_VERSION {
+            return Err(IRacingError::VersionMismatch {
+                expected: IRSDK_VERSION,
+                found: header.version,
+            });
+        }
+        let memory = open_shared_memory(MEM_MAP_FILE_NAME, header.required_size()).map_err(map_shared_memory_error)?;
+        self.memory = Some(Box::new(memory));
+        Ok(())
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.memory.is_some()
+    }
+
+    /// Whether the simulator is in a session, see [`Header::is_connected`].
+    pub fn is_sim_running(&self) -> bool {
+        self.get_header().is_some_and(|header| header.is_connected())
+    }
+
+    pub fn get_header(&self) -> Option<Header> {
+        Header::parse(self.memory.as_ref()?.as_slice())
+    }
+
+    /// The variables of the session, parsed once and shared by all samples.
+    pub fn get_vars(&self) -> Option<Arc<VarTable>> {
+        let bytes = self.memory.as_ref()?.as_slice();
+        self.var_table(bytes, &Header::parse(bytes)?)
+    }
+
+    /// Copies the latest data buffer.
+    ///
+    /// The buffer with the highest tick count is copied and kept when its
+    /// tick count did not change meanwhile, i.e. the simulator did not
+    /// start overwriting it. Returns `None` when not connected, outside a
+    /// session or after [`MAX_READ_ATTEMPTS`] torn reads.
+    pub fn get_telemetry(&self) -> Option<Telemetry> {
+        let bytes = self.memory.as_ref()?.as_slice();
+        for _ in 0..MAX_READ_ATTEMPTS {
+            let header = Header::parse(bytes)?;
+            if !header.is_connected() {
+                return None;
+            }
+            let vars = self.var_table(bytes, &header)?;
+            let (index, buffer) = header.latest_buffer()?;
+            let start = usize::try_from(buffer.buffer_offset).ok()?;
+            let len = usize::try_from(header.buffer_len).ok()?;
+            let data = bytes.get(start..start + len)?.to_vec();
+            if Header::tick_count_of(bytes, index)? == buffer.tick_count {
+                return Some(Telemetry {
+                    tick_count: buffer.tick_count,
+                    session_info_update: header.session_info_update,
+                    vars,
+                    data,
+                });
+            }
+            hint::spin_loop();
+        }
+        None
+    }
+
+    /// Copies the session info YAML together with its update count.
+    ///
+    /// The copy is only returned when the count did not change while
+    /// copying, i.e. iRacing did not rewrite the string meanwhile.
+    pub fn get_session_info_text(&self) -> Option<(i32, String)> {
+        let bytes = self.memory.as_ref()?.as_slice();
+        for _ in 0..MAX_READ_ATTEMPTS {
+            let header = Header::parse(bytes)?;
+            let start = usize::try_from(header.session_info_offset).ok()?;
+            let len = usize::try_from(header.session_info_len).ok()?;
+            let text = decode_text(bytes.get(start..start + len)?);
+            if Header::parse(bytes)?.session_info_update == header.session_info_update {
+                return Some((header.session_info_update, text));
+            }
+            hint::spin_loop();
+        }
+        None
+    }
+
+    /// The parsed session info, re-parsed only when its update count
+    /// changed since the last call.
+    pub fn get_session_info(&self) -> Option<Arc<SessionInfo>> {
+        let update = self.get_header()?.session_info_update;
+        if let Some((cached, info)) = &*self.session_info.borrow()
+            && *cached == update
+        {
+            return info.clone();
+        }
+        let (update, text) = self.get_session_info_text()?;
+        let info = SessionInfo::parse(&text).ok().map(Arc::new);
+        self.session_info.replace(Some((update, info.clone())));
+        info
+    }
+
+    fn var_table(&self, bytes: &[u8], header: &Header) -> Option<Arc<VarTable>> {
+        let key = (header.num_vars, header.var_header_offset, header.buffer_len);
+        if let Some((cached, vars)) = &*self.vars.borrow()
+            && *cached == key
+        {
+            return Some(vars.clone());
+        }
+        let vars = Arc::new(VarTable::parse(bytes, header)?);
+        self.vars.replace(Some((key, vars.clone())));
+        Some(vars)
+    }
+}
+
+impl Default for IRacingClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn map_shared_memory_error(err: crate::utils::SharedMemoryError) -> IRacingError {
+    match err {
+        crate::utils::SharedMemoryError::SharedMemoryNotFound(msg) => IRacingError::SharedMemoryNotFound(msg),
+        crate::utils::SharedMemoryError::ConnectionFailed(msg) => IRacingError::ConnectionFailed(msg),
+    }
+}
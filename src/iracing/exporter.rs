@@ -0,0 +1,361 @@
+use crate::export::SessionTracker;
+use crate::iracing::{IRacingError, IbtWriter, Result, Telemetry, VarHeader, VarType};
+use crate::recording::{Frame, Recording};
+use crate::telemetry::{FieldDescriptor, FieldType, FrameAligner, Page, TelemetrySource, Value};
+use std::fs::File;
+use std::io::{BufWriter, Read, Seek, Write};
+use std::marker::PhantomData;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// iRacing variables of well-known page elements as
+/// `(path, variable, type, unit, factor, offset)`, where the variable holds
+/// `value * factor + offset` of the element at `path`.
+///
+/// Paths start with `physics.` or `graphics.`, elements a game does not
+/// have are skipped.
+pub const IBT_VARIABLES: &[(&str, &str, VarType, &str, f64, f64)] = &[
+    ("physics.speed_kmh", "Speed", VarType::Float, "m/s", 1.0 / 3.6, 0.0),
+    ("physics.rpms", "RPM", VarType::Float, "revs/min", 1.0, 0.0),
+    ("physics.gear", "Gear", VarType::Int, "", 1.0, -1.0),
+    ("physics.gas", "Throttle", VarType::Float, "%", 1.0, 0.0),
+    ("physics.brake", "Brake", VarType::Float, "%", 1.0, 0.0),
+    ("physics.clutch", "Clutch", VarType::Float, "%", -1.0, 1.0),
+    ("physics.fuel", "FuelLevel", VarType::Float, "l", 1.0, 0.0),
+    ("physics.acc_g[0]", "LatAccel", VarType::Float, "m/s^2", 9.80665, 0.0),
+    ("physics.acc_g[1]", "VertAccel", VarType::Float, "m/s^2", 9.80665, 0.0),
+    ("physics.acc_g[2]", "LongAccel", VarType::Float, "m/s^2", 9.80665, 0.0),
+    ("physics.local_velocity[2]", "VelocityX", VarType::Float, "m/s", 1.0, 0.0),
+    ("physics.local_velocity[0]", "VelocityY", VarType::Float, "m/s", 1.0, 0.0),
+    ("physics.local_velocity[1]", "VelocityZ", VarType::Float, "m/s", 1.0, 0.0),
+    ("physics.heading", "Yaw", VarType::Float, "rad", 1.0, 0.0),
+    ("physics.pitch", "Pitch", VarType::Float, "rad", 1.0, 0.0),
+    ("physics.roll", "Roll", VarType::Float, "rad", 1.0, 0.0),
+    ("physics.local_angular_velocity[1]", "YawRate", VarType::Float, "rad/s", 1.0, 0.0),
+    ("physics.local_angular_velocity[0]", "PitchRate", VarType::Float, "rad/s", 1.0, 0.0),
+    ("physics.local_angular_velocity[2]", "RollRate", VarType::Float, "rad/s", 1.0, 0.0),
+    ("physics.tyre_core_temperature[0]", "LFtempCM", VarType::Float, "C", 1.0, 0.0),
+    ("physics.tyre_core_temperature[1]", "RFtempCM", VarType::Float, "C", 1.0, 0.0),
+    ("physics.tyre_core_temperature[2]", "LRtempCM", VarType::Float, "C", 1.0, 0.0),
+    ("physics.tyre_core_temperature[3]", "RRtempCM", VarType::Float, "C", 1.0, 0.0),
+    ("physics.suspension_travel[0]", "LFshockDefl", VarType::Float, "m", 1.0, 0.0),
+    ("physics.suspension_travel[1]", "RFshockDefl", VarType::Float, "m", 1.0, 0.0),
+    ("physics.suspension_travel[2]", "LRshockDefl", VarType::Float, "m", 1.0, 0.0),
+    ("physics.suspension_travel[3]", "RRshockDefl", VarType::Float, "m", 1.0, 0.0),
+    ("physics.brake_bias", "dcBrakeBias", VarType::Float, "%", 100.0, 0.0),
+    ("physics.water_temp", "WaterTemp", VarType::Float, "C", 1.0, 0.0),
+    ("physics.air_temp", "AirTemp", VarType::Float, "C", 1.0, 0.0),
+    ("physics.road_temp", "TrackTempCrew", VarType::Float, "C", 1.0, 0.0),
+    ("graphics.completed_laps", "Lap", VarType::Int, "", 1.0, 1.0),
+    ("graphics.completed_laps", "LapCompleted", VarType::Int, "", 1.0, 0.0),
+    ("graphics.i_current_time", "LapCurrentLapTime", VarType::Float, "s", 0.001, 0.0),
+    ("graphics.i_last_time", "LapLastLapTime", VarType::Float, "s", 0.001, 0.0),
+    ("graphics.i_best_time", "LapBestLapTime", VarType::Float, "s", 0.001, 0.0),
+    ("graphics.normalized_car_position", "LapDistPct", VarType::Float, "%", 1.0, 0.0),
+    ("graphics.is_in_pit_lane", "OnPitRoad", VarType::Bool, "", 1.0, 0.0),
+    ("graphics.position", "PlayerCarPosition", VarType::Int, "", 1.0, 0.0),
+    ("graphics.tc", "dcTractionControl", VarType::Float, "", 1.0, 0.0),
+    ("graphics.abs", "dcABS", VarType::Float, "", 1.0, 0.0),
+];
+
+/// Converts a recording of a Kunos game into an iRacing `.ibt` file, so it
+/// opens in tools made for iRacing telemetry.
+///
+/// Physics and graphics are aligned onto one grid, each sample becomes a
+/// record holding the elements of [`IBT_VARIABLES`] under their iRacing
+/// names and units, together with `SessionTime`, `SessionTick` and
+/// `SessionNum`, which numbers the sessions as [`SessionTracker`] does. The
+/// session info YAML describes track, car and driver from the static info
+/// and lists every session.
+///
+/// ```no_run
+/// use race_sdk::acc::AssettoCorsaCompetizione;
+/// use race_sdk::iracing::{IbtExporter, IbtFile};
+/// use race_sdk::recording::Recording;
+///
+/// let recording = Recording::open("session.rsdk").unwrap();
+/// IbtExporter::<AssettoCorsaCompetizione>::new().with_rate(60).write(recording, "session.ibt").unwrap();
+///
+/// let mut ibt = IbtFile::open("session.ibt").unwrap();
+/// for lap in ibt.laps().unwrap().iter().filter(|lap| lap.complete) {
+///     println!("Lap {}: {:.3}s", lap.lap, lap.duration());
+/// }
+/// ```
+pub struct IbtExporter<S: TelemetrySource> {
+    rate: u16,
+    start_date: Option<SystemTime>,
+    source: PhantomData<fn() -> S>,
+}
+
+impl<S: TelemetrySource> Default for IbtExporter<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: TelemetrySource> IbtExporter<S> {
+    /// Exports at 60 Hz, the rate of iRacing's own files.
+    pub fn new() -> Self {
+        Self {
+            rate: 60,
+            start_date: None,
+            source: PhantomData,
+        }
+    }
+
+    pub fn with_rate(mut self, rate: u16) -> Self {
+        self.rate = rate.max(1);
+        self
+    }
+
+    /// Start of the session, defaults to the time of the export.
+    pub fn with_start_date(mut self, date: SystemTime) -> Self {
+        self.start_date = Some(date);
+        self
+    }
+
+    /// The variables of every record, the synthetic session variables
+    /// followed by the elements of [`IBT_VARIABLES`] the game has, each
+    /// described by its page path.
+    pub fn vars() -> Vec<VarHeader> {
+        let mut vars = vec![
+            VarHeader::new("SessionTime", VarType::Double, 1, "s", "Seconds since session start"),
+            VarHeader::new("SessionTick", VarType::Int, 1, "", "Current update number"),
+            VarHeader::new("SessionNum", VarType::Int, 1, "", "Session number"),
+        ];
+        vars.extend(
+            Self::mappings()
+                .into_iter()
+                .map(|mapping| VarHeader::new(mapping.name, mapping.var_type, 1, mapping.unit, mapping.path)),
+        );
+        vars
+    }
+
+    /// Converts a recording into an `.ibt` file written to `writer`, which
+    /// is returned once complete.
+    pub fn export<R: Read, W: Write + Seek>(&self, recording: Recording<R>, writer: W) -> Result<W> {
+        let mappings = Self::mappings();
+        let mut ibt = IbtWriter::new(writer, Self::vars(), i32::from(self.rate))?;
+        if let Some(date) = self.start_date {
+            ibt = ibt.with_start_date(date);
+        }
+
+        let mut aligner = FrameAligner::<S::Physics, S::Graphics>::new(self.rate as f64)
+            .map_err(|err| IRacingError::InvalidData(err.to_string()))?
+            .with_max_gap(None);
+        let mut static_info = None;
+        let mut tracker = SessionTracker::new();
+        let mut sessions = Vec::new();
+        for frame in recording.frames::<S>() {
+            let frame = frame?;
+            let frames = match frame.frame {
+                Frame::Physics(page) => aligner.push_physics((frame.time, page)),
+                Frame::Graphics(page) => aligner.push_graphics((frame.time, page)),
+                Frame::StaticInfo(page) => {
+                    static_info = Some(page);
+                    Vec::new()
+                }
+            };
+            for combined in frames {
+                tracker.update(&combined.graphics);
+                let session = tracker.session();
+                if sessions.len() <= session as usize {
+                    sessions.push(None);
+                }
+                if let Some(Value::Enum(_, Some(name))) = combined.graphics.get("session") {
+                    sessions[session as usize] = Some(name);
+                }
+                let mut sample = ibt.new_sample();
+                sample.set("SessionTime", combined.time.as_secs_f64())?;
+                sample.set("SessionTick", ibt.record_count() as i32)?;
+                sample.set("SessionNum", session)?;
+                let physics = combined.physics.to_bytes();
+                let graphics = combined.graphics.to_bytes();
+                for mapping in &mappings {
+                    let bytes = if mapping.physics { &physics } else { &graphics };
+                    mapping.write(&mut sample, bytes)?;
+                }
+                ibt.write_sample(&sample)?;
+            }
+        }
+
+        ibt.set_session_info(&session_info::<S>(static_info.as_ref(), &sessions));
+        ibt.finish()
+    }
+
+    /// Writes the `.ibt` file to `path`.
+    pub fn write<R: Read>(&self, recording: Recording<R>, path: impl AsRef<Path>) -> Result<()> {
+        let mut writer = self.export(recording, BufWriter::new(File::create(path)?))?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn mappings() -> Vec<Mapping> {
+        IBT_VARIABLES
+            .iter()
+            .filter_map(|&(path, name, var_type, unit, factor, offset)| {
+                let (page, element) = path.split_once('.')?;
+                let (physics, fields) = match page {
+                    "physics" => (true, S::Physics::fields()),
+                    "graphics" => (false, S::Graphics::fields()),
+                    _ => return None,
+                };
+                let (field, index) = fields
+                    .iter()
+                    .find_map(|field| (0..field.count()).find(|&i| field.element_path(i) == element).map(|i| (field, i)))?;
+                Some(Mapping {
+                    physics,
+                    field,
+                    index,
+                    name,
+                    var_type,
+                    unit,
+                    factor,
+                    offset,
+                    path,
+                })
+            })
+            .filter(|mapping| !matches!(mapping.field.ty, FieldType::Text(_)))
+            .collect()
+    }
+}
+
+/// An element of [`IBT_VARIABLES`] resolved against the pages of a game.
+struct Mapping {
+    physics: bool,
+    field: &'static FieldDescriptor,
+    index: usize,
+    name: &'static str,
+    var_type: VarType,
+    unit: &'static str,
+    factor: f64,
+    offset: f64,
+    path: &'static str,
+}
+
+impl Mapping {
+    fn write(&self, sample: &mut Telemetry, bytes: &[u8]) -> Result<()> {
+        let value = self.field.read(bytes, self.index).as_f64().unwrap_or_default() * self.factor + self.offset;
+        match self.var_type {
+            VarType::Float => sample.set(self.name, value as f32),
+            VarType::Double => sample.set(self.name, value),
+            VarType::Int | VarType::BitField => sample.set(self.name, value.round() as i32),
+            VarType::Bool => sample.set(self.name, value != 0.0),
+            VarType::Char => Err(IRacingError::InvalidData(format!("cannot export {} as text", self.name))),
+        }
+    }
+}
+
+/// The session info YAML of an exported file, with the type of each
+/// session, at least one, and the player as the only driver.
+fn session_info<S: TelemetrySource>(static_info: Option<&S::StaticInfo>, sessions: &[Option<&str>]) -> String {
+    let text = |path: &str| match static_info.and_then(|page| page.get(path)) {
+        Some(Value::Text(text)) => text,
+        _ => String::new(),
+    };
+    let number = |path: &str| static_info.and_then(|page| page.get(path)).and_then(|value| value.as_f64()).unwrap_or_default();
+    let driver = format!("{} {}", text("player_name"), text("player_surname")).trim().to_string();
+    let car = yaml_scalar(&text("car_model"));
+
+    let mut yaml = String::from("---\nWeekendInfo:\n");
+    yaml.push_str(&format!(" TrackName: {}\n", yaml_scalar(&text("track"))));
+    yaml.push_str(&format!(" TrackDisplayName: {}\n", yaml_scalar(&text("track"))));
+    yaml.push_str(&format!(" TrackConfigName: {}\n", yaml_scalar(&text("track_configuration"))));
+    yaml.push_str(&format!(" TrackLength: {:.2} km\n", number("track_spline_length") / 1000.0));
+    yaml.push_str(" Category: Road\n");
+    yaml.push_str(&format!(" SimMode: {}\n", S::GAME));
+    yaml.push_str(&format!(" BuildVersion: {}\n", yaml_scalar(&text("sm_version"))));
+    yaml.push_str(" WeekendOptions:\n  NumStarters: 1\n\n");
+    yaml.push_str("SessionInfo:\n Sessions:\n");
+    for (number, session) in sessions.iter().enumerate().chain(sessions.is_empty().then_some((0, &None))) {
+        yaml.push_str(&format!(" - SessionNum: {}\n", number));
+        yaml.push_str(&format!("   SessionType: {}\n", session.unwrap_or("Practice")));
+        yaml.push_str(&format!("   SessionName: {}\n", session.unwrap_or("Practice").to_uppercase()));
+    }
+    yaml.push('\n');
+    yaml.push_str("DriverInfo:\n DriverCarIdx: 0\n");
+    yaml.push_str(&format!(" DriverCarRedLine: {:.3}\n", number("max_rpm")));
+    yaml.push_str(&format!(" DriverCarFuelMaxLtr: {:.3}\n", number("max_fuel")));
+    yaml.push_str(" Drivers:\n - CarIdx: 0\n");
+    yaml.push_str(&format!("   UserName: {}\n", yaml_scalar(&driver)));
+    yaml.push_str(&format!("   CarScreenName: {}\n", car));
+    yaml.push_str(&format!("   CarPath: {}\n", car));
+    yaml.push_str("   CarNumber: \"0\"\n\n");
+    yaml.push_str("SplitTimeInfo:\n Sectors:\n - SectorNum: 0\n   SectorStartPct: 0.000000\n\n...\n");
+    yaml
+}
+
+/// Quotes text the session info parser would otherwise read as a key or
+/// list item.
+fn yaml_scalar(text: &str) -> String {
+    if text.contains(": ") || text.starts_with(['-', '"']) {
+        format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::acc::{AccSessionType, AssettoCorsaCompetizione, Graphics, Physics, StaticInfo};
+    use crate::iracing::IbtFile;
+    use crate::recording::Recorder;
+    use std::io::Cursor;
+    use std::time::Duration;
+
+    /// A practice of three completed laps and a race of two, each 10 s long
+    /// and sampled at 10 Hz.
+    fn recording() -> Recording<Cursor<Vec<u8>>> {
+        let mut recorder = Recorder::<AssettoCorsaCompetizione, _>::new(Vec::new(), &StaticInfo::default()).unwrap();
+        for i in 0..600 {
+            let time = Duration::from_millis(i as u64 * 100);
+            let (session, completed_laps) = if i < 350 {
+                (AccSessionType::Practice, i / 100)
+            } else {
+                (AccSessionType::Race, (i - 350) / 100)
+            };
+            let physics = Physics {
+                packet_id: i,
+                ..Physics::default()
+            };
+            let graphics = Graphics {
+                packet_id: i,
+                session: session.into(),
+                completed_laps,
+                ..Graphics::default()
+            };
+            recorder.write_physics(time, &physics).unwrap();
+            recorder.write_graphics(time, &graphics).unwrap();
+        }
+        Recording::new(Cursor::new(recorder.finish().unwrap())).unwrap()
+    }
+
+    #[test]
+    fn exported_laps_round_trip() {
+        let exporter = IbtExporter::<AssettoCorsaCompetizione>::new().with_rate(10);
+        let bytes = exporter.export(recording(), Cursor::new(Vec::new())).unwrap().into_inner();
+        let mut ibt = IbtFile::new(Cursor::new(bytes)).unwrap();
+
+        let sessions = &ibt.session_info().unwrap().sessions;
+        let types: Vec<_> = sessions.iter().map(|session| (session.number, session.session_type.as_str())).collect();
+        assert_eq!(types, [(0, "Practice"), (1, "Race")]);
+
+        let laps: Vec<_> = ibt.laps().unwrap().iter().map(|lap| (lap.session, lap.lap, lap.complete)).collect();
+        assert_eq!(
+            laps,
+            [
+                (0, 1, false),
+                (0, 2, true),
+                (0, 3, true),
+                (0, 4, false),
+                (1, 1, false),
+                (1, 2, true),
+                (1, 3, false),
+            ]
+        );
+        for lap in ibt.laps().unwrap().iter().filter(|lap| lap.complete) {
+            assert!((lap.duration() - 10.0).abs() < 0.2, "lap {} took {}s", lap.lap, lap.duration());
+        }
+    }
+}
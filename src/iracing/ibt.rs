@@ -0,0 +1,339 @@
+use crate::iracing::{HEADER_SIZE, Header, IRSDK_VERSION, IRacingError, Result, SessionInfo, Telemetry, VAR_HEADER_SIZE, VarTable, VarValue, decode_text};
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Size of the `irsdk_diskSubHeader` following the header in `.ibt` files.
+pub const DISK_HEADER_SIZE: usize = 32;
+
+/// The `irsdk_diskSubHeader` of an `.ibt` file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DiskHeader {
+    /// Unix time the session started.
+    pub session_start_date: i64,
+    /// `SessionTime` of the first and last record, in seconds.
+    pub session_start_time: f64,
+    pub session_end_time: f64,
+    pub lap_count: i32,
+    /// Zero when iRacing did not close the file.
+    pub record_count: i32,
+}
+
+impl DiskHeader {
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        let bytes = bytes.get(..DISK_HEADER_SIZE)?;
+        let field = |offset: usize| <[u8; 8]>::try_from(&bytes[offset..offset + 8]).unwrap();
+        Some(Self {
+            session_start_date: i64::from_le_bytes(field(0)),
+            session_start_time: f64::from_le_bytes(field(8)),
+            session_end_time: f64::from_le_bytes(field(16)),
+            lap_count: i32::from_le_bytes(bytes[24..28].try_into().unwrap()),
+            record_count: i32::from_le_bytes(bytes[28..32].try_into().unwrap()),
+        })
+    }
+
+    pub fn to_bytes(&self) -> [u8; DISK_HEADER_SIZE] {
+        let mut bytes = [0; DISK_HEADER_SIZE];
+        bytes[0..8].copy_from_slice(&self.session_start_date.to_le_bytes());
+        bytes[8..16].copy_from_slice(&self.session_start_time.to_le_bytes());
+        bytes[16..24].copy_from_slice(&self.session_end_time.to_le_bytes());
+        bytes[24..28].copy_from_slice(&self.lap_count.to_le_bytes());
+        bytes[28..32].copy_from_slice(&self.record_count.to_le_bytes());
+        bytes
+    }
+}
+
+/// A lap of an `.ibt` file, see [`IbtFile::laps`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct IbtLap {
+    /// The `SessionNum` variable during the lap, `0` in files without it.
+    pub session: i32,
+    /// The `Lap` variable during the lap.
+    pub lap: i32,
+    /// Indices of its records.
+    pub records: Range<usize>,
+    /// `SessionTime` at the start and the end of the lap, in seconds.
+    pub start_time: f64,
+    pub end_time: f64,
+    /// Whether the lap counter counted up by one both at the start and at
+    /// the end of the lap within the same session, the first and the last
+    /// lap of a session are usually partial.
+    pub complete: bool,
+}
+
+impl IbtLap {
+    /// In seconds.
+    pub fn duration(&self) -> f64 {
+        self.end_time - self.start_time
+    }
+}
+
+/// Reads the telemetry files iRacing writes to `Documents/iRacing/telemetry`.
+///
+/// They hold the same header, variables and session info as the
+/// memory-mapped file, followed by one record per sample, so the samples
+/// read as [`Telemetry`] and work on any platform.
+///
+/// ```no_run
+/// use race_sdk::iracing::IbtFile;
+///
+/// let mut ibt = IbtFile::open("mx5 mx52016_spa 2024-03-01 20-15-00.ibt").unwrap();
+/// if let Some(info) = ibt.session_info() {
+///     println!("{} at {}", info.drivers.player().map_or("", |driver| driver.car_screen_name.as_str()), info.weekend.track_display_name);
+/// }
+/// println!("{} samples at {} Hz", ibt.record_count(), ibt.header().tick_rate);
+///
+/// for lap in ibt.laps().unwrap().iter().filter(|lap| lap.complete) {
+///     println!("Lap {}: {:.3}s", lap.lap, lap.duration());
+/// }
+///
+/// let speeds = ibt.column::<f32>("Speed").unwrap();
+/// let top = speeds.iter().copied().fold(0.0, f32::max);
+/// println!("Top speed {:.1} km/h", top * 3.6);
+/// ```
+pub struct IbtFile<R> {
+    reader: R,
+    header: Header,
+    disk: DiskHeader,
+    vars: Arc<VarTable>,
+    session_info_text: String,
+    session_info: Option<SessionInfo>,
+    data_offset: u64,
+    record_len: usize,
+    record_count: usize,
+    position: usize,
+}
+
+impl IbtFile<BufReader<File>> {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read + Seek> IbtFile<R> {
+    /// Reads the headers, variables and session info, leaving the file at
+    /// its first record.
+    pub fn new(mut reader: R) -> Result<Self> {
+        let mut bytes = [0; HEADER_SIZE + DISK_HEADER_SIZE];
+        reader.read_exact(&mut bytes).map_err(|_| invalid("file shorter than its headers"))?;
+        let header = Header::parse(&bytes).ok_or_else(|| invalid("file shorter than its headers"))?;
+        if header.version != IRSDK_VERSION {
+            return Err(IRacingError::VersionMismatch {
+                expected: IRSDK_VERSION,
+                found: header.version,
+            });
+        }
+        let disk = DiskHeader::parse(&bytes[HEADER_SIZE..]).ok_or_else(|| invalid("file shorter than its headers"))?;
+
+        let var_len = (header.num_vars.max(0) as usize)
+            .checked_mul(VAR_HEADER_SIZE)
+            .ok_or_else(|| invalid("invalid variable headers"))?;
+        let var_bytes = read_at(&mut reader, header.var_header_offset, var_len)?;
+        let mut table_header = header.clone();
+        table_header.var_header_offset = 0;
+        let vars = VarTable::parse(&var_bytes, &table_header).ok_or_else(|| invalid("invalid variable headers"))?;
+        let session_bytes = read_at(&mut reader, header.session_info_offset, header.session_info_len.max(0) as usize)?;
+        let session_info_text = decode_text(&session_bytes);
+        let session_info = SessionInfo::parse(&session_info_text).ok();
+
+        let record_len = usize::try_from(header.buffer_len)
+            .ok()
+            .filter(|&len| len > 0)
+            .ok_or_else(|| invalid("empty records"))?;
+        let data_offset = u64::try_from(header.buffers[0].buffer_offset).map_err(|_| invalid("negative record offset"))?;
+        let available = (reader.seek(SeekFrom::End(0))?.saturating_sub(data_offset) / record_len as u64) as usize;
+        // Files of a crashed session have no record count, and the count
+        // of a truncated file exceeds its records.
+        let record_count = match usize::try_from(disk.record_count) {
+            Ok(count) if count > 0 => count.min(available),
+            _ => available,
+        };
+        reader.seek(SeekFrom::Start(data_offset))?;
+
+        Ok(Self {
+            reader,
+            header,
+            disk,
+            vars: Arc::new(vars),
+            session_info_text,
+            session_info,
+            data_offset,
+            record_len,
+            record_count,
+            position: 0,
+        })
+    }
+
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    pub fn disk_header(&self) -> &DiskHeader {
+        &self.disk
+    }
+
+    pub fn vars(&self) -> &Arc<VarTable> {
+        &self.vars
+    }
+
+    /// The parsed session info, `None` when the YAML could not be parsed.
+    pub fn session_info(&self) -> Option<&SessionInfo> {
+        self.session_info.as_ref()
+    }
+
+    pub fn session_info_text(&self) -> &str {
+        &self.session_info_text
+    }
+
+    /// The records in the file, which may be fewer than the disk header
+    /// claims when the file was cut off.
+    pub fn record_count(&self) -> usize {
+        self.record_count
+    }
+
+    /// Moves to record `index`, the next sample read.
+    pub fn seek(&mut self, index: usize) -> Result<()> {
+        let index = index.min(self.record_count);
+        self.reader.seek(SeekFrom::Start(self.data_offset + (index * self.record_len) as u64))?;
+        self.position = index;
+        Ok(())
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Reads the next record, `None` after the last one.
+    pub fn next_sample(&mut self) -> Result<Option<Telemetry>> {
+        if self.position >= self.record_count {
+            return Ok(None);
+        }
+        let mut data = vec![0; self.record_len];
+        self.reader.read_exact(&mut data)?;
+        let index = self.position;
+        self.position += 1;
+
+        let mut telemetry = Telemetry {
+            tick_count: index as i32,
+            session_info_update: self.header.session_info_update,
+            vars: self.vars.clone(),
+            data,
+        };
+        if let Some(tick) = telemetry.get::<i32>("SessionTick") {
+            telemetry.tick_count = tick;
+        }
+        Ok(Some(telemetry))
+    }
+
+    /// Reads record `index`.
+    pub fn sample(&mut self, index: usize) -> Result<Telemetry> {
+        self.seek(index)?;
+        self.next_sample()?
+            .ok_or_else(|| invalid(&format!("record {} of {}", index, self.record_count)))
+    }
+
+    /// Reads one variable of every record, e.g. for plotting.
+    ///
+    /// Leaves the file after its last record.
+    pub fn column<T: VarValue>(&mut self, name: &str) -> Result<Vec<T>> {
+        self.check_var::<T>(name)?;
+        self.seek(0)?;
+        let mut values = Vec::with_capacity(self.record_count);
+        while let Some(sample) = self.next_sample()? {
+            values.extend(sample.get::<T>(name));
+        }
+        Ok(values)
+    }
+
+    /// Splits the records into laps where the `Lap` or the `SessionNum`
+    /// variable changes.
+    ///
+    /// Leaves the file after its last record.
+    pub fn laps(&mut self) -> Result<Vec<IbtLap>> {
+        self.check_var::<i32>("Lap")?;
+        self.check_var::<f64>("SessionTime")?;
+        if self.vars.get("SessionNum").is_some() {
+            self.check_var::<i32>("SessionNum")?;
+        }
+        self.seek(0)?;
+
+        let mut laps: Vec<IbtLap> = Vec::new();
+        while let Some(sample) = self.next_sample()? {
+            let index = self.position - 1;
+            let session = sample.get::<i32>("SessionNum").unwrap_or_default();
+            let lap = sample.get::<i32>("Lap").unwrap_or_default();
+            let time = sample.get::<f64>("SessionTime").unwrap_or_default();
+            match laps.last_mut() {
+                Some(current) if current.session == session && current.lap == lap => {
+                    current.records.end = index + 1;
+                    current.end_time = time;
+                }
+                previous => {
+                    let mut started = false;
+                    if let Some(previous) = previous {
+                        // `SessionTime` starts over in a new session.
+                        if previous.session == session {
+                            previous.end_time = time;
+                            started = lap == previous.lap + 1;
+                        }
+                        previous.complete &= started;
+                    }
+                    laps.push(IbtLap {
+                        session,
+                        lap,
+                        records: index..index + 1,
+                        start_time: time,
+                        end_time: time,
+                        complete: started,
+                    });
+                }
+            }
+        }
+        if let Some(last) = laps.last_mut() {
+            last.complete = false;
+        }
+        Ok(laps)
+    }
+
+    /// Moves to the start of a lap and returns its samples.
+    pub fn lap_samples(&mut self, lap: &IbtLap) -> Result<impl Iterator<Item = Result<Telemetry>> + '_> {
+        self.seek(lap.records.start)?;
+        Ok(self.by_ref().take(lap.records.len()))
+    }
+
+    fn check_var<T: VarValue>(&self, name: &str) -> Result<()> {
+        match self.vars.get(name) {
+            Some(var) if T::accepts(var.var_type) => Ok(()),
+            Some(var) => Err(invalid(&format!("variable {} is {:?}", name, var.var_type))),
+            None => Err(invalid(&format!("no variable {}", name))),
+        }
+    }
+}
+
+impl<R: Read + Seek> Iterator for IbtFile<R> {
+    type Item = Result<Telemetry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_sample().transpose()
+    }
+}
+
+/// Reads `len` bytes at `offset`, checking they are inside the file
+/// before allocating them.
+fn read_at<R: Read + Seek>(reader: &mut R, offset: i32, len: usize) -> Result<Vec<u8>> {
+    let offset = u64::try_from(offset).map_err(|_| invalid("negative offset"))?;
+    let file_len = reader.seek(SeekFrom::End(0))?;
+    if offset.checked_add(len as u64).is_none_or(|end| end > file_len) {
+        return Err(invalid("file shorter than its headers"));
+    }
+    reader.seek(SeekFrom::Start(offset))?;
+    let mut bytes = vec![0; len];
+    reader.read_exact(&mut bytes).map_err(|_| invalid("file shorter than its headers"))?;
+    Ok(bytes)
+}
+
+fn invalid(msg: &str) -> IRacingError {
+    IRacingError::InvalidData(msg.to_string())
+}
@@ -0,0 +1,156 @@
+pub mod client;
+pub mod exporter;
+pub mod ibt;
+pub mod session;
+pub mod types;
+pub mod writer;
+pub mod yaml;
+pub use client::*;
+pub use exporter::*;
+pub use ibt::*;
+pub use session::*;
+pub use types::*;
+pub use writer::*;
+pub use yaml::*;
+
+use crate::SharedMemory;
+use crate::recording::RecordingError;
+use crate::telemetry::Sampler;
+use std::fmt;
+use std::io;
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub enum IRacingError {
+    SharedMemoryNotFound(String),
+    ConnectionFailed(String),
+    InvalidData(String),
+    VersionMismatch { expected: i32, found: i32 },
+    Io(io::Error),
+    Recording(RecordingError),
+}
+
+impl fmt::Display for IRacingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IRacingError::SharedMemoryNotFound(msg) => write!(f, "Shared memory not found: {}", msg),
+            IRacingError::ConnectionFailed(msg) => write!(f, "Connection failed: {}", msg),
+            IRacingError::InvalidData(msg) => write!(f, "Invalid data format: {}", msg),
+            IRacingError::VersionMismatch { expected, found } => {
+                write!(f, "Header version {} does not match the supported {}", found, expected)
+            }
+            IRacingError::Io(err) => write!(f, "I/O error: {}", err),
+            IRacingError::Recording(err) => write!(f, "Recording error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for IRacingError {}
+
+impl From<io::Error> for IRacingError {
+    fn from(err: io::Error) -> Self {
+        IRacingError::Io(err)
+    }
+}
+
+impl From<RecordingError> for IRacingError {
+    fn from(err: RecordingError) -> Self {
+        IRacingError::Recording(err)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, IRacingError>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConnectionStatus {
+    Disconnected,
+    Connected,
+}
+
+/// iRacing, read through its self-describing `IRSDKMemMapFileName`
+/// memory-mapped file.
+///
+/// ```no_run
+/// use race_sdk::iracing::IRacing;
+///
+/// let mut iracing = IRacing::new();
+/// iracing.connect().unwrap();
+///
+/// for sample in iracing.sampler().take(60) {
+///     let rpm = sample.frame.get::<f32>("RPM").unwrap_or_default();
+///     let gear = sample.frame.get::<i32>("Gear").unwrap_or_default();
+///     println!("{} gear {} at {:.0} rpm", sample.packet_id, gear, rpm);
+/// }
+/// ```
+pub struct IRacing {
+    client: IRacingClient,
+}
+
+impl IRacing {
+    pub fn new() -> Self {
+        Self { client: IRacingClient::new() }
+    }
+
+    /// Creates a facade reading from a custom memory source, see
+    /// [`IRacingClient::from_shared_memory`].
+    pub fn from_shared_memory(memory: Box<dyn SharedMemory>) -> Self {
+        Self {
+            client: IRacingClient::from_shared_memory(memory),
+        }
+    }
+
+    pub fn connect(&mut self) -> Result<()> {
+        self.client.connect()
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.client.is_connected()
+    }
+
+    pub fn status(&self) -> ConnectionStatus {
+        if self.is_connected() {
+            ConnectionStatus::Connected
+        } else {
+            ConnectionStatus::Disconnected
+        }
+    }
+
+    /// Whether the simulator is in a session.
+    pub fn is_sim_running(&self) -> bool {
+        self.client.is_sim_running()
+    }
+
+    pub fn get_header(&self) -> Option<Header> {
+        self.client.get_header()
+    }
+
+    pub fn get_vars(&self) -> Option<Arc<VarTable>> {
+        self.client.get_vars()
+    }
+
+    /// Copies the latest sample, see [`IRacingClient::get_telemetry`].
+    pub fn get_telemetry(&self) -> Option<Telemetry> {
+        self.client.get_telemetry()
+    }
+
+    /// The parsed session info, see [`IRacingClient::get_session_info`].
+    pub fn get_session_info(&self) -> Option<Arc<SessionInfo>> {
+        self.client.get_session_info()
+    }
+
+    /// Reads one variable of the latest sample, see [`Telemetry::get`].
+    pub fn get<T: VarValue>(&self, name: &str) -> Option<T> {
+        self.client.get_telemetry()?.get(name)
+    }
+
+    /// Returns a sampler that emits every new tick.
+    pub fn sampler(&self) -> Sampler<Telemetry, impl FnMut() -> Option<Telemetry> + '_> {
+        Sampler::new(|| self.client.get_telemetry())
+    }
+}
+
+impl Default for IRacing {
+    fn default() -> Self {
+        Self::new()
+    }
+}
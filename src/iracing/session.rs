@@ -0,0 +1,456 @@
+use crate::iracing::{Result, YamlValue};
+
+/// The session info iRacing publishes as YAML next to the telemetry, in
+/// the memory-mapped file and in `.ibt` files.
+///
+/// The commonly used fields are typed, units stripped from the values;
+/// everything else, including the car-specific setup, stays reachable
+/// through [`root`](Self::root).
+///
+/// ```no_run
+/// use race_sdk::iracing::IRacing;
+///
+/// let mut iracing = IRacing::new();
+/// iracing.connect().unwrap();
+///
+/// if let Some(info) = iracing.get_session_info() {
+///     println!("{} ({:.2} km)", info.weekend.track_display_name, info.weekend.track_length_km);
+///     for driver in &info.drivers.drivers {
+///         println!("#{} {} - {} ({})", driver.car_number, driver.user_name, driver.car_screen_name, driver.i_rating);
+///     }
+///     if let Some(pressure) = info.car_setup.as_ref().and_then(|setup| setup.path("Tires.LeftFront.StartingPressure")) {
+///         println!("LF starting pressure {:?} {:?}", pressure.number(), pressure.unit());
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionInfo {
+    pub weekend: WeekendInfo,
+    /// `SessionInfo.Sessions`.
+    pub sessions: Vec<Session>,
+    pub drivers: DriverInfo,
+    /// `SplitTimeInfo.Sectors`.
+    pub sectors: Vec<Sector>,
+    /// The car-specific `CarSetup`, missing unless the setup may be shown.
+    pub car_setup: Option<YamlValue>,
+    pub root: YamlValue,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WeekendInfo {
+    pub track_name: String,
+    pub track_id: i32,
+    pub track_length_km: f32,
+    pub track_display_name: String,
+    pub track_display_short_name: String,
+    pub track_config_name: String,
+    pub track_city: String,
+    pub track_country: String,
+    pub track_num_turns: i32,
+    pub track_pit_speed_limit_kph: f32,
+    pub track_type: String,
+    pub track_weather_type: String,
+    pub track_skies: String,
+    pub track_surface_temp_c: f32,
+    pub track_air_temp_c: f32,
+    pub track_air_pressure_hg: f32,
+    /// In m/s.
+    pub track_wind_vel: f32,
+    /// In radians.
+    pub track_wind_dir: f32,
+    /// In percent.
+    pub track_relative_humidity: f32,
+    pub series_id: i32,
+    pub season_id: i32,
+    pub session_id: i32,
+    pub sub_session_id: i32,
+    pub league_id: i32,
+    pub official: bool,
+    pub race_week: i32,
+    pub event_type: String,
+    pub category: String,
+    pub sim_mode: String,
+    pub team_racing: bool,
+    pub num_car_classes: i32,
+    pub num_car_types: i32,
+    pub build_version: String,
+    pub options: WeekendOptions,
+}
+
+/// `WeekendInfo.WeekendOptions`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WeekendOptions {
+    pub num_starters: i32,
+    pub starting_grid: String,
+    pub qualify_scoring: String,
+    pub course_cautions: String,
+    pub standing_start: bool,
+    pub restarts: String,
+    pub weather_type: String,
+    pub skies: String,
+    pub time_of_day: String,
+    pub date: String,
+    pub is_fixed_setup: bool,
+    /// `None` when unlimited.
+    pub incident_limit: Option<i32>,
+    pub fast_repairs_limit: Option<i32>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Session {
+    pub number: i32,
+    /// `None` when unlimited.
+    pub laps: Option<i32>,
+    /// In seconds, `None` when unlimited.
+    pub time: Option<f32>,
+    /// E.g. `Practice`, `Lone Qualify` or `Race`.
+    pub session_type: String,
+    pub session_name: String,
+    pub skipped: bool,
+    pub track_rubber_state: String,
+    pub results_positions: Vec<ResultPosition>,
+    pub results_fastest_lap: Vec<FastestLap>,
+    pub results_average_lap_time: f32,
+    pub results_num_caution_flags: i32,
+    pub results_num_caution_laps: i32,
+    pub results_num_lead_changes: i32,
+    pub results_laps_complete: i32,
+    pub results_official: bool,
+}
+
+/// One entry of `ResultsPositions`. Times are in seconds, negative when
+/// not set.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResultPosition {
+    pub position: i32,
+    pub class_position: i32,
+    pub car_idx: i32,
+    pub lap: i32,
+    pub time: f32,
+    pub fastest_lap: i32,
+    pub fastest_time: f32,
+    pub last_time: f32,
+    pub laps_led: i32,
+    pub laps_complete: i32,
+    pub laps_driven: f32,
+    pub incidents: i32,
+    pub reason_out_id: i32,
+    pub reason_out: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FastestLap {
+    pub car_idx: i32,
+    pub fastest_lap: i32,
+    pub fastest_time: f32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DriverInfo {
+    /// Index of the player's car in the `CarIdx` arrays.
+    pub driver_car_idx: i32,
+    pub driver_user_id: i32,
+    pub pace_car_idx: i32,
+    pub driver_car_idle_rpm: f32,
+    pub driver_car_red_line: f32,
+    pub driver_car_fuel_kg_per_ltr: f32,
+    pub driver_car_fuel_max_ltr: f32,
+    pub driver_car_max_fuel_pct: f32,
+    pub driver_car_gear_num_forward: i32,
+    pub driver_car_sl_first_rpm: f32,
+    pub driver_car_sl_shift_rpm: f32,
+    pub driver_car_sl_last_rpm: f32,
+    pub driver_car_sl_blink_rpm: f32,
+    /// In seconds.
+    pub driver_car_est_lap_time: f32,
+    pub driver_setup_name: String,
+    pub driver_setup_is_modified: bool,
+    pub driver_incident_count: i32,
+    pub drivers: Vec<Driver>,
+}
+
+impl DriverInfo {
+    /// The entry of the car at `car_idx`.
+    pub fn driver(&self, car_idx: i32) -> Option<&Driver> {
+        self.drivers.iter().find(|driver| driver.car_idx == car_idx)
+    }
+
+    pub fn player(&self) -> Option<&Driver> {
+        self.driver(self.driver_car_idx)
+    }
+}
+
+/// One entry of `DriverInfo.Drivers`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Driver {
+    pub car_idx: i32,
+    pub user_name: String,
+    pub abbrev_name: String,
+    pub initials: String,
+    pub user_id: i32,
+    pub team_id: i32,
+    pub team_name: String,
+    /// As displayed, e.g. `007`.
+    pub car_number: String,
+    pub car_number_raw: i32,
+    pub car_path: String,
+    pub car_class_id: i32,
+    pub car_id: i32,
+    pub car_is_pace_car: bool,
+    pub car_is_ai: bool,
+    pub car_screen_name: String,
+    pub car_screen_name_short: String,
+    pub car_class_short_name: String,
+    pub car_class_rel_speed: i32,
+    pub car_class_est_lap_time: f32,
+    /// `None` when iRacing writes `0xundefined`.
+    pub car_class_color: Option<u32>,
+    pub i_rating: i32,
+    pub lic_level: i32,
+    pub lic_sub_level: i32,
+    pub lic_string: String,
+    pub lic_color: Option<u32>,
+    pub is_spectator: bool,
+    pub club_name: String,
+    pub division_name: String,
+    pub cur_driver_incident_count: i32,
+    pub team_incident_count: i32,
+}
+
+/// One entry of `SplitTimeInfo.Sectors`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Sector {
+    pub number: i32,
+    /// Fraction of the lap where the sector starts.
+    pub start_pct: f32,
+}
+
+impl SessionInfo {
+    pub fn parse(text: &str) -> Result<Self> {
+        let root = YamlValue::parse(text)?;
+        Ok(Self::from_yaml(root))
+    }
+
+    /// Parses the null-terminated string of the memory-mapped file or an
+    /// `.ibt` file, which iRacing writes in Latin-1.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Self::parse(&decode_text(bytes))
+    }
+
+    pub fn from_yaml(root: YamlValue) -> Self {
+        let empty = YamlValue::Scalar(String::new());
+        let node = |key: &str| root.get(key).unwrap_or(&empty);
+        let sessions = node("SessionInfo").get("Sessions").unwrap_or(&empty);
+        let sectors = node("SplitTimeInfo").get("Sectors").unwrap_or(&empty);
+        Self {
+            weekend: weekend_info(node("WeekendInfo")),
+            sessions: sessions.items().iter().map(session).collect(),
+            drivers: driver_info(node("DriverInfo")),
+            sectors: sectors
+                .items()
+                .iter()
+                .map(|sector| Sector {
+                    number: int(sector, "SectorNum"),
+                    start_pct: float(sector, "SectorStartPct"),
+                })
+                .collect(),
+            car_setup: root.get("CarSetup").cloned(),
+            root,
+        }
+    }
+
+    pub fn session(&self, number: i32) -> Option<&Session> {
+        self.sessions.iter().find(|session| session.number == number)
+    }
+}
+
+fn weekend_info(node: &YamlValue) -> WeekendInfo {
+    let empty = YamlValue::Scalar(String::new());
+    let options = node.get("WeekendOptions").unwrap_or(&empty);
+    WeekendInfo {
+        track_name: text(node, "TrackName"),
+        track_id: int(node, "TrackID"),
+        track_length_km: float(node, "TrackLength"),
+        track_display_name: text(node, "TrackDisplayName"),
+        track_display_short_name: text(node, "TrackDisplayShortName"),
+        track_config_name: text(node, "TrackConfigName"),
+        track_city: text(node, "TrackCity"),
+        track_country: text(node, "TrackCountry"),
+        track_num_turns: int(node, "TrackNumTurns"),
+        track_pit_speed_limit_kph: float(node, "TrackPitSpeedLimit"),
+        track_type: text(node, "TrackType"),
+        track_weather_type: text(node, "TrackWeatherType"),
+        track_skies: text(node, "TrackSkies"),
+        track_surface_temp_c: float(node, "TrackSurfaceTemp"),
+        track_air_temp_c: float(node, "TrackAirTemp"),
+        track_air_pressure_hg: float(node, "TrackAirPressure"),
+        track_wind_vel: float(node, "TrackWindVel"),
+        track_wind_dir: float(node, "TrackWindDir"),
+        track_relative_humidity: float(node, "TrackRelativeHumidity"),
+        series_id: int(node, "SeriesID"),
+        season_id: int(node, "SeasonID"),
+        session_id: int(node, "SessionID"),
+        sub_session_id: int(node, "SubSessionID"),
+        league_id: int(node, "LeagueID"),
+        official: flag(node, "Official"),
+        race_week: int(node, "RaceWeek"),
+        event_type: text(node, "EventType"),
+        category: text(node, "Category"),
+        sim_mode: text(node, "SimMode"),
+        team_racing: flag(node, "TeamRacing"),
+        num_car_classes: int(node, "NumCarClasses"),
+        num_car_types: int(node, "NumCarTypes"),
+        build_version: text(node, "BuildVersion"),
+        options: WeekendOptions {
+            num_starters: int(options, "NumStarters"),
+            starting_grid: text(options, "StartingGrid"),
+            qualify_scoring: text(options, "QualifyScoring"),
+            course_cautions: text(options, "CourseCautions"),
+            standing_start: flag(options, "StandingStart"),
+            restarts: text(options, "Restarts"),
+            weather_type: text(options, "WeatherType"),
+            skies: text(options, "Skies"),
+            time_of_day: text(options, "TimeOfDay"),
+            date: text(options, "Date"),
+            is_fixed_setup: flag(options, "IsFixedSetup"),
+            incident_limit: limit(options, "IncidentLimit"),
+            fast_repairs_limit: limit(options, "FastRepairsLimit"),
+        },
+    }
+}
+
+fn session(node: &YamlValue) -> Session {
+    let list = |key: &str| node.get(key).map(YamlValue::items).unwrap_or_default();
+    Session {
+        number: int(node, "SessionNum"),
+        laps: limit(node, "SessionLaps"),
+        time: node.get("SessionTime").and_then(YamlValue::number).map(|time| time as f32),
+        session_type: text(node, "SessionType"),
+        session_name: text(node, "SessionName"),
+        skipped: flag(node, "SessionSkipped"),
+        track_rubber_state: text(node, "SessionTrackRubberState"),
+        results_positions: list("ResultsPositions").iter().map(result_position).collect(),
+        results_fastest_lap: list("ResultsFastestLap")
+            .iter()
+            .map(|lap| FastestLap {
+                car_idx: int(lap, "CarIdx"),
+                fastest_lap: int(lap, "FastestLap"),
+                fastest_time: float(lap, "FastestTime"),
+            })
+            .collect(),
+        results_average_lap_time: float(node, "ResultsAverageLapTime"),
+        results_num_caution_flags: int(node, "ResultsNumCautionFlags"),
+        results_num_caution_laps: int(node, "ResultsNumCautionLaps"),
+        results_num_lead_changes: int(node, "ResultsNumLeadChanges"),
+        results_laps_complete: int(node, "ResultsLapsComplete"),
+        results_official: flag(node, "ResultsOfficial"),
+    }
+}
+
+fn result_position(node: &YamlValue) -> ResultPosition {
+    ResultPosition {
+        position: int(node, "Position"),
+        class_position: int(node, "ClassPosition"),
+        car_idx: int(node, "CarIdx"),
+        lap: int(node, "Lap"),
+        time: float(node, "Time"),
+        fastest_lap: int(node, "FastestLap"),
+        fastest_time: float(node, "FastestTime"),
+        last_time: float(node, "LastTime"),
+        laps_led: int(node, "LapsLed"),
+        laps_complete: int(node, "LapsComplete"),
+        laps_driven: float(node, "LapsDriven"),
+        incidents: int(node, "Incidents"),
+        reason_out_id: int(node, "ReasonOutId"),
+        reason_out: text(node, "ReasonOutStr"),
+    }
+}
+
+fn driver_info(node: &YamlValue) -> DriverInfo {
+    DriverInfo {
+        driver_car_idx: int(node, "DriverCarIdx"),
+        driver_user_id: int(node, "DriverUserID"),
+        pace_car_idx: int(node, "PaceCarIdx"),
+        driver_car_idle_rpm: float(node, "DriverCarIdleRPM"),
+        driver_car_red_line: float(node, "DriverCarRedLine"),
+        driver_car_fuel_kg_per_ltr: float(node, "DriverCarFuelKgPerLtr"),
+        driver_car_fuel_max_ltr: float(node, "DriverCarFuelMaxLtr"),
+        driver_car_max_fuel_pct: float(node, "DriverCarMaxFuelPct"),
+        driver_car_gear_num_forward: int(node, "DriverCarGearNumForward"),
+        driver_car_sl_first_rpm: float(node, "DriverCarSLFirstRPM"),
+        driver_car_sl_shift_rpm: float(node, "DriverCarSLShiftRPM"),
+        driver_car_sl_last_rpm: float(node, "DriverCarSLLastRPM"),
+        driver_car_sl_blink_rpm: float(node, "DriverCarSLBlinkRPM"),
+        driver_car_est_lap_time: float(node, "DriverCarEstLapTime"),
+        driver_setup_name: text(node, "DriverSetupName"),
+        driver_setup_is_modified: flag(node, "DriverSetupIsModified"),
+        driver_incident_count: int(node, "DriverIncidentCount"),
+        drivers: node.get("Drivers").map(YamlValue::items).unwrap_or_default().iter().map(driver).collect(),
+    }
+}
+
+fn driver(node: &YamlValue) -> Driver {
+    let color = |key: &str| node.get(key).and_then(YamlValue::number).map(|color| color as u32);
+    Driver {
+        car_idx: int(node, "CarIdx"),
+        user_name: text(node, "UserName"),
+        abbrev_name: text(node, "AbbrevName"),
+        initials: text(node, "Initials"),
+        user_id: int(node, "UserID"),
+        team_id: int(node, "TeamID"),
+        team_name: text(node, "TeamName"),
+        car_number: text(node, "CarNumber"),
+        car_number_raw: int(node, "CarNumberRaw"),
+        car_path: text(node, "CarPath"),
+        car_class_id: int(node, "CarClassID"),
+        car_id: int(node, "CarID"),
+        car_is_pace_car: flag(node, "CarIsPaceCar"),
+        car_is_ai: flag(node, "CarIsAI"),
+        car_screen_name: text(node, "CarScreenName"),
+        car_screen_name_short: text(node, "CarScreenNameShort"),
+        car_class_short_name: text(node, "CarClassShortName"),
+        car_class_rel_speed: int(node, "CarClassRelSpeed"),
+        car_class_est_lap_time: float(node, "CarClassEstLapTime"),
+        car_class_color: color("CarClassColor"),
+        i_rating: int(node, "IRating"),
+        lic_level: int(node, "LicLevel"),
+        lic_sub_level: int(node, "LicSubLevel"),
+        lic_string: text(node, "LicString"),
+        lic_color: color("LicColor"),
+        is_spectator: flag(node, "IsSpectator"),
+        club_name: text(node, "ClubName"),
+        division_name: text(node, "DivisionName"),
+        cur_driver_incident_count: int(node, "CurDriverIncidentCount"),
+        team_incident_count: int(node, "TeamIncidentCount"),
+    }
+}
+
+fn text(node: &YamlValue, key: &str) -> String {
+    node.get(key).and_then(YamlValue::as_str).unwrap_or_default().to_string()
+}
+
+fn int(node: &YamlValue, key: &str) -> i32 {
+    node.get(key).and_then(YamlValue::number).map_or(0, |value| value as i32)
+}
+
+fn float(node: &YamlValue, key: &str) -> f32 {
+    node.get(key).and_then(YamlValue::number).map_or(0.0, |value| value as f32)
+}
+
+fn flag(node: &YamlValue, key: &str) -> bool {
+    node.get(key).and_then(YamlValue::number).is_some_and(|value| value != 0.0)
+}
+
+/// A count iRacing writes as `unlimited` when there is none.
+fn limit(node: &YamlValue, key: &str) -> Option<i32> {
+    node.get(key).and_then(YamlValue::number).map(|value| value as i32)
+}
+
+/// Decodes a null-terminated string as UTF-8 where valid, else Latin-1.
+pub(crate) fn decode_text(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&c| c == 0).unwrap_or(bytes.len());
+    match std::str::from_utf8(&bytes[..end]) {
+        Ok(text) => text.to_string(),
+        Err(_) => bytes[..end].iter().map(|&c| char::from(c)).collect(),
+    }
+}
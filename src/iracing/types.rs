@@ -0,0 +1,649 @@
+use crate::iracing::{IRacingError, Result};
+use crate::telemetry::Packet;
+use crate::utils::flags::bit_flags;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// The `ver` of the memory-mapped file these types describe.
+pub const IRSDK_VERSION: i32 = 2;
+pub const MEM_MAP_FILE_NAME: &str = r"Local\IRSDKMemMapFileName";
+pub const MAX_BUFFERS: usize = 4;
+pub const HEADER_SIZE: usize = 112;
+pub const VAR_HEADER_SIZE: usize = 144;
+
+const VAR_NAME_LENGTH: usize = 32;
+const VAR_DESC_LENGTH: usize = 64;
+
+/// The `irsdk_stConnected` bit of the header status.
+const STATUS_CONNECTED: i32 = 1;
+
+/// One of the rotating data buffers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VarBuffer {
+    pub tick_count: i32,
+    /// From the start of the file.
+    pub buffer_offset: i32,
+}
+
+/// The `irsdk_header` at the start of the file. Offsets are from the start
+/// of the file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Header {
+    pub version: i32,
+    pub status: i32,
+    /// Samples per second.
+    pub tick_rate: i32,
+    /// Increases whenever the session info changes.
+    pub session_info_update: i32,
+    pub session_info_len: i32,
+    pub session_info_offset: i32,
+    pub num_vars: i32,
+    pub var_header_offset: i32,
+    pub num_buffers: i32,
+    pub buffer_len: i32,
+    pub buffers: [VarBuffer; MAX_BUFFERS],
+}
+
+impl Header {
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        let bytes = bytes.get(..HEADER_SIZE)?;
+        let int = |offset: usize| read_i32(bytes, offset);
+        Some(Self {
+            version: int(0),
+            status: int(4),
+            tick_rate: int(8),
+            session_info_update: int(12),
+            session_info_len: int(16),
+            session_info_offset: int(20),
+            num_vars: int(24),
+            var_header_offset: int(28),
+            num_buffers: int(32),
+            buffer_len: int(36),
+            buffers: [0, 1, 2, 3].map(|i| VarBuffer {
+                tick_count: int(48 + i * 16),
+                buffer_offset: int(52 + i * 16),
+            }),
+        })
+    }
+
+    pub fn to_bytes(&self) -> [u8; HEADER_SIZE] {
+        let mut bytes = [0; HEADER_SIZE];
+        let fields = [
+            self.version,
+            self.status,
+            self.tick_rate,
+            self.session_info_update,
+            self.session_info_len,
+            self.session_info_offset,
+            self.num_vars,
+            self.var_header_offset,
+            self.num_buffers,
+            self.buffer_len,
+        ];
+        for (i, value) in fields.iter().enumerate() {
+            bytes[i * 4..i * 4 + 4].copy_from_slice(&value.to_le_bytes());
+        }
+        for (i, buffer) in self.buffers.iter().enumerate() {
+            bytes[48 + i * 16..52 + i * 16].copy_from_slice(&buffer.tick_count.to_le_bytes());
+            bytes[52 + i * 16..56 + i * 16].copy_from_slice(&buffer.buffer_offset.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Whether iRacing is running a session, the file stays mapped for a
+    /// while after it left.
+    pub fn is_connected(&self) -> bool {
+        self.status & STATUS_CONNECTED != 0
+    }
+
+    /// The buffer with the highest tick count, the one written last.
+    pub fn latest_buffer(&self) -> Option<(usize, VarBuffer)> {
+        let count = usize::try_from(self.num_buffers).ok()?.min(MAX_BUFFERS);
+        self.buffers[..count].iter().copied().enumerate().max_by_key(|(_, buffer)| buffer.tick_count)
+    }
+
+    /// The bytes the file needs to hold everything the header points at.
+    pub fn required_size(&self) -> usize {
+        let end = |offset: i32, len: i64| (i64::from(offset) + len).max(0) as usize;
+        let mut size = HEADER_SIZE
+            .max(end(self.var_header_offset, i64::from(self.num_vars) * VAR_HEADER_SIZE as i64))
+            .max(end(self.session_info_offset, i64::from(self.session_info_len)));
+        let count = usize::try_from(self.num_buffers).unwrap_or(0).min(MAX_BUFFERS);
+        for buffer in &self.buffers[..count] {
+            size = size.max(end(buffer.buffer_offset, i64::from(self.buffer_len)));
+        }
+        size
+    }
+
+    /// Reads the tick count of buffer `index` straight from the file.
+    pub fn tick_count_of(bytes: &[u8], index: usize) -> Option<i32> {
+        bytes.get(48 + index * 16..52 + index * 16).map(|bytes| read_i32(bytes, 0))
+    }
+}
+
+/// The `irsdk_VarType` of a variable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum VarType {
+    Char = 0,
+    Bool = 1,
+    Int = 2,
+    BitField = 3,
+    Float = 4,
+    Double = 5,
+}
+
+impl VarType {
+    pub fn from_raw(value: i32) -> Option<Self> {
+        Some(match value {
+            0 => VarType::Char,
+            1 => VarType::Bool,
+            2 => VarType::Int,
+            3 => VarType::BitField,
+            4 => VarType::Float,
+            5 => VarType::Double,
+            _ => return None,
+        })
+    }
+
+    /// Bytes per element.
+    pub fn size(self) -> usize {
+        match self {
+            VarType::Char | VarType::Bool => 1,
+            VarType::Int | VarType::BitField | VarType::Float => 4,
+            VarType::Double => 8,
+        }
+    }
+}
+
+/// The `irsdk_varHeader` describing one variable.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VarHeader {
+    pub var_type: VarType,
+    /// From the start of a data buffer.
+    pub offset: usize,
+    /// Elements, more than one for arrays such as `CarIdxLapDistPct`.
+    pub count: usize,
+    /// Whether the elements are samples over the last tick rather than an
+    /// array.
+    pub count_as_time: bool,
+    pub name: String,
+    pub description: String,
+    pub unit: String,
+}
+
+impl VarHeader {
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        let bytes = bytes.get(..VAR_HEADER_SIZE)?;
+        Some(Self {
+            var_type: VarType::from_raw(read_i32(bytes, 0))?,
+            offset: usize::try_from(read_i32(bytes, 4)).ok()?,
+            count: usize::try_from(read_i32(bytes, 8)).ok()?,
+            count_as_time: bytes[12] != 0,
+            name: read_str(&bytes[16..16 + VAR_NAME_LENGTH]),
+            description: read_str(&bytes[48..48 + VAR_DESC_LENGTH]),
+            unit: read_str(&bytes[112..112 + VAR_NAME_LENGTH]),
+        })
+    }
+
+    /// Describes a variable, its offset is assigned when it is added to a
+    /// record layout, see [`IbtWriter::new`](crate::iracing::IbtWriter::new).
+    pub fn new(name: &str, var_type: VarType, count: usize, unit: &str, description: &str) -> Self {
+        Self {
+            var_type,
+            offset: 0,
+            count,
+            count_as_time: false,
+            name: name.to_string(),
+            description: description.to_string(),
+            unit: unit.to_string(),
+        }
+    }
+
+    /// Bytes of all elements.
+    pub fn size(&self) -> usize {
+        self.var_type.size() * self.count
+    }
+
+    /// Names longer than their fields are cut.
+    pub fn to_bytes(&self) -> [u8; VAR_HEADER_SIZE] {
+        let mut bytes = [0; VAR_HEADER_SIZE];
+        bytes[0..4].copy_from_slice(&(self.var_type as i32).to_le_bytes());
+        bytes[4..8].copy_from_slice(&(self.offset as i32).to_le_bytes());
+        bytes[8..12].copy_from_slice(&(self.count as i32).to_le_bytes());
+        bytes[12] = u8::from(self.count_as_time);
+        write_str(&mut bytes[16..16 + VAR_NAME_LENGTH], &self.name);
+        write_str(&mut bytes[48..48 + VAR_DESC_LENGTH], &self.description);
+        write_str(&mut bytes[112..112 + VAR_NAME_LENGTH], &self.unit);
+        bytes
+    }
+}
+
+/// The variables of a session, looked up by name.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VarTable {
+    vars: Vec<VarHeader>,
+    by_name: HashMap<String, usize>,
+}
+
+impl VarTable {
+    /// Reads the variable headers the header points at.
+    pub fn parse(bytes: &[u8], header: &Header) -> Option<Self> {
+        let start = usize::try_from(header.var_header_offset).ok()?;
+        let count = usize::try_from(header.num_vars).ok()?;
+        let vars = (0..count)
+            .map(|i| VarHeader::parse(bytes.get(start + i * VAR_HEADER_SIZE..)?))
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self::new(vars))
+    }
+
+    pub fn new(vars: Vec<VarHeader>) -> Self {
+        let by_name = vars.iter().enumerate().map(|(i, var)| (var.name.clone(), i)).collect();
+        Self { vars, by_name }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&VarHeader> {
+        self.by_name.get(name).map(|&i| &self.vars[i])
+    }
+
+    pub fn vars(&self) -> &[VarHeader] {
+        &self.vars
+    }
+
+    pub fn len(&self) -> usize {
+        self.vars.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vars.is_empty()
+    }
+}
+
+/// A type a variable can be read as, see [`Telemetry::get`].
+pub trait VarValue: Sized {
+    /// Whether a variable of this type can be read as `Self`.
+    fn accepts(var_type: VarType) -> bool;
+
+    /// Decodes one element from its little-endian bytes.
+    fn decode(bytes: &[u8]) -> Self;
+
+    /// Encodes one element as little-endian bytes, see [`Telemetry::set`].
+    fn encode(&self, out: &mut [u8]);
+}
+
+impl VarValue for f32 {
+    fn accepts(var_type: VarType) -> bool {
+        var_type == VarType::Float
+    }
+
+    fn decode(bytes: &[u8]) -> Self {
+        f32::from_le_bytes(bytes[..4].try_into().unwrap())
+    }
+
+    fn encode(&self, out: &mut [u8]) {
+        out[..4].copy_from_slice(&self.to_le_bytes());
+    }
+}
+
+impl VarValue for f64 {
+    fn accepts(var_type: VarType) -> bool {
+        var_type == VarType::Double
+    }
+
+    fn decode(bytes: &[u8]) -> Self {
+        f64::from_le_bytes(bytes[..8].try_into().unwrap())
+    }
+
+    fn encode(&self, out: &mut [u8]) {
+        out[..8].copy_from_slice(&self.to_le_bytes());
+    }
+}
+
+impl VarValue for i32 {
+    fn accepts(var_type: VarType) -> bool {
+        matches!(var_type, VarType::Int | VarType::BitField)
+    }
+
+    fn decode(bytes: &[u8]) -> Self {
+        read_i32(bytes, 0)
+    }
+
+    fn encode(&self, out: &mut [u8]) {
+        out[..4].copy_from_slice(&self.to_le_bytes());
+    }
+}
+
+impl VarValue for u32 {
+    fn accepts(var_type: VarType) -> bool {
+        matches!(var_type, VarType::Int | VarType::BitField)
+    }
+
+    fn decode(bytes: &[u8]) -> Self {
+        read_i32(bytes, 0) as u32
+    }
+
+    fn encode(&self, out: &mut [u8]) {
+        out[..4].copy_from_slice(&self.to_le_bytes());
+    }
+}
+
+impl VarValue for bool {
+    fn accepts(var_type: VarType) -> bool {
+        var_type == VarType::Bool
+    }
+
+    fn decode(bytes: &[u8]) -> Self {
+        bytes[0] != 0
+    }
+
+    fn encode(&self, out: &mut [u8]) {
+        out[0] = u8::from(*self);
+    }
+}
+
+impl VarValue for u8 {
+    fn accepts(var_type: VarType) -> bool {
+        matches!(var_type, VarType::Char | VarType::Bool)
+    }
+
+    fn decode(bytes: &[u8]) -> Self {
+        bytes[0]
+    }
+
+    fn encode(&self, out: &mut [u8]) {
+        out[0] = *self;
+    }
+}
+
+/// A variable of any type, see [`Telemetry::value`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum VarData {
+    Char(Vec<u8>),
+    Bool(Vec<bool>),
+    Int(Vec<i32>),
+    BitField(Vec<u32>),
+    Float(Vec<f32>),
+    Double(Vec<f64>),
+}
+
+/// One sample of all variables, copied from the latest data buffer.
+///
+/// ```no_run
+/// use race_sdk::iracing::{IRacing, SessionFlags};
+///
+/// let mut iracing = IRacing::new();
+/// iracing.connect().unwrap();
+///
+/// if let Some(telemetry) = iracing.get_telemetry() {
+///     let speed = telemetry.get::<f32>("Speed").unwrap_or_default();
+///     let lap_dist = telemetry.get_array::<f32>("CarIdxLapDistPct").unwrap_or_default();
+///     let flags = telemetry.get::<SessionFlags>("SessionFlags").unwrap_or_default();
+///     println!("{:.0} km/h, {} cars, {:?}", speed * 3.6, lap_dist.len(), flags);
+///     if flags.contains(SessionFlags::YELLOW) {
+///         println!("Yellow flag");
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Telemetry {
+    /// The tick count of the buffer.
+    pub tick_count: i32,
+    /// The session info update the sample was taken under.
+    pub session_info_update: i32,
+    pub vars: Arc<VarTable>,
+    pub data: Vec<u8>,
+}
+
+impl Telemetry {
+    pub fn var(&self, name: &str) -> Option<&VarHeader> {
+        self.vars.get(name)
+    }
+
+    /// Reads the first element of a variable.
+    ///
+    /// Returns `None` when the variable does not exist or has a type `T`
+    /// cannot be read from, e.g. `get::<f32>` of a `Double`.
+    pub fn get<T: VarValue>(&self, name: &str) -> Option<T> {
+        self.get_at(name, 0)
+    }
+
+    /// Reads element `index` of an array variable.
+    pub fn get_at<T: VarValue>(&self, name: &str, index: usize) -> Option<T> {
+        let var = self.var(name)?;
+        if !T::accepts(var.var_type) || index >= var.count {
+            return None;
+        }
+        let start = var.offset + index * var.var_type.size();
+        self.data.get(start..start + var.var_type.size()).map(T::decode)
+    }
+
+    /// Writes the first element of a variable, e.g. to build the records
+    /// of an [`IbtWriter`](crate::iracing::IbtWriter).
+    pub fn set<T: VarValue>(&mut self, name: &str, value: T) -> Result<()> {
+        self.set_at(name, 0, value)
+    }
+
+    /// Writes element `index` of an array variable.
+    pub fn set_at<T: VarValue>(&mut self, name: &str, index: usize, value: T) -> Result<()> {
+        let var = self.vars.get(name).ok_or_else(|| IRacingError::InvalidData(format!("no variable {}", name)))?;
+        if !T::accepts(var.var_type) || index >= var.count {
+            return Err(IRacingError::InvalidData(format!(
+                "cannot write element {} of {:?} variable {}",
+                index, var.var_type, name
+            )));
+        }
+        let start = var.offset + index * var.var_type.size();
+        let out = self
+            .data
+            .get_mut(start..start + var.var_type.size())
+            .ok_or_else(|| IRacingError::InvalidData(format!("variable {} outside the record", name)))?;
+        value.encode(out);
+        Ok(())
+    }
+
+    /// Reads all elements of a variable.
+    pub fn get_array<T: VarValue>(&self, name: &str) -> Option<Vec<T>> {
+        let var = self.var(name)?;
+        if !T::accepts(var.var_type) {
+            return None;
+        }
+        let bytes = self.data.get(var.offset..var.offset + var.size())?;
+        Some(bytes.chunks_exact(var.var_type.size()).map(T::decode).collect())
+    }
+
+    /// Reads a variable of whatever type it has.
+    pub fn value(&self, name: &str) -> Option<VarData> {
+        Some(match self.var(name)?.var_type {
+            VarType::Char => VarData::Char(self.get_array(name)?),
+            VarType::Bool => VarData::Bool(self.get_array(name)?),
+            VarType::Int => VarData::Int(self.get_array(name)?),
+            VarType::BitField => VarData::BitField(self.get_array(name)?),
+            VarType::Float => VarData::Float(self.get_array(name)?),
+            VarType::Double => VarData::Double(self.get_array(name)?),
+        })
+    }
+
+    /// `SessionFlags`, empty when missing.
+    pub fn session_flags(&self) -> SessionFlags {
+        self.get("SessionFlags").unwrap_or_default()
+    }
+
+    pub fn session_state(&self) -> Option<SessionState> {
+        SessionState::from_raw(self.get("SessionState")?)
+    }
+
+    pub fn track_location(&self) -> Option<TrackLocation> {
+        TrackLocation::from_raw(self.get("PlayerTrackSurface")?)
+    }
+}
+
+impl Packet for Telemetry {
+    fn packet_id(&self) -> i32 {
+        self.tick_count
+    }
+}
+
+bit_flags! {
+    /// The `irsdk_Flags` of `SessionFlags` and `CarIdxSessionFlags`.
+    pub struct SessionFlags(u32) {
+        const CHECKERED = 0x0000_0001;
+        const WHITE = 0x0000_0002;
+        const GREEN = 0x0000_0004;
+        const YELLOW = 0x0000_0008;
+        const RED = 0x0000_0010;
+        const BLUE = 0x0000_0020;
+        const DEBRIS = 0x0000_0040;
+        const CROSSED = 0x0000_0080;
+        const YELLOW_WAVING = 0x0000_0100;
+        const ONE_LAP_TO_GREEN = 0x0000_0200;
+        const GREEN_HELD = 0x0000_0400;
+        const TEN_TO_GO = 0x0000_0800;
+        const FIVE_TO_GO = 0x0000_1000;
+        const RANDOM_WAVING = 0x0000_2000;
+        const CAUTION = 0x0000_4000;
+        const CAUTION_WAVING = 0x0000_8000;
+        const BLACK = 0x0001_0000;
+        const DISQUALIFY = 0x0002_0000;
+        const SERVICEABLE = 0x0004_0000;
+        const FURLED = 0x0008_0000;
+        const REPAIR = 0x0010_0000;
+        const START_HIDDEN = 0x1000_0000;
+        const START_READY = 0x2000_0000;
+        const START_SET = 0x4000_0000;
+        const START_GO = 0x8000_0000;
+    }
+}
+
+bit_flags! {
+    /// The `irsdk_EngineWarnings` of `EngineWarnings`.
+    pub struct EngineWarnings(u32) {
+        const WATER_TEMP_WARNING = 0x01;
+        const FUEL_PRESSURE_WARNING = 0x02;
+        const OIL_PRESSURE_WARNING = 0x04;
+        const ENGINE_STALLED = 0x08;
+        const PIT_SPEED_LIMITER = 0x10;
+        const REV_LIMITER_ACTIVE = 0x20;
+        const OIL_TEMP_WARNING = 0x40;
+    }
+}
+
+bit_flags! {
+    /// The `irsdk_PitSvFlags` of `PitSvFlags`.
+    pub struct PitServiceFlags(u32) {
+        const LF_TIRE_CHANGE = 0x01;
+        const RF_TIRE_CHANGE = 0x02;
+        const LR_TIRE_CHANGE = 0x04;
+        const RR_TIRE_CHANGE = 0x08;
+        const FUEL_FILL = 0x10;
+        const WINDSHIELD_TEAROFF = 0x20;
+        const FAST_REPAIR = 0x40;
+    }
+}
+
+bit_flags! {
+    /// The `irsdk_CameraState` of `CamCameraState`.
+    pub struct CameraState(u32) {
+        const IS_SESSION_SCREEN = 0x001;
+        const IS_SCENIC_ACTIVE = 0x002;
+        const CAM_TOOL_ACTIVE = 0x004;
+        const UI_HIDDEN = 0x008;
+        const USE_AUTO_SHOT_SELECTION = 0x010;
+        const USE_TEMPORARY_EDITS = 0x020;
+        const USE_KEY_ACCELERATION = 0x040;
+        const USE_KEY_10X_ACCELERATION = 0x080;
+        const USE_MOUSE_AIM_MODE = 0x100;
+    }
+}
+
+macro_rules! flags_var_value {
+    ($($name:ident),*) => {
+        $(impl VarValue for $name {
+            fn accepts(var_type: VarType) -> bool {
+                matches!(var_type, VarType::Int | VarType::BitField)
+            }
+
+            fn decode(bytes: &[u8]) -> Self {
+                Self(u32::decode(bytes))
+            }
+
+            fn encode(&self, out: &mut [u8]) {
+                self.0.encode(out)
+            }
+        })*
+    };
+}
+
+flags_var_value!(SessionFlags, EngineWarnings, PitServiceFlags, CameraState);
+
+/// The `irsdk_TrkLoc` of `PlayerTrackSurface` and `CarIdxTrackSurface`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i32)]
+pub enum TrackLocation {
+    NotInWorld = -1,
+    OffTrack = 0,
+    InPitStall = 1,
+    ApproachingPits = 2,
+    OnTrack = 3,
+}
+
+impl TrackLocation {
+    pub fn from_raw(value: i32) -> Option<Self> {
+        Some(match value {
+            -1 => TrackLocation::NotInWorld,
+            0 => TrackLocation::OffTrack,
+            1 => TrackLocation::InPitStall,
+            2 => TrackLocation::ApproachingPits,
+            3 => TrackLocation::OnTrack,
+            _ => return None,
+        })
+    }
+}
+
+/// The `irsdk_SessionState` of `SessionState`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i32)]
+pub enum SessionState {
+    Invalid = 0,
+    GetInCar = 1,
+    Warmup = 2,
+    ParadeLaps = 3,
+    Racing = 4,
+    Checkered = 5,
+    CoolDown = 6,
+}
+
+impl SessionState {
+    pub fn from_raw(value: i32) -> Option<Self> {
+        Some(match value {
+            0 => SessionState::Invalid,
+            1 => SessionState::GetInCar,
+            2 => SessionState::Warmup,
+            3 => SessionState::ParadeLaps,
+            4 => SessionState::Racing,
+            5 => SessionState::Checkered,
+            6 => SessionState::CoolDown,
+            _ => return None,
+        })
+    }
+}
+
+fn read_i32(bytes: &[u8], offset: usize) -> i32 {
+    i32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+/// A null-terminated string of the header, iRacing writes them in
+/// Latin-1.
+pub(crate) fn read_str(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&c| c == 0).unwrap_or(bytes.len());
+    bytes[..end].iter().map(|&c| char::from(c)).collect()
+}
+
+/// Writes a null-terminated Latin-1 string, characters outside Latin-1
+/// become `?`.
+pub(crate) fn write_str(out: &mut [u8], text: &str) {
+    let bytes = text.chars().map(|c| u8::try_from(u32::from(c)).unwrap_or(b'?'));
+    let len = out.len().saturating_sub(1);
+    for (slot, byte) in out[..len].iter_mut().zip(bytes) {
+        *slot = byte;
+    }
+}
@@ -0,0 +1,191 @@
+use crate::iracing::{
+    DISK_HEADER_SIZE, DiskHeader, HEADER_SIZE, Header, IRSDK_VERSION, IRacingError, MAX_BUFFERS, Result, Telemetry, VAR_HEADER_SIZE, VarBuffer, VarHeader,
+    VarTable,
+};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Writes `.ibt` telemetry files that iRacing tools and [`IbtFile`](crate::iracing::IbtFile) read.
+///
+/// The variable headers follow the headers, then come the records and the
+/// session info. The headers are written last by [`IbtWriter::finish`],
+/// once the record count, lap count and session times are known.
+///
+/// ```no_run
+/// use race_sdk::iracing::{IbtWriter, VarHeader, VarType};
+///
+/// let vars = vec![
+///     VarHeader::new("SessionTime", VarType::Double, 1, "s", "Seconds since session start"),
+///     VarHeader::new("Lap", VarType::Int, 1, "", "Laps started by player car"),
+///     VarHeader::new("Speed", VarType::Float, 1, "m/s", "GPS vehicle speed"),
+/// ];
+/// let mut ibt = IbtWriter::create("out.ibt", vars, 60).unwrap();
+/// for tick in 0..600 {
+///     let mut sample = ibt.new_sample();
+///     sample.set("SessionTime", tick as f64 / 60.0).unwrap();
+///     sample.set("Lap", 1).unwrap();
+///     sample.set("Speed", 50.0f32).unwrap();
+///     ibt.write_sample(&sample).unwrap();
+/// }
+/// ibt.set_session_info("WeekendInfo:\n TrackName: spa\n");
+/// ibt.finish().unwrap();
+/// ```
+pub struct IbtWriter<W: Write + Seek> {
+    writer: W,
+    vars: Arc<VarTable>,
+    tick_rate: i32,
+    start_date: i64,
+    session_info: String,
+    data_offset: u64,
+    record_len: usize,
+    disk: DiskHeader,
+    last_tick: i32,
+    last_lap: Option<i32>,
+}
+
+impl IbtWriter<BufWriter<File>> {
+    pub fn create(path: impl AsRef<Path>, vars: Vec<VarHeader>, tick_rate: i32) -> Result<Self> {
+        Self::new(BufWriter::new(File::create(path)?), vars, tick_rate)
+    }
+}
+
+impl<W: Write + Seek> IbtWriter<W> {
+    /// Lays the variables out one after another in a record, in the given
+    /// order, and writes their headers.
+    pub fn new(mut writer: W, mut vars: Vec<VarHeader>, tick_rate: i32) -> Result<Self> {
+        let mut names = HashSet::new();
+        let mut offset = 0;
+        for var in &mut vars {
+            if !names.insert(var.name.clone()) {
+                return Err(IRacingError::InvalidData(format!("duplicate variable {}", var.name)));
+            }
+            if var.count == 0 {
+                return Err(IRacingError::InvalidData(format!("variable {} has no elements", var.name)));
+            }
+            var.offset = offset;
+            offset += var.size();
+        }
+        let vars = VarTable::new(vars);
+
+        writer.seek(SeekFrom::Start(0))?;
+        writer.write_all(&[0; HEADER_SIZE + DISK_HEADER_SIZE])?;
+        for var in vars.vars() {
+            writer.write_all(&var.to_bytes())?;
+        }
+        Ok(Self {
+            writer,
+            data_offset: (HEADER_SIZE + DISK_HEADER_SIZE + vars.len() * VAR_HEADER_SIZE) as u64,
+            vars: Arc::new(vars),
+            tick_rate,
+            start_date: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs() as i64),
+            session_info: String::new(),
+            record_len: offset,
+            disk: DiskHeader::default(),
+            last_tick: 0,
+            last_lap: None,
+        })
+    }
+
+    /// Start of the session, defaults to the time the writer was created.
+    pub fn with_start_date(mut self, date: SystemTime) -> Self {
+        self.start_date = date.duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs() as i64);
+        self
+    }
+
+    pub fn vars(&self) -> &Arc<VarTable> {
+        &self.vars
+    }
+
+    /// A record with every variable zeroed, filled with [`Telemetry::set`].
+    pub fn new_sample(&self) -> Telemetry {
+        Telemetry {
+            tick_count: self.disk.record_count,
+            session_info_update: 0,
+            vars: self.vars.clone(),
+            data: vec![0; self.record_len],
+        }
+    }
+
+    /// The session info YAML written by [`IbtWriter::finish`], see
+    /// [`SessionInfo`](crate::iracing::SessionInfo) for what readers expect.
+    pub fn set_session_info(&mut self, text: &str) {
+        self.session_info = text.to_string();
+    }
+
+    /// Appends a record, which must have been laid out by this writer.
+    ///
+    /// `SessionTime` and `Lap`, when present, fill the session times and
+    /// the lap count of the disk header.
+    pub fn write_sample(&mut self, sample: &Telemetry) -> Result<()> {
+        if !Arc::ptr_eq(&sample.vars, &self.vars) || sample.data.len() != self.record_len {
+            return Err(IRacingError::InvalidData("sample not created by this writer".to_string()));
+        }
+        self.writer.write_all(&sample.data)?;
+
+        if let Some(time) = sample.get::<f64>("SessionTime") {
+            if self.disk.record_count == 0 {
+                self.disk.session_start_time = time;
+            }
+            self.disk.session_end_time = time;
+        }
+        if let Some(lap) = sample.get::<i32>("Lap")
+            && self.last_lap != Some(lap)
+        {
+            self.disk.lap_count += 1;
+            self.last_lap = Some(lap);
+        }
+        self.last_tick = sample.get::<i32>("SessionTick").unwrap_or(self.disk.record_count);
+        self.disk.record_count += 1;
+        Ok(())
+    }
+
+    pub fn record_count(&self) -> usize {
+        self.disk.record_count as usize
+    }
+
+    /// Appends the session info and writes the headers, returning the
+    /// underlying writer.
+    pub fn finish(mut self) -> Result<W> {
+        let session_offset = self.data_offset + (self.record_len * self.disk.record_count as usize) as u64;
+        self.writer.seek(SeekFrom::Start(session_offset))?;
+        self.writer.write_all(self.session_info.as_bytes())?;
+        self.writer.write_all(&[0])?;
+
+        let mut buffers = [VarBuffer::default(); MAX_BUFFERS];
+        buffers[0] = VarBuffer {
+            tick_count: self.last_tick,
+            buffer_offset: offset(self.data_offset)?,
+        };
+        let header = Header {
+            version: IRSDK_VERSION,
+            // Written by a running session, as in files iRacing writes.
+            status: 1,
+            tick_rate: self.tick_rate,
+            session_info_update: 0,
+            session_info_len: self.session_info.len() as i32 + 1,
+            session_info_offset: offset(session_offset)?,
+            num_vars: self.vars.len() as i32,
+            var_header_offset: (HEADER_SIZE + DISK_HEADER_SIZE) as i32,
+            num_buffers: 1,
+            buffer_len: self.record_len as i32,
+            buffers,
+        };
+        self.disk.session_start_date = self.start_date;
+
+        self.writer.seek(SeekFrom::Start(0))?;
+        self.writer.write_all(&header.to_bytes())?;
+        self.writer.write_all(&self.disk.to_bytes())?;
+        self.writer.seek(SeekFrom::End(0))?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Offsets in the headers are 32-bit.
+fn offset(value: u64) -> Result<i32> {
+    i32::try_from(value).map_err(|_| IRacingError::InvalidData("file larger than 2 GiB".to_string()))
+}
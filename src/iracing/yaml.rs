@@ -0,0 +1,248 @@
+use crate::iracing::{IRacingError, Result};
+
+/// A node of the session info YAML, maps keep their entries in order.
+///
+/// Scalars are kept as text since iRacing mixes numbers with their units
+/// (`6.93 km`) and words (`unlimited`), see [`YamlValue::number`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum YamlValue {
+    Scalar(String),
+    Map(Vec<(String, YamlValue)>),
+    List(Vec<YamlValue>),
+}
+
+impl YamlValue {
+    /// Parses the subset of YAML iRacing writes: block maps and lists,
+    /// plain and double-quoted scalars.
+    ///
+    /// Plain scalars are taken verbatim up to the end of the line, names
+    /// such as `Team: #1 {fast}` are not valid YAML but are written
+    /// unquoted by iRacing.
+    pub fn parse(text: &str) -> Result<Self> {
+        let lines = text
+            .lines()
+            .map(|line| line.trim_end_matches([' ', '\r']))
+            .filter(|line| !line.trim().is_empty() && *line != "---" && *line != "...")
+            .map(|line| {
+                let text = line.trim_start_matches(' ');
+                Line {
+                    indent: line.len() - text.len(),
+                    text,
+                }
+            })
+            .collect::<Vec<_>>();
+        if let Some(line) = lines.iter().find(|line| line.text.starts_with('\t')) {
+            return Err(IRacingError::InvalidData(format!("session info: tab indentation at '{}'", line.text)));
+        }
+        let mut parser = Parser { lines, pos: 0, depth: 0 };
+        parser.node(0)
+    }
+
+    /// The value of `key` in a map.
+    pub fn get(&self, key: &str) -> Option<&YamlValue> {
+        match self {
+            YamlValue::Map(entries) => entries.iter().find(|(name, _)| name == key).map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Follows a path of map keys separated by dots, e.g.
+    /// `"Tires.LeftFront.StartingPressure"`.
+    pub fn path(&self, path: &str) -> Option<&YamlValue> {
+        path.split('.').try_fold(self, |value, key| value.get(key))
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            YamlValue::Scalar(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// The entries of a list, empty for anything else since iRacing writes
+    /// empty lists as an empty value.
+    pub fn items(&self) -> &[YamlValue] {
+        match self {
+            YamlValue::List(items) => items,
+            _ => &[],
+        }
+    }
+
+    pub fn entries(&self) -> &[(String, YamlValue)] {
+        match self {
+            YamlValue::Map(entries) => entries,
+            _ => &[],
+        }
+    }
+
+    /// The leading number of a scalar, ignoring a unit after it
+    /// (`138.0 kPa`, `55 %`). Hexadecimal values start with `0x`.
+    pub fn number(&self) -> Option<f64> {
+        let token = self.as_str()?.split_whitespace().next()?;
+        match token.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16).ok().map(|value| value as f64),
+            None => token.parse().ok(),
+        }
+    }
+
+    /// The unit after the number of a scalar, e.g. `kPa` of `138.0 kPa`.
+    pub fn unit(&self) -> Option<&str> {
+        let mut tokens = self.as_str()?.split_whitespace();
+        tokens.next()?;
+        tokens.next()
+    }
+}
+
+struct Line<'a> {
+    indent: usize,
+    text: &'a str,
+}
+
+/// Maps and lists nested deeper than this are rejected.
+const MAX_DEPTH: usize = 128;
+
+struct Parser<'a> {
+    lines: Vec<Line<'a>>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    /// Parses the node starting at the current line, which is indented by
+    /// at least `indent`, failing past [`MAX_DEPTH`] rather than
+    /// overflowing the stack.
+    fn node(&mut self, indent: usize) -> Result<YamlValue> {
+        let Some(line) = self.lines.get(self.pos).filter(|line| line.indent >= indent) else {
+            return Ok(YamlValue::Scalar(String::new()));
+        };
+        let indent = line.indent;
+        if split_key(line.text).is_none() && !is_item(line.text) {
+            self.pos += 1;
+            return Ok(YamlValue::Scalar(unquote(line.text)));
+        }
+        if self.depth == MAX_DEPTH {
+            return Err(IRacingError::InvalidData(format!("session info: nested deeper than {} levels", MAX_DEPTH)));
+        }
+        self.depth += 1;
+        let node = if is_item(line.text) { self.list(indent) } else { self.map(indent) };
+        self.depth -= 1;
+        node
+    }
+
+    fn map(&mut self, indent: usize) -> Result<YamlValue> {
+        let mut entries: Vec<(String, YamlValue)> = Vec::new();
+        while let Some(line) = self.lines.get(self.pos) {
+            if line.indent < indent || (line.indent == indent && is_item(line.text)) {
+                break;
+            }
+            let split = split_key(line.text).filter(|_| line.indent == indent);
+            let Some((key, value)) = split else {
+                // A deeper line that is no child continues the previous
+                // scalar.
+                if let Some((_, YamlValue::Scalar(text))) = entries.last_mut() {
+                    text.push(' ');
+                    text.push_str(line.text);
+                }
+                self.pos += 1;
+                continue;
+            };
+            self.pos += 1;
+            let value = if !value.is_empty() {
+                YamlValue::Scalar(unquote(value))
+            } else {
+                match self.lines.get(self.pos) {
+                    Some(next) if next.indent > indent || (next.indent == indent && is_item(next.text)) => self.node(next.indent)?,
+                    _ => YamlValue::Scalar(String::new()),
+                }
+            };
+            entries.push((key.to_string(), value));
+        }
+        Ok(YamlValue::Map(entries))
+    }
+
+    fn list(&mut self, indent: usize) -> Result<YamlValue> {
+        let mut items = Vec::new();
+        while let Some(line) = self.lines.get_mut(self.pos) {
+            if line.indent != indent || !is_item(line.text) {
+                break;
+            }
+            // The item's content continues at the column after the dash,
+            // where its further lines are indented.
+            let rest = line.text[1..].trim_start_matches(' ');
+            if rest.is_empty() {
+                self.pos += 1;
+                items.push(self.node(indent + 1)?);
+                continue;
+            }
+            line.indent += line.text.len() - rest.len();
+            line.text = rest;
+            let indent = line.indent;
+            items.push(self.node(indent)?);
+        }
+        Ok(YamlValue::List(items))
+    }
+}
+
+fn is_item(text: &str) -> bool {
+    text == "-" || text.starts_with("- ")
+}
+
+/// Splits `Key: value` at the first colon followed by a space or the end of
+/// the line, keys never contain spaces.
+fn split_key(text: &str) -> Option<(&str, &str)> {
+    let colon = text.find(": ").or_else(|| text.strip_suffix(':').map(str::len))?;
+    let key = &text[..colon];
+    if key.is_empty() || key.contains(' ') || key.starts_with('"') {
+        return None;
+    }
+    Some((key, text[colon + 1..].trim()))
+}
+
+/// Strips the quotes of a double-quoted scalar, plain scalars are kept
+/// verbatim.
+fn unquote(text: &str) -> String {
+    match text.strip_prefix('"').and_then(|text| text.strip_suffix('"')) {
+        Some(inner) => inner.replace("\\\"", "\"").replace("\\\\", "\\"),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `depth` maps, each the only value of the one before.
+    fn nested_maps(depth: usize) -> String {
+        let mut text: String = (0..depth - 1).map(|i| format!("{}Key:\n", " ".repeat(i))).collect();
+        text.push_str(&format!("{}Key: value\n", " ".repeat(depth - 1)));
+        text
+    }
+
+    #[test]
+    fn depth_limit() {
+        let deepest = YamlValue::parse(&nested_maps(MAX_DEPTH)).unwrap();
+        assert_eq!(deepest.path(&vec!["Key"; MAX_DEPTH].join(".")).and_then(YamlValue::as_str), Some("value"));
+        assert!(YamlValue::parse(&nested_maps(MAX_DEPTH + 1)).is_err());
+
+        assert!(YamlValue::parse(&format!("{}x", "- ".repeat(MAX_DEPTH))).is_ok());
+        assert!(YamlValue::parse(&format!("{}x", "- ".repeat(MAX_DEPTH + 1))).is_err());
+        // Fails at the limit instead of recursing through the document.
+        assert!(YamlValue::parse(&nested_maps(3000)).is_err());
+    }
+
+    #[test]
+    fn session_info_subset() {
+        let text = "---\nWeekendInfo:\n TrackName: spa\n TrackLength: 6.93 km\n TeamName: #1 {fast}\n\
+                    DriverInfo:\n Drivers:\n - CarIdx: 0\n   UserName: \"Max \\\"Mad\\\" Power\"\n \
+                    - CarIdx: 1\n   UserName: Long\n    Name\n Empty:\n...\n";
+        let yaml = YamlValue::parse(text).unwrap();
+        let length = yaml.path("WeekendInfo.TrackLength").unwrap();
+        assert_eq!((length.number(), length.unit()), (Some(6.93), Some("km")));
+        assert_eq!(yaml.path("WeekendInfo.TeamName").and_then(YamlValue::as_str), Some("#1 {fast}"));
+        let drivers = yaml.path("DriverInfo.Drivers").unwrap().items();
+        let names: Vec<_> = drivers.iter().filter_map(|driver| driver.get("UserName")?.as_str()).collect();
+        assert_eq!(names, ["Max \"Mad\" Power", "Long Name"]);
+        assert!(yaml.path("DriverInfo.Empty").unwrap().items().is_empty());
+        assert!(YamlValue::parse("Key:\n\tValue: 1\n").is_err());
+    }
+}
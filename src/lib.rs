@@ -8,4 +8,13 @@ pub mod iracing;
 pub mod lmu;
 pub mod rr;
 
+pub mod export;
+pub mod motec;
+pub mod recording;
+#[cfg(feature = "sqlite")]
+pub mod store;
+pub mod telemetry;
+
 mod utils;
+
+pub use utils::{SharedMemory, SharedMemoryError};
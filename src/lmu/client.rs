@@ -0,0 +1,152 @@
+use crate::lmu::{
+    EXTENDED_BUFFER_NAME, Extended, LeMansUltimateError, PIT_INFO_BUFFER_NAME, PitInfo, RULES_BUFFER_NAME, Rules, SCORING_BUFFER_NAME, Scoring,
+    TELEMETRY_BUFFER_NAME, Telemetry, WEATHER_BUFFER_NAME, Weather, update_versions_of,
+};
+use crate::utils::{SharedMemory, open_shared_memory};
+use std::hint;
+use std::mem;
+use std::sync::atomic::{Ordering, fence};
+
+/// Reads attempted by the `get_*` methods of [`LeMansUltimateClient`]
+/// before giving up.
+pub const MAX_READ_ATTEMPTS: usize = 64;
+
+pub struct LeMansUltimateClient {
+    telemetry_memory: Option<Box<dyn SharedMemory>>,
+    scoring_memory: Option<Box<dyn SharedMemory>>,
+    rules_memory: Option<Box<dyn SharedMemory>>,
+    extended_memory: Option<Box<dyn SharedMemory>>,
+    pit_info_memory: Option<Box<dyn SharedMemory>>,
+    weather_memory: Option<Box<dyn SharedMemory>>,
+}
+
+impl LeMansUltimateClient {
+    pub fn new() -> Self {
+        Self {
+            telemetry_memory: None,
+            scoring_memory: None,
+            rules_memory: None,
+            extended_memory: None,
+            pit_info_memory: None,
+            weather_memory: None,
+        }
+    }
+
+    /// Creates a client reading from custom memory sources instead of the
+    /// game, each holding a complete buffer.
+    pub fn from_shared_memory(
+        telemetry: Box<dyn SharedMemory>,
+        scoring: Box<dyn SharedMemory>,
+        rules: Box<dyn SharedMemory>,
+        extended: Box<dyn SharedMemory>,
+        pit_info: Box<dyn SharedMemory>,
+        weather: Box<dyn SharedMemory>,
+    ) -> Self {
+        Self {
+            telemetry_memory: Some(telemetry),
+            scoring_memory: Some(scoring),
+            rules_memory: Some(rules),
+            extended_memory: Some(extended),
+            pit_info_memory: Some(pit_info),
+            weather_memory: Some(weather),
+        }
+    }
+
+    /// Opens the buffers of the rFactor 2 shared memory plugin, which LMU
+    /// loads when it is enabled in `CustomPluginVariables.JSON`.
+    ///
+    /// Telemetry, scoring and extended are required. Rules, pit info and
+    /// weather can be turned off in the plugin's options and are read only
+    /// when present.
+    pub fn connect(&mut self) -> Result<(), LeMansUltimateError> {
+        match (
+            open_shared_memory(TELEMETRY_BUFFER_NAME, Telemetry::BUFFER_SIZE),
+            open_shared_memory(SCORING_BUFFER_NAME, Scoring::BUFFER_SIZE),
+            open_shared_memory(EXTENDED_BUFFER_NAME, mem::size_of::<Extended>()),
+        ) {
+            (Ok(telemetry), Ok(scoring), Ok(extended)) => {
+                self.telemetry_memory = Some(Box::new(telemetry));
+                self.scoring_memory = Some(Box::new(scoring));
+                self.extended_memory = Some(Box::new(extended));
+                self.rules_memory = open_optional(RULES_BUFFER_NAME, Rules::BUFFER_SIZE);
+                self.pit_info_memory = open_optional(PIT_INFO_BUFFER_NAME, mem::size_of::<PitInfo>());
+                self.weather_memory = open_optional(WEATHER_BUFFER_NAME, mem::size_of::<Weather>());
+                Ok(())
+            }
+            (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => {
+                *self = Self::new();
+                Err(map_shared_memory_error(e))
+            }
+        }
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.telemetry_memory.is_some() && self.scoring_memory.is_some() && self.extended_memory.is_some()
+    }
+
+    /// Reads a consistent copy of the telemetry buffer.
+    ///
+    /// The game increments `mVersionUpdateBegin` before and
+    /// `mVersionUpdateEnd` after writing a buffer, a copy is only returned
+    /// when both were equal and unchanged across it. Returns `None` when
+    /// not connected or the game kept writing for [`MAX_READ_ATTEMPTS`]
+    /// reads, the same holds for the other buffers.
+    pub fn get_telemetry(&self) -> Option<Telemetry> {
+        read_consistent(self.telemetry_memory.as_deref()?, Telemetry::from_bytes)
+    }
+
+    pub fn get_scoring(&self) -> Option<Scoring> {
+        read_consistent(self.scoring_memory.as_deref()?, Scoring::from_bytes)
+    }
+
+    pub fn get_rules(&self) -> Option<Rules> {
+        read_consistent(self.rules_memory.as_deref()?, Rules::from_bytes)
+    }
+
+    pub fn get_extended(&self) -> Option<Extended> {
+        read_consistent(self.extended_memory.as_deref()?, Extended::from_bytes)
+    }
+
+    pub fn get_pit_info(&self) -> Option<PitInfo> {
+        read_consistent(self.pit_info_memory.as_deref()?, PitInfo::from_bytes)
+    }
+
+    pub fn get_weather(&self) -> Option<Weather> {
+        read_consistent(self.weather_memory.as_deref()?, Weather::from_bytes)
+    }
+}
+
+impl Default for LeMansUltimateClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn read_consistent<T>(memory: &dyn SharedMemory, read: impl Fn(&[u8]) -> Option<T>) -> Option<T> {
+    let bytes = memory.as_slice();
+    for _ in 0..MAX_READ_ATTEMPTS {
+        let (begin, end) = update_versions_of(bytes)?;
+        if begin == end {
+            // Keep the copy between the two reads of the version counters.
+            fence(Ordering::Acquire);
+            let value = read(bytes)?;
+            fence(Ordering::Acquire);
+            if update_versions_of(bytes)? == (begin, end) {
+                return Some(value);
+            }
+        }
+        hint::spin_loop();
+    }
+    None
+}
+
+fn open_optional(name: &str, size: usize) -> Option<Box<dyn SharedMemory>> {
+    open_shared_memory(name, size).ok().map(|memory| Box::new(memory) as Box<dyn SharedMemory>)
+}
+
+fn map_shared_memory_error(err: crate::utils::SharedMemoryError) -> LeMansUltimateError {
+    match err {
+        crate::utils::SharedMemoryError::SharedMemoryNotFound(msg) => LeMansUltimateError::SharedMemoryNotFound(msg),
+        crate::utils::SharedMemoryError::ConnectionFailed(msg) => LeMansUltimateError::ConnectionFailed(msg),
+    }
+}
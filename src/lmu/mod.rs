@@ -0,0 +1,222 @@
+pub mod client;
+pub mod rest;
+pub mod types;
+pub use client::*;
+pub use rest::*;
+pub use types::*;
+
+use crate::SharedMemory;
+use crate::telemetry::Sampler;
+use std::cell::{Ref, RefCell};
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum LeMansUltimateError {
+    SharedMemoryNotFound(String),
+    ConnectionFailed(String),
+    InvalidData(String),
+    Io(io::Error),
+    /// The REST API answered `path` with an HTTP status other than 200.
+    Http {
+        status: u16,
+        path: String,
+    },
+}
+
+impl fmt::Display for LeMansUltimateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LeMansUltimateError::SharedMemoryNotFound(msg) => write!(f, "Shared memory not found: {}", msg),
+            LeMansUltimateError::ConnectionFailed(msg) => write!(f, "Connection failed: {}", msg),
+            LeMansUltimateError::InvalidData(msg) => write!(f, "Invalid data format: {}", msg),
+            LeMansUltimateError::Io(err) => write!(f, "I/O error: {}", err),
+            LeMansUltimateError::Http { status, path } => write!(f, "HTTP status {} for {}", status, path),
+        }
+    }
+}
+
+impl std::error::Error for LeMansUltimateError {}
+
+impl From<io::Error> for LeMansUltimateError {
+    fn from(err: io::Error) -> Self {
+        LeMansUltimateError::Io(err)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, LeMansUltimateError>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConnectionStatus {
+    Disconnected,
+    Connected,
+}
+
+/// Le Mans Ultimate, read through the rFactor 2 style buffers of its shared
+/// memory plugin.
+///
+/// Telemetry holds the physics of the vehicles the game simulates in
+/// detail, scoring the session and the standings of all vehicles. Both
+/// identify vehicles by their slot id.
+///
+/// ```no_run
+/// use race_sdk::lmu::LeMansUltimate;
+///
+/// let mut lmu = LeMansUltimate::new();
+/// lmu.connect().unwrap();
+///
+/// if let Some(scoring) = lmu.get_scoring() {
+///     println!("{} at {}", scoring.info.session_type().map_or(String::new(), |session| session.to_string()), scoring.info.track_name_str());
+///     for vehicle in scoring.standings() {
+///         println!("P{} {} ({}) {:.3}", vehicle.place, vehicle.driver_name_str(), vehicle.vehicle_class_str(), { vehicle.best_lap_time });
+///     }
+/// }
+/// if let Some(player) = lmu.get_player_telemetry() {
+///     let fl = player.wheels[0].surface_temperature_celsius();
+///     println!("{:.0} km/h gear {}, front left {:.0}/{:.0}/{:.0} °C", player.speed_kmh(), { player.gear }, fl[0], fl[1], fl[2]);
+/// }
+/// ```
+///
+/// With [`LeMansUltimate::with_rest`], the standings also carry what the
+/// game's REST API adds, such as car numbers and teams. The API is slow to
+/// answer, so it is only requested by [`LeMansUltimate::poll_rest`]:
+///
+/// ```no_run
+/// use race_sdk::lmu::{LeMansUltimate, LeMansUltimateRest, RestPoller};
+///
+/// let mut lmu = LeMansUltimate::new().with_rest(RestPoller::new(LeMansUltimateRest::new()));
+/// lmu.connect().unwrap();
+///
+/// loop {
+///     lmu.poll_rest();
+///     for vehicle in lmu.get_standings() {
+///         println!("P{} #{} {}", vehicle.scoring.place, vehicle.car_number().unwrap_or("-"), vehicle.scoring.driver_name_str());
+///     }
+///     std::thread::sleep(std::time::Duration::from_millis(100));
+/// }
+/// ```
+pub struct LeMansUltimate {
+    client: LeMansUltimateClient,
+    rest: Option<RefCell<RestPoller>>,
+}
+
+impl LeMansUltimate {
+    pub fn new() -> Self {
+        Self {
+            client: LeMansUltimateClient::new(),
+            rest: None,
+        }
+    }
+
+    /// Creates a facade reading from custom memory sources, see
+    /// [`LeMansUltimateClient::from_shared_memory`].
+    pub fn from_shared_memory(
+        telemetry: Box<dyn SharedMemory>,
+        scoring: Box<dyn SharedMemory>,
+        rules: Box<dyn SharedMemory>,
+        extended: Box<dyn SharedMemory>,
+        pit_info: Box<dyn SharedMemory>,
+        weather: Box<dyn SharedMemory>,
+    ) -> Self {
+        Self {
+            client: LeMansUltimateClient::from_shared_memory(telemetry, scoring, rules, extended, pit_info, weather),
+            rest: None,
+        }
+    }
+
+    /// Merges the responses of the REST API into the standings, see
+    /// [`LeMansUltimate::poll_rest`] and [`LeMansUltimate::get_standings`].
+    pub fn with_rest(mut self, poller: RestPoller) -> Self {
+        self.rest = Some(RefCell::new(poller));
+        self
+    }
+
+    pub fn connect(&mut self) -> Result<()> {
+        self.client.connect()
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.client.is_connected()
+    }
+
+    pub fn status(&self) -> ConnectionStatus {
+        if self.is_connected() {
+            ConnectionStatus::Connected
+        } else {
+            ConnectionStatus::Disconnected
+        }
+    }
+
+    /// Reads a consistent copy of the buffer, see [`LeMansUltimateClient::get_telemetry`].
+    pub fn get_telemetry(&self) -> Option<Telemetry> {
+        self.client.get_telemetry()
+    }
+
+    pub fn get_scoring(&self) -> Option<Scoring> {
+        self.client.get_scoring()
+    }
+
+    pub fn get_rules(&self) -> Option<Rules> {
+        self.client.get_rules()
+    }
+
+    pub fn get_extended(&self) -> Option<Extended> {
+        self.client.get_extended()
+    }
+
+    pub fn get_pit_info(&self) -> Option<PitInfo> {
+        self.client.get_pit_info()
+    }
+
+    pub fn get_weather(&self) -> Option<Weather> {
+        self.client.get_weather()
+    }
+
+    /// The scoring standings in order of place, each merged with its
+    /// vehicle of the REST standings last polled by
+    /// [`LeMansUltimate::poll_rest`]. See [`merge_standings`].
+    ///
+    /// Only reads the shared memory, it never waits for the API.
+    pub fn get_standings(&self) -> Vec<VehicleStanding> {
+        let Some(scoring) = self.client.get_scoring() else {
+            return Vec::new();
+        };
+        match self.rest() {
+            Some(rest) => merge_standings(&scoring, rest.standings()),
+            None => merge_standings(&scoring, &[]),
+        }
+    }
+
+    /// The latest responses of the REST API, polled by
+    /// [`LeMansUltimate::poll_rest`].
+    pub fn rest(&self) -> Option<Ref<'_, RestPoller>> {
+        self.rest.as_ref().map(RefCell::borrow)
+    }
+
+    /// Requests the REST endpoints due, see [`RestPoller::poll`].
+    pub fn poll_rest(&self) -> Vec<RestEndpoint> {
+        self.rest.as_ref().map_or_else(Vec::new, |rest| rest.borrow_mut().poll())
+    }
+
+    /// The telemetry of the vehicle the scoring marks as the player's.
+    pub fn get_player_telemetry(&self) -> Option<VehicleTelemetry> {
+        let id = self.client.get_scoring()?.player()?.id;
+        self.client.get_telemetry()?.vehicle(id).copied()
+    }
+
+    /// Returns a sampler that emits every telemetry update with its capture time.
+    pub fn telemetry_sampler(&self) -> Sampler<Telemetry, impl FnMut() -> Option<Telemetry> + '_> {
+        Sampler::new(|| self.client.get_telemetry())
+    }
+
+    /// Returns a sampler that emits every scoring update with its capture time.
+    pub fn scoring_sampler(&self) -> Sampler<Scoring, impl FnMut() -> Option<Scoring> + '_> {
+        Sampler::new(|| self.client.get_scoring())
+    }
+}
+
+impl Default for LeMansUltimate {
+    fn default() -> Self {
+        Self::new()
+    }
+}
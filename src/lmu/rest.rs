@@ -0,0 +1,877 @@
+use crate::lmu::{LeMansUltimateError, Result, Scoring, VehicleScoring};
+use crate::utils::json::{self, Json};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpStream};
+use std::time::{Duration, Instant};
+
+/// Port of the HTTP API the game serves on localhost.
+pub const REST_PORT: u16 = 6397;
+
+pub const SESSION_INFO_PATH: &str = "/rest/watch/sessionInfo";
+pub const STANDINGS_PATH: &str = "/rest/watch/standings";
+pub const GARAGE_SETUP_PATH: &str = "/rest/garage/getPlayerGarageData";
+pub const PIT_STRATEGY_PATH: &str = "/rest/garage/UIScreen/RepairAndRefuel";
+pub const STRATEGY_USAGE_PATH: &str = "/rest/strategy/usage";
+pub const CARS_PATH: &str = "/rest/race/car";
+pub const TRACKS_PATH: &str = "/rest/race/track";
+
+/// Largest response body read, larger ones fail before being allocated.
+pub const MAX_BODY_SIZE: usize = 16 << 20;
+
+/// Client for the local HTTP API of Le Mans Ultimate, which serves data
+/// the shared memory plugin lacks: car numbers and teams, the garage
+/// setup, virtual energy and the content installed.
+///
+/// Every call makes one `GET` request. The address can point at any
+/// server answering the same paths, such as a mock serving recorded
+/// responses.
+///
+/// ```no_run
+/// use race_sdk::lmu::LeMansUltimateRest;
+///
+/// let rest = LeMansUltimateRest::new();
+/// let session = rest.get_session_info().unwrap();
+/// println!("{} at {}", session.session, session.track_name);
+/// for standing in rest.get_standings().unwrap() {
+///     println!("P{} #{} {} ({})", standing.position, standing.car_number, standing.driver_name, standing.full_team_name);
+/// }
+/// let strategy = rest.get_pit_strategy().unwrap();
+/// println!("{:.1}/{:.1} L, {:.0}% energy", strategy.current_fuel, strategy.max_fuel, strategy.virtual_energy_fraction() * 100.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LeMansUltimateRest {
+    address: SocketAddr,
+    timeout: Duration,
+}
+
+impl LeMansUltimateRest {
+    /// Targets the game on `127.0.0.1:6397` with a one second timeout.
+    pub fn new() -> Self {
+        Self {
+            address: SocketAddr::from((Ipv4Addr::LOCALHOST, REST_PORT)),
+            timeout: Duration::from_secs(1),
+        }
+    }
+
+    pub fn with_address(mut self, address: SocketAddr) -> Self {
+        self.address = address;
+        self
+    }
+
+    /// Timeout for connecting and for each read and write.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Whether the API answers, which it does while the game runs.
+    pub fn is_available(&self) -> bool {
+        self.get_text(SESSION_INFO_PATH).is_ok()
+    }
+
+    /// The body of a `GET` request to `path`, for endpoints without a
+    /// typed model.
+    pub fn get_text(&self, path: &str) -> Result<String> {
+        let stream = TcpStream::connect_timeout(&self.address, self.timeout)?;
+        stream.set_read_timeout(Some(self.timeout))?;
+        stream.set_write_timeout(Some(self.timeout))?;
+        write!(
+            &stream,
+            "GET {} HTTP/1.1\r\nHost: {}\r\nAccept: application/json\r\nConnection: close\r\n\r\n",
+            path, self.address
+        )?;
+        read_response(BufReader::new(stream), path)
+    }
+
+    pub fn get_session_info(&self) -> Result<RestSessionInfo> {
+        RestSessionInfo::parse(&self.get_text(SESSION_INFO_PATH)?)
+    }
+
+    pub fn get_standings(&self) -> Result<Vec<RestStanding>> {
+        RestStanding::parse_list(&self.get_text(STANDINGS_PATH)?)
+    }
+
+    /// The setup of the player's car as the garage shows it.
+    pub fn get_garage_setup(&self) -> Result<GarageSetup> {
+        GarageSetup::parse(&self.get_text(GARAGE_SETUP_PATH)?)
+    }
+
+    /// Fuel, virtual energy and tyres from the repair and refuel screen.
+    pub fn get_pit_strategy(&self) -> Result<PitStrategy> {
+        PitStrategy::parse(&self.get_text(PIT_STRATEGY_PATH)?)
+    }
+
+    /// Fuel and virtual energy used per lap by each driver.
+    pub fn get_strategy_usage(&self) -> Result<Vec<LapUsage>> {
+        LapUsage::parse_list(&self.get_text(STRATEGY_USAGE_PATH)?)
+    }
+
+    /// The cars installed.
+    pub fn get_cars(&self) -> Result<Vec<CarEntry>> {
+        CarEntry::parse_list(&self.get_text(CARS_PATH)?)
+    }
+
+    /// The tracks installed.
+    pub fn get_tracks(&self) -> Result<Vec<TrackEntry>> {
+        TrackEntry::parse_list(&self.get_text(TRACKS_PATH)?)
+    }
+}
+
+impl Default for LeMansUltimateRest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reads an HTTP/1.1 response, with a `Content-Length`, chunked or
+/// delimited by the server closing the connection, of at most
+/// [`MAX_BODY_SIZE`] bytes.
+fn read_response(mut reader: impl BufRead, path: &str) -> Result<String> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let status = line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse::<u16>().ok())
+        .ok_or_else(|| LeMansUltimateError::InvalidData(format!("malformed status line {:?}", line.trim_end())))?;
+
+    let mut content_length = None;
+    let mut chunked = false;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 || line.trim_end().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            let value = value.trim();
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.parse::<usize>().ok();
+            } else if name.eq_ignore_ascii_case("transfer-encoding") {
+                chunked = value.eq_ignore_ascii_case("chunked");
+            }
+        }
+    }
+
+    let mut body = Vec::new();
+    if chunked {
+        loop {
+            line.clear();
+            reader.read_line(&mut line)?;
+            let size_text = line.trim_end().split(';').next().unwrap_or("");
+            let size = usize::from_str_radix(size_text, 16).map_err(|_| LeMansUltimateError::InvalidData(format!("malformed chunk size {:?}", size_text)))?;
+            if size == 0 {
+                break;
+            }
+            if size > MAX_BODY_SIZE - body.len() {
+                return Err(too_large(path));
+            }
+            let start = body.len();
+            body.resize(start + size, 0);
+            reader.read_exact(&mut body[start..])?;
+            line.clear();
+            reader.read_line(&mut line)?;
+        }
+    } else if let Some(length) = content_length {
+        if length > MAX_BODY_SIZE {
+            return Err(too_large(path));
+        }
+        body.resize(length, 0);
+        reader.read_exact(&mut body)?;
+    } else {
+        reader.take(MAX_BODY_SIZE as u64 + 1).read_to_end(&mut body)?;
+        if body.len() > MAX_BODY_SIZE {
+            return Err(too_large(path));
+        }
+    }
+
+    if status != 200 {
+        return Err(LeMansUltimateError::Http {
+            status,
+            path: path.to_string(),
+        });
+    }
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+fn too_large(path: &str) -> LeMansUltimateError {
+    LeMansUltimateError::InvalidData(format!("response to {} exceeds {} bytes", path, MAX_BODY_SIZE))
+}
+
+fn parse_json(text: &str) -> Result<Json> {
+    json::parse(text).map_err(LeMansUltimateError::InvalidData)
+}
+
+fn string(json: &Json, key: &str) -> String {
+    json.get(key).and_then(Json::as_str).unwrap_or_default().to_string()
+}
+
+fn number(json: &Json, key: &str) -> f64 {
+    json.get(key).and_then(Json::as_f64).unwrap_or(0.0)
+}
+
+fn integer(json: &Json, key: &str) -> i32 {
+    json.get(key).and_then(Json::as_f64).map_or(0, |value| value as i32)
+}
+
+fn flag(json: &Json, key: &str) -> bool {
+    json.get(key).and_then(Json::as_bool).unwrap_or(false)
+}
+
+fn list<T>(text: &str, parse: impl Fn(&Json) -> T) -> Result<Vec<T>> {
+    match parse_json(text)? {
+        Json::Array(values) => Ok(values.iter().map(parse).collect()),
+        _ => Err(LeMansUltimateError::InvalidData("expected a JSON array".to_string())),
+    }
+}
+
+/// The session, from `/rest/watch/sessionInfo`.
+///
+/// Members missing from a response are left at their default, the same
+/// holds for the other models.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RestSessionInfo {
+    pub track_name: String,
+    /// Such as `PRACTICE1`, `QUALIFY1` or `RACE1`.
+    pub session: String,
+    pub server_name: String,
+    pub player_name: String,
+    /// Seconds since the session started.
+    pub current_event_time: f64,
+    /// Seconds at which the session ends.
+    pub end_event_time: f64,
+    pub maximum_laps: i32,
+    /// Lap length in m.
+    pub lap_distance: f64,
+    pub number_of_vehicles: i32,
+    /// See [`GamePhase`](crate::lmu::GamePhase).
+    pub game_phase: i32,
+    pub in_realtime: bool,
+    pub ambient_temp: f64,
+    pub track_temp: f64,
+    /// 0.0 to 1.0.
+    pub raining: f64,
+    pub dark_cloud: f64,
+    pub min_path_wetness: f64,
+    pub max_path_wetness: f64,
+    pub average_path_wetness: f64,
+    /// Fraction of the session completed by time, -1.0 when unlimited.
+    pub time_completion: f64,
+    /// Fraction of the session completed by laps, -1.0 when unlimited.
+    pub laps_completion: f64,
+}
+
+impl RestSessionInfo {
+    pub fn parse(text: &str) -> Result<Self> {
+        let json = parse_json(text)?;
+        let completion = json.get("raceCompletion").unwrap_or(&Json::Null);
+        Ok(Self {
+            track_name: string(&json, "trackName"),
+            session: string(&json, "session"),
+            server_name: string(&json, "serverName"),
+            player_name: string(&json, "playerName"),
+            current_event_time: number(&json, "currentEventTime"),
+            end_event_time: number(&json, "endEventTime"),
+            maximum_laps: integer(&json, "maximumLaps"),
+            lap_distance: number(&json, "lapDistance"),
+            number_of_vehicles: integer(&json, "numberOfVehicles"),
+            game_phase: integer(&json, "gamePhase"),
+            in_realtime: flag(&json, "inRealtime"),
+            ambient_temp: number(&json, "ambientTemp"),
+            track_temp: number(&json, "trackTemp"),
+            raining: number(&json, "raining"),
+            dark_cloud: number(&json, "darkCloud"),
+            min_path_wetness: number(&json, "minPathWetness"),
+            max_path_wetness: number(&json, "maxPathWetness"),
+            average_path_wetness: number(&json, "averagePathWetness"),
+            time_completion: number(completion, "timeCompletion"),
+            laps_completion: number(completion, "lapsCompletion"),
+        })
+    }
+
+    /// Seconds left in the session, `None` when it is not timed.
+    pub fn time_remaining(&self) -> Option<f64> {
+        (self.end_event_time > 0.0).then(|| (self.end_event_time - self.current_event_time).max(0.0))
+    }
+}
+
+/// A vehicle of `/rest/watch/standings`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RestStanding {
+    /// Slot id, matches [`VehicleScoring::id`], -1 when not listed.
+    pub slot_id: i32,
+    pub driver_name: String,
+    pub vehicle_name: String,
+    pub vehicle_filename: String,
+    pub car_class: String,
+    pub car_number: String,
+    pub full_team_name: String,
+    pub position: i32,
+    pub laps_completed: i32,
+    pub best_lap_time: f64,
+    pub last_lap_time: f64,
+    pub estimated_lap_time: f64,
+    pub time_into_lap: f64,
+    pub time_behind_leader: f64,
+    pub laps_behind_leader: i32,
+    pub time_behind_next: f64,
+    pub laps_behind_next: i32,
+    pub pitstops: i32,
+    pub penalties: i32,
+    /// Distance around the lap in m.
+    pub lap_distance: f64,
+    /// Fuel left as a fraction of the tank.
+    pub fuel_fraction: f64,
+    /// Such as `FSTAT_NONE` or `FSTAT_FINISHED`.
+    pub finish_status: String,
+    /// Such as `NONE`, `REQUEST` or `STOPPED`.
+    pub pit_state: String,
+    pub pitting: bool,
+    pub in_garage_stall: bool,
+    pub player: bool,
+    pub has_focus: bool,
+    pub under_yellow: bool,
+}
+
+impl RestStanding {
+    pub fn parse_list(text: &str) -> Result<Vec<Self>> {
+        list(text, Self::from_json)
+    }
+
+    fn from_json(json: &Json) -> Self {
+        Self {
+            slot_id: json.get("slotID").and_then(Json::as_i32).unwrap_or(-1),
+            driver_name: string(json, "driverName"),
+            vehicle_name: string(json, "vehicleName"),
+            vehicle_filename: string(json, "vehicleFilename"),
+            car_class: string(json, "carClass"),
+            car_number: match json.get("carNumber") {
+                Some(Json::Number(number)) => number.to_string(),
+                Some(value) => value.as_str().unwrap_or_default().to_string(),
+                None => String::new(),
+            },
+            full_team_name: string(json, "fullTeamName"),
+            position: integer(json, "position"),
+            laps_completed: integer(json, "lapsCompleted"),
+            best_lap_time: number(json, "bestLapTime"),
+            last_lap_time: number(json, "lastLapTime"),
+            estimated_lap_time: number(json, "estimatedLapTime"),
+            time_into_lap: number(json, "timeIntoLap"),
+            time_behind_leader: number(json, "timeBehindLeader"),
+            laps_behind_leader: integer(json, "lapsBehindLeader"),
+            time_behind_next: number(json, "timeBehindNext"),
+            laps_behind_next: integer(json, "lapsBehindNext"),
+            pitstops: integer(json, "pitstops"),
+            penalties: integer(json, "penalties"),
+            lap_distance: number(json, "lapDistance"),
+            fuel_fraction: number(json, "fuelFraction"),
+            finish_status: string(json, "finishStatus"),
+            pit_state: string(json, "pitState"),
+            pitting: flag(json, "pitting"),
+            in_garage_stall: flag(json, "inGarageStall"),
+            player: flag(json, "player"),
+            has_focus: flag(json, "hasFocus"),
+            under_yellow: flag(json, "underYellow"),
+        }
+    }
+}
+
+/// A value of the garage setup, such as `VM_FUEL_LEVEL`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SetupValue {
+    pub key: String,
+    /// The value as the garage displays it, such as `60.0L`.
+    pub text: String,
+    /// The setting index the game stores.
+    pub value: f64,
+    /// Whether the car allows adjusting it.
+    pub available: bool,
+}
+
+/// The setup of the player's car, from `/rest/garage/getPlayerGarageData`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GarageSetup {
+    pub values: Vec<SetupValue>,
+}
+
+impl GarageSetup {
+    pub fn parse(text: &str) -> Result<Self> {
+        let json = parse_json(text)?;
+        let values = json.get("carSetup").and_then(|setup| setup.get("garageValues")).unwrap_or(&json);
+        let Json::Object(members) = values else {
+            return Err(LeMansUltimateError::InvalidData("expected garage values".to_string()));
+        };
+        Ok(Self {
+            values: members
+                .iter()
+                .filter(|(_, value)| value.get("stringValue").is_some())
+                .map(|(key, value)| SetupValue {
+                    key: value.get("key").and_then(Json::as_str).unwrap_or(key).to_string(),
+                    text: string(value, "stringValue"),
+                    value: number(value, "value"),
+                    available: flag(value, "available"),
+                })
+                .collect(),
+        })
+    }
+
+    /// The value of `key`, compared ignoring ASCII case.
+    pub fn get(&self, key: &str) -> Option<&SetupValue> {
+        self.values.iter().find(|value| value.key.eq_ignore_ascii_case(key))
+    }
+}
+
+/// Fuel, virtual energy and tyres, from the repair and refuel screen at
+/// `/rest/garage/UIScreen/RepairAndRefuel`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PitStrategy {
+    /// Fuel in the tank in l.
+    pub current_fuel: f64,
+    pub max_fuel: f64,
+    /// Virtual energy left, in the unit of `max_virtual_energy`, 0.0 for
+    /// cars without it.
+    pub current_virtual_energy: f64,
+    pub max_virtual_energy: f64,
+    /// Tyre life left as a fraction, front left, front right, rear left,
+    /// rear right.
+    pub tyre_life: [f64; 4],
+    /// Estimated length of the planned pit stop in s.
+    pub pit_stop_time: f64,
+}
+
+impl PitStrategy {
+    pub fn parse(text: &str) -> Result<Self> {
+        let json = parse_json(text)?;
+        let fuel = json.get("fuelInfo").unwrap_or(&Json::Null);
+        let mut tyre_life = [0.0; 4];
+        if let Some(life) = json.get("wheelInfo").and_then(|wheels| wheels.get("wheelLife")).and_then(Json::as_array) {
+            for (slot, value) in tyre_life.iter_mut().zip(life) {
+                *slot = value.as_f64().unwrap_or(0.0);
+            }
+        }
+        Ok(Self {
+            current_fuel: number(fuel, "currentFuel"),
+            max_fuel: number(fuel, "maxFuel"),
+            current_virtual_energy: number(fuel, "currentVirtualEnergy"),
+            max_virtual_energy: number(fuel, "maxVirtualEnergy"),
+            tyre_life,
+            pit_stop_time: json.get("pitStopLength").map_or(0.0, |length| number(length, "timeInSeconds")),
+        })
+    }
+
+    pub fn fuel_fraction(&self) -> f64 {
+        if self.max_fuel > 0.0 { self.current_fuel / self.max_fuel } else { 0.0 }
+    }
+
+    pub fn virtual_energy_fraction(&self) -> f64 {
+        if self.max_virtual_energy > 0.0 {
+            self.current_virtual_energy / self.max_virtual_energy
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Fuel and virtual energy a driver used on a lap, from
+/// `/rest/strategy/usage`, which lists the laps by driver name.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LapUsage {
+    pub driver_name: String,
+    pub lap: i32,
+    /// Fuel used in l.
+    pub fuel: f64,
+    /// Virtual energy used as a fraction of the maximum.
+    pub virtual_energy: f64,
+}
+
+impl LapUsage {
+    pub fn parse_list(text: &str) -> Result<Vec<Self>> {
+        let Json::Object(drivers) = parse_json(text)? else {
+            return Err(LeMansUltimateError::InvalidData("expected laps by driver".to_string()));
+        };
+        let mut usage = Vec::new();
+        for (driver_name, laps) in &drivers {
+            for (index, lap) in laps.as_array().unwrap_or_default().iter().enumerate() {
+                usage.push(Self {
+                    driver_name: driver_name.clone(),
+                    lap: lap.get("lap").and_then(Json::as_i32).unwrap_or(index as i32 + 1),
+                    fuel: number(lap, "fuel"),
+                    virtual_energy: lap.get("ve").or_else(|| lap.get("virtualEnergy")).and_then(Json::as_f64).unwrap_or(0.0),
+                });
+            }
+        }
+        Ok(usage)
+    }
+}
+
+/// A car of `/rest/race/car`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CarEntry {
+    pub id: String,
+    pub name: String,
+    pub veh_file: String,
+    pub team: String,
+    pub number: String,
+    pub manufacturer: String,
+    pub classes: Vec<String>,
+}
+
+impl CarEntry {
+    pub fn parse_list(text: &str) -> Result<Vec<Self>> {
+        list(text, |json| Self {
+            id: string(json, "id"),
+            name: string(json, "name"),
+            veh_file: string(json, "vehFile"),
+            team: string(json, "team"),
+            number: string(json, "number"),
+            manufacturer: string(json, "manufacturer"),
+            classes: json
+                .get("classes")
+                .and_then(Json::as_array)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(Json::as_str)
+                .map(str::to_string)
+                .collect(),
+        })
+    }
+}
+
+/// A track layout of `/rest/race/track`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrackEntry {
+    pub id: String,
+    pub name: String,
+    pub short_name: String,
+    /// Lap length in m, 0.0 when not listed.
+    pub length: f64,
+}
+
+impl TrackEntry {
+    pub fn parse_list(text: &str) -> Result<Vec<Self>> {
+        list(text, |json| Self {
+            id: string(json, "id"),
+            name: string(json, "name"),
+            short_name: string(json, "shortName"),
+            length: number(json, "length"),
+        })
+    }
+}
+
+/// An endpoint [`RestPoller`] refreshes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RestEndpoint {
+    SessionInfo,
+    Standings,
+    GarageSetup,
+    PitStrategy,
+    StrategyUsage,
+}
+
+impl RestEndpoint {
+    pub const ALL: [RestEndpoint; 5] = [
+        RestEndpoint::SessionInfo,
+        RestEndpoint::Standings,
+        RestEndpoint::GarageSetup,
+        RestEndpoint::PitStrategy,
+        RestEndpoint::StrategyUsage,
+    ];
+
+    /// How often [`RestPoller`] refreshes it unless told otherwise.
+    pub fn default_interval(self) -> Duration {
+        match self {
+            RestEndpoint::SessionInfo | RestEndpoint::Standings => Duration::from_millis(500),
+            RestEndpoint::PitStrategy => Duration::from_secs(1),
+            RestEndpoint::GarageSetup | RestEndpoint::StrategyUsage => Duration::from_secs(5),
+        }
+    }
+}
+
+/// Keeps the latest response of each endpoint, refreshing those due on
+/// every [`RestPoller::poll`].
+///
+/// The API answers slower than the shared memory updates, so the poller
+/// is meant to be called from the same loop that reads the buffers, each
+/// endpoint being requested at most once per interval.
+///
+/// ```no_run
+/// use race_sdk::lmu::{LeMansUltimateRest, RestEndpoint, RestPoller};
+/// use std::time::Duration;
+///
+/// let mut poller = RestPoller::new(LeMansUltimateRest::new()).with_interval(RestEndpoint::GarageSetup, Duration::from_secs(30));
+/// loop {
+///     if poller.poll().contains(&RestEndpoint::PitStrategy)
+///         && let Some(strategy) = poller.pit_strategy()
+///     {
+///         println!("{:.1} L", strategy.current_fuel);
+///     }
+///     std::thread::sleep(Duration::from_millis(100));
+/// }
+/// ```
+pub struct RestPoller {
+    rest: LeMansUltimateRest,
+    intervals: [Option<Duration>; 5],
+    polled_at: [Option<Instant>; 5],
+    session_info: Option<RestSessionInfo>,
+    standings: Vec<RestStanding>,
+    garage_setup: Option<GarageSetup>,
+    pit_strategy: Option<PitStrategy>,
+    strategy_usage: Vec<LapUsage>,
+    last_error: Option<LeMansUltimateError>,
+}
+
+impl RestPoller {
+    /// Polls every endpoint at its [`RestEndpoint::default_interval`].
+    pub fn new(rest: LeMansUltimateRest) -> Self {
+        Self {
+            rest,
+            intervals: RestEndpoint::ALL.map(|endpoint| Some(endpoint.default_interval())),
+            polled_at: [None; 5],
+            session_info: None,
+            standings: Vec::new(),
+            garage_setup: None,
+            pit_strategy: None,
+            strategy_usage: Vec::new(),
+            last_error: None,
+        }
+    }
+
+    pub fn with_interval(mut self, endpoint: RestEndpoint, interval: Duration) -> Self {
+        self.intervals[endpoint as usize] = Some(interval);
+        self
+    }
+
+    /// Stops polling `endpoint`, its last response is kept.
+    pub fn without(mut self, endpoint: RestEndpoint) -> Self {
+        self.intervals[endpoint as usize] = None;
+        self
+    }
+
+    pub fn rest(&self) -> &LeMansUltimateRest {
+        &self.rest
+    }
+
+    /// Requests the endpoints due and returns those that answered.
+    ///
+    /// An endpoint that fails keeps its last response and is retried
+    /// after its interval, the error is kept in [`RestPoller::last_error`].
+    pub fn poll(&mut self) -> Vec<RestEndpoint> {
+        let now = Instant::now();
+        let mut updated = Vec::new();
+        for endpoint in RestEndpoint::ALL {
+            let index = endpoint as usize;
+            let Some(interval) = self.intervals[index] else {
+                continue;
+            };
+            if self.polled_at[index].is_some_and(|at| now.duration_since(at) < interval) {
+                continue;
+            }
+            self.polled_at[index] = Some(now);
+            match self.refresh(endpoint) {
+                Ok(()) => updated.push(endpoint),
+                Err(err) => self.last_error = Some(err),
+            }
+        }
+        updated
+    }
+
+    /// Requests `endpoint` now, regardless of its interval.
+    pub fn refresh(&mut self, endpoint: RestEndpoint) -> Result<()> {
+        match endpoint {
+            RestEndpoint::SessionInfo => self.session_info = Some(self.rest.get_session_info()?),
+            RestEndpoint::Standings => self.standings = self.rest.get_standings()?,
+            RestEndpoint::GarageSetup => self.garage_setup = Some(self.rest.get_garage_setup()?),
+            RestEndpoint::PitStrategy => self.pit_strategy = Some(self.rest.get_pit_strategy()?),
+            RestEndpoint::StrategyUsage => self.strategy_usage = self.rest.get_strategy_usage()?,
+        }
+        self.polled_at[endpoint as usize] = Some(Instant::now());
+        Ok(())
+    }
+
+    pub fn session_info(&self) -> Option<&RestSessionInfo> {
+        self.session_info.as_ref()
+    }
+
+    pub fn standings(&self) -> &[RestStanding] {
+        &self.standings
+    }
+
+    pub fn garage_setup(&self) -> Option<&GarageSetup> {
+        self.garage_setup.as_ref()
+    }
+
+    pub fn pit_strategy(&self) -> Option<&PitStrategy> {
+        self.pit_strategy.as_ref()
+    }
+
+    pub fn strategy_usage(&self) -> &[LapUsage] {
+        &self.strategy_usage
+    }
+
+    /// The error of the last request that failed.
+    pub fn last_error(&self) -> Option<&LeMansUltimateError> {
+        self.last_error.as_ref()
+    }
+}
+
+/// A vehicle of the shared memory scoring with what the API adds.
+#[derive(Debug, Clone)]
+pub struct VehicleStanding {
+    pub scoring: VehicleScoring,
+    /// `None` when the API is not polled or did not list the vehicle.
+    pub rest: Option<RestStanding>,
+}
+
+impl VehicleStanding {
+    /// The car number, which only the API provides.
+    pub fn car_number(&self) -> Option<&str> {
+        self.rest.as_ref().map(|rest| rest.car_number.as_str()).filter(|number| !number.is_empty())
+    }
+
+    pub fn team_name(&self) -> Option<&str> {
+        self.rest.as_ref().map(|rest| rest.full_team_name.as_str()).filter(|name| !name.is_empty())
+    }
+
+    pub fn fuel_fraction(&self) -> Option<f64> {
+        self.rest.as_ref().map(|rest| rest.fuel_fraction)
+    }
+}
+
+/// Pairs the scoring standings, in order of place, with the API's
+/// standings by slot id, falling back to the driver name for responses
+/// without slot ids.
+///
+/// The scoring is the fresher of the two and stays authoritative for what
+/// both provide.
+pub fn merge_standings(scoring: &Scoring, rest: &[RestStanding]) -> Vec<VehicleStanding> {
+    scoring
+        .standings()
+        .into_iter()
+        .map(|vehicle| {
+            let id = vehicle.id;
+            let rest = rest
+                .iter()
+                .find(|standing| standing.slot_id == id)
+                .or_else(|| {
+                    let name = vehicle.driver_name_str();
+                    rest.iter().find(|standing| standing.slot_id < 0 && standing.driver_name == name)
+                })
+                .cloned();
+            VehicleStanding { scoring: vehicle, rest }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::sync::mpsc::{self, Receiver};
+    use std::thread;
+
+    /// Answers one connection per response, in order, and reports the
+    /// request lines it received.
+    fn serve(responses: Vec<String>) -> (LeMansUltimateRest, Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let (sender, requests) = mpsc::channel();
+        thread::spawn(move || {
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request = String::new();
+                reader.read_line(&mut request).unwrap();
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap() > 0 && line != "\r\n" {
+                    line.clear();
+                }
+                let _ = sender.send(request.trim_end().to_string());
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        (LeMansUltimateRest::new().with_address(address), requests)
+    }
+
+    fn ok(body: &str) -> String {
+        format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        )
+    }
+
+    #[test]
+    fn session_info() {
+        let body = r#"{"trackName":"Circuit de la Sarthe","session":"RACE1","currentEventTime":100.5,"endEventTime":3700.5,
+            "maximumLaps":2147483647,"gamePhase":5,"inRealtime":true,"raceCompletion":{"timeCompletion":0.25,"lapsCompletion":-1.0}}"#;
+        let (rest, requests) = serve(vec![ok(body)]);
+        let info = rest.get_session_info().unwrap();
+        assert_eq!(requests.recv().unwrap(), format!("GET {} HTTP/1.1", SESSION_INFO_PATH));
+        assert_eq!(info.track_name, "Circuit de la Sarthe");
+        assert_eq!(info.session, "RACE1");
+        assert_eq!(info.maximum_laps, i32::MAX);
+        assert_eq!(info.game_phase, 5);
+        assert!(info.in_realtime);
+        assert_eq!(info.time_completion, 0.25);
+        assert_eq!(info.laps_completion, -1.0);
+        assert_eq!(info.time_remaining(), Some(3600.0));
+        assert_eq!(info.player_name, "");
+    }
+
+    #[test]
+    fn chunked_standings() {
+        let body = r#"[{"slotID":3,"driverName":"A. Driver","carNumber":51,"fullTeamName":"Team A","position":1},{"driverName":"B. Driver","carNumber":"7","position":2}]"#;
+        let (first, second) = body.split_at(40);
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n{:x};ext=1\r\n{}\r\n{:X}\r\n{}\r\n0\r\n\r\n",
+            first.len(),
+            first,
+            second.len(),
+            second
+        );
+        let (rest, requests) = serve(vec![response]);
+        let standings = rest.get_standings().unwrap();
+        assert_eq!(requests.recv().unwrap(), format!("GET {} HTTP/1.1", STANDINGS_PATH));
+        assert_eq!(standings.len(), 2);
+        assert_eq!((standings[0].slot_id, standings[0].car_number.as_str(), standings[0].position), (3, "51", 1));
+        assert_eq!(standings[0].full_team_name, "Team A");
+        assert_eq!((standings[1].slot_id, standings[1].car_number.as_str()), (-1, "7"));
+    }
+
+    #[test]
+    fn body_until_close() {
+        let (rest, _) = serve(vec!["HTTP/1.1 200 OK\r\nConnection: close\r\n\r\n[]".to_string()]);
+        assert!(rest.get_standings().unwrap().is_empty());
+    }
+
+    #[test]
+    fn non_200_status() {
+        let (rest, _) = serve(vec![
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 9\r\n\r\nnot found".to_string(),
+            "HTTP/1.1 503 Service Unavailable\r\nTransfer-Encoding: chunked\r\n\r\n4\r\nbusy\r\n0\r\n\r\n".to_string(),
+        ]);
+        match rest.get_session_info() {
+            Err(LeMansUltimateError::Http { status, path }) => assert_eq!((status, path.as_str()), (404, SESSION_INFO_PATH)),
+            other => panic!("expected HTTP 404, got {:?}", other),
+        }
+        match rest.get_standings() {
+            Err(LeMansUltimateError::Http { status, .. }) => assert_eq!(status, 503),
+            other => panic!("expected HTTP 503, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn oversized_bodies() {
+        let (rest, _) = serve(vec![
+            format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n[]", usize::MAX),
+            "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\nffffffffffff\r\n[]\r\n0\r\n\r\n".to_string(),
+            "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\nzz\r\n[]\r\n0\r\n\r\n".to_string(),
+        ]);
+        for _ in 0..3 {
+            assert!(matches!(rest.get_standings(), Err(LeMansUltimateError::InvalidData(_))));
+        }
+    }
+}
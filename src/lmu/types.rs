@@ -0,0 +1,1059 @@
+use crate::telemetry::Packet;
+use std::fmt;
+use std::mem;
+use std::ptr;
+
+/// Vehicles in the multi-vehicle buffers.
+pub const MAX_MAPPED_VEHICLES: usize = 128;
+
+pub const TELEMETRY_BUFFER_NAME: &str = "$rFactor2SMMP_Telemetry$";
+pub const SCORING_BUFFER_NAME: &str = "$rFactor2SMMP_Scoring$";
+pub const RULES_BUFFER_NAME: &str = "$rFactor2SMMP_Rules$";
+pub const EXTENDED_BUFFER_NAME: &str = "$rFactor2SMMP_Extended$";
+pub const PIT_INFO_BUFFER_NAME: &str = "$rFactor2SMMP_PitInfo$";
+pub const WEATHER_BUFFER_NAME: &str = "$rFactor2SMMP_Weather$";
+
+/// Bytes before the vehicles of the telemetry buffer: the two update
+/// counters, `mBytesUpdatedHint` and `mNumVehicles`.
+const TELEMETRY_HEADER_SIZE: usize = 16;
+/// Bytes before the scoring info and the rules: the two update counters
+/// and `mBytesUpdatedHint`.
+const BUFFER_HEADER_SIZE: usize = 12;
+
+/// Offset from absolute zero of the Kelvin temperatures of [`Wheel`].
+const KELVIN: f64 = 273.15;
+
+/// Reads `mVersionUpdateBegin` and `mVersionUpdateEnd` from the start of a
+/// buffer while the game may be writing it.
+///
+/// The game increments the first before and the second after an update,
+/// the buffer is consistent while both are equal.
+pub fn update_versions_of(bytes: &[u8]) -> Option<(u32, u32)> {
+    let words = bytes.get(0..8)?;
+    Some(unsafe {
+        (
+            ptr::read_volatile(words.as_ptr() as *const u32),
+            ptr::read_volatile(words.as_ptr().add(4) as *const u32),
+        )
+    })
+}
+
+/// Copies a `#[repr(C)]` type whose fields accept any bit pattern.
+fn read_struct<T>(bytes: &[u8], offset: usize) -> Option<T> {
+    let bytes = bytes.get(offset..offset + mem::size_of::<T>())?;
+    Some(unsafe { ptr::read_unaligned(bytes.as_ptr() as *const T) })
+}
+
+fn read_i32(bytes: &[u8], offset: usize) -> Option<i32> {
+    Some(i32::from_le_bytes(bytes.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+/// Copies `count` consecutive structs, clamped to [`MAX_MAPPED_VEHICLES`].
+fn read_array<T>(bytes: &[u8], offset: usize, count: i32) -> Option<Vec<T>> {
+    let count = usize::try_from(count).unwrap_or(0).min(MAX_MAPPED_VEHICLES);
+    (0..count).map(|i| read_struct(bytes, offset + i * mem::size_of::<T>())).collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum GamePhase {
+    Garage = 0,
+    WarmUp = 1,
+    GridWalk = 2,
+    Formation = 3,
+    Countdown = 4,
+    GreenFlag = 5,
+    FullCourseYellow = 6,
+    SessionStopped = 7,
+    SessionOver = 8,
+    PausedOrHeartbeat = 9,
+}
+
+impl GamePhase {
+    pub fn from_raw(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => GamePhase::Garage,
+            1 => GamePhase::WarmUp,
+            2 => GamePhase::GridWalk,
+            3 => GamePhase::Formation,
+            4 => GamePhase::Countdown,
+            5 => GamePhase::GreenFlag,
+            6 => GamePhase::FullCourseYellow,
+            7 => GamePhase::SessionStopped,
+            8 => GamePhase::SessionOver,
+            9 => GamePhase::PausedOrHeartbeat,
+            _ => return None,
+        })
+    }
+}
+
+/// The session of [`ScoringInfo::session`], numbered from one within its
+/// kind.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SessionType {
+    TestDay,
+    Practice(i32),
+    Qualifying(i32),
+    WarmUp,
+    Race(i32),
+}
+
+impl SessionType {
+    pub fn from_raw(value: i32) -> Option<Self> {
+        Some(match value {
+            0 => SessionType::TestDay,
+            1..=4 => SessionType::Practice(value),
+            5..=8 => SessionType::Qualifying(value - 4),
+            9 => SessionType::WarmUp,
+            10..=13 => SessionType::Race(value - 9),
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for SessionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SessionType::TestDay => write!(f, "Test Day"),
+            SessionType::Practice(n) => write!(f, "Practice {}", n),
+            SessionType::Qualifying(n) => write!(f, "Qualifying {}", n),
+            SessionType::WarmUp => write!(f, "Warm Up"),
+            SessionType::Race(n) => write!(f, "Race {}", n),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(i8)]
+pub enum FinishStatus {
+    None = 0,
+    Finished = 1,
+    Dnf = 2,
+    Disqualified = 3,
+}
+
+impl FinishStatus {
+    pub fn from_raw(value: i8) -> Option<Self> {
+        Some(match value {
+            0 => FinishStatus::None,
+            1 => FinishStatus::Finished,
+            2 => FinishStatus::Dnf,
+            3 => FinishStatus::Disqualified,
+            _ => return None,
+        })
+    }
+}
+
+/// Who drives a vehicle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(i8)]
+pub enum Control {
+    Nobody = -1,
+    LocalPlayer = 0,
+    LocalAi = 1,
+    Remote = 2,
+    Replay = 3,
+}
+
+impl Control {
+    pub fn from_raw(value: i8) -> Option<Self> {
+        Some(match value {
+            -1 => Control::Nobody,
+            0 => Control::LocalPlayer,
+            1 => Control::LocalAi,
+            2 => Control::Remote,
+            3 => Control::Replay,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum PitState {
+    None = 0,
+    Request = 1,
+    Entering = 2,
+    Stopped = 3,
+    Exiting = 4,
+}
+
+impl PitState {
+    pub fn from_raw(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => PitState::None,
+            1 => PitState::Request,
+            2 => PitState::Entering,
+            3 => PitState::Stopped,
+            4 => PitState::Exiting,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+#[repr(C, packed(4))]
+pub struct Vec3 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Vec3 {
+    pub fn length(&self) -> f64 {
+        let (x, y, z) = (self.x, self.y, self.z);
+        (x * x + y * y + z * z).sqrt()
+    }
+}
+
+/// One wheel of [`VehicleTelemetry::wheels`], ordered front left, front
+/// right, rear left, rear right.
+///
+/// Temperatures are in Kelvin, see [`Wheel::surface_temperature_celsius`].
+#[derive(Debug, Clone, Copy)]
+#[repr(C, packed(4))]
+pub struct Wheel {
+    /// In m.
+    pub suspension_deflection: f64,
+    pub ride_height: f64,
+    /// Pushrod load in N.
+    pub susp_force: f64,
+    /// In °C.
+    pub brake_temp: f64,
+    /// From `0.0` to `1.0`, depending on the pedal and the brake balance.
+    pub brake_pressure: f64,
+    /// In rad/s.
+    pub rotation: f64,
+    pub lateral_patch_vel: f64,
+    pub longitudinal_patch_vel: f64,
+    pub lateral_ground_vel: f64,
+    pub longitudinal_ground_vel: f64,
+    /// In rad.
+    pub camber: f64,
+    pub lateral_force: f64,
+    pub longitudinal_force: f64,
+    pub tire_load: f64,
+    /// Fraction of the contact patch that is sliding.
+    pub grip_fract: f64,
+    /// In kPa.
+    pub pressure: f64,
+    /// Left, center and right of the tread.
+    pub temperature: [f64; 3],
+    /// From `0.0` to `1.0`, fraction of the tread left.
+    pub wear: f64,
+    pub terrain_name: [u8; 16],
+    /// 0=dry, 1=wet, 2=grass, 3=dirt, 4=gravel, 5=rumble strip, 6=special.
+    pub surface_type: u8,
+    pub flat: u8,
+    pub detached: u8,
+    /// In cm.
+    pub static_undeflected_radius: u8,
+    pub vertical_tire_deflection: f64,
+    pub wheel_y_location: f64,
+    pub toe: f64,
+    pub tire_carcass_temperature: f64,
+    pub tire_inner_layer_temperature: [f64; 3],
+    pub expansion: [u8; 24],
+}
+
+impl Wheel {
+    pub fn terrain_name_str(&self) -> String {
+        decode_name(&self.terrain_name)
+    }
+
+    pub fn surface_temperature_celsius(&self) -> [f64; 3] {
+        self.temperature.map(|kelvin| kelvin - KELVIN)
+    }
+
+    pub fn carcass_temperature_celsius(&self) -> f64 {
+        self.tire_carcass_temperature - KELVIN
+    }
+}
+
+/// The physics of one vehicle, in SI units. Local vectors are relative to
+/// the car: x points left, y up and z back.
+#[derive(Debug, Clone, Copy)]
+#[repr(C, packed(4))]
+pub struct VehicleTelemetry {
+    /// Slot id, matches [`VehicleScoring::id`].
+    pub id: i32,
+    /// Seconds since the last update.
+    pub delta_time: f64,
+    /// Session time in seconds.
+    pub elapsed_time: f64,
+    pub lap_number: i32,
+    pub lap_start_et: f64,
+    pub vehicle_name: [u8; 64],
+    pub track_name: [u8; 64],
+
+    pub pos: Vec3,
+    pub local_vel: Vec3,
+    pub local_accel: Vec3,
+    /// Rows of the orientation matrix.
+    pub ori: [Vec3; 3],
+    pub local_rot: Vec3,
+    pub local_rot_accel: Vec3,
+
+    /// -1=reverse, 0=neutral, 1+=forward gears.
+    pub gear: i32,
+    pub engine_rpm: f64,
+    /// In °C.
+    pub engine_water_temp: f64,
+    pub engine_oil_temp: f64,
+    pub clutch_rpm: f64,
+
+    /// Driver inputs from `0.0` to `1.0`, steering from `-1.0` to `1.0`.
+    pub unfiltered_throttle: f64,
+    pub unfiltered_brake: f64,
+    pub unfiltered_steering: f64,
+    pub unfiltered_clutch: f64,
+    /// Inputs after the driving aids.
+    pub filtered_throttle: f64,
+    pub filtered_brake: f64,
+    pub filtered_steering: f64,
+    pub filtered_clutch: f64,
+
+    /// In Nm.
+    pub steering_shaft_torque: f64,
+    pub front_3rd_deflection: f64,
+    pub rear_3rd_deflection: f64,
+
+    pub front_wing_height: f64,
+    pub front_ride_height: f64,
+    pub rear_ride_height: f64,
+    pub drag: f64,
+    pub front_downforce: f64,
+    pub rear_downforce: f64,
+
+    /// In l.
+    pub fuel: f64,
+    pub engine_max_rpm: f64,
+    pub scheduled_stops: u8,
+    pub overheating: u8,
+    pub detached: u8,
+    pub headlights: u8,
+    /// 0=none, 1=some, 2=more, for eight locations around the car.
+    pub dent_severity: [u8; 8],
+    pub last_impact_et: f64,
+    pub last_impact_magnitude: f64,
+    pub last_impact_pos: Vec3,
+
+    /// In Nm.
+    pub engine_torque: f64,
+    /// Zero-based sector, with the pit lane in the sign bit, see
+    /// [`VehicleTelemetry::sector`].
+    pub current_sector: i32,
+    pub speed_limiter: u8,
+    pub max_gears: u8,
+    pub front_tire_compound_index: u8,
+    pub rear_tire_compound_index: u8,
+    /// In l.
+    pub fuel_capacity: f64,
+    pub front_flap_activated: u8,
+    pub rear_flap_activated: u8,
+    pub rear_flap_legal_status: u8,
+    pub ignition_starter: u8,
+    pub front_tire_compound_name: [u8; 18],
+    pub rear_tire_compound_name: [u8; 18],
+    pub speed_limiter_available: u8,
+    pub anti_stall_activated: u8,
+    pub unused: [u8; 2],
+    /// In degrees.
+    pub visual_steering_wheel_range: f32,
+    /// Fraction of the braking on the rear.
+    pub rear_brake_bias: f64,
+    pub turbo_boost_pressure: f64,
+    pub physics_to_graphics_offset: [f32; 3],
+    pub physical_steering_wheel_range: f32,
+
+    /// From `0.0` to `1.0`, of hybrid cars.
+    pub battery_charge_fraction: f64,
+    pub electric_boost_motor_torque: f64,
+    pub electric_boost_motor_rpm: f64,
+    pub electric_boost_motor_temperature: f64,
+    pub electric_boost_water_temperature: f64,
+    /// 0=unavailable, 1=inactive, 2=propulsion, 3=regeneration.
+    pub electric_boost_motor_state: u8,
+    pub expansion: [u8; 111],
+
+    pub wheels: [Wheel; 4],
+}
+
+impl VehicleTelemetry {
+    pub fn vehicle_name_str(&self) -> String {
+        decode_name(&self.vehicle_name)
+    }
+
+    pub fn track_name_str(&self) -> String {
+        decode_name(&self.track_name)
+    }
+
+    pub fn front_tire_compound_name_str(&self) -> String {
+        decode_name(&self.front_tire_compound_name)
+    }
+
+    pub fn rear_tire_compound_name_str(&self) -> String {
+        decode_name(&self.rear_tire_compound_name)
+    }
+
+    pub fn speed_kmh(&self) -> f64 {
+        self.local_vel.length() * 3.6
+    }
+
+    /// The zero-based sector, without the pit lane bit.
+    pub fn sector(&self) -> i32 {
+        self.current_sector & i32::MAX
+    }
+
+    pub fn is_in_pit_lane(&self) -> bool {
+        self.current_sector < 0
+    }
+}
+
+/// The session state of the scoring buffer.
+#[derive(Debug, Clone, Copy)]
+#[repr(C, packed(4))]
+pub struct ScoringInfo {
+    pub track_name: [u8; 64],
+    /// See [`ScoringInfo::session_type`].
+    pub session: i32,
+    /// Session time in seconds.
+    pub current_et: f64,
+    pub end_et: f64,
+    pub max_laps: i32,
+    /// Length of the track in m.
+    pub lap_dist: f64,
+    pub pointer1: [u8; 8],
+    pub num_vehicles: i32,
+    /// See [`ScoringInfo::game_phase`].
+    pub game_phase: u8,
+    /// Full-course yellow state, -1=invalid, 0=none, 1=pending, 2=pits
+    /// closed, 3=pit lead lap, 4=pits open, 5=last lap, 6=resume, 7=race
+    /// halt.
+    pub yellow_flag_state: i8,
+    /// Local yellows per sector.
+    pub sector_flag: [i8; 3],
+    pub start_light: u8,
+    pub num_red_lights: u8,
+    pub in_realtime: u8,
+    pub player_name: [u8; 32],
+    pub plr_file_name: [u8; 64],
+
+    pub dark_cloud: f64,
+    /// From `0.0` to `1.0`.
+    pub raining: f64,
+    /// In °C.
+    pub ambient_temp: f64,
+    pub track_temp: f64,
+    pub wind: Vec3,
+    pub min_path_wetness: f64,
+    pub max_path_wetness: f64,
+
+    /// 1=server, 2=client, 3=server and client.
+    pub game_mode: u8,
+    pub is_password_protected: u8,
+    pub server_port: u16,
+    pub server_public_ip: u32,
+    pub max_players: i32,
+    pub server_name: [u8; 32],
+    pub start_et: f32,
+    pub avg_path_wetness: f64,
+    pub expansion: [u8; 200],
+    pub pointer2: [u8; 8],
+}
+
+impl ScoringInfo {
+    pub fn track_name_str(&self) -> String {
+        decode_name(&self.track_name)
+    }
+
+    pub fn player_name_str(&self) -> String {
+        decode_name(&self.player_name)
+    }
+
+    pub fn server_name_str(&self) -> String {
+        decode_name(&self.server_name)
+    }
+
+    pub fn session_type(&self) -> Option<SessionType> {
+        SessionType::from_raw(self.session)
+    }
+
+    pub fn game_phase(&self) -> Option<GamePhase> {
+        GamePhase::from_raw(self.game_phase)
+    }
+}
+
+/// The standing of one vehicle. Times are in seconds and negative when not
+/// set.
+#[derive(Debug, Clone, Copy)]
+#[repr(C, packed(4))]
+pub struct VehicleScoring {
+    /// Slot id, matches [`VehicleTelemetry::id`].
+    pub id: i32,
+    pub driver_name: [u8; 32],
+    pub vehicle_name: [u8; 64],
+    pub total_laps: i16,
+    /// 0=sector 3, 1=sector 1, 2=sector 2.
+    pub sector: i8,
+    /// See [`VehicleScoring::finish_status`].
+    pub finish_status: i8,
+    /// Distance around the lap in m.
+    pub lap_dist: f64,
+    pub path_lateral: f64,
+    pub track_edge: f64,
+
+    pub best_sector1: f64,
+    /// Cumulative, sector 1 and 2.
+    pub best_sector2: f64,
+    pub best_lap_time: f64,
+    pub last_sector1: f64,
+    pub last_sector2: f64,
+    pub last_lap_time: f64,
+    pub cur_sector1: f64,
+    pub cur_sector2: f64,
+
+    pub num_pitstops: i16,
+    pub num_penalties: i16,
+    pub is_player: u8,
+    /// See [`VehicleScoring::control`].
+    pub control: i8,
+    pub in_pits: u8,
+    /// One-based position.
+    pub place: u8,
+    pub vehicle_class: [u8; 32],
+
+    pub time_behind_next: f64,
+    pub laps_behind_next: i32,
+    pub time_behind_leader: f64,
+    pub laps_behind_leader: i32,
+    pub lap_start_et: f64,
+
+    pub pos: Vec3,
+    pub local_vel: Vec3,
+    pub local_accel: Vec3,
+    pub ori: [Vec3; 3],
+    pub local_rot: Vec3,
+    pub local_rot_accel: Vec3,
+
+    pub headlights: u8,
+    /// See [`VehicleScoring::pit_state`].
+    pub pit_state: u8,
+    pub server_scored: u8,
+    /// The [`GamePhase`] of this vehicle, 10=under yellow, 11=under blue.
+    pub individual_phase: u8,
+    /// One-based qualifying position.
+    pub qualification: i32,
+    pub time_into_lap: f64,
+    pub estimated_lap_time: f64,
+    pub pit_group: [u8; 24],
+    /// 0=green, 6=blue.
+    pub flag: u8,
+    pub under_yellow: u8,
+    /// 0=no lap or time, 1=lap but no time, 2=lap and time.
+    pub count_lap_flag: u8,
+    pub in_garage_stall: u8,
+    pub upgrade_pack: [u8; 16],
+    /// In m.
+    pub pit_lap_dist: f32,
+    /// Sector times of the best lap, cumulative like `best_sector2`.
+    pub best_lap_sector1: f32,
+    pub best_lap_sector2: f32,
+    pub expansion: [u8; 48],
+}
+
+impl VehicleScoring {
+    pub fn driver_name_str(&self) -> String {
+        decode_name(&self.driver_name)
+    }
+
+    pub fn vehicle_name_str(&self) -> String {
+        decode_name(&self.vehicle_name)
+    }
+
+    pub fn vehicle_class_str(&self) -> String {
+        decode_name(&self.vehicle_class)
+    }
+
+    pub fn pit_group_str(&self) -> String {
+        decode_name(&self.pit_group)
+    }
+
+    pub fn finish_status(&self) -> Option<FinishStatus> {
+        FinishStatus::from_raw(self.finish_status)
+    }
+
+    pub fn control(&self) -> Option<Control> {
+        Control::from_raw(self.control)
+    }
+
+    pub fn pit_state(&self) -> Option<PitState> {
+        PitState::from_raw(self.pit_state)
+    }
+
+    pub fn speed_kmh(&self) -> f64 {
+        self.local_vel.length() * 3.6
+    }
+}
+
+/// Full-course yellow and safety car state of the rules buffer.
+#[derive(Debug, Clone, Copy)]
+#[repr(C, packed(4))]
+pub struct TrackRules {
+    pub current_et: f64,
+    /// 0=formation init, 1=formation update, 2=normal, 3=caution init,
+    /// 4=caution update.
+    pub stage: i32,
+    /// 0=left lane, 1=left to right, 2=right lane, 3=right to left,
+    /// 4=pit lane.
+    pub pole_column: i32,
+    pub num_actions: i32,
+    pub pointer1: [u8; 8],
+    pub num_participants: i32,
+    pub yellow_flag_detected: i8,
+    pub yellow_flag_laps_was_overridden: u8,
+    pub safety_car_exists: u8,
+    pub safety_car_active: u8,
+    pub safety_car_laps: i32,
+    pub safety_car_threshold: f32,
+    pub safety_car_lap_dist: f64,
+    pub safety_car_lap_dist_at_start: f32,
+    pub pit_lane_start_dist: f32,
+    pub teleport_lap_dist: f32,
+    pub input_expansion: [u8; 256],
+    /// See [`ScoringInfo::yellow_flag_state`].
+    pub yellow_flag_state: i8,
+    pub yellow_flag_laps: i16,
+    /// 0=no change, 1=go active, 2=head for pits.
+    pub safety_car_instruction: i32,
+    pub safety_car_speed: f32,
+    pub safety_car_minimum_speed: f32,
+    pub safety_car_maximum_speed: f32,
+    pub message: [u8; 96],
+    pub pointer2: [u8; 8],
+    pub expansion: [u8; 256],
+}
+
+impl TrackRules {
+    pub fn message_str(&self) -> String {
+        decode_name(&self.message)
+    }
+}
+
+/// An action of the rules buffer, e.g. a vehicle cutting the track.
+#[derive(Debug, Clone, Copy, Default)]
+#[repr(C, packed(4))]
+pub struct TrackRulesAction {
+    /// 0=add from track, 1=add from pit, 2=add from undq, 3=remove to pit,
+    /// 4=remove to DNF, 5=remove to DQ, 6=remove to unloaded, 7=move to
+    /// back, 8=longest time, 9=DQ.
+    pub command: i32,
+    pub id: i32,
+    pub et: f64,
+}
+
+/// The rules state of one vehicle.
+#[derive(Debug, Clone, Copy)]
+#[repr(C, packed(4))]
+pub struct TrackRulesParticipant {
+    pub id: i32,
+    /// One-based position in the formation, 0 when not frozen.
+    pub frozen_order: i16,
+    pub place: i16,
+    /// How much the vehicle causes a yellow.
+    pub yellow_severity: f32,
+    pub current_relative_distance: f64,
+    pub relative_laps: i32,
+    /// See [`TrackRules::pole_column`].
+    pub column_assignment: i32,
+    pub position_assignment: i32,
+    /// 0=closed, 1=open, 2=open to the lead lap.
+    pub pits_open: u8,
+    pub up_to_speed: u8,
+    pub unused: [u8; 2],
+    pub goal_relative_distance: f64,
+    pub message: [u8; 96],
+    pub expansion: [u8; 192],
+}
+
+impl TrackRulesParticipant {
+    pub fn message_str(&self) -> String {
+        decode_name(&self.message)
+    }
+}
+
+/// The driving aids of the player, from the extended buffer.
+#[derive(Debug, Clone, Copy, Default)]
+#[repr(C, packed(4))]
+pub struct PhysicsOptions {
+    /// 0=off, 1=low, 2=medium, 3=high.
+    pub traction_control: u8,
+    pub anti_lock_brakes: u8,
+    pub stability_control: u8,
+    pub auto_shift: u8,
+    pub auto_clutch: u8,
+    pub invulnerable: u8,
+    pub opposite_lock: u8,
+    pub steering_help: u8,
+    pub braking_help: u8,
+    pub spin_recovery: u8,
+    pub auto_pit: u8,
+    pub auto_lift: u8,
+    pub auto_blip: u8,
+    pub fuel_mult: u8,
+    pub tire_mult: u8,
+    pub mech_fail: u8,
+    pub allow_pitcrew_push: u8,
+    pub repeat_shifts: u8,
+    pub hold_clutch: u8,
+    pub auto_reverse: u8,
+    pub alternate_neutral: u8,
+    pub ai_control: u8,
+    pub unused1: u8,
+    pub unused2: u8,
+    pub manual_shift_override_time: f32,
+    pub auto_shift_override_time: f32,
+    pub speed_sensitive_steering: f32,
+    pub steer_ratio_speed: f32,
+}
+
+/// Impacts of one vehicle slot, accumulated by the plugin.
+#[derive(Debug, Clone, Copy, Default)]
+#[repr(C, packed(4))]
+pub struct TrackedDamage {
+    pub max_impact_magnitude: f64,
+    pub accumulated_impact_magnitude: f64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+#[repr(C, packed(4))]
+pub struct VehScoringCapture {
+    pub id: i32,
+    pub place: u8,
+    pub is_player: u8,
+    pub finish_status: i8,
+    pub unused: u8,
+}
+
+/// The standings captured when the last session ended.
+#[derive(Debug, Clone, Copy)]
+#[repr(C, packed(4))]
+pub struct SessionTransitionCapture {
+    pub game_phase: u8,
+    pub unused: [u8; 3],
+    pub session: i32,
+    pub num_scoring_vehicles: i32,
+    pub scoring_vehicles: [VehScoringCapture; MAX_MAPPED_VEHICLES],
+}
+
+/// The `$rFactor2SMMP_Extended$` buffer, state the plugin tracks on top of
+/// the game's.
+#[derive(Debug, Clone, Copy)]
+#[repr(C, packed(4))]
+pub struct Extended {
+    pub version_update_begin: u32,
+    pub version_update_end: u32,
+    /// Version of the shared memory plugin, e.g. `3.7.15.1`.
+    pub version: [u8; 12],
+    pub is_64bit: u8,
+    pub unused: [u8; 3],
+    pub physics: PhysicsOptions,
+    /// Indexed by vehicle id modulo [`MAX_MAPPED_VEHICLES`].
+    pub tracked_damages: [TrackedDamage; MAX_MAPPED_VEHICLES],
+    pub in_realtime_fc: u8,
+    pub multimedia_thread_started: u8,
+    pub simulation_thread_started: u8,
+    pub session_started: u8,
+    pub ticks_session_started: i64,
+    pub ticks_session_ended: i64,
+    pub session_transition_capture: SessionTransitionCapture,
+    pub displayed_message_update_capture: [u8; 128],
+    pub direct_memory_access_enabled: u8,
+    pub unused2: [u8; 3],
+    pub ticks_status_message_updated: i64,
+    pub status_message: [u8; 128],
+    pub ticks_last_history_message_updated: i64,
+    pub last_history_message: [u8; 128],
+    /// In m/s.
+    pub current_pit_speed_limit: f32,
+    pub scr_plugin_enabled: u8,
+    pub unused3: [u8; 3],
+    pub scr_plugin_double_file_type: i32,
+    pub ticks_lsi_phase_message_updated: i64,
+    pub lsi_phase_message: [u8; 96],
+    pub ticks_lsi_pit_state_message_updated: i64,
+    pub lsi_pit_state_message: [u8; 96],
+    pub ticks_lsi_order_instruction_message_updated: i64,
+    pub lsi_order_instruction_message: [u8; 96],
+    pub ticks_lsi_rules_instruction_message_updated: i64,
+    pub lsi_rules_instruction_message: [u8; 96],
+    /// Buffers the plugin does not update, see the plugin's
+    /// `UnsubscribedBuffersMask` option.
+    pub unsubscribed_buffers_mask: i32,
+    pub hw_control_input_enabled: u8,
+    pub weather_control_input_enabled: u8,
+    pub rules_control_input_enabled: u8,
+    pub plugin_control_input_enabled: u8,
+}
+
+impl Extended {
+    /// Copies the buffer out of raw shared memory without checking the
+    /// update counters, `None` if `bytes` is too short.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        read_struct(bytes, 0)
+    }
+
+    pub fn version_str(&self) -> String {
+        decode_name(&self.version)
+    }
+
+    pub fn status_message_str(&self) -> String {
+        decode_name(&self.status_message)
+    }
+
+    pub fn last_history_message_str(&self) -> String {
+        decode_name(&self.last_history_message)
+    }
+
+    /// Impacts of a vehicle by its slot id.
+    pub fn tracked_damage(&self, id: i32) -> TrackedDamage {
+        self.tracked_damages[id.rem_euclid(MAX_MAPPED_VEHICLES as i32) as usize]
+    }
+}
+
+/// The pit menu as shown to the player.
+#[derive(Debug, Clone, Copy)]
+#[repr(C, packed(4))]
+pub struct PitMenu {
+    pub category_index: i32,
+    pub category_name: [u8; 32],
+    pub choice_index: i32,
+    pub choice_string: [u8; 32],
+    pub num_choices: i32,
+    pub expansion: [u8; 256],
+}
+
+/// The `$rFactor2SMMP_PitInfo$` buffer.
+#[derive(Debug, Clone, Copy)]
+#[repr(C, packed(4))]
+pub struct PitInfo {
+    pub version_update_begin: u32,
+    pub version_update_end: u32,
+    pub pit_menu: PitMenu,
+}
+
+impl PitInfo {
+    /// Copies the buffer out of raw shared memory without checking the
+    /// update counters, `None` if `bytes` is too short.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        read_struct(bytes, 0)
+    }
+
+    pub fn category_name_str(&self) -> String {
+        decode_name(&self.pit_menu.category_name)
+    }
+
+    pub fn choice_str(&self) -> String {
+        decode_name(&self.pit_menu.choice_string)
+    }
+}
+
+/// The weather forecast of the session.
+#[derive(Debug, Clone, Copy)]
+#[repr(C, packed(4))]
+pub struct WeatherControlInfo {
+    /// Session time the forecast applies from, in seconds.
+    pub et: f64,
+    /// Rain from `0.0` to `1.0` at the center and the eight surrounding
+    /// nodes of the track.
+    pub raining: [f64; 9],
+    pub cloudiness: f64,
+    pub ambient_temp_k: f64,
+    /// In m/s.
+    pub wind_max_speed: f64,
+    pub apply_cloudiness_instantly: u8,
+    pub unused1: u8,
+    pub unused2: u8,
+    pub unused3: u8,
+    pub expansion: [u8; 508],
+}
+
+/// The `$rFactor2SMMP_Weather$` buffer.
+#[derive(Debug, Clone, Copy)]
+#[repr(C, packed(4))]
+pub struct Weather {
+    pub version_update_begin: u32,
+    pub version_update_end: u32,
+    /// Distance between the weather nodes in m.
+    pub track_node_size: f64,
+    pub weather_info: WeatherControlInfo,
+}
+
+impl Weather {
+    /// Copies the buffer out of raw shared memory without checking the
+    /// update counters, `None` if `bytes` is too short.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        read_struct(bytes, 0)
+    }
+
+    pub fn ambient_temp_celsius(&self) -> f64 {
+        self.weather_info.ambient_temp_k - KELVIN
+    }
+}
+
+/// The `$rFactor2SMMP_Telemetry$` buffer, the physics of the vehicles the
+/// game simulates in detail, usually only the player's.
+#[derive(Debug, Clone, Default)]
+pub struct Telemetry {
+    /// `mVersionUpdateBegin` of the copy, increases with every update.
+    pub version: u32,
+    pub vehicles: Vec<VehicleTelemetry>,
+}
+
+impl Telemetry {
+    /// Size of the buffer in shared memory.
+    pub const BUFFER_SIZE: usize = TELEMETRY_HEADER_SIZE + MAX_MAPPED_VEHICLES * mem::size_of::<VehicleTelemetry>();
+
+    /// Copies the buffer out of raw shared memory without checking the
+    /// update counters, `None` if `bytes` is too short.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let (version, _) = update_versions_of(bytes)?;
+        let vehicles = read_array(bytes, TELEMETRY_HEADER_SIZE, read_i32(bytes, 12)?)?;
+        Some(Self { version, vehicles })
+    }
+
+    /// The vehicle with slot id `id`.
+    pub fn vehicle(&self, id: i32) -> Option<&VehicleTelemetry> {
+        self.vehicles.iter().find(|vehicle| vehicle.id == id)
+    }
+}
+
+impl Packet for Telemetry {
+    fn packet_id(&self) -> i32 {
+        self.version as i32
+    }
+}
+
+/// The `$rFactor2SMMP_Scoring$` buffer, the session and the standings of all
+/// vehicles, updated about five times per second.
+#[derive(Debug, Clone, Default)]
+pub struct Scoring {
+    /// `mVersionUpdateBegin` of the copy, increases with every update.
+    pub version: u32,
+    pub info: ScoringInfo,
+    pub vehicles: Vec<VehicleScoring>,
+}
+
+impl Scoring {
+    pub const BUFFER_SIZE: usize = BUFFER_HEADER_SIZE + mem::size_of::<ScoringInfo>() + MAX_MAPPED_VEHICLES * mem::size_of::<VehicleScoring>();
+
+    /// Copies the buffer out of raw shared memory without checking the
+    /// update counters, `None` if `bytes` is too short.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let (version, _) = update_versions_of(bytes)?;
+        let info: ScoringInfo = read_struct(bytes, BUFFER_HEADER_SIZE)?;
+        let vehicles = read_array(bytes, BUFFER_HEADER_SIZE + mem::size_of::<ScoringInfo>(), info.num_vehicles)?;
+        Some(Self { version, info, vehicles })
+    }
+
+    /// The vehicle driven by the local player.
+    pub fn player(&self) -> Option<&VehicleScoring> {
+        self.vehicles.iter().find(|vehicle| vehicle.is_player != 0)
+    }
+
+    /// The vehicle with slot id `id`.
+    pub fn vehicle(&self, id: i32) -> Option<&VehicleScoring> {
+        self.vehicles.iter().find(|vehicle| vehicle.id == id)
+    }
+
+    /// The vehicles ordered by position.
+    pub fn standings(&self) -> Vec<VehicleScoring> {
+        let mut vehicles = self.vehicles.clone();
+        vehicles.sort_by_key(|vehicle| vehicle.place);
+        vehicles
+    }
+}
+
+impl Packet for Scoring {
+    fn packet_id(&self) -> i32 {
+        self.version as i32
+    }
+}
+
+/// The `$rFactor2SMMP_Rules$` buffer, the race control state.
+#[derive(Debug, Clone, Default)]
+pub struct Rules {
+    /// `mVersionUpdateBegin` of the copy, increases with every update.
+    pub version: u32,
+    pub track_rules: TrackRules,
+    pub actions: Vec<TrackRulesAction>,
+    pub participants: Vec<TrackRulesParticipant>,
+}
+
+impl Rules {
+    pub const BUFFER_SIZE: usize = BUFFER_HEADER_SIZE
+        + mem::size_of::<TrackRules>()
+        + MAX_MAPPED_VEHICLES * (mem::size_of::<TrackRulesAction>() + mem::size_of::<TrackRulesParticipant>());
+
+    /// Copies the buffer out of raw shared memory without checking the
+    /// update counters, `None` if `bytes` is too short.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let (version, _) = update_versions_of(bytes)?;
+        let track_rules: TrackRules = read_struct(bytes, BUFFER_HEADER_SIZE)?;
+        let actions_offset = BUFFER_HEADER_SIZE + mem::size_of::<TrackRules>();
+        let participants_offset = actions_offset + MAX_MAPPED_VEHICLES * mem::size_of::<TrackRulesAction>();
+        Some(Self {
+            version,
+            actions: read_array(bytes, actions_offset, track_rules.num_actions)?,
+            participants: read_array(bytes, participants_offset, track_rules.num_participants)?,
+            track_rules,
+        })
+    }
+
+    pub fn participant(&self, id: i32) -> Option<&TrackRulesParticipant> {
+        self.participants.iter().find(|participant| participant.id == id)
+    }
+}
+
+impl Packet for Rules {
+    fn packet_id(&self) -> i32 {
+        self.version as i32
+    }
+}
+
+/// Decodes a fixed-size, null-terminated string, UTF-8 or else Latin-1 as
+/// older content writes it.
+pub fn decode_name(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&c| c == 0).unwrap_or(bytes.len());
+    match std::str::from_utf8(&bytes[..end]) {
+        Ok(text) => text.to_string(),
+        Err(_) => bytes[..end].iter().map(|&c| char::from(c)).collect(),
+    }
+}
+
+macro_rules! zeroed_default {
+    ($($ty:ty),*) => {
+        $(
+            impl Default for $ty {
+                fn default() -> Self {
+                    unsafe { mem::zeroed() }
+                }
+            }
+        )*
+    };
+}
+
+zeroed_default!(
+    Wheel,
+    VehicleTelemetry,
+    ScoringInfo,
+    VehicleScoring,
+    TrackRules,
+    TrackRulesParticipant,
+    SessionTransitionCapture,
+    Extended,
+    PitMenu,
+    PitInfo,
+    WeatherControlInfo,
+    Weather
+);
@@ -0,0 +1,312 @@
+use crate::export::{Column, SessionTracker, select_columns};
+use crate::motec::{CHANNEL_NAMES, Channel, ChannelData, Ldx, Log, Result};
+use crate::recording::{Frame, PageKind, Recording};
+use crate::telemetry::{FieldType, Page, Resampler, TelemetrySource, Value};
+use std::fs::File;
+use std::io::{BufWriter, Read, Write};
+use std::iter;
+use std::marker::PhantomData;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Columns exported when nothing is selected.
+const DEFAULT_COLUMNS: &[&str] = &[
+    "physics.*",
+    "graphics.completed_laps",
+    "graphics.i_current_time",
+    "graphics.normalized_car_position",
+];
+
+/// Converts a recording of a Kunos game into a MoTeC i2 log.
+///
+/// Physics and graphics are resampled to fixed rates, each selected element
+/// becomes a channel. Well-known elements get their i2 names and units (see
+/// [`CHANNEL_NAMES`]), all others keep their flattened name. Static info
+/// elements are written at the graphics rate, text fields are skipped.
+/// Venue, vehicle and driver come from the static info, lap markers are set
+/// where [`SessionTracker`] starts a new lap or session.
+///
+/// ```no_run
+/// use race_sdk::acc::AssettoCorsaCompetizione;
+/// use race_sdk::motec::MotecExporter;
+/// use race_sdk::recording::Recording;
+///
+/// let recording = Recording::open("session.rsdk").unwrap();
+/// MotecExporter::<AssettoCorsaCompetizione>::new()
+///     .with_physics_rate(50)
+///     .write(recording, "session.ld")
+///     .unwrap();
+/// ```
+pub struct MotecExporter<S: TelemetrySource> {
+    patterns: Vec<String>,
+    excludes: Vec<String>,
+    physics_rate: u16,
+    graphics_rate: u16,
+    datetime: Option<SystemTime>,
+    event: String,
+    source: PhantomData<fn() -> S>,
+}
+
+impl<S: TelemetrySource> Default for MotecExporter<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: TelemetrySource> MotecExporter<S> {
+    /// Exports all physics elements at 100 Hz and the lap number, lap time
+    /// and track position at 10 Hz.
+    pub fn new() -> Self {
+        Self {
+            patterns: Vec::new(),
+            excludes: Vec::new(),
+            physics_rate: 100,
+            graphics_rate: 10,
+            datetime: None,
+            event: String::new(),
+            source: PhantomData,
+        }
+    }
+
+    /// Adds the elements matching `pattern`, see
+    /// [`select_columns`](crate::export::select_columns) for the syntax.
+    pub fn select(mut self, pattern: &str) -> Self {
+        self.patterns.push(pattern.to_string());
+        self
+    }
+
+    /// Drops the elements matching `pattern` from the selection.
+    pub fn exclude(mut self, pattern: &str) -> Self {
+        self.excludes.push(pattern.to_string());
+        self
+    }
+
+    pub fn with_physics_rate(mut self, rate: u16) -> Self {
+        self.physics_rate = rate.max(1);
+        self
+    }
+
+    pub fn with_graphics_rate(mut self, rate: u16) -> Self {
+        self.graphics_rate = rate.max(1);
+        self
+    }
+
+    /// Start of the session shown in i2, in UTC. Defaults to the time of the export.
+    pub fn with_datetime(mut self, datetime: SystemTime) -> Self {
+        self.datetime = Some(datetime);
+        self
+    }
+
+    pub fn with_event(mut self, event: &str) -> Self {
+        self.event = event.to_string();
+        self
+    }
+
+    /// Converts a recording into a log and its lap markers.
+    pub fn export<R: Read>(&self, recording: Recording<R>) -> Result<(Log, Ldx)> {
+        let patterns = if self.patterns.is_empty() {
+            DEFAULT_COLUMNS.iter().map(|pattern| pattern.to_string()).collect()
+        } else {
+            self.patterns.clone()
+        };
+        let mut channels: Vec<ChannelBuilder> = select_columns::<S>(&patterns, &self.excludes)?
+            .into_iter()
+            .filter(|column| !matches!(column.field.ty, FieldType::Text(_)))
+            .map(ChannelBuilder::new)
+            .collect();
+
+        let mut physics = Resampler::<S::Physics>::new(self.physics_rate as f64)?.with_max_gap(None);
+        let mut graphics = Resampler::<S::Graphics>::new(self.graphics_rate as f64)?.with_max_gap(None);
+        let mut physics_start = None;
+        let mut graphics_start = None;
+        let mut static_info = None;
+        let mut static_bytes = S::StaticInfo::default().to_bytes();
+        let mut session = None;
+        let mut tracker = SessionTracker::new();
+        let mut tracking = false;
+        let mut laps = Vec::new();
+        let mut lap_times = Vec::new();
+
+        for frame in recording.frames::<S>() {
+            let frame = frame?;
+            match frame.frame {
+                Frame::Physics(page) => {
+                    for sample in physics.push((frame.time, page)) {
+                        physics_start.get_or_insert(sample.time);
+                        push_samples(&mut channels, PageKind::Physics, &sample.frame.to_bytes());
+                    }
+                }
+                Frame::Graphics(page) => {
+                    let (last_session, last_lap) = (tracker.session(), tracker.lap());
+                    if tracker.update(&page) && tracking {
+                        laps.push(frame.time);
+                        // The game's lap time only belongs to a lap completed
+                        // in the same session, others are measured below.
+                        let completed = tracker.session() == last_session && tracker.lap() > last_lap;
+                        lap_times.push(match page.get("i_last_time") {
+                            Some(Value::Int(millis)) if millis > 0 && completed => Duration::from_millis(millis as u64),
+                            _ => Duration::ZERO,
+                        });
+                    }
+                    tracking = true;
+                    if let Some(Value::Enum(_, Some(name))) = page.get("session") {
+                        session = Some(name);
+                    }
+                    for sample in graphics.push((frame.time, page)) {
+                        graphics_start.get_or_insert(sample.time);
+                        push_samples(&mut channels, PageKind::Graphics, &sample.frame.to_bytes());
+                        push_samples(&mut channels, PageKind::StaticInfo, &static_bytes);
+                    }
+                }
+                Frame::StaticInfo(page) => {
+                    static_bytes = page.to_bytes();
+                    static_info = Some(page);
+                }
+            }
+        }
+
+        // Every channel starts at the beginning of the log, the slower
+        // stream is padded with its first sample.
+        let start = physics_start.into_iter().chain(graphics_start).min().unwrap_or_default();
+        for channel in &mut channels {
+            let (first, rate) = match channel.column.page {
+                PageKind::Physics => (physics_start, self.physics_rate),
+                PageKind::Graphics | PageKind::StaticInfo => (graphics_start, self.graphics_rate),
+            };
+            let padding = first.map_or(0, |first| ((first - start).as_secs_f64() * rate as f64).round() as usize);
+            channel.pad_front(padding);
+        }
+
+        let beacons: Vec<Duration> = laps.iter().map(|time| time.saturating_sub(start)).collect();
+        for i in 1..lap_times.len() {
+            if lap_times[i].is_zero() {
+                lap_times[i] = beacons[i].saturating_sub(beacons[i - 1]);
+            }
+        }
+
+        let text = |path: &str| match static_info.as_ref().and_then(|page: &S::StaticInfo| page.get(path)) {
+            Some(Value::Text(text)) => text,
+            _ => String::new(),
+        };
+        let driver = format!("{} {}", text("player_name"), text("player_surname")).trim().to_string();
+        let (date, time) = date_time(self.datetime.unwrap_or_else(SystemTime::now));
+        let log = Log {
+            date,
+            time,
+            driver,
+            vehicle: text("car_model"),
+            venue: text("track"),
+            event: if self.event.is_empty() { S::GAME.to_string() } else { self.event.clone() },
+            session: session.unwrap_or_default().to_string(),
+            comment: format!("Shared memory {}", text("sm_version")).trim_end().to_string(),
+            channels: channels
+                .into_iter()
+                .map(|channel| {
+                    let rate = match channel.column.page {
+                        PageKind::Physics => self.physics_rate,
+                        _ => self.graphics_rate,
+                    };
+                    Channel::new(&channel.name, &channel.unit, rate, channel.data)
+                })
+                .collect(),
+            ..Log::default()
+        };
+        Ok((log, Ldx::from_laps(beacons, &lap_times)))
+    }
+
+    /// Writes the log to `path` and the lap markers next to it as `.ldx`.
+    pub fn write<R: Read>(&self, recording: Recording<R>, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let (log, ldx) = self.export(recording)?;
+        let mut writer = BufWriter::new(File::create(path)?);
+        log.write(&mut writer)?;
+        writer.flush()?;
+        let mut writer = BufWriter::new(File::create(path.with_extension("ldx"))?);
+        ldx.write(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+struct ChannelBuilder {
+    column: Column,
+    name: String,
+    unit: String,
+    factor: f32,
+    data: ChannelData,
+}
+
+impl ChannelBuilder {
+    fn new(column: Column) -> Self {
+        let path = column.field.element_path(column.index);
+        let (name, unit, factor) = match CHANNEL_NAMES.iter().find(|(known, ..)| *known == path) {
+            Some((_, name, unit, factor)) => (name.to_string(), unit.to_string(), *factor),
+            None => (column.name.clone(), column.field.unit.unwrap_or_default().to_string(), 1.0),
+        };
+        let data = match column.field.ty {
+            FieldType::I32 | FieldType::Enum(_) if factor == 1.0 => ChannelData::I32(Vec::new()),
+            _ => ChannelData::F32(Vec::new()),
+        };
+        Self {
+            column,
+            name,
+            unit,
+            factor,
+            data,
+        }
+    }
+
+    fn push(&mut self, bytes: &[u8]) {
+        let value = self.column.read(bytes);
+        match &mut self.data {
+            ChannelData::I32(data) => data.push(match value {
+                Value::Int(value) | Value::Enum(value, _) => value,
+                _ => 0,
+            }),
+            ChannelData::F32(data) => data.push(value.as_f64().unwrap_or_default() as f32 * self.factor),
+            _ => {}
+        }
+    }
+
+    fn pad_front(&mut self, count: usize) {
+        match &mut self.data {
+            ChannelData::I32(data) if !data.is_empty() => {
+                data.splice(0..0, iter::repeat_n(data[0], count));
+            }
+            ChannelData::F32(data) if !data.is_empty() => {
+                data.splice(0..0, iter::repeat_n(data[0], count));
+            }
+            _ => {}
+        }
+    }
+}
+
+fn push_samples(channels: &mut [ChannelBuilder], page: PageKind, bytes: &[u8]) {
+    for channel in channels.iter_mut().filter(|channel| channel.column.page == page) {
+        channel.push(bytes);
+    }
+}
+
+/// Formats a point in time as the `dd/mm/yyyy` and `hh:mm:ss` strings of an
+/// `.ld` header, in UTC.
+fn date_time(time: SystemTime) -> (String, String) {
+    let secs = time.duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs());
+    let (days, secs) = ((secs / 86_400) as i64, secs % 86_400);
+
+    // Civil date from days since 1970-01-01, see
+    // https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (
+        format!("{:02}/{:02}/{:04}", day, month, year),
+        format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60),
+    )
+}
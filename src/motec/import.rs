@@ -0,0 +1,242 @@
+use crate::motec::{CHANNEL_NAMES, Channel, Log};
+use crate::recording::{Frame, PageKind};
+use crate::telemetry::{FieldDescriptor, FieldType, Page, TelemetrySource, Timestamped, Value};
+use std::iter;
+use std::time::Duration;
+
+/// Names other tools use for the channels in [`CHANNEL_NAMES`], as
+/// `(alias, channel)`. Matching ignores case.
+pub const CHANNEL_ALIASES: &[(&str, &str)] = &[
+    ("Speed", "Ground Speed"),
+    ("Vehicle Speed", "Ground Speed"),
+    ("RPM", "Engine RPM"),
+    ("Engine Speed", "Engine RPM"),
+    ("Throttle", "Throttle Pos"),
+    ("Throttle Position", "Throttle Pos"),
+    ("Brake", "Brake Pos"),
+    ("Brake Position", "Brake Pos"),
+    ("Clutch", "Clutch Pos"),
+    ("Steering Angle", "Steering Pos"),
+    ("Steering", "Steering Pos"),
+    ("Fuel", "Fuel Level"),
+    ("Lateral Acceleration", "G Force Lat"),
+    ("Longitudinal Acceleration", "G Force Long"),
+    ("Vertical Acceleration", "G Force Vert"),
+    ("Engine Temp", "Water Temp"),
+    ("Ambient Temperature", "Air Temp"),
+    ("Lap", "Lap Number"),
+    ("Laps", "Lap Number"),
+];
+
+/// Unit conversions as `(from, to, factor, offset)`, `to = from * factor + offset`.
+const UNIT_CONVERSIONS: &[(&str, &str, f64, f64)] = &[
+    ("m/s", "km/h", 3.6, 0.0),
+    ("mph", "km/h", 1.609344, 0.0),
+    ("F", "C", 5.0 / 9.0, -160.0 / 9.0),
+    ("K", "C", 1.0, -273.15),
+    ("kPa", "psi", 0.145_037_738, 0.0),
+    ("bar", "psi", 14.503_773_8, 0.0),
+    ("m", "mm", 1000.0, 0.0),
+    ("cm", "mm", 10.0, 0.0),
+    ("ms", "s", 0.001, 0.0),
+    ("gal", "l", 3.785_411_78, 0.0),
+];
+
+/// An `.ld` log mapped onto the pages of `S`.
+///
+/// Channels with a well-known name (see [`CHANNEL_NAMES`] and
+/// [`CHANNEL_ALIASES`]) or named after a page element, and with a
+/// convertible unit, fill the matching page elements. Physics and graphics
+/// pages are sampled at the highest frequency among their channels and
+/// built one at a time by [`frames`](Self::frames). Every other channel is
+/// kept as is in `channels`.
+///
+/// ```no_run
+/// use race_sdk::acc::AssettoCorsaCompetizione;
+/// use race_sdk::motec::{Import, Log};
+/// use race_sdk::recording::Frame;
+///
+/// let log = Log::open("old_session.ld").unwrap();
+/// let import = Import::<AssettoCorsaCompetizione>::new(&log);
+/// for frame in import.frames() {
+///     if let Frame::Physics(physics) = &frame.frame {
+///         println!("{:?} {:?}", frame.time, physics.speed_kmh);
+///     }
+/// }
+/// for channel in &import.channels {
+///     println!("{} [{}]", channel.name, channel.unit);
+/// }
+/// ```
+pub struct Import<'a, S: TelemetrySource> {
+    /// Channels that have no counterpart in the pages of `S`.
+    pub channels: Vec<Channel>,
+    static_info: S::StaticInfo,
+    physics: Vec<Mapping<'a>>,
+    graphics: Vec<Mapping<'a>>,
+}
+
+impl<'a, S: TelemetrySource> Import<'a, S> {
+    pub fn new(log: &'a Log) -> Self {
+        let mut physics = Vec::new();
+        let mut graphics = Vec::new();
+        let mut channels = Vec::new();
+        for channel in &log.channels {
+            // The first channel feeding an element wins, e.g. `Ground Speed` over `Speed`.
+            let taken = |mappings: &[Mapping], mapping: &Mapping| mappings.iter().any(|other| other.is_same_element(mapping));
+            match Mapping::new::<S>(channel) {
+                Some((Target::Physics, mapping)) if !taken(&physics, &mapping) => physics.push(mapping),
+                Some((Target::Graphics, mapping)) if !taken(&graphics, &mapping) => graphics.push(mapping),
+                _ => channels.push(channel.clone()),
+            }
+        }
+
+        let mut static_info = S::StaticInfo::default();
+        for (path, value) in [("track", &log.venue), ("car_model", &log.vehicle), ("player_name", &log.driver)] {
+            // Not every game has every field.
+            let _ = static_info.set(path, &Value::Text(value.clone()));
+        }
+
+        Self {
+            channels,
+            static_info,
+            physics,
+            graphics,
+        }
+    }
+
+    /// Static info first, then physics and graphics in time order.
+    pub fn frames(&self) -> impl Iterator<Item = Timestamped<Frame<S>>> + '_ {
+        let mut physics = sample::<S::Physics>(&self.physics).peekable();
+        let mut graphics = sample::<S::Graphics>(&self.graphics).peekable();
+        let pages = iter::from_fn(move || match (physics.peek(), graphics.peek()) {
+            (Some(p), Some(g)) if g.0 < p.0 => graphics.next().map(|(time, page)| (time, Frame::Graphics(page))),
+            (Some(_), _) => physics.next().map(|(time, page)| (time, Frame::Physics(page))),
+            (None, Some(_)) => graphics.next().map(|(time, page)| (time, Frame::Graphics(page))),
+            (None, None) => None,
+        });
+        iter::once((Duration::ZERO, Frame::StaticInfo(self.static_info.clone())))
+            .chain(pages)
+            .map(Timestamped::from)
+    }
+}
+
+enum Target {
+    Physics,
+    Graphics,
+}
+
+/// A channel feeding one page element.
+struct Mapping<'a> {
+    channel: &'a Channel,
+    field: &'static FieldDescriptor,
+    index: usize,
+    factor: f64,
+    offset: f64,
+}
+
+impl<'a> Mapping<'a> {
+    fn new<S: TelemetrySource>(channel: &'a Channel) -> Option<(Target, Self)> {
+        if channel.data.is_empty() || channel.frequency == 0 {
+            return None;
+        }
+        let name = CHANNEL_ALIASES
+            .iter()
+            .find(|(alias, _)| alias.eq_ignore_ascii_case(&channel.name))
+            .map_or(channel.name.as_str(), |(_, name)| name);
+        let (target, (field, index), factor, offset) = match CHANNEL_NAMES.iter().find(|(_, known, ..)| known.eq_ignore_ascii_case(name)) {
+            Some((path, _, unit, page_factor)) => {
+                let (factor, offset) = convert(&channel.unit, unit)?;
+                let (target, element) = find_element::<S>(path)?;
+                (target, element, factor / *page_factor as f64, offset / *page_factor as f64)
+            }
+            // Channels written under their element name, like the ones
+            // `MotecExporter` has no i2 name for.
+            None => {
+                let (target, element) = find_element::<S>(&channel.name)?;
+                let (factor, offset) = convert(&channel.unit, element.0.unit.unwrap_or_default())?;
+                (target, element, factor, offset)
+            }
+        };
+        Some((
+            target,
+            Self {
+                channel,
+                field,
+                index,
+                factor,
+                offset,
+            },
+        ))
+    }
+
+    fn is_same_element(&self, other: &Mapping) -> bool {
+        std::ptr::eq(self.field, other.field) && self.index == other.index
+    }
+
+    fn value(&self, time: f64) -> Value {
+        let index = ((time * self.channel.frequency as f64) as usize).min(self.channel.data.len() - 1);
+        let value = self.channel.value(index).unwrap_or_default() * self.factor + self.offset;
+        match self.field.ty {
+            FieldType::F32 => Value::Float(value as f32),
+            _ => Value::Int(value.round() as i32),
+        }
+    }
+}
+
+/// Finds an element by path or flattened name, optionally prefixed with
+/// its page as in `graphics.packet_id`. Physics wins over graphics.
+fn find_element<S: TelemetrySource>(name: &str) -> Option<(Target, (&'static FieldDescriptor, usize))> {
+    let (page, name) = match name.split_once('.') {
+        Some((page, name)) if PageKind::from_name(page).is_some() => (PageKind::from_name(page), name),
+        _ => (None, name),
+    };
+    if page.is_none_or(|page| page == PageKind::Physics)
+        && let Some(element) = element::<S::Physics>(name)
+    {
+        return Some((Target::Physics, element));
+    }
+    if page.is_none_or(|page| page == PageKind::Graphics)
+        && let Some(element) = element::<S::Graphics>(name)
+    {
+        return Some((Target::Graphics, element));
+    }
+    None
+}
+
+fn element<P: Page>(name: &str) -> Option<(&'static FieldDescriptor, usize)> {
+    P::fields().iter().find_map(|field| {
+        (0..field.count())
+            .find(|index| field.element_path(*index) == name || field.element_name(*index) == name)
+            .map(|index| (field, index))
+    })
+}
+
+/// `(factor, offset)` converting a value in `from` into `to`. Channels
+/// without a unit are taken to be in the expected one.
+fn convert(from: &str, to: &str) -> Option<(f64, f64)> {
+    if from.is_empty() || from.eq_ignore_ascii_case(to) {
+        return Some((1.0, 0.0));
+    }
+    UNIT_CONVERSIONS
+        .iter()
+        .find(|(a, b, ..)| a.eq_ignore_ascii_case(from) && *b == to)
+        .map(|(_, _, factor, offset)| (*factor, *offset))
+}
+
+/// Samples pages at the highest frequency of the channels, holding each
+/// channel's last sample in between.
+fn sample<'m, P: Page>(mappings: &'m [Mapping]) -> impl Iterator<Item = (Duration, P)> + 'm {
+    let rate = mappings.iter().map(|mapping| mapping.channel.frequency).max().unwrap_or_default();
+    let duration = mappings.iter().map(|mapping| mapping.channel.duration()).max().unwrap_or_default();
+    let count = (duration.as_secs_f64() * rate as f64).round() as usize;
+    let template = P::default().to_bytes();
+    (0..count).filter_map(move |tick| {
+        let time = tick as f64 / rate as f64;
+        let mut bytes = template.clone();
+        for mapping in mappings {
+            // Text elements cannot take a channel value and keep the default.
+            let _ = mapping.field.write(&mut bytes, mapping.index, &mapping.value(time));
+        }
+        P::from_bytes(&bytes).map(|page| (Duration::from_secs_f64(time), page))
+    })
+}
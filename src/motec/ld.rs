@@ -0,0 +1,464 @@
+//! The MoTeC `.ld` log format.
+//!
+//! All integers are little-endian, strings are fixed-size and zero padded,
+//! pointers are absolute file offsets. A file written by [`Log::write`] is
+//! laid out as
+//!
+//! ```text
+//! 0x0000  Header       1762 bytes
+//! 0x06e2  Event        1154 bytes   name[64] session[64] comment[1024] venue_ptr: u16
+//! 0x0b64  Venue        1100 bytes   name[64] ...                       vehicle_ptr: u16
+//! 0x0fb0  Vehicle       260 bytes   id[64] ... weight: u32 type[32] comment[32]
+//! 0x10b4  Channel{n}    124 bytes   a doubly linked list of channel descriptions
+//!         data                      the samples of every channel, one after another
+//! ```
+//!
+//! A channel sample converts to its physical value as
+//! `(raw / scale * 10^-decimals + shift) * multiplier`.
+//!
+//! [`Log::read`] accepts files from other tools as well, it follows the
+//! pointers instead of assuming this layout.
+
+use crate::motec::{MotecError, Result};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::time::Duration;
+
+pub(crate) const HEADER_SIZE: usize = 0x6e2;
+pub(crate) const EVENT_SIZE: usize = 1154;
+pub(crate) const VENUE_SIZE: usize = 1100;
+pub(crate) const VEHICLE_SIZE: usize = 260;
+pub(crate) const CHANNEL_SIZE: usize = 124;
+
+/// The samples of a channel in their storage type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChannelData {
+    I16(Vec<i16>),
+    I32(Vec<i32>),
+    /// Half precision floats as raw bits.
+    F16(Vec<u16>),
+    F32(Vec<f32>),
+}
+
+impl ChannelData {
+    pub fn len(&self) -> usize {
+        match self {
+            ChannelData::I16(data) => data.len(),
+            ChannelData::I32(data) => data.len(),
+            ChannelData::F16(data) => data.len(),
+            ChannelData::F32(data) => data.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The storage type codes written to the channel description.
+    fn type_codes(&self) -> (u16, u16) {
+        match self {
+            ChannelData::I16(_) => (0x03, 2),
+            ChannelData::I32(_) => (0x05, 4),
+            ChannelData::F16(_) => (0x07, 2),
+            ChannelData::F32(_) => (0x07, 4),
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.len() * self.type_codes().1 as usize
+    }
+
+    /// Reads `count` samples of the given storage type.
+    fn read(bytes: &[u8], type_a: u16, type_b: u16, count: usize) -> Option<Self> {
+        let data = bytes.get(..count.checked_mul(type_b as usize)?)?;
+        match (type_a, type_b) {
+            (0x07, 2) => Some(ChannelData::F16(data.chunks_exact(2).map(|b| u16::from_le_bytes([b[0], b[1]])).collect())),
+            (0x07, 4) => Some(ChannelData::F32(
+                data.chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect(),
+            )),
+            (0x00 | 0x03 | 0x05, 2) => Some(ChannelData::I16(data.chunks_exact(2).map(|b| i16::from_le_bytes([b[0], b[1]])).collect())),
+            (0x00 | 0x03 | 0x05, 4) => Some(ChannelData::I32(
+                data.chunks_exact(4).map(|b| i32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect(),
+            )),
+            _ => None,
+        }
+    }
+
+    /// The raw sample at `index`.
+    pub fn raw(&self, index: usize) -> Option<f64> {
+        match self {
+            ChannelData::I16(data) => data.get(index).map(|value| *value as f64),
+            ChannelData::I32(data) => data.get(index).map(|value| *value as f64),
+            ChannelData::F16(data) => data.get(index).map(|value| f16_to_f32(*value) as f64),
+            ChannelData::F32(data) => data.get(index).map(|value| *value as f64),
+        }
+    }
+
+    fn write(&self, writer: &mut impl Write) -> Result<()> {
+        let mut bytes = Vec::with_capacity(self.size());
+        match self {
+            ChannelData::I16(data) => data.iter().for_each(|value| bytes.extend_from_slice(&value.to_le_bytes())),
+            ChannelData::I32(data) => data.iter().for_each(|value| bytes.extend_from_slice(&value.to_le_bytes())),
+            ChannelData::F16(data) => data.iter().for_each(|value| bytes.extend_from_slice(&value.to_le_bytes())),
+            ChannelData::F32(data) => data.iter().for_each(|value| bytes.extend_from_slice(&value.to_le_bytes())),
+        }
+        writer.write_all(&bytes)?;
+        Ok(())
+    }
+}
+
+/// One logged channel, sampled at a fixed frequency from the start of the log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Channel {
+    /// Up to 31 characters, e.g. `Ground Speed`.
+    pub name: String,
+    /// Up to 7 characters.
+    pub short_name: String,
+    /// Up to 11 characters, e.g. `km/h`.
+    pub unit: String,
+    /// Samples per second.
+    pub frequency: u16,
+    pub shift: i16,
+    pub multiplier: i16,
+    pub scale: i16,
+    pub decimals: i16,
+    pub data: ChannelData,
+}
+
+impl Channel {
+    /// A channel storing its physical values directly.
+    pub fn new(name: &str, unit: &str, frequency: u16, data: ChannelData) -> Self {
+        Self {
+            name: name.to_string(),
+            short_name: String::new(),
+            unit: unit.to_string(),
+            frequency,
+            shift: 0,
+            multiplier: 1,
+            scale: 1,
+            decimals: 0,
+            data,
+        }
+    }
+
+    /// The physical value of the sample at `index`.
+    pub fn value(&self, index: usize) -> Option<f64> {
+        let scale = if self.scale == 0 { 1.0 } else { self.scale as f64 };
+        let raw = self.data.raw(index)?;
+        Some((raw / scale * 10f64.powi(-(self.decimals as i32)) + self.shift as f64) * self.multiplier as f64)
+    }
+
+    /// The physical values of all samples.
+    pub fn values(&self) -> Vec<f64> {
+        (0..self.data.len()).filter_map(|index| self.value(index)).collect()
+    }
+
+    /// The time covered by the samples.
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.data.len() as f64 / self.frequency.max(1) as f64)
+    }
+}
+
+/// A MoTeC log, the contents of an `.ld` file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Log {
+    /// `dd/mm/yyyy`
+    pub date: String,
+    /// `hh:mm:ss`
+    pub time: String,
+    pub driver: String,
+    pub vehicle: String,
+    pub vehicle_type: String,
+    pub vehicle_comment: String,
+    pub vehicle_weight: u32,
+    pub venue: String,
+    pub event: String,
+    pub session: String,
+    pub comment: String,
+    pub short_comment: String,
+    pub channels: Vec<Channel>,
+}
+
+impl Log {
+    pub fn channel(&self, name: &str) -> Option<&Channel> {
+        self.channels.iter().find(|channel| channel.name == name)
+    }
+
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+
+    pub fn read(reader: &mut impl Read) -> Result<Self> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        Self::from_bytes(&bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let invalid = |what: &str| MotecError::InvalidFormat(what.to_string());
+        if bytes.len() < HEADER_SIZE || get_u32(bytes, 0) != Some(0x40) {
+            return Err(invalid("not an .ld file"));
+        }
+        let mut log = Log {
+            date: get_str(bytes, 0x5e, 16),
+            time: get_str(bytes, 0x7e, 16),
+            driver: get_str(bytes, 0x9e, 64),
+            vehicle: get_str(bytes, 0xde, 64),
+            venue: get_str(bytes, 0x15e, 64),
+            short_comment: get_str(bytes, 0x624, 64),
+            ..Log::default()
+        };
+
+        let event_ptr = get_u32(bytes, 0x24).unwrap_or(0) as usize;
+        if event_ptr > 0 {
+            log.event = get_str(bytes, event_ptr, 64);
+            log.session = get_str(bytes, event_ptr + 64, 64);
+            log.comment = get_str(bytes, event_ptr + 128, 1024);
+            let venue_ptr = get_u16(bytes, event_ptr + 1152).unwrap_or(0) as usize;
+            if venue_ptr > 0 {
+                log.venue = get_str(bytes, venue_ptr, 64);
+                let vehicle_ptr = get_u16(bytes, venue_ptr + 1098).unwrap_or(0) as usize;
+                if vehicle_ptr > 0 {
+                    log.vehicle = get_str(bytes, vehicle_ptr, 64);
+                    log.vehicle_weight = get_u32(bytes, vehicle_ptr + 192).unwrap_or(0);
+                    log.vehicle_type = get_str(bytes, vehicle_ptr + 196, 32);
+                    log.vehicle_comment = get_str(bytes, vehicle_ptr + 228, 32);
+                }
+            }
+        }
+
+        // The descriptions form a linked list, a corrupt file could make it a
+        // cycle so the walk is bounded by the number that fit into the file.
+        let mut ptr = get_u32(bytes, 0x08).ok_or_else(|| invalid("header"))? as usize;
+        while ptr != 0 {
+            if log.channels.len() > bytes.len() / CHANNEL_SIZE {
+                return Err(invalid("channel list does not end"));
+            }
+            let meta = bytes.get(ptr..ptr + CHANNEL_SIZE).ok_or_else(|| invalid("channel description out of bounds"))?;
+            let data_ptr = get_u32(meta, 8).unwrap_or(0) as usize;
+            let count = get_u32(meta, 12).unwrap_or(0) as usize;
+            let (type_a, type_b) = (get_u16(meta, 18).unwrap_or(0), get_u16(meta, 20).unwrap_or(0));
+            let name = get_str(meta, 32, 32);
+            let data = bytes
+                .get(data_ptr..)
+                .and_then(|data| ChannelData::read(data, type_a, type_b, count))
+                .ok_or_else(|| MotecError::InvalidFormat(format!("data of channel {}", name)))?;
+            log.channels.push(Channel {
+                name,
+                short_name: get_str(meta, 64, 8),
+                unit: get_str(meta, 72, 12),
+                frequency: get_u16(meta, 22).unwrap_or(0),
+                shift: get_u16(meta, 24).unwrap_or(0) as i16,
+                multiplier: get_u16(meta, 26).unwrap_or(1) as i16,
+                scale: get_u16(meta, 28).unwrap_or(1) as i16,
+                decimals: get_u16(meta, 30).unwrap_or(0) as i16,
+                data,
+            });
+            ptr = get_u32(meta, 4).unwrap_or(0) as usize;
+        }
+        Ok(log)
+    }
+
+    pub fn write(&self, writer: &mut impl Write) -> Result<()> {
+        let event_ptr = HEADER_SIZE;
+        let venue_ptr = event_ptr + EVENT_SIZE;
+        let vehicle_ptr = venue_ptr + VENUE_SIZE;
+        let meta_ptr = vehicle_ptr + VEHICLE_SIZE;
+        let data_ptr = meta_ptr + self.channels.len() * CHANNEL_SIZE;
+
+        let mut out = Vec::with_capacity(data_ptr);
+        put_u32(&mut out, 0x40);
+        put_zeros(&mut out, 4);
+        put_u32(&mut out, if self.channels.is_empty() { 0 } else { meta_ptr as u32 });
+        put_u32(&mut out, if self.channels.is_empty() { 0 } else { data_ptr as u32 });
+        put_zeros(&mut out, 20);
+        put_u32(&mut out, event_ptr as u32);
+        put_zeros(&mut out, 24);
+        put_u16(&mut out, 1);
+        put_u16(&mut out, 0x4240);
+        put_u16(&mut out, 0xf);
+        put_u32(&mut out, 0x1f44);
+        put_str(&mut out, "ADL", 8);
+        put_u16(&mut out, 420);
+        put_u16(&mut out, 0xadb0);
+        put_u32(&mut out, self.channels.len() as u32);
+        put_zeros(&mut out, 4);
+        put_str(&mut out, &self.date, 16);
+        put_zeros(&mut out, 16);
+        put_str(&mut out, &self.time, 16);
+        put_zeros(&mut out, 16);
+        put_str(&mut out, &self.driver, 64);
+        put_str(&mut out, &self.vehicle, 64);
+        put_zeros(&mut out, 64);
+        put_str(&mut out, &self.venue, 64);
+        put_zeros(&mut out, 64 + 1024);
+        put_u32(&mut out, 0xc81a4);
+        put_zeros(&mut out, 66);
+        put_str(&mut out, &self.short_comment, 64);
+        put_zeros(&mut out, 126);
+        debug_assert_eq!(out.len(), event_ptr);
+
+        put_str(&mut out, &self.event, 64);
+        put_str(&mut out, &self.session, 64);
+        put_str(&mut out, &self.comment, 1024);
+        put_u16(&mut out, venue_ptr as u16);
+
+        put_str(&mut out, &self.venue, 64);
+        put_zeros(&mut out, 1034);
+        put_u16(&mut out, vehicle_ptr as u16);
+
+        put_str(&mut out, &self.vehicle, 64);
+        put_zeros(&mut out, 128);
+        put_u32(&mut out, self.vehicle_weight);
+        put_str(&mut out, &self.vehicle_type, 32);
+        put_str(&mut out, &self.vehicle_comment, 32);
+        debug_assert_eq!(out.len(), meta_ptr);
+
+        let mut channel_data_ptr = data_ptr;
+        for (i, channel) in self.channels.iter().enumerate() {
+            let this = meta_ptr + i * CHANNEL_SIZE;
+            let previous = if i == 0 { 0 } else { this - CHANNEL_SIZE };
+            let next = if i + 1 == self.channels.len() { 0 } else { this + CHANNEL_SIZE };
+            let (type_a, type_b) = channel.data.type_codes();
+            put_u32(&mut out, previous as u32);
+            put_u32(&mut out, next as u32);
+            put_u32(&mut out, channel_data_ptr as u32);
+            put_u32(&mut out, channel.data.len() as u32);
+            put_u16(&mut out, 0x2ee1 + i as u16);
+            put_u16(&mut out, type_a);
+            put_u16(&mut out, type_b);
+            put_u16(&mut out, channel.frequency);
+            put_u16(&mut out, channel.shift as u16);
+            put_u16(&mut out, channel.multiplier as u16);
+            put_u16(&mut out, channel.scale as u16);
+            put_u16(&mut out, channel.decimals as u16);
+            put_str(&mut out, &channel.name, 32);
+            put_str(&mut out, &channel.short_name, 8);
+            put_str(&mut out, &channel.unit, 12);
+            put_zeros(&mut out, 40);
+            channel_data_ptr += channel.data.size();
+        }
+        debug_assert_eq!(out.len(), data_ptr);
+
+        writer.write_all(&out)?;
+        for channel in &self.channels {
+            channel.data.write(writer)?;
+        }
+        Ok(())
+    }
+}
+
+fn get_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(bytes.get(offset..offset + 2)?.try_into().ok()?))
+}
+
+fn get_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(bytes.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+/// Reads a zero terminated Windows-1252 string, decoded as Latin-1.
+fn get_str(bytes: &[u8], offset: usize, len: usize) -> String {
+    let field = bytes.get(offset..(offset + len).min(bytes.len())).unwrap_or_default();
+    let end = field.iter().position(|&b| b == 0).unwrap_or(field.len());
+    field[..end].iter().map(|&b| b as char).collect::<String>().trim().to_string()
+}
+
+fn f16_to_f32(bits: u16) -> f32 {
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = ((bits >> 10) & 0x1f) as i32;
+    let mantissa = (bits & 0x3ff) as f32;
+    match exponent {
+        0 => sign * mantissa * 2f32.powi(-24),
+        0x1f if mantissa == 0.0 => sign * f32::INFINITY,
+        0x1f => f32::NAN,
+        _ => sign * (1.0 + mantissa / 1024.0) * 2f32.powi(exponent - 15),
+    }
+}
+
+fn put_u16(out: &mut Vec<u8>, value: u16) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_zeros(out: &mut Vec<u8>, len: usize) {
+    out.resize(out.len() + len, 0);
+}
+
+/// Writes `value` as Windows-1252 into a zero padded field of `len` bytes,
+/// keeping a terminating zero. Characters outside Latin-1 become `?`.
+fn put_str(out: &mut Vec<u8>, value: &str, len: usize) {
+    let start = out.len();
+    out.extend(value.chars().take(len - 1).map(|c| if (c as u32) < 0x100 { c as u8 } else { b'?' }));
+    out.resize(start + len, 0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log() -> Log {
+        let mut scaled = Channel::new("Brake Temp FL", "C", 20, ChannelData::I16(vec![0, 1234, -5, i16::MAX]));
+        scaled.short_name = "BrkTFL".to_string();
+        scaled.scale = 2;
+        scaled.decimals = 1;
+        scaled.shift = 3;
+        scaled.multiplier = 4;
+        Log {
+            date: "01/03/2024".to_string(),
+            time: "20:15:00".to_string(),
+            driver: "Jürgen Müller".to_string(),
+            vehicle: "ferrari_296_gt3".to_string(),
+            vehicle_type: "GT3".to_string(),
+            vehicle_comment: "#71".to_string(),
+            vehicle_weight: 1290,
+            venue: "spa".to_string(),
+            event: "Practice".to_string(),
+            session: "Practice".to_string(),
+            comment: "a comment".to_string(),
+            short_comment: "short".to_string(),
+            channels: vec![
+                Channel::new("Ground Speed", "km/h", 100, ChannelData::F32(vec![0.0, 123.25, -1.5, f32::MAX])),
+                Channel::new("Gear", "", 10, ChannelData::I32(vec![-1, 0, 6])),
+                Channel::new("Lap Distance", "%", 1, ChannelData::F16(vec![0x3c00, 0xc000, 0x0001])),
+                scaled,
+            ],
+        }
+    }
+
+    fn round_trip(log: &Log) -> Log {
+        let mut bytes = Vec::new();
+        log.write(&mut bytes).unwrap();
+        Log::from_bytes(&bytes).unwrap()
+    }
+
+    #[test]
+    fn log_round_trip() {
+        let log = log();
+        let read = round_trip(&log);
+        assert_eq!(read, log);
+        assert_eq!(read.channels[2].values(), [1.0, -2.0, 2f64.powi(-24)]);
+        assert_eq!(read.channels[3].value(1), Some((1234.0 / 2.0 / 10.0 + 3.0) * 4.0));
+        assert_eq!(read.channels[0].duration(), Duration::from_millis(40));
+
+        let empty = Log::default();
+        assert_eq!(round_trip(&empty), empty);
+    }
+
+    #[test]
+    fn malformed_files() {
+        let mut bytes = Vec::new();
+        log().write(&mut bytes).unwrap();
+        assert!(Log::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Log::from_bytes(&bytes[..HEADER_SIZE - 1]).is_err());
+
+        // The first channel pointing back at itself.
+        let first = HEADER_SIZE + EVENT_SIZE + VENUE_SIZE + VEHICLE_SIZE;
+        let mut cyclic = bytes.clone();
+        cyclic[first + 4..first + 8].copy_from_slice(&(first as u32).to_le_bytes());
+        assert!(Log::from_bytes(&cyclic).is_err());
+    }
+}
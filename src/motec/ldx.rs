@@ -0,0 +1,64 @@
+//! The MoTeC `.ldx` companion file.
+//!
+//! i2 keeps lap markers (beacons) and summary details next to the log in a
+//! small XML file named like the `.ld` file. Marker times are microseconds
+//! from the start of the log.
+
+use crate::motec::Result;
+use std::io::Write;
+use std::time::Duration;
+
+/// The contents of an `.ldx` file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Ldx {
+    /// Times at which a lap was completed, from the start of the log.
+    pub beacons: Vec<Duration>,
+    /// `(id, value)` pairs shown in i2's details, e.g. `("Total Laps", "12")`.
+    pub details: Vec<(String, String)>,
+}
+
+impl Ldx {
+    /// Lap markers with the standard details derived from them.
+    ///
+    /// `lap_times` are the times of the completed laps in order, when known
+    /// more precisely than the distance between two beacons.
+    pub fn from_laps(beacons: Vec<Duration>, lap_times: &[Duration]) -> Self {
+        let mut details = vec![("Total Laps".to_string(), beacons.len().to_string())];
+        if let Some((lap, time)) = lap_times.iter().enumerate().filter(|(_, time)| !time.is_zero()).min_by_key(|(_, time)| **time) {
+            details.push(("Fastest Time".to_string(), format_lap_time(*time)));
+            details.push(("Fastest Lap".to_string(), (lap + 1).to_string()));
+        }
+        Self { beacons, details }
+    }
+
+    pub fn write(&self, writer: &mut impl Write) -> Result<()> {
+        let mut xml = String::new();
+        xml.push_str("<?xml version=\"1.0\"?>\n");
+        xml.push_str("<LDXFile Locale=\"English_United States.1252\" DefaultLocale=\"C\" Version=\"1.6\">\n");
+        xml.push_str(" <Layers>\n  <Layer>\n   <MarkerBlock>\n    <MarkerGroup Name=\"Beacons\" Index=\"3\">\n");
+        for (i, beacon) in self.beacons.iter().enumerate() {
+            xml.push_str(&format!(
+                "     <Marker Version=\"100\" ClassName=\"BCN\" Name=\"Manual.{}\" Flags=\"77\" Time=\"{}\"/>\n",
+                i + 1,
+                beacon.as_micros()
+            ));
+        }
+        xml.push_str("    </MarkerGroup>\n   </MarkerBlock>\n   <RangeBlock/>\n  </Layer>\n  <Details>\n");
+        for (id, value) in &self.details {
+            xml.push_str(&format!("   <String Id=\"{}\" Value=\"{}\"/>\n", escape(id), escape(value)));
+        }
+        xml.push_str("  </Details>\n </Layers>\n</LDXFile>\n");
+        writer.write_all(xml.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// `m:ss.mmm`, the way i2 shows lap times.
+pub fn format_lap_time(time: Duration) -> String {
+    let millis = time.as_millis();
+    format!("{}:{:02}.{:03}", millis / 60_000, millis / 1000 % 60, millis % 1000)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('"', "&quot;").replace('<', "&lt;").replace('>', "&gt;")
+}
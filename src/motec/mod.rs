@@ -0,0 +1,102 @@
+pub mod exporter;
+pub mod import;
+pub mod ld;
+pub mod ldx;
+pub use exporter::*;
+pub use import::*;
+pub use ld::{Channel, ChannelData, Log};
+pub use ldx::Ldx;
+
+use crate::export::ExportError;
+use crate::recording::RecordingError;
+use crate::telemetry::ResampleError;
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum MotecError {
+    Io(io::Error),
+    Recording(RecordingError),
+    Resample(ResampleError),
+    Export(ExportError),
+    InvalidFormat(String),
+}
+
+impl fmt::Display for MotecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MotecError::Io(err) => write!(f, "I/O error: {}", err),
+            MotecError::Recording(err) => write!(f, "Recording error: {}", err),
+            MotecError::Resample(err) => write!(f, "Resampling error: {}", err),
+            MotecError::Export(err) => write!(f, "Export error: {}", err),
+            MotecError::InvalidFormat(what) => write!(f, "Invalid .ld file: {}", what),
+        }
+    }
+}
+
+impl std::error::Error for MotecError {}
+
+impl From<io::Error> for MotecError {
+    fn from(err: io::Error) -> Self {
+        MotecError::Io(err)
+    }
+}
+
+impl From<RecordingError> for MotecError {
+    fn from(err: RecordingError) -> Self {
+        MotecError::Recording(err)
+    }
+}
+
+impl From<ResampleError> for MotecError {
+    fn from(err: ResampleError) -> Self {
+        MotecError::Resample(err)
+    }
+}
+
+impl From<ExportError> for MotecError {
+    fn from(err: ExportError) -> Self {
+        MotecError::Export(err)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, MotecError>;
+
+/// i2 channel names of well-known page elements as
+/// `(path, channel, unit, factor)`, where `factor` converts the page value
+/// into the channel unit.
+pub const CHANNEL_NAMES: &[(&str, &str, &str, f32)] = &[
+    ("speed_kmh", "Ground Speed", "km/h", 1.0),
+    ("rpms", "Engine RPM", "rpm", 1.0),
+    ("gear", "Gear", "", 1.0),
+    ("gas", "Throttle Pos", "%", 100.0),
+    ("brake", "Brake Pos", "%", 100.0),
+    ("clutch", "Clutch Pos", "%", 100.0),
+    ("steer_angle", "Steering Pos", "", 1.0),
+    ("fuel", "Fuel Level", "l", 1.0),
+    ("acc_g[0]", "G Force Lat", "G", 1.0),
+    ("acc_g[1]", "G Force Vert", "G", 1.0),
+    ("acc_g[2]", "G Force Long", "G", 1.0),
+    ("tyre_core_temperature[0]", "Tyre Temp FL", "C", 1.0),
+    ("tyre_core_temperature[1]", "Tyre Temp FR", "C", 1.0),
+    ("tyre_core_temperature[2]", "Tyre Temp RL", "C", 1.0),
+    ("tyre_core_temperature[3]", "Tyre Temp RR", "C", 1.0),
+    ("wheels_pressure[0]", "Tyre Pres FL", "psi", 1.0),
+    ("wheels_pressure[1]", "Tyre Pres FR", "psi", 1.0),
+    ("wheels_pressure[2]", "Tyre Pres RL", "psi", 1.0),
+    ("wheels_pressure[3]", "Tyre Pres RR", "psi", 1.0),
+    ("brake_temp[0]", "Brake Temp FL", "C", 1.0),
+    ("brake_temp[1]", "Brake Temp FR", "C", 1.0),
+    ("brake_temp[2]", "Brake Temp RL", "C", 1.0),
+    ("brake_temp[3]", "Brake Temp RR", "C", 1.0),
+    ("suspension_travel[0]", "Susp Pos FL", "mm", 1000.0),
+    ("suspension_travel[1]", "Susp Pos FR", "mm", 1000.0),
+    ("suspension_travel[2]", "Susp Pos RL", "mm", 1000.0),
+    ("suspension_travel[3]", "Susp Pos RR", "mm", 1000.0),
+    ("water_temp", "Water Temp", "C", 1.0),
+    ("air_temp", "Air Temp", "C", 1.0),
+    ("road_temp", "Track Temp", "C", 1.0),
+    ("completed_laps", "Lap Number", "", 1.0),
+    ("i_current_time", "Lap Time", "s", 0.001),
+    ("normalized_car_position", "Lap Distance Pct", "%", 100.0),
+];
@@ -0,0 +1,477 @@
+use crate::recording::format::{self, Header, PageKind, RecordedLayout, RecordedType};
+use crate::recording::{Record, RecordWriter, RecorderStats, Recording, RecordingError, Result};
+use crate::utils::lz;
+use std::io::{Read, Write};
+use std::time::Duration;
+
+/// Frames per block used by [`Recorder::with_compression`](crate::recording::Recorder::with_compression)
+/// callers that have no better idea, about three seconds of ACC physics.
+pub const DEFAULT_BLOCK_FRAMES: usize = 1024;
+
+const CODEC_STORED: u8 = 0;
+const CODEC_LZ: u8 = 1;
+
+/// Where a compressed block lives in a recording and what it covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockInfo {
+    pub offset: u64,
+    pub first_time: Duration,
+    pub last_time: Duration,
+    pub frames: u32,
+    pub raw_size: u64,
+    pub compressed_size: u64,
+}
+
+/// The block index of a compressed recording.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlockIndex {
+    pub blocks: Vec<BlockInfo>,
+}
+
+impl BlockIndex {
+    /// The block containing `time`, i.e. the last one starting at or before
+    /// it, or the first block if `time` lies before all of them.
+    pub fn find(&self, time: Duration) -> Option<&BlockInfo> {
+        let block = self.blocks.partition_point(|block| block.first_time <= time).saturating_sub(1);
+        self.blocks.get(block)
+    }
+
+    pub fn frames(&self) -> u64 {
+        self.blocks.iter().map(|block| block.frames as u64).sum()
+    }
+
+    pub fn raw_size(&self) -> u64 {
+        self.blocks.iter().map(|block| block.raw_size).sum()
+    }
+
+    pub fn compressed_size(&self) -> u64 {
+        self.blocks.iter().map(|block| block.compressed_size).sum()
+    }
+
+    /// How many times smaller the blocks are than the same frames stored plainly.
+    pub fn compression_ratio(&self) -> f64 {
+        ratio(self.raw_size(), self.compressed_size())
+    }
+
+    pub(crate) fn write(&self, writer: &mut impl Write) -> Result<()> {
+        format::write_u32(writer, self.blocks.len() as u32)?;
+        for block in &self.blocks {
+            format::write_u64(writer, block.offset)?;
+            format::write_u64(writer, block.first_time.as_nanos() as u64)?;
+            format::write_u64(writer, block.last_time.as_nanos() as u64)?;
+            format::write_u32(writer, block.frames)?;
+            format::write_u64(writer, block.raw_size)?;
+            format::write_u64(writer, block.compressed_size)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn read(reader: &mut impl Read) -> Result<Self> {
+        let count = format::read_u32(reader)?;
+        let mut blocks = Vec::new();
+        for _ in 0..count {
+            blocks.push(BlockInfo {
+                offset: format::read_u64(reader)?,
+                first_time: Duration::from_nanos(format::read_u64(reader)?),
+                last_time: Duration::from_nanos(format::read_u64(reader)?),
+                frames: format::read_u32(reader)?,
+                raw_size: format::read_u64(reader)?,
+                compressed_size: format::read_u64(reader)?,
+            });
+        }
+        Ok(Self { blocks })
+    }
+}
+
+pub(crate) fn ratio(raw: u64, compressed: u64) -> f64 {
+    if compressed == 0 { 1.0 } else { raw as f64 / compressed as f64 }
+}
+
+/// Rewrites a recording with compressed blocks of `block_frames` frames.
+///
+/// Works on plain and already compressed recordings alike, the frames are
+/// carried over bit for bit.
+///
+/// ```no_run
+/// use race_sdk::recording::{self, Recording};
+/// use std::fs::File;
+/// use std::io::BufWriter;
+///
+/// let recording = Recording::open("stint.rsdk").unwrap();
+/// let output = BufWriter::new(File::create("stint.compressed.rsdk").unwrap());
+/// let stats = recording::compress(recording, output, recording::DEFAULT_BLOCK_FRAMES).unwrap();
+/// println!("compressed {:.1}x", stats.compression_ratio());
+/// ```
+pub fn compress<R: Read, W: Write>(mut recording: Recording<R>, writer: W, block_frames: usize) -> Result<RecorderStats> {
+    let header = Header {
+        version: format::VERSION,
+        ..recording.header().clone()
+    };
+    let mut writer = RecordWriter::new(writer, header)?;
+    writer.set_block_frames(block_frames);
+    while let Some(record) = recording.next_record()? {
+        writer.write_frame(record)?;
+    }
+    let stats = writer.finish()?.1;
+    Ok(stats)
+}
+
+/// Whether a frame can go into a block, i.e. matches its page layout.
+pub(crate) fn blockable(header: &Header, record: &Record) -> bool {
+    header.layout(record.page).is_some_and(|layout| layout.size == record.bytes.len())
+}
+
+/// Encodes frames accepted by [`blockable`] into a block record body.
+pub(crate) fn encode_block(header: &Header, frames: &[Record]) -> Vec<u8> {
+    let mut raw = Vec::new();
+    raw.extend_from_slice(&(frames.len() as u32).to_le_bytes());
+    raw.extend(frames.iter().map(|frame| frame.page as u8));
+    let mut last_time = frames.first().map_or(0, |frame| frame.time.as_nanos() as i64);
+    raw.extend_from_slice(&(last_time as u64).to_le_bytes());
+    for frame in frames.iter().skip(1) {
+        let time = frame.time.as_nanos() as i64;
+        write_varint(&mut raw, zigzag(time.wrapping_sub(last_time)));
+        last_time = time;
+    }
+
+    for (page, layout) in pages(header) {
+        let bytes: Vec<&[u8]> = frames.iter().filter(|frame| frame.page == page).map(|frame| frame.bytes.as_slice()).collect();
+        if bytes.is_empty() {
+            continue;
+        }
+        let codings = word_codings(layout);
+        let count = bytes.len();
+        let mut previous = vec![0u32; codings.len()];
+        let mut columns = vec![0u32; codings.len() * count];
+        for (frame, bytes) in bytes.iter().enumerate() {
+            for (word, coding) in codings.iter().enumerate() {
+                let value = read_word(bytes, word);
+                columns[word * count + frame] = coding.encode(value, previous[word]);
+                previous[word] = value;
+            }
+        }
+        for column in columns.chunks(count) {
+            for shift in [0, 8, 16, 24] {
+                raw.extend(column.iter().map(|word| (word >> shift) as u8));
+            }
+        }
+    }
+
+    let compressed = lz::compress(&raw);
+    let (codec, data) = if compressed.len() < raw.len() {
+        (CODEC_LZ, &compressed)
+    } else {
+        (CODEC_STORED, &raw)
+    };
+    let mut body = Vec::with_capacity(data.len() + 5);
+    body.push(codec);
+    body.extend_from_slice(&(raw.len() as u32).to_le_bytes());
+    body.extend_from_slice(data);
+    body
+}
+
+/// Decodes a block record body back into its frames.
+pub(crate) fn decode_block(header: &Header, body: &[u8]) -> Result<Vec<Record>> {
+    let corrupt = || RecordingError::InvalidFormat("corrupt compressed block".to_string());
+    if body.len() < 5 {
+        return Err(corrupt());
+    }
+    let raw_length = u32::from_le_bytes([body[1], body[2], body[3], body[4]]) as usize;
+    let raw = match body[0] {
+        CODEC_STORED => body[5..].to_vec(),
+        CODEC_LZ => lz::decompress(&body[5..], raw_length).ok_or_else(corrupt)?,
+        codec => return Err(RecordingError::InvalidFormat(format!("unknown block codec {}", codec))),
+    };
+    if raw.len() != raw_length {
+        return Err(corrupt());
+    }
+
+    let mut cursor = Cursor { bytes: &raw, pos: 0 };
+    let count = u32::from_le_bytes(cursor.take(4).ok_or_else(corrupt)?.try_into().unwrap()) as usize;
+    let page_ids = cursor.take(count).ok_or_else(corrupt)?;
+    let mut frame_pages = Vec::with_capacity(count);
+    for &page in page_ids {
+        frame_pages.push(PageKind::from_u8(page).ok_or_else(|| RecordingError::InvalidFormat(format!("unknown page {}", page)))?);
+    }
+    // The first time is written even for an empty block.
+    let mut time = u64::from_le_bytes(cursor.take(8).ok_or_else(corrupt)?.try_into().unwrap()) as i64;
+    let mut times = Vec::with_capacity(count);
+    if count > 0 {
+        times.push(time);
+        for _ in 1..count {
+            time = time.wrapping_add(unzigzag(cursor.varint().ok_or_else(corrupt)?));
+            times.push(time);
+        }
+    }
+
+    let mut page_frames: Vec<(PageKind, std::vec::IntoIter<Vec<u8>>)> = Vec::new();
+    for (page, layout) in pages(header) {
+        let frames = frame_pages.iter().filter(|&&frame_page| frame_page == page).count();
+        if frames == 0 {
+            continue;
+        }
+        let codings = word_codings(layout);
+        let planes = cursor.take(codings.len() * 4 * frames).ok_or_else(corrupt)?;
+        let mut previous = vec![0u32; codings.len()];
+        let mut decoded = Vec::with_capacity(frames);
+        for frame in 0..frames {
+            let mut bytes = Vec::with_capacity(codings.len() * 4);
+            for (word, coding) in codings.iter().enumerate() {
+                let plane = word * 4 * frames;
+                let encoded = u32::from_le_bytes([
+                    planes[plane + frame],
+                    planes[plane + frames + frame],
+                    planes[plane + 2 * frames + frame],
+                    planes[plane + 3 * frames + frame],
+                ]);
+                let value = coding.decode(encoded, previous[word]);
+                previous[word] = value;
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+            bytes.truncate(layout.size);
+            decoded.push(bytes);
+        }
+        page_frames.push((page, decoded.into_iter()));
+    }
+    if cursor.pos != raw.len() {
+        return Err(corrupt());
+    }
+
+    let mut records = Vec::with_capacity(count);
+    for (page, time) in frame_pages.into_iter().zip(times) {
+        let frames = page_frames
+            .iter_mut()
+            .find(|(frame_page, _)| *frame_page == page)
+            .ok_or_else(|| RecordingError::InvalidFormat(format!("no layout for {:?} page", page)))?;
+        records.push(Record {
+            page,
+            time: Duration::from_nanos(time as u64),
+            bytes: frames.1.next().ok_or_else(corrupt)?,
+        });
+    }
+    Ok(records)
+}
+
+#[derive(Debug, Clone, Copy)]
+enum WordCoding {
+    Xor,
+    Delta,
+}
+
+impl WordCoding {
+    fn encode(self, value: u32, previous: u32) -> u32 {
+        match self {
+            WordCoding::Xor => value ^ previous,
+            WordCoding::Delta => value.wrapping_sub(previous),
+        }
+    }
+
+    fn decode(self, encoded: u32, previous: u32) -> u32 {
+        match self {
+            WordCoding::Xor => encoded ^ previous,
+            WordCoding::Delta => previous.wrapping_add(encoded),
+        }
+    }
+}
+
+/// Counters change in steps and encode best as differences, floats and
+/// text as XOR of their bits.
+fn word_codings(layout: &RecordedLayout) -> Vec<WordCoding> {
+    let mut codings = vec![WordCoding::Xor; layout.size.div_ceil(4)];
+    for field in &layout.fields {
+        if matches!(field.ty, RecordedType::I32 | RecordedType::Enum(_)) && field.offset % 4 == 0 {
+            let start = field.offset / 4;
+            codings[start..start + field.count()].fill(WordCoding::Delta);
+        }
+    }
+    codings
+}
+
+fn pages(header: &Header) -> impl Iterator<Item = (PageKind, &RecordedLayout)> {
+    [PageKind::Physics, PageKind::Graphics, PageKind::StaticInfo]
+        .into_iter()
+        .filter_map(|page| header.layout(page).map(|layout| (page, layout)))
+}
+
+fn read_word(bytes: &[u8], word: usize) -> u32 {
+    let mut value = [0; 4];
+    let start = (word * 4).min(bytes.len());
+    let end = (word * 4 + 4).min(bytes.len());
+    value[..end - start].copy_from_slice(&bytes[start..end]);
+    u32::from_le_bytes(value)
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn unzigzag(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let end = self.pos.checked_add(len).filter(|&end| end <= self.bytes.len())?;
+        let bytes = &self.bytes[self.pos..end];
+        self.pos = end;
+        Some(bytes)
+    }
+
+    fn varint(&mut self) -> Option<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ac::{Graphics, Physics};
+    use crate::telemetry::{Game, Page};
+
+    fn header() -> Header {
+        Header {
+            version: format::VERSION,
+            game: Game::AssettoCorsa,
+            sm_version: "1.7".to_string(),
+            started: None,
+            layouts: vec![
+                RecordedLayout::of::<Physics>(PageKind::Physics),
+                RecordedLayout::of::<Graphics>(PageKind::Graphics),
+            ],
+        }
+    }
+
+    fn frame(page: PageKind, nanos: u64, bytes: Vec<u8>) -> Record {
+        Record {
+            page,
+            time: Duration::from_nanos(nanos),
+            bytes,
+        }
+    }
+
+    /// Physics and graphics frames with NaNs, infinities, negative
+    /// counters and times that go back.
+    fn frames() -> Vec<Record> {
+        let mut frames = Vec::new();
+        for i in 0..300i32 {
+            let mut physics = Physics {
+                packet_id: 1000 - i * 7,
+                gear: i % 3 - 1,
+                rpms: -i,
+                speed_kmh: -(i as f32) * 0.5,
+                steer_angle: if i % 5 == 0 { f32::NAN } else { i as f32 * -0.01 },
+                ..Physics::default()
+            };
+            physics.velocity = [f32::NEG_INFINITY, -0.0, f32::from_bits(0x7fc0_1234)];
+            let time = if i == 150 { 5_000 } else { 1_000_000 * i as u64 };
+            frames.push(frame(PageKind::Physics, time, physics.to_bytes()));
+            if i % 10 == 0 {
+                let graphics = Graphics {
+                    packet_id: -i,
+                    completed_laps: i / 100,
+                    i_current_time: i32::MIN + i,
+                    ..Graphics::default()
+                };
+                frames.push(frame(PageKind::Graphics, time + 1, graphics.to_bytes()));
+            }
+        }
+        frames
+    }
+
+    #[test]
+    fn round_trip() {
+        let header = header();
+        let frames = frames();
+        let body = encode_block(&header, &frames);
+        assert_eq!(body[0], CODEC_LZ);
+        assert!(body.len() < frames.iter().map(|frame| frame.bytes.len()).sum::<usize>() / 4);
+        assert_eq!(decode_block(&header, &body).unwrap(), frames);
+    }
+
+    #[test]
+    fn round_trip_stored() {
+        let header = header();
+        let mut state = 0x9e37_79b9_u32;
+        let noise: Vec<u8> = (0..std::mem::size_of::<Physics>())
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            })
+            .collect();
+        let frames = vec![frame(PageKind::Physics, u64::MAX >> 1, noise)];
+        let body = encode_block(&header, &frames);
+        assert_eq!(body[0], CODEC_STORED);
+        assert_eq!(decode_block(&header, &body).unwrap(), frames);
+    }
+
+    #[test]
+    fn empty_block() {
+        let header = header();
+        let body = encode_block(&header, &[]);
+        assert_eq!(decode_block(&header, &body).unwrap(), Vec::new());
+    }
+
+    #[test]
+    fn corrupt_body() {
+        let header = header();
+        let is_corrupt = |body: &[u8]| matches!(decode_block(&header, body), Err(RecordingError::InvalidFormat(_)));
+
+        let body = encode_block(&header, &frames());
+        for len in 0..body.len() {
+            assert!(is_corrupt(&body[..len]), "{} bytes", len);
+        }
+        let mut trailing = body.clone();
+        trailing.push(0);
+        assert!(is_corrupt(&trailing));
+        let mut length = body.clone();
+        length[1] ^= 1;
+        assert!(is_corrupt(&length));
+        let mut codec = body.clone();
+        codec[0] = 9;
+        assert!(matches!(decode_block(&header, &codec), Err(RecordingError::InvalidFormat(message)) if message == "unknown block codec 9"));
+
+        let frames = vec![frame(PageKind::Physics, 0, Physics::default().to_bytes())];
+        let mut stored = encode_block(&header, &frames);
+        assert_eq!(decode_block(&header, &stored).unwrap(), frames);
+        stored[0] = CODEC_STORED;
+        assert!(is_corrupt(&stored));
+
+        let raw = [1, 0, 0, 0, 200, 0, 0, 0, 0, 0, 0, 0, 0];
+        let mut page = vec![CODEC_STORED];
+        page.extend_from_slice(&(raw.len() as u32).to_le_bytes());
+        page.extend_from_slice(&raw);
+        assert!(matches!(decode_block(&header, &page), Err(RecordingError::InvalidFormat(message)) if message == "unknown page 200"));
+
+        let statics = vec![frame(PageKind::StaticInfo, 0, vec![0; 16])];
+        let mut with_static = header.clone();
+        with_static.layouts.push(RecordedLayout {
+            page: PageKind::StaticInfo,
+            size: 16,
+            fields: Vec::new(),
+        });
+        assert!(decode_block(&header, &encode_block(&with_static, &statics)).is_err());
+    }
+}
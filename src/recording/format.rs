@@ -0,0 +1,556 @@
+//! Binary layout of recording files.
+//!
+//! A recording is a header followed by a stream of records. All integers are
+//! little-endian, `str` is a `u16` byte length followed by UTF-8 bytes.
+//!
+//! ```text
+//! File       := Header Record*
+//!
+//! Header     := magic: "RSDKREC\0"
+//!               version: u16                  currently 1
+//!               game: str                     "ac", "acc" or "ace"
+//!               sm_version: str               shared-memory version from StaticInfo
+//!               started: u64                  Unix time in ms the recording was created, 0 if unknown
+//!               page_count: u8
+//!               Layout{page_count}
+//!
+//! Layout     := page: u8                      0 physics, 1 graphics, 2 static info
+//!               size: u32                     size of the page in bytes
+//!               field_count: u16
+//!               Field{field_count}
+//!
+//! Field      := name: str
+//!               type: u8                      0 i32, 1 f32, 2 UTF-16 text, 3 enum
+//!               [text] length: u16            in UTF-16 code units
+//!               [enum] variant_count: u16
+//!                      (value: i32, name: str){variant_count}
+//!               offset: u32
+//!               dim_count: u8
+//!               Dim{dim_count}
+//!
+//! Dim        := kind: u8                      0 index, 1 axis, 2 wheel, 3 axle
+//!               [index] length: u16
+//!
+//! Record     := tag: u8
+//!               length: u32                   length of the body
+//!               body[length]
+//! ```
+//!
+//! Record tags:
+//!
+//! * `0` end of recording. The body is empty, or the `u64` file offset of
+//!   the index when the recording has one. A file without it was not
+//!   closed cleanly, every complete record before the cut is still readable
+//!   and [`rebuild_index`](crate::recording::rebuild_index) restores the end.
+//! * `1` frame, body is `page: u8, time: u64, bytes[length - 9]` where `time`
+//!   is nanoseconds since the start of the recording and `bytes` is the page
+//!   exactly as laid out in shared memory with padding zeroed.
+//! * `2` compressed block of consecutive frames, see below.
+//! * `3` index, see below.
+//!
+//! A block holds a run of frames, typically a few seconds:
+//!
+//! ```text
+//! Block      := codec: u8                     0 stored, 1 LZ
+//!               raw_length: u32               length of Columns
+//!               data                          Columns, compressed by codec
+//!
+//! Columns    := frame_count: u32
+//!               page: u8{frame_count}         page of each frame, in order
+//!               first_time: u64
+//!               time_delta: varint{frame_count - 1}   zigzag LEB128 nanoseconds
+//!               PageColumns{pages present, in page order}
+//!
+//! PageColumns:= byte planes of the encoded 32-bit words of all frames of the page,
+//!               word-major: word 0 byte 0 of every frame, word 0 byte 1, ...
+//! ```
+//!
+//! Each word of a page is encoded against the same word of the previous
+//! frame of that page in the block: words of `i32` and enum fields store the
+//! wrapping difference, all other words the XOR. The first frame is encoded
+//! against zero, so every block decodes on its own. Slowly changing values
+//! turn into runs of zero bytes which the LZ codec packs tightly. A block
+//! starts with the current static info so a reader seeking to it has one.
+//!
+//! The index lists the blocks of the file and places to start reading from
+//! for random access:
+//!
+//! ```text
+//! Index      := block_count: u32
+//!               (offset: u64, first_time: u64, last_time: u64, frames: u32,
+//!                raw_size: u64, compressed_size: u64){block_count}
+//!               [checkpoint_count: u32
+//!                Checkpoint{checkpoint_count}
+//!                lap_count: u32
+//!                (session: u32, lap: i32, start: u64, end: u64){lap_count}]
+//!
+//! Checkpoint := offset: u64, time: u64
+//!               static_offset: u64            frame or block with the current static info, 0 for none
+//!               session: u32, lap: i32
+//!               flags: u8                     bit 0 physics_packet set, bit 1 graphics_packet set
+//!               physics_packet: i32, graphics_packet: i32
+//! ```
+//!
+//! `offset` is the position of the block record from the start of the file,
+//! `raw_size` the size the frames would take as plain frame records. The
+//! checkpoints and laps were appended to the index later, readers that only
+//! know about blocks ignore them. Recordings without blocks have an index
+//! too. Checkpoints point at every block and, for plain frames, at a frame
+//! every second and at the start of every lap. Their packet ids are those of
+//! the last frames before the checkpoint.
+//!
+//! Readers skip records with unknown tags, so new record types can be added
+//! without breaking older readers. The static info page is written once at
+//! the start and again whenever it changes.
+//!
+//! The layouts make a file self-describing: readers map recorded fields onto
+//! their own structs by name, so recordings stay readable when a page gains
+//! or loses fields.
+
+use crate::recording::{RecordingError, Result};
+use crate::telemetry::{Dim, FieldDescriptor, FieldType, Game, Page};
+use std::io::{Read, Write};
+use std::mem;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const MAGIC: &[u8; 8] = b"RSDKREC\0";
+pub const VERSION: u16 = 1;
+
+pub const TAG_END: u8 = 0;
+pub const TAG_FRAME: u8 = 1;
+pub const TAG_BLOCK: u8 = 2;
+pub const TAG_INDEX: u8 = 3;
+
+/// Largest record body readers accept, far above any page or block a
+/// recorder writes, so that a corrupt length fails instead of allocating.
+pub const MAX_RECORD_SIZE: usize = 256 << 20;
+
+/// Which page a frame belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PageKind {
+    Physics = 0,
+    Graphics = 1,
+    StaticInfo = 2,
+}
+
+impl PageKind {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(PageKind::Physics),
+            1 => Some(PageKind::Graphics),
+            2 => Some(PageKind::StaticInfo),
+            _ => None,
+        }
+    }
+
+    /// Lowercase name, used where pages are addressed by text.
+    pub fn name(&self) -> &'static str {
+        match self {
+            PageKind::Physics => "physics",
+            PageKind::Graphics => "graphics",
+            PageKind::StaticInfo => "static",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "physics" => Some(PageKind::Physics),
+            "graphics" => Some(PageKind::Graphics),
+            "static" => Some(PageKind::StaticInfo),
+            _ => None,
+        }
+    }
+}
+
+/// The storage type of a recorded field, the owned twin of [`FieldType`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordedType {
+    I32,
+    F32,
+    Text(usize),
+    Enum(Vec<(i32, String)>),
+}
+
+impl RecordedType {
+    fn element_size(&self) -> usize {
+        match self {
+            RecordedType::Text(len) => len * 2,
+            _ => 4,
+        }
+    }
+
+    fn matches(&self, ty: &FieldType) -> bool {
+        matches!(
+            (self, ty),
+            (RecordedType::I32, FieldType::I32) | (RecordedType::F32, FieldType::F32) | (RecordedType::Enum(_), FieldType::Enum(_))
+        ) || matches!((self, ty), (RecordedType::Text(a), FieldType::Text(b)) if a == b)
+    }
+}
+
+/// A field as described in a recording header.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedField {
+    pub name: String,
+    pub ty: RecordedType,
+    pub offset: usize,
+    pub dims: Vec<Dim>,
+}
+
+impl RecordedField {
+    /// Number of elements, saturating for dimensions no valid header has.
+    pub fn count(&self) -> usize {
+        self.dims.iter().fold(1usize, |count, dim| count.saturating_mul(dim.count()))
+    }
+
+    pub fn size(&self) -> usize {
+        self.count().saturating_mul(self.ty.element_size())
+    }
+
+    /// The end of the field within its page, `None` if it overflows.
+    fn checked_end(&self) -> Option<usize> {
+        let count = self.dims.iter().try_fold(1usize, |count, dim| count.checked_mul(dim.count()))?;
+        self.offset.checked_add(count.checked_mul(self.ty.element_size())?)
+    }
+}
+
+/// The layout of one page as stored in a recording header.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedLayout {
+    pub page: PageKind,
+    pub size: usize,
+    pub fields: Vec<RecordedField>,
+}
+
+impl RecordedLayout {
+    pub fn of<T: Page>(page: PageKind) -> Self {
+        Self {
+            page,
+            size: mem::size_of::<T>(),
+            fields: T::fields().iter().map(recorded_field).collect(),
+        }
+    }
+
+    pub fn field(&self, name: &str) -> Option<&RecordedField> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+
+    /// Whether bytes of this layout can be read as `T` without conversion.
+    pub fn matches<T: Page>(&self) -> bool {
+        self.size == mem::size_of::<T>()
+            && self.fields.len() == T::fields().len()
+            && self.fields.iter().zip(T::fields()).all(|(recorded, field)| {
+                recorded.name == field.name && recorded.offset == field.offset && recorded.dims == field.dims && recorded.ty.matches(&field.ty)
+            })
+    }
+
+    /// Decodes recorded bytes into `T`, mapping fields by name when the
+    /// recorded layout differs from the current one.
+    ///
+    /// Fields missing from the recording keep their default value, array
+    /// fields of a different length are truncated or padded with defaults.
+    pub fn decode<T: Page>(&self, bytes: &[u8]) -> Option<T> {
+        if bytes.len() < self.size {
+            return None;
+        }
+        if self.matches::<T>() {
+            return T::from_bytes(bytes);
+        }
+
+        let mut out = T::default().to_bytes();
+        for field in T::fields() {
+            let Some(recorded) = self.field(field.name) else {
+                continue;
+            };
+            match (&recorded.ty, field.ty) {
+                (RecordedType::Text(recorded_len), FieldType::Text(len)) => {
+                    let size = (*recorded_len).min(len - 1) * 2;
+                    out[field.offset..field.offset + size].copy_from_slice(&bytes[recorded.offset..recorded.offset + size]);
+                    out[field.offset + size..field.offset + len * 2].fill(0);
+                }
+                (recorded_ty, ty) if recorded_ty.matches(&ty) => {
+                    let size = recorded.size().min(field.size());
+                    out[field.offset..field.offset + size].copy_from_slice(&bytes[recorded.offset..recorded.offset + size]);
+                }
+                _ => {}
+            }
+        }
+        T::from_bytes(&out)
+    }
+}
+
+fn recorded_field(field: &FieldDescriptor) -> RecordedField {
+    RecordedField {
+        name: field.name.to_string(),
+        ty: match field.ty {
+            FieldType::I32 => RecordedType::I32,
+            FieldType::F32 => RecordedType::F32,
+            FieldType::Text(len) => RecordedType::Text(len),
+            FieldType::Enum(variants) => RecordedType::Enum(variants.iter().map(|(value, name)| (*value, name.to_string())).collect()),
+        },
+        offset: field.offset,
+        dims: field.dims.to_vec(),
+    }
+}
+
+/// The header at the start of every recording.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Header {
+    pub version: u16,
+    pub game: Game,
+    pub sm_version: String,
+    /// When the recording was created, `None` if unknown.
+    pub started: Option<SystemTime>,
+    pub layouts: Vec<RecordedLayout>,
+}
+
+impl Header {
+    pub fn layout(&self, page: PageKind) -> Option<&RecordedLayout> {
+        self.layouts.iter().find(|layout| layout.page == page)
+    }
+
+    pub fn write(&self, writer: &mut impl Write) -> Result<()> {
+        writer.write_all(MAGIC)?;
+        write_u16(writer, self.version)?;
+        write_str(writer, self.game.code())?;
+        write_str(writer, &self.sm_version)?;
+        let millis = self
+            .started
+            .and_then(|started| started.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |since| since.as_millis() as u64);
+        write_u64(writer, millis)?;
+        write_u8(writer, self.layouts.len() as u8)?;
+        for layout in &self.layouts {
+            write_u8(writer, layout.page as u8)?;
+            write_u32(writer, layout.size as u32)?;
+            write_u16(writer, layout.fields.len() as u16)?;
+            for field in &layout.fields {
+                write_field(writer, field)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn read(reader: &mut impl Read) -> Result<Self> {
+        let mut magic = [0; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(RecordingError::InvalidFormat("not a recording file".to_string()));
+        }
+        let version = read_u16(reader)?;
+        if version != VERSION {
+            return Err(RecordingError::UnsupportedVersion(version));
+        }
+        let code = read_str(reader)?;
+        let game = Game::from_code(&code).ok_or_else(|| RecordingError::InvalidFormat(format!("unknown game {}", code)))?;
+        let sm_version = read_str(reader)?;
+        let started = Some(read_u64(reader)?)
+            .filter(|millis| *millis != 0)
+            .map(|millis| UNIX_EPOCH + Duration::from_millis(millis));
+
+        let mut layouts = Vec::new();
+        for _ in 0..read_u8(reader)? {
+            let page = read_u8(reader)?;
+            let page = PageKind::from_u8(page).ok_or_else(|| RecordingError::InvalidFormat(format!("unknown page {}", page)))?;
+            let size = read_u32(reader)? as usize;
+            let mut fields = Vec::new();
+            for _ in 0..read_u16(reader)? {
+                let field = read_field(reader)?;
+                if field.checked_end().is_none_or(|end| end > size) {
+                    return Err(RecordingError::InvalidFormat(format!("field {} exceeds its page", field.name)));
+                }
+                fields.push(field);
+            }
+            layouts.push(RecordedLayout { page, size, fields });
+        }
+
+        Ok(Self {
+            version,
+            game,
+            sm_version,
+            started,
+            layouts,
+        })
+    }
+}
+
+fn write_field(writer: &mut impl Write, field: &RecordedField) -> Result<()> {
+    write_str(writer, &field.name)?;
+    match &field.ty {
+        RecordedType::I32 => write_u8(writer, 0)?,
+        RecordedType::F32 => write_u8(writer, 1)?,
+        RecordedType::Text(len) => {
+            write_u8(writer, 2)?;
+            write_u16(writer, *len as u16)?;
+        }
+        RecordedType::Enum(variants) => {
+            write_u8(writer, 3)?;
+            write_u16(writer, variants.len() as u16)?;
+            for (value, name) in variants {
+                write_u32(writer, *value as u32)?;
+                write_str(writer, name)?;
+            }
+        }
+    }
+    write_u32(writer, field.offset as u32)?;
+    write_u8(writer, field.dims.len() as u8)?;
+    for dim in &field.dims {
+        match dim {
+            Dim::Index(len) => {
+                write_u8(writer, 0)?;
+                write_u16(writer, *len as u16)?;
+            }
+            Dim::Axis => write_u8(writer, 1)?,
+            Dim::Wheel => write_u8(writer, 2)?,
+            Dim::Axle => write_u8(writer, 3)?,
+        }
+    }
+    Ok(())
+}
+
+fn read_field(reader: &mut impl Read) -> Result<RecordedField> {
+    let name = read_str(reader)?;
+    let ty = match read_u8(reader)? {
+        0 => RecordedType::I32,
+        1 => RecordedType::F32,
+        2 => RecordedType::Text(read_u16(reader)? as usize),
+        3 => {
+            let mut variants = Vec::new();
+            for _ in 0..read_u16(reader)? {
+                let value = read_u32(reader)? as i32;
+                variants.push((value, read_str(reader)?));
+            }
+            RecordedType::Enum(variants)
+        }
+        ty => return Err(RecordingError::InvalidFormat(format!("unknown type {} of field {}", ty, name))),
+    };
+    let offset = read_u32(reader)? as usize;
+    let mut dims = Vec::new();
+    for _ in 0..read_u8(reader)? {
+        dims.push(match read_u8(reader)? {
+            0 => Dim::Index(read_u16(reader)? as usize),
+            1 => Dim::Axis,
+            2 => Dim::Wheel,
+            3 => Dim::Axle,
+            kind => return Err(RecordingError::InvalidFormat(format!("unknown dimension {} of field {}", kind, name))),
+        });
+    }
+    Ok(RecordedField { name, ty, offset, dims })
+}
+
+pub(crate) fn write_u8(writer: &mut impl Write, value: u8) -> Result<()> {
+    writer.write_all(&[value])?;
+    Ok(())
+}
+
+pub(crate) fn write_u16(writer: &mut impl Write, value: u16) -> Result<()> {
+    writer.write_all(&value.to_le_bytes())?;
+    Ok(())
+}
+
+pub(crate) fn write_u32(writer: &mut impl Write, value: u32) -> Result<()> {
+    writer.write_all(&value.to_le_bytes())?;
+    Ok(())
+}
+
+pub(crate) fn write_u64(writer: &mut impl Write, value: u64) -> Result<()> {
+    writer.write_all(&value.to_le_bytes())?;
+    Ok(())
+}
+
+pub(crate) fn write_str(writer: &mut impl Write, value: &str) -> Result<()> {
+    write_u16(writer, value.len() as u16)?;
+    writer.write_all(value.as_bytes())?;
+    Ok(())
+}
+
+pub(crate) fn read_u8(reader: &mut impl Read) -> Result<u8> {
+    let mut bytes = [0; 1];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes[0])
+}
+
+pub(crate) fn read_u16(reader: &mut impl Read) -> Result<u16> {
+    let mut bytes = [0; 2];
+    reader.read_exact(&mut bytes)?;
+    Ok(u16::from_le_bytes(bytes))
+}
+
+pub(crate) fn read_u32(reader: &mut impl Read) -> Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+pub(crate) fn read_u64(reader: &mut impl Read) -> Result<u64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+pub(crate) fn read_str(reader: &mut impl Read) -> Result<String> {
+    let mut bytes = vec![0; read_u16(reader)? as usize];
+    reader.read_exact(&mut bytes)?;
+    String::from_utf8(bytes).map_err(|_| RecordingError::InvalidFormat("invalid UTF-8 string".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ac::{Graphics, Physics, StaticInfo};
+    use std::io::Cursor;
+
+    fn header(started: Option<SystemTime>) -> Header {
+        Header {
+            version: VERSION,
+            game: Game::AssettoCorsa,
+            sm_version: "1.7".to_string(),
+            started,
+            layouts: vec![
+                RecordedLayout::of::<Physics>(PageKind::Physics),
+                RecordedLayout::of::<Graphics>(PageKind::Graphics),
+                RecordedLayout::of::<StaticInfo>(PageKind::StaticInfo),
+            ],
+        }
+    }
+
+    fn round_trip(header: &Header) -> Result<Header> {
+        let mut bytes = Vec::new();
+        header.write(&mut bytes)?;
+        Header::read(&mut Cursor::new(bytes))
+    }
+
+    #[test]
+    fn header_round_trip() {
+        for started in [None, Some(UNIX_EPOCH + Duration::from_millis(1_700_000_000_123))] {
+            let header = header(started);
+            assert_eq!(round_trip(&header).unwrap(), header);
+        }
+    }
+
+    #[test]
+    fn other_versions_rejected() {
+        let mut newer = header(None);
+        newer.version = VERSION + 1;
+        assert!(matches!(round_trip(&newer), Err(RecordingError::UnsupportedVersion(version)) if version == VERSION + 1));
+
+        let mut bytes = Vec::new();
+        header(None).write(&mut bytes).unwrap();
+        bytes[0] = b'X';
+        assert!(matches!(Header::read(&mut Cursor::new(bytes)), Err(RecordingError::InvalidFormat(_))));
+    }
+
+    #[test]
+    fn layout_decodes_its_page() {
+        let graphics = Graphics {
+            packet_id: 42,
+            completed_laps: 3,
+            normalized_car_position: 0.25,
+            ..Graphics::default()
+        };
+        let layout = RecordedLayout::of::<Graphics>(PageKind::Graphics);
+        assert!(layout.matches::<Graphics>());
+        let decoded: Graphics = layout.decode(&graphics.to_bytes()).unwrap();
+        assert_eq!(decoded.to_bytes(), graphics.to_bytes());
+        assert!(layout.decode::<Graphics>(&graphics.to_bytes()[..layout.size - 1]).is_none());
+    }
+}
@@ -0,0 +1,434 @@
+use crate::export::SessionTracker;
+use crate::recording::compression::{self, BlockIndex, BlockInfo};
+use crate::recording::format::{self, Header, PageKind, TAG_BLOCK, TAG_END, TAG_FRAME, TAG_INDEX};
+use crate::recording::{Record, RecordingError, Result, reader};
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::mem;
+use std::path::Path;
+use std::time::Duration;
+
+/// Recording time between two checkpoints of plain frames.
+pub(crate) const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A place in a recording where reading can start.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint {
+    /// File offset of a frame or block record.
+    pub offset: u64,
+    pub time: Duration,
+    /// File offset of the frame or block holding the static info current at
+    /// the checkpoint, `0` when there is none or the checkpoint is a block.
+    pub static_offset: u64,
+    /// Session and lap numbered by [`SessionTracker`].
+    pub session: u32,
+    pub lap: i32,
+    /// `packet_id` of the last physics frame before the checkpoint.
+    pub physics_packet: Option<i32>,
+    /// `packet_id` of the last graphics frame before the checkpoint.
+    pub graphics_packet: Option<i32>,
+}
+
+/// Where a lap lies in a recording.
+///
+/// Lap `n` is the stretch during which `n` laps were completed, the first
+/// and the last lap of a recording are usually partial.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexedLap {
+    pub session: u32,
+    pub lap: i32,
+    pub start: Duration,
+    pub end: Duration,
+}
+
+/// The seek index of a recording, mapping times, packet ids, laps and
+/// sessions to file offsets. See [`Recording::index`](crate::recording::Recording::index).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecordingIndex {
+    pub checkpoints: Vec<Checkpoint>,
+    pub laps: Vec<IndexedLap>,
+}
+
+impl RecordingIndex {
+    /// The last checkpoint at or before `time`, or the first one if `time`
+    /// lies before all of them.
+    pub fn find(&self, time: Duration) -> Option<&Checkpoint> {
+        let checkpoint = self.checkpoints.partition_point(|checkpoint| checkpoint.time <= time).saturating_sub(1);
+        self.checkpoints.get(checkpoint)
+    }
+
+    /// The last checkpoint before the physics frame with `packet_id`.
+    ///
+    /// Packet ids restart with the game, so this looks for the first run of
+    /// checkpoints that passes `packet_id`.
+    pub fn find_packet(&self, packet_id: i32) -> Option<&Checkpoint> {
+        let passed = self
+            .checkpoints
+            .iter()
+            .position(|checkpoint| checkpoint.physics_packet.is_some_and(|packet| packet >= packet_id));
+        let end = passed.unwrap_or(self.checkpoints.len());
+        self.checkpoints[..end].last().or(self.checkpoints.first())
+    }
+
+    /// The first lap numbered `lap`, from any session.
+    pub fn lap(&self, lap: i32) -> Option<&IndexedLap> {
+        self.laps.iter().find(|indexed| indexed.lap == lap)
+    }
+
+    pub fn session_lap(&self, session: u32, lap: i32) -> Option<&IndexedLap> {
+        self.laps.iter().find(|indexed| indexed.session == session && indexed.lap == lap)
+    }
+
+    pub fn sessions(&self) -> u32 {
+        self.laps.last().map_or(0, |lap| lap.session + 1)
+    }
+
+    pub(crate) fn write(&self, writer: &mut impl Write) -> Result<()> {
+        format::write_u32(writer, self.checkpoints.len() as u32)?;
+        for checkpoint in &self.checkpoints {
+            format::write_u64(writer, checkpoint.offset)?;
+            format::write_u64(writer, checkpoint.time.as_nanos() as u64)?;
+            format::write_u64(writer, checkpoint.static_offset)?;
+            format::write_u32(writer, checkpoint.session)?;
+            format::write_u32(writer, checkpoint.lap as u32)?;
+            let flags = checkpoint.physics_packet.is_some() as u8 | (checkpoint.graphics_packet.is_some() as u8) << 1;
+            format::write_u8(writer, flags)?;
+            format::write_u32(writer, checkpoint.physics_packet.unwrap_or_default() as u32)?;
+            format::write_u32(writer, checkpoint.graphics_packet.unwrap_or_default() as u32)?;
+        }
+        format::write_u32(writer, self.laps.len() as u32)?;
+        for lap in &self.laps {
+            format::write_u32(writer, lap.session)?;
+            format::write_u32(writer, lap.lap as u32)?;
+            format::write_u64(writer, lap.start.as_nanos() as u64)?;
+            format::write_u64(writer, lap.end.as_nanos() as u64)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn read(reader: &mut impl Read) -> Result<Self> {
+        let mut index = Self::default();
+        for _ in 0..format::read_u32(reader)? {
+            let offset = format::read_u64(reader)?;
+            let time = Duration::from_nanos(format::read_u64(reader)?);
+            let static_offset = format::read_u64(reader)?;
+            let session = format::read_u32(reader)?;
+            let lap = format::read_u32(reader)? as i32;
+            let flags = format::read_u8(reader)?;
+            let physics_packet = format::read_u32(reader)? as i32;
+            let graphics_packet = format::read_u32(reader)? as i32;
+            index.checkpoints.push(Checkpoint {
+                offset,
+                time,
+                static_offset,
+                session,
+                lap,
+                physics_packet: (flags & 1 != 0).then_some(physics_packet),
+                graphics_packet: (flags & 2 != 0).then_some(graphics_packet),
+            });
+        }
+        for _ in 0..format::read_u32(reader)? {
+            index.laps.push(IndexedLap {
+                session: format::read_u32(reader)?,
+                lap: format::read_u32(reader)? as i32,
+                start: Duration::from_nanos(format::read_u64(reader)?),
+                end: Duration::from_nanos(format::read_u64(reader)?),
+            });
+        }
+        Ok(index)
+    }
+}
+
+/// Offsets of the page words the index is built from.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct IndexFields {
+    pub(crate) physics_packet: Option<usize>,
+    graphics_packet: Option<usize>,
+    completed_laps: Option<usize>,
+    session: Option<usize>,
+    session_index: Option<usize>,
+}
+
+impl IndexFields {
+    pub(crate) fn new(header: &Header) -> Self {
+        let offset = |page, name| header.layout(page).and_then(|layout| layout.field(name)).map(|field| field.offset);
+        Self {
+            physics_packet: offset(PageKind::Physics, "packet_id"),
+            graphics_packet: offset(PageKind::Graphics, "packet_id"),
+            completed_laps: offset(PageKind::Graphics, "completed_laps"),
+            session: offset(PageKind::Graphics, "session"),
+            session_index: offset(PageKind::Graphics, "session_index"),
+        }
+    }
+}
+
+/// Reads the `i32` at `offset` of a frame.
+pub(crate) fn read_word(record: &Record, offset: Option<usize>) -> Option<i32> {
+    let offset = offset?;
+    Some(i32::from_le_bytes(record.bytes.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+/// Builds a [`RecordingIndex`] from the records of a file as they are written
+/// or scanned.
+pub(crate) struct IndexBuilder {
+    fields: IndexFields,
+    index: RecordingIndex,
+    static_offset: u64,
+    physics_packet: Option<i32>,
+    graphics_packet: Option<i32>,
+    tracker: SessionTracker,
+    last_time: Duration,
+    moment: Option<Checkpoint>,
+}
+
+impl IndexBuilder {
+    pub(crate) fn new(header: &Header) -> Self {
+        Self {
+            fields: IndexFields::new(header),
+            index: RecordingIndex::default(),
+            static_offset: 0,
+            physics_packet: None,
+            graphics_packet: None,
+            tracker: SessionTracker::new(),
+            last_time: Duration::ZERO,
+            moment: None,
+        }
+    }
+
+    /// A plain frame record at `offset`.
+    pub(crate) fn frame(&mut self, offset: u64, record: &Record) {
+        // Checkpoints point at the first frame of their time so seeking to
+        // that time returns all of its frames.
+        if self.moment.is_none_or(|moment| moment.time != record.time) {
+            self.moment = Some(Checkpoint {
+                offset,
+                time: record.time,
+                static_offset: if record.page == PageKind::StaticInfo { 0 } else { self.static_offset },
+                session: self.tracker.session() as u32,
+                lap: self.tracker.lap(),
+                physics_packet: self.physics_packet,
+                graphics_packet: self.graphics_packet,
+            });
+        }
+        let lap_started = self.update(record);
+        if record.page == PageKind::StaticInfo {
+            self.static_offset = offset;
+        }
+        let Some(moment) = self.moment else {
+            return;
+        };
+        let checkpoint = Checkpoint {
+            session: self.tracker.session() as u32,
+            lap: self.tracker.lap(),
+            ..moment
+        };
+        match self.index.checkpoints.last_mut() {
+            Some(last) if last.offset == moment.offset => *last = checkpoint,
+            Some(last) if !lap_started && record.time < last.time + CHECKPOINT_INTERVAL => {}
+            _ => self.index.checkpoints.push(checkpoint),
+        }
+    }
+
+    /// A block record at `offset` holding `records`.
+    pub(crate) fn block(&mut self, offset: u64, records: &[Record]) {
+        let Some(first) = records.first() else {
+            return;
+        };
+        self.index.checkpoints.push(Checkpoint {
+            offset,
+            time: first.time,
+            static_offset: 0,
+            session: self.tracker.session() as u32,
+            lap: self.tracker.lap(),
+            physics_packet: self.physics_packet,
+            graphics_packet: self.graphics_packet,
+        });
+        for record in records {
+            self.update(record);
+            if record.page == PageKind::StaticInfo {
+                self.static_offset = offset;
+            }
+        }
+    }
+
+    pub(crate) fn finish(&mut self) -> RecordingIndex {
+        if let Some(lap) = self.index.laps.last_mut() {
+            lap.end = self.last_time;
+        }
+        mem::take(&mut self.index)
+    }
+
+    /// Tracks packet ids, sessions and laps, returns whether a lap started.
+    fn update(&mut self, record: &Record) -> bool {
+        self.last_time = self.last_time.max(record.time);
+        match record.page {
+            PageKind::Physics => {
+                self.physics_packet = read_word(record, self.fields.physics_packet).or(self.physics_packet);
+                false
+            }
+            PageKind::Graphics => {
+                self.graphics_packet = read_word(record, self.fields.graphics_packet).or(self.graphics_packet);
+                let Some(lap) = read_word(record, self.fields.completed_laps) else {
+                    return false;
+                };
+                let key = (read_word(record, self.fields.session), read_word(record, self.fields.session_index));
+                let started = self.tracker.update_words(key, Some(lap)) || self.index.laps.is_empty();
+                if started {
+                    if let Some(previous) = self.index.laps.last_mut() {
+                        previous.end = record.time;
+                    }
+                    self.index.laps.push(IndexedLap {
+                        session: self.tracker.session() as u32,
+                        lap,
+                        start: record.time,
+                        end: record.time,
+                    });
+                }
+                started
+            }
+            PageKind::StaticInfo => false,
+        }
+    }
+}
+
+/// Encodes the body of an index record.
+pub(crate) fn index_body(blocks: &BlockIndex, index: &RecordingIndex) -> Result<Vec<u8>> {
+    let mut body = Vec::new();
+    blocks.write(&mut body)?;
+    index.write(&mut body)?;
+    Ok(body)
+}
+
+/// What [`rebuild_index`] found and did.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RebuildReport {
+    pub frames: u64,
+    pub blocks: u64,
+    pub checkpoints: u64,
+    pub laps: u64,
+    /// Whether the file ended without an end marker.
+    pub truncated: bool,
+    /// Complete records that could not be decoded and were dropped.
+    pub skipped_records: u64,
+    /// Bytes removed: skipped records, a partially written record at the
+    /// end and the old index.
+    pub removed_bytes: u64,
+}
+
+/// Rebuilds the index of a recording in place.
+///
+/// Meant for files that were cut off mid-write, e.g. by a crash: every
+/// complete record is kept, a partial record at the end is removed and a
+/// fresh index and end marker are appended. Records that cannot be decoded
+/// are dropped and the ones after them moved up. Files from before the
+/// index existed get one as well.
+///
+/// ```no_run
+/// use race_sdk::recording;
+///
+/// let report = recording::rebuild_index("crashed.rsdk").unwrap();
+/// println!("kept {} frames, skipped {} records", report.frames, report.skipped_records);
+/// ```
+pub fn rebuild_index(path: impl AsRef<Path>) -> Result<RebuildReport> {
+    let path = path.as_ref();
+    let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+    let length = file.seek(SeekFrom::End(0))?;
+
+    // Records are read through a second handle and written back over
+    // skipped ones, never ahead of what was read.
+    let mut reader = BufReader::new(File::open(path)?);
+    let header = Header::read(&mut reader)?;
+    let mut builder = IndexBuilder::new(&header);
+    let mut blocks = BlockIndex::default();
+    let mut report = RebuildReport {
+        truncated: true,
+        ..RebuildReport::default()
+    };
+    let mut position = reader.stream_position()?;
+    let mut end = position;
+    while let Some((tag, body)) = read_record(&mut reader)? {
+        let kept = match tag {
+            TAG_FRAME => match reader::decode_frame(&body) {
+                Ok(record) => {
+                    builder.frame(end, &record);
+                    report.frames += 1;
+                    true
+                }
+                Err(_) => false,
+            },
+            TAG_BLOCK => match compression::decode_block(&header, &body) {
+                Ok(records) => {
+                    if let (Some(first), Some(last)) = (records.first(), records.last()) {
+                        builder.block(end, &records);
+                        blocks.blocks.push(BlockInfo {
+                            offset: end,
+                            first_time: first.time,
+                            last_time: last.time,
+                            frames: records.len() as u32,
+                            raw_size: records.iter().map(|record| record.bytes.len() as u64 + 14).sum(),
+                            compressed_size: body.len() as u64 + 5,
+                        });
+                        report.frames += records.len() as u64;
+                    }
+                    true
+                }
+                Err(_) => false,
+            },
+            // The old trailer is replaced.
+            TAG_INDEX | TAG_END => {
+                report.truncated = false;
+                break;
+            }
+            _ => true,
+        };
+        let size = body.len() as u64 + 5;
+        if !kept {
+            report.skipped_records += 1;
+        } else {
+            if end != position {
+                file.seek(SeekFrom::Start(end))?;
+                format::write_u8(&mut file, tag)?;
+                format::write_u32(&mut file, body.len() as u32)?;
+                file.write_all(&body)?;
+            }
+            end += size;
+        }
+        position += size;
+    }
+    drop(reader);
+
+    let index = builder.finish();
+    report.blocks = blocks.blocks.len() as u64;
+    report.checkpoints = index.checkpoints.len() as u64;
+    report.laps = index.laps.len() as u64;
+    report.removed_bytes = length - end;
+
+    file.set_len(end)?;
+    file.seek(SeekFrom::Start(end))?;
+    let body = index_body(&blocks, &index)?;
+    let mut writer = BufWriter::new(&mut file);
+    format::write_u8(&mut writer, TAG_INDEX)?;
+    format::write_u32(&mut writer, body.len() as u32)?;
+    writer.write_all(&body)?;
+    format::write_u8(&mut writer, TAG_END)?;
+    format::write_u32(&mut writer, 8)?;
+    format::write_u64(&mut writer, end)?;
+    writer.flush()?;
+    drop(writer);
+    file.sync_all()?;
+    Ok(report)
+}
+
+/// Reads a whole record, `None` at the end of the file or a cut-off record.
+fn read_record(reader: &mut impl Read) -> Result<Option<(u8, Vec<u8>)>> {
+    let mut head = [0; 5];
+    if reader::read_full(reader, &mut head)? < 5 {
+        return Ok(None);
+    }
+    let length = u32::from_le_bytes([head[1], head[2], head[3], head[4]]) as usize;
+    let mut body = Vec::new();
+    if reader.take(length as u64).read_to_end(&mut body).map_err(RecordingError::from)? < length {
+        return Ok(None);
+    }
+    Ok(Some((head[0], body)))
+}
@@ -0,0 +1,51 @@
+pub mod compression;
+pub mod format;
+pub mod index;
+pub mod reader;
+pub mod recorder;
+pub mod replay;
+pub use compression::{BlockIndex, BlockInfo, DEFAULT_BLOCK_FRAMES, compress};
+pub use format::{Header, PageKind, RecordedField, RecordedLayout, RecordedType};
+pub use index::{Checkpoint, IndexedLap, RebuildReport, RecordingIndex, rebuild_index};
+pub use reader::*;
+pub use recorder::*;
+pub use replay::*;
+
+use crate::telemetry::Game;
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum RecordingError {
+    Io(io::Error),
+    InvalidFormat(String),
+    UnsupportedVersion(u16),
+    GameMismatch {
+        expected: Game,
+        found: Game,
+    },
+    /// A replay speed that is not a finite positive number.
+    InvalidSpeed(f64),
+}
+
+impl fmt::Display for RecordingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordingError::Io(err) => write!(f, "I/O error: {}", err),
+            RecordingError::InvalidFormat(msg) => write!(f, "Invalid recording: {}", msg),
+            RecordingError::UnsupportedVersion(version) => write!(f, "Unsupported recording version: {}", version),
+            RecordingError::GameMismatch { expected, found } => write!(f, "Recording is from {}, expected {}", found, expected),
+            RecordingError::InvalidSpeed(speed) => write!(f, "Invalid replay speed: {}", speed),
+        }
+    }
+}
+
+impl std::error::Error for RecordingError {}
+
+impl From<io::Error> for RecordingError {
+    fn from(err: io::Error) -> Self {
+        RecordingError::Io(err)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, RecordingError>;
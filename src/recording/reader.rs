@@ -0,0 +1,617 @@
+use crate::recording::compression::{self, BlockIndex};
+use crate::recording::format::{self, Header, PageKind, TAG_BLOCK, TAG_END, TAG_FRAME, TAG_INDEX};
+use crate::recording::index::{self, Checkpoint, IndexFields, RecordingIndex};
+use crate::recording::{RecordingError, Result};
+use crate::telemetry::{TelemetrySource, Timestamped};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::path::Path;
+use std::time::Duration;
+
+/// A raw frame as stored in a recording.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub page: PageKind,
+    pub time: Duration,
+    pub bytes: Vec<u8>,
+}
+
+/// A decoded frame of a Kunos game.
+pub enum Frame<S: TelemetrySource> {
+    Physics(S::Physics),
+    Graphics(S::Graphics),
+    StaticInfo(S::StaticInfo),
+}
+
+impl<S: TelemetrySource> Clone for Frame<S> {
+    fn clone(&self) -> Self {
+        match self {
+            Frame::Physics(physics) => Frame::Physics(physics.clone()),
+            Frame::Graphics(graphics) => Frame::Graphics(graphics.clone()),
+            Frame::StaticInfo(static_info) => Frame::StaticInfo(static_info.clone()),
+        }
+    }
+}
+
+/// Reads a recording written by [`Recorder`](crate::recording::Recorder).
+///
+/// ```no_run
+/// use race_sdk::acc::AssettoCorsaCompetizione;
+/// use race_sdk::recording::{Frame, Recording};
+///
+/// let recording = Recording::open("session.rsdk").unwrap();
+/// println!("{} recorded with shared memory {}", recording.header().game, recording.header().sm_version);
+///
+/// for frame in recording.frames::<AssettoCorsaCompetizione>() {
+///     let frame = frame.unwrap();
+///     if let Frame::Physics(physics) = frame.frame {
+///         println!("{:?} {:.1} km/h", frame.time, physics.speed_kmh);
+///     }
+/// }
+/// ```
+///
+/// Compressed recordings are decoded transparently. With a seekable reader
+/// recordings support jumping to a point in time, a lap or a packet through
+/// their index:
+///
+/// ```no_run
+/// use race_sdk::acc::AssettoCorsaCompetizione;
+/// use race_sdk::recording::Recording;
+/// use std::time::Duration;
+///
+/// let mut recording = Recording::open("stint.rsdk").unwrap();
+/// if let Some(index) = recording.block_index().unwrap() {
+///     println!("{} blocks, compressed {:.1}x", index.blocks.len(), index.compression_ratio());
+/// }
+/// recording.seek(Duration::from_secs(1800)).unwrap();
+/// let record = recording.next_record().unwrap();
+///
+/// if let Some(lap) = recording.index().unwrap().and_then(|index| index.lap(5)).copied() {
+///     for frame in recording.range(lap.start..lap.end).unwrap().frames::<AssettoCorsaCompetizione>() {
+///         let frame = frame.unwrap();
+///     }
+/// }
+/// ```
+pub struct Recording<R> {
+    reader: R,
+    header: Header,
+    fields: IndexFields,
+    pending: VecDeque<Record>,
+    last_static: Option<Vec<u8>>,
+    index: Option<BlockIndex>,
+    seek_index: Option<RecordingIndex>,
+    skip: Option<Skip>,
+    held_static: Option<Record>,
+    finished: bool,
+    truncated: bool,
+}
+
+/// Where reading continues after a seek.
+#[derive(Debug, Clone, Copy)]
+enum Skip {
+    Time(Duration),
+    Packet(i32),
+}
+
+impl Recording<BufReader<File>> {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read> Recording<R> {
+    /// Reads the header, leaving the reader at the first record.
+    pub fn new(mut reader: R) -> Result<Self> {
+        let header = Header::read(&mut reader)?;
+        Ok(Self {
+            reader,
+            fields: IndexFields::new(&header),
+            header,
+            pending: VecDeque::new(),
+            last_static: None,
+            index: None,
+            seek_index: None,
+            skip: None,
+            held_static: None,
+            finished: false,
+            truncated: false,
+        })
+    }
+
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Whether the file ended without an end marker, e.g. because the
+    /// recording process was killed.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Reads the next frame, `None` at the end of the recording.
+    ///
+    /// A record cut off by a truncated file is treated as the end. Static
+    /// info repeated at the start of compressed blocks is only returned when
+    /// it changed.
+    pub fn next_record(&mut self) -> Result<Option<Record>> {
+        loop {
+            while let Some(record) = self.pending.pop_front() {
+                if let Some(skip) = self.skip {
+                    let reached = match skip {
+                        Skip::Time(time) => record.time >= time,
+                        Skip::Packet(packet_id) => {
+                            record.page == PageKind::Physics && index::read_word(&record, self.fields.physics_packet).is_some_and(|packet| packet >= packet_id)
+                        }
+                    };
+                    if !reached {
+                        if record.page == PageKind::StaticInfo {
+                            self.held_static = Some(record);
+                        }
+                        continue;
+                    }
+                    self.skip = None;
+                    if let Some(static_info) = self.held_static.take() {
+                        self.pending.push_front(record);
+                        self.pending.push_front(static_info);
+                        continue;
+                    }
+                }
+                if record.page == PageKind::StaticInfo {
+                    if self.last_static.as_ref() == Some(&record.bytes) {
+                        continue;
+                    }
+                    self.last_static = Some(record.bytes.clone());
+                }
+                return Ok(Some(record));
+            }
+            if self.finished {
+                return Ok(None);
+            }
+            let Some((tag, body)) = self.read_raw()? else {
+                return Ok(None);
+            };
+            match tag {
+                TAG_END => self.finished = true,
+                TAG_FRAME => self.pending.push_back(decode_frame(&body)?),
+                TAG_BLOCK => self.pending.extend(compression::decode_block(&self.header, &body)?),
+                _ => {}
+            }
+        }
+    }
+
+    /// Decodes a record into the page types of `S`.
+    pub fn decode<S: TelemetrySource>(&self, record: &Record) -> Result<Frame<S>> {
+        if self.header.game != S::GAME {
+            return Err(RecordingError::GameMismatch {
+                expected: S::GAME,
+                found: self.header.game,
+            });
+        }
+        let layout = self
+            .header
+            .layout(record.page)
+            .ok_or_else(|| RecordingError::InvalidFormat(format!("no layout for {:?} page", record.page)))?;
+        let invalid = || RecordingError::InvalidFormat(format!("undecodable {:?} frame at {:?}", record.page, record.time));
+        Ok(match record.page {
+            PageKind::Physics => Frame::Physics(layout.decode(&record.bytes).ok_or_else(invalid)?),
+            PageKind::Graphics => Frame::Graphics(layout.decode(&record.bytes).ok_or_else(invalid)?),
+            PageKind::StaticInfo => Frame::StaticInfo(layout.decode(&record.bytes).ok_or_else(invalid)?),
+        })
+    }
+
+    /// Iterates over all frames decoded into the page types of `S`.
+    pub fn frames<S: TelemetrySource>(self) -> Frames<S, R> {
+        Frames {
+            recording: self,
+            source: PhantomData,
+        }
+    }
+
+    fn read_raw(&mut self) -> Result<Option<(u8, Vec<u8>)>> {
+        let mut head = [0; 5];
+        match read_full(&mut self.reader, &mut head)? {
+            0 => {
+                self.finished = true;
+                self.truncated = true;
+                return Ok(None);
+            }
+            5 => {}
+            _ => return Ok(self.cut_off()),
+        }
+        let length = u32::from_le_bytes([head[1], head[2], head[3], head[4]]) as usize;
+        if length > format::MAX_RECORD_SIZE {
+            self.finished = true;
+            return Err(RecordingError::InvalidFormat(format!("record of {} bytes", length)));
+        }
+        // Grows with the data actually read, so a truncated file does not
+        // allocate the whole length up front.
+        let mut body = Vec::new();
+        if read_full_to_end(&mut self.reader, &mut body, length)? < length {
+            return Ok(self.cut_off());
+        }
+        Ok(Some((head[0], body)))
+    }
+
+    fn cut_off(&mut self) -> Option<(u8, Vec<u8>)> {
+        self.finished = true;
+        self.truncated = true;
+        None
+    }
+}
+
+impl<R: Read + Seek> Recording<R> {
+    /// The block index of a compressed recording, `None` for plain or
+    /// truncated recordings.
+    ///
+    /// Block offsets count from the start of the reader.
+    pub fn block_index(&mut self) -> Result<Option<&BlockIndex>> {
+        self.load_index()?;
+        Ok(self.index.as_ref().filter(|index| !index.blocks.is_empty()))
+    }
+
+    /// The seek index, `None` for truncated recordings and those written
+    /// before it existed. [`rebuild_index`](crate::recording::rebuild_index)
+    /// adds it to both.
+    pub fn index(&mut self) -> Result<Option<&RecordingIndex>> {
+        self.load_index()?;
+        Ok(self.seek_index.as_ref())
+    }
+
+    /// Continues reading at `time`.
+    ///
+    /// The next records are the static info current at `time`, followed by
+    /// the frames from `time` on. Returns whether an index was used, without
+    /// one the recording is read again from the start up to `time`.
+    pub fn seek(&mut self, time: Duration) -> Result<bool> {
+        let checkpoint = self.index()?.and_then(|index| index.find(time)).copied();
+        if checkpoint.is_none()
+            && let Some(block) = self.block_index()?.and_then(|index| index.find(time)).copied()
+        {
+            self.restart(block.offset, 0)?;
+            self.skip = Some(Skip::Time(time));
+            return Ok(true);
+        }
+        self.seek_to(checkpoint, Skip::Time(time))
+    }
+
+    /// Continues reading at the start of lap `lap`, the first one with that
+    /// number if the recording holds several sessions.
+    ///
+    /// Laps are numbered by completed laps, see [`IndexedLap`](crate::recording::IndexedLap).
+    /// Returns `false` without moving when there is no such lap or no index.
+    pub fn seek_lap(&mut self, lap: i32) -> Result<bool> {
+        let Some(lap) = self.index()?.and_then(|index| index.lap(lap)).copied() else {
+            return Ok(false);
+        };
+        self.seek(lap.start)
+    }
+
+    /// Continues reading at the physics frame with `packet_id`, or the first
+    /// one after it.
+    ///
+    /// Like [`seek`](Recording::seek) the static info comes first, graphics
+    /// frames before the physics frame are skipped. Returns whether an index
+    /// was used.
+    pub fn seek_packet(&mut self, packet_id: i32) -> Result<bool> {
+        let checkpoint = self.index()?.and_then(|index| index.find_packet(packet_id)).copied();
+        self.seek_to(checkpoint, Skip::Packet(packet_id))
+    }
+
+    /// Reads the records from `range.start` up to `range.end`, preceded by
+    /// the static info current at the start.
+    pub fn range(&mut self, range: Range<Duration>) -> Result<RecordRange<'_, R>> {
+        self.seek(range.start)?;
+        Ok(RecordRange {
+            recording: self,
+            end: range.end,
+        })
+    }
+
+    fn seek_to(&mut self, checkpoint: Option<Checkpoint>, skip: Skip) -> Result<bool> {
+        match checkpoint {
+            Some(checkpoint) => self.restart(checkpoint.offset, checkpoint.static_offset)?,
+            None => {
+                let mut header = Vec::new();
+                self.header.write(&mut header)?;
+                self.restart(header.len() as u64, 0)?;
+            }
+        }
+        self.skip = Some(skip);
+        Ok(checkpoint.is_some())
+    }
+
+    /// Continues reading at the record at `offset`, with the static info in the
+    /// record at `static_offset` as the current one unless it is `0`.
+    fn restart(&mut self, offset: u64, static_offset: u64) -> Result<()> {
+        self.pending.clear();
+        self.last_static = None;
+        self.held_static = None;
+        self.skip = None;
+        self.finished = false;
+        self.truncated = false;
+        if static_offset != 0 {
+            self.reader.seek(SeekFrom::Start(static_offset))?;
+            self.held_static = match self.read_raw()? {
+                Some((TAG_FRAME, body)) => Some(decode_frame(&body)?),
+                Some((TAG_BLOCK, body)) => compression::decode_block(&self.header, &body)?
+                    .into_iter()
+                    .rfind(|record| record.page == PageKind::StaticInfo),
+                _ => None,
+            };
+            if self.held_static.is_none() {
+                return Err(RecordingError::InvalidFormat(format!("no static info at offset {}", static_offset)));
+            }
+            self.finished = false;
+            self.truncated = false;
+        }
+        self.reader.seek(SeekFrom::Start(offset))?;
+        Ok(())
+    }
+
+    fn load_index(&mut self) -> Result<()> {
+        if self.index.is_none() {
+            let position = self.reader.stream_position()?;
+            let index = self.read_index();
+            self.reader.seek(SeekFrom::Start(position))?;
+            if let Some((blocks, seek_index)) = index? {
+                self.index = Some(blocks);
+                self.seek_index = seek_index;
+            }
+        }
+        Ok(())
+    }
+
+    fn read_index(&mut self) -> Result<Option<(BlockIndex, Option<RecordingIndex>)>> {
+        let length = self.reader.seek(SeekFrom::End(0))?;
+        if length < 13 {
+            return Ok(None);
+        }
+        self.reader.seek(SeekFrom::End(-13))?;
+        let mut end = [0; 13];
+        self.reader.read_exact(&mut end)?;
+        if end[0] != TAG_END || end[1..5] != 8u32.to_le_bytes() {
+            return Ok(None);
+        }
+        let offset = u64::from_le_bytes(end[5..].try_into().unwrap());
+        if offset >= length {
+            return Err(RecordingError::InvalidFormat("index out of bounds".to_string()));
+        }
+        self.reader.seek(SeekFrom::Start(offset))?;
+        if format::read_u8(&mut self.reader)? != TAG_INDEX {
+            return Err(RecordingError::InvalidFormat(format!("no index at offset {}", offset)));
+        }
+        let size = format::read_u32(&mut self.reader)? as u64;
+        if offset + 5 + size > length - 13 {
+            return Err(RecordingError::InvalidFormat("index exceeds the file".to_string()));
+        }
+        let mut body = vec![0; size as usize];
+        self.reader.read_exact(&mut body)?;
+        let mut body = body.as_slice();
+        let blocks = BlockIndex::read(&mut body)?;
+        // Indexes written before checkpoints existed end after the blocks.
+        let seek_index = if body.is_empty() { None } else { Some(RecordingIndex::read(&mut body)?) };
+        Ok(Some((blocks, seek_index)))
+    }
+}
+
+impl<R: Read> Iterator for Recording<R> {
+    type Item = Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_record().transpose()
+    }
+}
+
+/// Iterator over the decoded frames of a recording, see [`Recording::frames`].
+pub struct Frames<S: TelemetrySource, R> {
+    recording: Recording<R>,
+    source: PhantomData<fn() -> S>,
+}
+
+impl<S: TelemetrySource, R> Frames<S, R> {
+    pub fn recording(&self) -> &Recording<R> {
+        &self.recording
+    }
+}
+
+impl<S: TelemetrySource, R: Read> Iterator for Frames<S, R> {
+    type Item = Result<Timestamped<Frame<S>>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = match self.recording.next_record() {
+            Ok(Some(record)) => record,
+            Ok(None) => return None,
+            Err(err) => return Some(Err(err)),
+        };
+        Some(self.recording.decode(&record).map(|frame| Timestamped { time: record.time, frame }))
+    }
+}
+
+/// The records of a time range, see [`Recording::range`].
+pub struct RecordRange<'a, R> {
+    recording: &'a mut Recording<R>,
+    end: Duration,
+}
+
+impl<'a, R: Read> RecordRange<'a, R> {
+    /// Iterates over the frames of the range decoded into the page types of `S`.
+    pub fn frames<S: TelemetrySource>(self) -> RangeFrames<'a, S, R> {
+        RangeFrames {
+            range: self,
+            source: PhantomData,
+        }
+    }
+}
+
+impl<R: Read> Iterator for RecordRange<'_, R> {
+    type Item = Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.recording.next_record() {
+            Ok(Some(record)) if record.time >= self.end => {
+                // Left for the next read, static info must not count as seen.
+                if record.page == PageKind::StaticInfo {
+                    self.recording.last_static = None;
+                }
+                self.recording.pending.push_front(record);
+                None
+            }
+            record => record.transpose(),
+        }
+    }
+}
+
+/// Iterator over the decoded frames of a time range, see [`RecordRange::frames`].
+pub struct RangeFrames<'a, S: TelemetrySource, R> {
+    range: RecordRange<'a, R>,
+    source: PhantomData<fn() -> S>,
+}
+
+impl<S: TelemetrySource, R: Read> Iterator for RangeFrames<'_, S, R> {
+    type Item = Result<Timestamped<Frame<S>>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = match self.range.next()? {
+            Ok(record) => record,
+            Err(err) => return Some(Err(err)),
+        };
+        Some(self.range.recording.decode(&record).map(|frame| Timestamped { time: record.time, frame }))
+    }
+}
+
+pub(crate) fn decode_frame(body: &[u8]) -> Result<Record> {
+    if body.len() < 9 {
+        return Err(RecordingError::InvalidFormat("frame record too short".to_string()));
+    }
+    let page = PageKind::from_u8(body[0]).ok_or_else(|| RecordingError::InvalidFormat(format!("unknown page {}", body[0])))?;
+    let mut time = &body[1..9];
+    Ok(Record {
+        page,
+        time: Duration::from_nanos(format::read_u64(&mut time)?),
+        bytes: body[9..].to_vec(),
+    })
+}
+
+/// Appends up to `length` bytes to `buf`, returning how many were read
+/// before EOF.
+fn read_full_to_end(reader: &mut impl Read, buf: &mut Vec<u8>, length: usize) -> io::Result<usize> {
+    reader.take(length as u64).read_to_end(buf)
+}
+
+/// Like `read_exact`, but returns how much was read instead of failing at EOF.
+pub(crate) fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut read = 0;
+    while read < buf.len() {
+        match reader.read(&mut buf[read..]) {
+            Ok(0) => break,
+            Ok(n) => read += n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(read)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ac::{AssettoCorsa, Graphics, Physics, StaticInfo};
+    use crate::recording::Recorder;
+    use crate::telemetry::Page;
+    use std::io::Cursor;
+
+    /// 30 s of physics at 100 Hz and graphics at 10 Hz, a lap every 10 s.
+    fn records() -> Vec<Record> {
+        let mut records = Vec::new();
+        for i in 0..3000 {
+            let time = Duration::from_millis(i as u64 * 10);
+            let physics = Physics {
+                packet_id: i,
+                speed_kmh: i as f32 * 0.1,
+                ..Physics::default()
+            };
+            records.push(Record {
+                page: PageKind::Physics,
+                time,
+                bytes: physics.to_bytes(),
+            });
+            if i % 10 == 0 {
+                let graphics = Graphics {
+                    packet_id: i / 10,
+                    completed_laps: i / 1000,
+                    ..Graphics::default()
+                };
+                records.push(Record {
+                    page: PageKind::Graphics,
+                    time,
+                    bytes: graphics.to_bytes(),
+                });
+            }
+        }
+        records
+    }
+
+    fn write(records: &[Record], block_frames: usize) -> Vec<u8> {
+        let mut recorder = Recorder::<AssettoCorsa, _>::new(Vec::new(), &StaticInfo::default()).unwrap();
+        if block_frames > 0 {
+            recorder = recorder.with_compression(block_frames);
+        }
+        for record in records {
+            match record.page {
+                PageKind::Physics => recorder.write_physics(record.time, &Physics::from_bytes(&record.bytes).unwrap()),
+                _ => recorder.write_graphics(record.time, &Graphics::from_bytes(&record.bytes).unwrap()),
+            }
+            .unwrap();
+        }
+        recorder.finish().unwrap()
+    }
+
+    fn read(bytes: Vec<u8>) -> (Vec<Record>, bool) {
+        let mut recording = Recording::new(Cursor::new(bytes)).unwrap();
+        let mut records = Vec::new();
+        while let Some(record) = recording.next_record().unwrap() {
+            records.push(record);
+        }
+        (records, recording.is_truncated())
+    }
+
+    #[test]
+    fn records_round_trip() {
+        let records = records();
+        for block_frames in [0, 64] {
+            let (read, truncated) = read(write(&records, block_frames));
+            assert_eq!(read[0].page, PageKind::StaticInfo);
+            assert_eq!(read[1..], records[..], "block_frames {}", block_frames);
+            assert!(!truncated);
+        }
+    }
+
+    #[test]
+    fn index_seeks_to_laps() {
+        for block_frames in [0, 64] {
+            let mut recording = Recording::new(Cursor::new(write(&records(), block_frames))).unwrap();
+            let laps: Vec<_> = recording.index().unwrap().unwrap().laps.iter().map(|lap| (lap.lap, lap.start)).collect();
+            assert_eq!(laps, [(0, Duration::ZERO), (1, Duration::from_secs(10)), (2, Duration::from_secs(20))]);
+
+            assert!(recording.seek_lap(2).unwrap());
+            assert_eq!(recording.next_record().unwrap().unwrap().page, PageKind::StaticInfo);
+            let first = recording.next_record().unwrap().unwrap();
+            assert_eq!(first.time, Duration::from_secs(20));
+            assert!(!recording.seek_lap(3).unwrap());
+        }
+    }
+
+    #[test]
+    fn truncated_file_reads_a_prefix() {
+        let records = records();
+        let bytes = write(&records, 64);
+        for len in [bytes.len() / 3, bytes.len() / 2 + 7, bytes.len() - 1] {
+            let (read, truncated) = read(bytes[..len].to_vec());
+            assert!(truncated, "{} bytes", len);
+            assert_eq!(read[1..], records[..read.len() - 1], "{} bytes", len);
+        }
+    }
+}
@@ -0,0 +1,311 @@
+use crate::recording::compression::{self, BlockIndex, BlockInfo};
+use crate::recording::format::{self, Header, PageKind, RecordedLayout, TAG_BLOCK, TAG_END, TAG_FRAME, TAG_INDEX};
+use crate::recording::index::{self, IndexBuilder};
+use crate::recording::{Record, Result};
+use crate::telemetry::{Packet, Page, TelemetrySource, Value};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::marker::PhantomData;
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RecorderStats {
+    pub physics_frames: u64,
+    pub graphics_frames: u64,
+    pub static_frames: u64,
+    /// Size the written frames take as plain frame records.
+    pub raw_bytes: u64,
+    /// Size of the records written so far, excluding the header.
+    pub bytes_written: u64,
+}
+
+impl RecorderStats {
+    /// How many times smaller the recording is than without compression.
+    ///
+    /// Frames still buffered for the current block are not counted yet.
+    pub fn compression_ratio(&self) -> f64 {
+        compression::ratio(self.raw_bytes, self.bytes_written)
+    }
+}
+
+/// Writes a session of a Kunos game into a recording file.
+///
+/// See [`format`](crate::recording::format) for the file layout.
+///
+/// ```no_run
+/// use race_sdk::acc::AssettoCorsaCompetizione;
+/// use race_sdk::recording::Recorder;
+///
+/// let mut acc = AssettoCorsaCompetizione::new();
+/// acc.connect().unwrap();
+///
+/// let mut recorder = Recorder::create_for(&acc, "session.rsdk").unwrap();
+/// for _ in 0..100_000 {
+///     recorder.record(&acc).unwrap();
+///     std::thread::sleep(std::time::Duration::from_millis(1));
+/// }
+/// recorder.finish().unwrap();
+/// ```
+///
+/// Long sessions should be recorded with
+/// [`with_compression`](Recorder::with_compression), which typically shrinks
+/// them by an order of magnitude while keeping them seekable.
+pub struct Recorder<S: TelemetrySource, W: Write> {
+    writer: RecordWriter<W>,
+    epoch: Instant,
+    last_physics: Option<i32>,
+    last_graphics: Option<i32>,
+    last_static: Option<Vec<u8>>,
+    source: PhantomData<fn() -> S>,
+}
+
+impl<S: TelemetrySource> Recorder<S, BufWriter<File>> {
+    pub fn create(path: impl AsRef<Path>, static_info: &S::StaticInfo) -> Result<Self> {
+        Self::new(BufWriter::new(File::create(path)?), static_info)
+    }
+
+    /// Creates a recording file for the game behind `source`.
+    pub fn create_for(source: &S, path: impl AsRef<Path>) -> Result<Self> {
+        let static_info = source.get_static_info().unwrap_or_default();
+        Self::create(path, &static_info)
+    }
+}
+
+impl<S: TelemetrySource, W: Write> Recorder<S, W> {
+    /// Writes the header and the initial static info.
+    pub fn new(writer: W, static_info: &S::StaticInfo) -> Result<Self> {
+        let sm_version = match static_info.get("sm_version") {
+            Some(Value::Text(version)) => version,
+            _ => String::new(),
+        };
+        let header = Header {
+            version: format::VERSION,
+            game: S::GAME,
+            sm_version,
+            started: Some(SystemTime::now()),
+            layouts: vec![
+                RecordedLayout::of::<S::Physics>(PageKind::Physics),
+                RecordedLayout::of::<S::Graphics>(PageKind::Graphics),
+                RecordedLayout::of::<S::StaticInfo>(PageKind::StaticInfo),
+            ],
+        };
+
+        let mut recorder = Self {
+            writer: RecordWriter::new(writer, header)?,
+            epoch: Instant::now(),
+            last_physics: None,
+            last_graphics: None,
+            last_static: None,
+            source: PhantomData,
+        };
+        recorder.write_static_info(Duration::ZERO, static_info)?;
+        Ok(recorder)
+    }
+
+    /// Measures the times used by [`Recorder::record`] from `epoch`, e.g. the
+    /// epoch of a [`Sampler`](crate::telemetry::Sampler).
+    pub fn with_epoch(mut self, epoch: Instant) -> Self {
+        self.epoch = epoch;
+        self
+    }
+
+    /// Stores frames in compressed blocks of `block_frames` frames, `0`
+    /// writes plain frames again.
+    ///
+    /// See [`format`](crate::recording::format) for the encoding and
+    /// [`DEFAULT_BLOCK_FRAMES`](crate::recording::DEFAULT_BLOCK_FRAMES) for a
+    /// reasonable block size. Larger blocks compress better, smaller ones
+    /// lose less when the recording is cut off and seek faster.
+    pub fn with_compression(mut self, block_frames: usize) -> Self {
+        self.writer.set_block_frames(block_frames);
+        self
+    }
+
+    pub fn stats(&self) -> RecorderStats {
+        self.writer.stats
+    }
+
+    /// The blocks written so far.
+    pub fn block_index(&self) -> &BlockIndex {
+        &self.writer.index
+    }
+
+    pub fn write_physics(&mut self, time: Duration, physics: &S::Physics) -> Result<()> {
+        self.last_physics = Some(physics.packet_id());
+        self.write_frame(PageKind::Physics, time, physics.to_bytes())
+    }
+
+    pub fn write_graphics(&mut self, time: Duration, graphics: &S::Graphics) -> Result<()> {
+        self.last_graphics = Some(graphics.packet_id());
+        self.write_frame(PageKind::Graphics, time, graphics.to_bytes())
+    }
+
+    /// Writes the static info if it differs from the last one written.
+    pub fn write_static_info(&mut self, time: Duration, static_info: &S::StaticInfo) -> Result<bool> {
+        let bytes = static_info.to_bytes();
+        if self.last_static.as_ref() == Some(&bytes) {
+            return Ok(false);
+        }
+        self.write_frame(PageKind::StaticInfo, time, bytes.clone())?;
+        self.last_static = Some(bytes);
+        Ok(true)
+    }
+
+    /// Reads all pages from `source` and writes the ones that changed.
+    ///
+    /// Returns the number of frames written.
+    pub fn record(&mut self, source: &S) -> Result<usize> {
+        let time = self.epoch.elapsed();
+        let mut written = 0;
+        if let Some(physics) = source.get_physics()
+            && self.last_physics != Some(physics.packet_id())
+        {
+            self.write_physics(time, &physics)?;
+            written += 1;
+        }
+        if let Some(graphics) = source.get_graphics()
+            && self.last_graphics != Some(graphics.packet_id())
+        {
+            self.write_graphics(time, &graphics)?;
+            written += 1;
+        }
+        if let Some(static_info) = source.get_static_info()
+            && self.write_static_info(time, &static_info)?
+        {
+            written += 1;
+        }
+        Ok(written)
+    }
+
+    /// Writes the pending block, the index and the end marker, then flushes
+    /// the writer.
+    pub fn finish(self) -> Result<W> {
+        Ok(self.writer.finish()?.0)
+    }
+
+    fn write_frame(&mut self, page: PageKind, time: Duration, bytes: Vec<u8>) -> Result<()> {
+        self.writer.write_frame(Record { page, time, bytes })
+    }
+}
+
+/// The record level of a recording file, shared by the recorder and the
+/// compressor.
+pub(crate) struct RecordWriter<W: Write> {
+    writer: W,
+    header: Header,
+    position: u64,
+    block_frames: usize,
+    block: Vec<Record>,
+    block_raw_size: u64,
+    last_static: Option<Record>,
+    index: BlockIndex,
+    builder: IndexBuilder,
+    stats: RecorderStats,
+}
+
+impl<W: Write> RecordWriter<W> {
+    pub(crate) fn new(mut writer: W, header: Header) -> Result<Self> {
+        let mut bytes = Vec::new();
+        header.write(&mut bytes)?;
+        writer.write_all(&bytes)?;
+        Ok(Self {
+            writer,
+            position: bytes.len() as u64,
+            block_frames: 0,
+            block: Vec::new(),
+            block_raw_size: 0,
+            last_static: None,
+            index: BlockIndex::default(),
+            builder: IndexBuilder::new(&header),
+            header,
+            stats: RecorderStats::default(),
+        })
+    }
+
+    pub(crate) fn set_block_frames(&mut self, block_frames: usize) {
+        self.block_frames = block_frames;
+    }
+
+    pub(crate) fn write_frame(&mut self, record: Record) -> Result<()> {
+        match record.page {
+            PageKind::Physics => self.stats.physics_frames += 1,
+            PageKind::Graphics => self.stats.graphics_frames += 1,
+            PageKind::StaticInfo => {
+                self.stats.static_frames += 1;
+                self.last_static = Some(record.clone());
+            }
+        }
+
+        if self.block_frames == 0 || !compression::blockable(&self.header, &record) {
+            self.flush_block()?;
+            self.builder.frame(self.position, &record);
+            let raw_size = record.bytes.len() as u64 + 14;
+            self.stats.raw_bytes += raw_size;
+            let mut body = Vec::with_capacity(record.bytes.len() + 9);
+            body.push(record.page as u8);
+            body.extend_from_slice(&(record.time.as_nanos() as u64).to_le_bytes());
+            body.extend_from_slice(&record.bytes);
+            return self.write_record(TAG_FRAME, &body);
+        }
+
+        // Every block carries the static info so it can be decoded on its own.
+        if self.block.is_empty()
+            && record.page != PageKind::StaticInfo
+            && let Some(static_info) = &self.last_static
+            && compression::blockable(&self.header, static_info)
+        {
+            self.block.push(Record {
+                time: record.time,
+                ..static_info.clone()
+            });
+        }
+        self.block_raw_size += record.bytes.len() as u64 + 14;
+        self.block.push(record);
+        if self.block.len() >= self.block_frames {
+            self.flush_block()?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn finish(mut self) -> Result<(W, RecorderStats)> {
+        self.flush_block()?;
+        let offset = self.position;
+        let body = index::index_body(&self.index, &self.builder.finish())?;
+        self.write_record(TAG_INDEX, &body)?;
+        self.write_record(TAG_END, &offset.to_le_bytes())?;
+        self.writer.flush()?;
+        Ok((self.writer, self.stats))
+    }
+
+    fn flush_block(&mut self) -> Result<()> {
+        let (Some(first), Some(last)) = (self.block.first(), self.block.last()) else {
+            return Ok(());
+        };
+        let body = compression::encode_block(&self.header, &self.block);
+        let info = BlockInfo {
+            offset: self.position,
+            first_time: first.time,
+            last_time: last.time,
+            frames: self.block.len() as u32,
+            raw_size: self.block_raw_size,
+            compressed_size: body.len() as u64 + 5,
+        };
+        self.builder.block(self.position, &self.block);
+        self.write_record(TAG_BLOCK, &body)?;
+        self.index.blocks.push(info);
+        self.stats.raw_bytes += self.block_raw_size;
+        self.block.clear();
+        self.block_raw_size = 0;
+        Ok(())
+    }
+
+    fn write_record(&mut self, tag: u8, body: &[u8]) -> Result<()> {
+        format::write_u8(&mut self.writer, tag)?;
+        format::write_u32(&mut self.writer, body.len() as u32)?;
+        self.writer.write_all(body)?;
+        self.position += body.len() as u64 + 5;
+        self.stats.bytes_written += body.len() as u64 + 5;
+        Ok(())
+    }
+}
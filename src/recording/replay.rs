@@ -0,0 +1,567 @@
+use crate::SharedMemory;
+use crate::recording::index::IndexBuilder;
+use crate::recording::{Frame, IndexedLap, PageKind, Record, Recording, RecordingError, Result};
+use crate::telemetry::{Page, TelemetrySource};
+use std::cell::UnsafeCell;
+use std::io::{Read, Seek};
+use std::marker::PhantomData;
+use std::mem;
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// Recording time decoded ahead of the playback position.
+const WINDOW: Duration = Duration::from_secs(10);
+
+/// Plays a recording back through the regular clients.
+///
+/// The replay hands out one [`SharedMemory`] source per page, all driven by
+/// a shared playback clock. A client built from them returns the frame that
+/// was current at the playback position, exactly like the live game would.
+///
+/// ```no_run
+/// use race_sdk::acc::AssettoCorsaCompetizione;
+/// use race_sdk::recording::Replay;
+///
+/// let replay = Replay::<AssettoCorsaCompetizione>::open("session.rsdk").unwrap();
+/// let acc = AssettoCorsaCompetizione::from_shared_memory(
+///     Box::new(replay.physics_page()),
+///     Box::new(replay.graphics_page()),
+///     Box::new(replay.static_page()),
+/// );
+///
+/// replay.seek_lap(3);
+/// replay.set_speed(2.0).unwrap();
+/// replay.play();
+/// while !replay.is_finished() {
+///     if let Some(physics) = acc.get_physics() {
+///         println!("{:?} {:.1} km/h", replay.position(), physics.speed_kmh);
+///     }
+///     std::thread::sleep(std::time::Duration::from_millis(10));
+/// }
+/// ```
+///
+/// Frames are read from the recording as playback reaches them, only about
+/// ten seconds of them are kept decoded in memory.
+pub struct Replay<S: TelemetrySource> {
+    stream: Arc<Mutex<dyn Stream>>,
+    laps: Arc<[IndexedLap]>,
+    duration: Duration,
+    clock: Arc<Mutex<Clock>>,
+    source: PhantomData<fn() -> S>,
+}
+
+impl<S: TelemetrySource> Clone for Replay<S> {
+    fn clone(&self) -> Self {
+        Self {
+            stream: self.stream.clone(),
+            laps: self.laps.clone(),
+            duration: self.duration,
+            clock: self.clock.clone(),
+            source: PhantomData,
+        }
+    }
+}
+
+impl<S: TelemetrySource + 'static> Replay<S> {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_recording(Recording::open(path)?)
+    }
+
+    /// Opens a recording for playback, the replay starts paused at its beginning.
+    ///
+    /// Laps come from the recording's index, a recording without one is
+    /// read through once to number them.
+    pub fn from_recording<R: Read + Seek + Send + 'static>(mut recording: Recording<R>) -> Result<Self> {
+        if recording.header().game != S::GAME {
+            return Err(RecordingError::GameMismatch {
+                expected: S::GAME,
+                found: recording.header().game,
+            });
+        }
+        let index = recording.index()?.cloned();
+        let anchors: Vec<Duration> = match &index {
+            Some(index) => index.checkpoints.iter().map(|checkpoint| checkpoint.time).collect(),
+            None => recording
+                .block_index()?
+                .map(|blocks| blocks.blocks.iter().map(|block| block.first_time).collect())
+                .unwrap_or_default(),
+        };
+
+        // The end is read from the last checkpoint on, or the whole
+        // recording when the laps have to be numbered as well.
+        let mut builder = index.is_none().then(|| IndexBuilder::new(recording.header()));
+        let last_anchor = if builder.is_none() { anchors.last().copied() } else { None };
+        recording.seek(last_anchor.unwrap_or_default())?;
+        let mut duration = Duration::ZERO;
+        while let Some(record) = recording.next_record()? {
+            duration = duration.max(record.time);
+            if let Some(builder) = &mut builder {
+                builder.frame(0, &record);
+            }
+        }
+        let laps = match (index, builder) {
+            (Some(index), _) => index.laps,
+            (None, Some(mut builder)) => builder.finish().laps,
+            (None, None) => Vec::new(),
+        };
+
+        recording.seek(Duration::ZERO)?;
+        let mut start = Duration::ZERO;
+        while let Some(record) = recording.next_record()? {
+            if record.page != PageKind::StaticInfo {
+                start = record.time;
+                break;
+            }
+        }
+
+        let stream = Player::<S, R> {
+            recording,
+            anchors,
+            window: None,
+            error: None,
+            source: PhantomData,
+        };
+        Ok(Self {
+            stream: Arc::new(Mutex::new(stream)),
+            laps: laps.into(),
+            duration,
+            clock: Arc::new(Mutex::new(Clock {
+                position: start,
+                anchor: Instant::now(),
+                speed: 1.0,
+                paused: true,
+            })),
+            source: PhantomData,
+        })
+    }
+}
+
+impl<S: TelemetrySource> Replay<S> {
+    pub fn physics_page(&self) -> ReplayPage {
+        self.page(PageKind::Physics)
+    }
+
+    pub fn graphics_page(&self) -> ReplayPage {
+        self.page(PageKind::Graphics)
+    }
+
+    pub fn static_page(&self) -> ReplayPage {
+        self.page(PageKind::StaticInfo)
+    }
+
+    /// A memory source for one page, driven by this replay's clock.
+    pub fn page(&self, page: PageKind) -> ReplayPage {
+        let size = match page {
+            PageKind::Physics => mem::size_of::<S::Physics>(),
+            PageKind::Graphics => mem::size_of::<S::Graphics>(),
+            PageKind::StaticInfo => mem::size_of::<S::StaticInfo>(),
+        };
+        ReplayPage {
+            page,
+            stream: self.stream.clone(),
+            clock: self.clock.clone(),
+            buffer: (0..size).map(|_| UnsafeCell::new(0)).collect(),
+        }
+    }
+
+    /// Time of the last recorded frame.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Current playback position in recording time.
+    pub fn position(&self) -> Duration {
+        self.clock().now().min(self.duration)
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.clock().paused
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.position() >= self.duration
+    }
+
+    pub fn play(&self) {
+        let mut clock = self.clock();
+        clock.rebase();
+        clock.paused = false;
+    }
+
+    pub fn pause(&self) {
+        let mut clock = self.clock();
+        clock.rebase();
+        clock.paused = true;
+    }
+
+    /// Playback speed relative to real time, `0.5` is half speed, `4.0` four
+    /// times faster. Fails unless `speed` is finite and positive.
+    pub fn set_speed(&self, speed: f64) -> Result<()> {
+        if !speed.is_finite() || speed <= 0.0 {
+            return Err(RecordingError::InvalidSpeed(speed));
+        }
+        let mut clock = self.clock();
+        clock.rebase();
+        clock.speed = speed;
+        Ok(())
+    }
+
+    pub fn speed(&self) -> f64 {
+        self.clock().speed
+    }
+
+    pub fn seek(&self, position: Duration) {
+        let mut clock = self.clock();
+        clock.position = position.min(self.duration);
+        clock.anchor = Instant::now();
+    }
+
+    /// Seeks to the start of lap `lap`, the first one with that number if
+    /// the recording holds several sessions. Returns `false` if the
+    /// recording has no such lap.
+    ///
+    /// Laps are numbered by completed laps, see [`IndexedLap`].
+    pub fn seek_lap(&self, lap: i32) -> bool {
+        self.seek_to_lap(self.laps.iter().find(|indexed| indexed.lap == lap))
+    }
+
+    /// Seeks to the start of lap `lap` of session `session`.
+    pub fn seek_session_lap(&self, session: u32, lap: i32) -> bool {
+        self.seek_to_lap(self.laps.iter().find(|indexed| indexed.session == session && indexed.lap == lap))
+    }
+
+    /// Start of lap `lap` of session `session`, `None` if it was not recorded.
+    pub fn lap_start(&self, session: u32, lap: i32) -> Option<Duration> {
+        self.laps
+            .iter()
+            .find(|indexed| indexed.session == session && indexed.lap == lap)
+            .map(|lap| lap.start)
+    }
+
+    /// The recorded laps in order, numbered like the recording's index.
+    pub fn laps(&self) -> &[IndexedLap] {
+        &self.laps
+    }
+
+    /// Pauses and moves to the next physics frame.
+    pub fn step(&self) {
+        let mut clock = self.clock();
+        clock.rebase();
+        clock.paused = true;
+        let mut stream = self.stream();
+        let mut window = stream.window(clock.position);
+        loop {
+            let times = &window.page(PageKind::Physics).times;
+            if let Some(time) = times.get(times.partition_point(|time| *time <= clock.position)) {
+                clock.position = *time;
+                return;
+            }
+            if window.end >= self.duration {
+                return;
+            }
+            window = stream.window(window.end);
+        }
+    }
+
+    /// Pauses and moves to the previous physics frame.
+    pub fn step_back(&self) {
+        let mut clock = self.clock();
+        clock.rebase();
+        clock.paused = true;
+        let Some(before) = clock.position.checked_sub(Duration::from_nanos(1)) else {
+            return;
+        };
+        // The window starting just before the position holds the previous
+        // frame as its first one, if not among the later ones.
+        let window = self.stream().window(before);
+        let times = &window.page(PageKind::Physics).times;
+        let index = times.partition_point(|time| *time <= before);
+        if index > 0 {
+            clock.position = times[index - 1];
+        }
+    }
+
+    /// The error that stopped reading the recording during playback, after
+    /// which the pages keep their last frames.
+    pub fn take_error(&self) -> Option<RecordingError> {
+        self.stream().take_error()
+    }
+
+    fn clock(&self) -> MutexGuard<'_, Clock> {
+        self.clock.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn stream(&self) -> MutexGuard<'_, dyn Stream + 'static> {
+        self.stream.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn seek_to_lap(&self, lap: Option<&IndexedLap>) -> bool {
+        match lap {
+            Some(lap) => {
+                self.seek(lap.start);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// A [`SharedMemory`] source returning the frame current at the replay position.
+///
+/// [`as_slice`](SharedMemory::as_slice) copies the frame into a buffer
+/// owned by the page and the next call overwrites it, the way the game
+/// overwrites its shared memory. [`read_into`](SharedMemory::read_into),
+/// which the clients use, copies it into the caller's buffer instead.
+pub struct ReplayPage {
+    page: PageKind,
+    stream: Arc<Mutex<dyn Stream>>,
+    clock: Arc<Mutex<Clock>>,
+    buffer: Box<[UnsafeCell<u8>]>,
+}
+
+impl ReplayPage {
+    fn current(&self) -> (Arc<Window>, Duration) {
+        let now = self.clock.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).now();
+        let window = self.stream.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).window(now);
+        (window, now)
+    }
+}
+
+impl SharedMemory for ReplayPage {
+    fn as_slice(&self) -> &[u8] {
+        let (window, now) = self.current();
+        let frame = window.page(self.page).frame_at(now);
+        let buffer = UnsafeCell::raw_get(self.buffer.as_ptr());
+        let len = frame.len().min(self.buffer.len());
+        // The buffer is never reallocated and the page is not `Sync`, so no
+        // other call copies at the same time. As with shared memory, bytes
+        // returned earlier change to the new frame.
+        unsafe {
+            std::ptr::copy_nonoverlapping(frame.as_ptr(), buffer, len);
+            std::slice::from_raw_parts(buffer, len)
+        }
+    }
+
+    fn read_into(&self, buf: &mut [u8]) -> usize {
+        let (window, now) = self.current();
+        let frame = window.page(self.page).frame_at(now);
+        let len = frame.len().min(buf.len());
+        buf[..len].copy_from_slice(&frame[..len]);
+        len
+    }
+}
+
+/// Reads windows of a recording for the pages of a replay.
+trait Stream: Send {
+    /// The window holding the frames current at `time`.
+    fn window(&mut self, time: Duration) -> Arc<Window>;
+
+    fn take_error(&mut self) -> Option<RecordingError>;
+}
+
+struct Player<S: TelemetrySource, R: Read + Seek> {
+    recording: Recording<R>,
+    /// Times reading can start at, from the recording's index.
+    anchors: Vec<Duration>,
+    window: Option<Arc<Window>>,
+    error: Option<RecordingError>,
+    source: PhantomData<fn() -> S>,
+}
+
+impl<S: TelemetrySource, R: Read + Seek + Send> Stream for Player<S, R> {
+    fn window(&mut self, time: Duration) -> Arc<Window> {
+        if let Some(window) = &self.window
+            && window.start <= time
+            && time < window.end
+        {
+            return window.clone();
+        }
+        let window = Arc::new(match self.load(time) {
+            Ok(window) => window,
+            Err(err) => {
+                // Keeps showing what was read so far.
+                self.error = Some(err);
+                let mut window = self.window.take().map(Arc::unwrap_or_clone).unwrap_or_else(Window::new::<S>);
+                window.end = Duration::MAX;
+                window
+            }
+        });
+        self.window = Some(window.clone());
+        window
+    }
+
+    fn take_error(&mut self) -> Option<RecordingError> {
+        self.error.take()
+    }
+}
+
+impl<S: TelemetrySource, R: Read + Seek> Player<S, R> {
+    /// Decodes the frames current at `time` and those of the following
+    /// [`WINDOW`].
+    fn load(&mut self, time: Duration) -> Result<Window> {
+        let anchor = self.anchors.partition_point(|anchor| *anchor <= time);
+        let mut current: [Option<Record>; 3] = Default::default();
+        let mut ahead = Vec::new();
+        self.recording
+            .seek(anchor.checked_sub(1).map_or(Duration::ZERO, |anchor| self.anchors[anchor]))?;
+        let mut end = Duration::MAX;
+        while let Some(record) = self.recording.next_record()? {
+            if record.time <= time {
+                let slot = slot(record.page);
+                current[slot] = Some(record);
+            } else if record.time < time + WINDOW {
+                ahead.push(record);
+            } else {
+                end = time + WINDOW;
+                break;
+            }
+        }
+
+        // Pages that were not written since the anchor are looked up before it.
+        for anchor in (1..anchor).rev() {
+            let missing = current.each_ref().map(Option::is_none);
+            if !missing.contains(&true) {
+                break;
+            }
+            self.recording.seek(self.anchors[anchor - 1])?;
+            while let Some(record) = self.recording.next_record()?
+                && record.time < self.anchors[anchor]
+            {
+                let slot = slot(record.page);
+                if missing[slot] {
+                    current[slot] = Some(record);
+                }
+            }
+        }
+
+        let mut window = Window::new::<S>();
+        window.start = time;
+        window.end = end;
+        for record in current.into_iter().flatten().chain(ahead) {
+            let frames = &mut window.pages[slot(record.page)];
+            match self.recording.decode::<S>(&record)? {
+                Frame::Physics(page) => frames.push(record.time, &page),
+                Frame::Graphics(page) => frames.push(record.time, &page),
+                Frame::StaticInfo(page) => frames.push(record.time, &page),
+            }
+        }
+        Ok(window)
+    }
+}
+
+fn slot(page: PageKind) -> usize {
+    match page {
+        PageKind::Physics => 0,
+        PageKind::Graphics => 1,
+        PageKind::StaticInfo => 2,
+    }
+}
+
+/// The decoded frames of a stretch of the recording.
+#[derive(Clone)]
+struct Window {
+    /// Every page starts with the frame current at `start`, if any, and
+    /// holds all frames up to `end`.
+    start: Duration,
+    end: Duration,
+    pages: [PageFrames; 3],
+}
+
+impl Window {
+    fn new<S: TelemetrySource>() -> Self {
+        Self {
+            start: Duration::ZERO,
+            end: Duration::ZERO,
+            pages: [
+                PageFrames::new::<S::Physics>(),
+                PageFrames::new::<S::Graphics>(),
+                PageFrames::new::<S::StaticInfo>(),
+            ],
+        }
+    }
+
+    fn page(&self, page: PageKind) -> &PageFrames {
+        &self.pages[slot(page)]
+    }
+}
+
+/// Frames of one page, stored back to back.
+///
+/// The storage is made of `u32`s so that every frame is aligned for the
+/// 4-byte fields of the pages.
+#[derive(Clone)]
+struct PageFrames {
+    size: usize,
+    times: Vec<Duration>,
+    data: Vec<u32>,
+    default: Vec<u32>,
+}
+
+impl PageFrames {
+    fn new<T: Page>() -> Self {
+        let size = mem::size_of::<T>();
+        Self {
+            size,
+            times: Vec::new(),
+            data: Vec::new(),
+            default: words(&T::default().to_bytes()),
+        }
+    }
+
+    fn push<T: Page>(&mut self, time: Duration, page: &T) {
+        self.times.push(time);
+        self.data.extend(words(&page.to_bytes()));
+    }
+
+    /// The last frame at or before `time`, or the first one.
+    fn frame_at(&self, time: Duration) -> &[u8] {
+        self.frame(self.times.partition_point(|frame| *frame <= time).saturating_sub(1))
+    }
+
+    fn frame(&self, index: usize) -> &[u8] {
+        let words = self.size.div_ceil(4);
+        let frame = if self.times.is_empty() {
+            &self.default[..]
+        } else {
+            &self.data[index * words..(index + 1) * words]
+        };
+        let bytes = unsafe { std::slice::from_raw_parts(frame.as_ptr() as *const u8, frame.len() * 4) };
+        &bytes[..self.size]
+    }
+}
+
+fn words(bytes: &[u8]) -> Vec<u32> {
+    bytes
+        .chunks(4)
+        .map(|chunk| {
+            let mut word = [0; 4];
+            word[..chunk.len()].copy_from_slice(chunk);
+            u32::from_ne_bytes(word)
+        })
+        .collect()
+}
+
+struct Clock {
+    /// Recording time at `anchor`.
+    position: Duration,
+    anchor: Instant,
+    speed: f64,
+    paused: bool,
+}
+
+impl Clock {
+    fn now(&self) -> Duration {
+        if self.paused {
+            self.position
+        } else {
+            self.position + self.anchor.elapsed().mul_f64(self.speed)
+        }
+    }
+
+    /// Folds the elapsed time into `position` before a parameter change.
+    fn rebase(&mut self) {
+        self.position = self.now();
+        self.anchor = Instant::now();
+    }
+}
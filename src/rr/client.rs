@@ -0,0 +1,106 @@
+use crate::rr::{R3E_VERSION_MAJOR, R3E_VERSION_MINOR, RaceRoomData, RaceRoomError, Shared, Snapshot};
+use crate::utils::{SharedMemory, open_shared_memory};
+use std::mem;
+
+pub struct RaceRoomClient {
+    memory: Option<Box<dyn SharedMemory>>,
+    data: Option<RaceRoomData>,
+}
+
+impl RaceRoomClient {
+    pub fn new() -> Self {
+        Self { memory: None, data: None }
+    }
+
+    /// Creates a client reading from a custom memory source instead of the
+    /// game.
+    pub fn from_shared_memory(memory: Box<dyn SharedMemory>) -> Self {
+        Self {
+            memory: Some(memory),
+            data: None,
+        }
+    }
+
+    /// Opens `$R3E` and checks that the game writes the layout of this crate.
+    pub fn connect(&mut self) -> Result<(), RaceRoomError> {
+        self.memory = None;
+        let memory = open_shared_memory("$R3E", mem::size_of::<Shared>()).map_err(map_shared_memory_error)?;
+        check_layout(memory.as_slice())?;
+        self.memory = Some(Box::new(memory));
+        Ok(())
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.memory.is_some()
+    }
+
+    /// The `(major, minor)` version written by the game.
+    pub fn version(&self) -> Option<(i32, i32)> {
+        let memory = self.memory.as_ref()?;
+        Shared::version_of(memory.as_slice())
+    }
+
+    /// Reads the page, `None` when not connected or the version does not match.
+    pub fn get_shared(&self) -> Option<Shared> {
+        let memory = self.memory.as_ref()?;
+        if Shared::version_of(memory.as_slice())? != (R3E_VERSION_MAJOR, R3E_VERSION_MINOR) {
+            return None;
+        }
+        Shared::from_bytes(memory.as_slice())
+    }
+
+    /// Sets the content data used by [`get_snapshot`](RaceRoomClient::get_snapshot).
+    pub fn set_data(&mut self, data: RaceRoomData) {
+        self.data = Some(data);
+    }
+
+    pub fn data(&self) -> Option<&RaceRoomData> {
+        self.data.as_ref()
+    }
+
+    /// Reads the page with the names of the track and the cars resolved
+    /// through the content data. Without content data only the track names
+    /// from the shared memory are filled in.
+    pub fn get_snapshot(&self) -> Option<Snapshot> {
+        let shared = self.get_shared()?;
+        Some(match &self.data {
+            Some(data) => data.snapshot(shared),
+            None => RaceRoomData::default().snapshot(shared),
+        })
+    }
+}
+
+impl Default for RaceRoomClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn check_layout(bytes: &[u8]) -> Result<(), RaceRoomError> {
+    let found = Shared::version_of(bytes).ok_or_else(|| RaceRoomError::InvalidData("page too short".to_string()))?;
+    if found != (R3E_VERSION_MAJOR, R3E_VERSION_MINOR) {
+        return Err(RaceRoomError::VersionMismatch {
+            expected: (R3E_VERSION_MAJOR, R3E_VERSION_MINOR),
+            found,
+        });
+    }
+    let shared = Shared::from_bytes(bytes).ok_or_else(|| RaceRoomError::InvalidData("page too short".to_string()))?;
+    let (offset, size) = (shared.all_drivers_offset, shared.driver_data_size);
+    if offset as usize != mem::offset_of!(Shared, num_cars) || size as usize != mem::size_of::<crate::rr::DriverData>() {
+        return Err(RaceRoomError::InvalidData(format!(
+            "driver data at offset {} with size {}, expected {} and {}",
+            offset,
+            size,
+            mem::offset_of!(Shared, num_cars),
+            mem::size_of::<crate::rr::DriverData>()
+        )));
+    }
+    Ok(())
+}
+
+fn map_shared_memory_error(err: crate::utils::SharedMemoryError) -> RaceRoomError {
+    match err {
+        crate::utils::SharedMemoryError::SharedMemoryNotFound(msg) => RaceRoomError::SharedMemoryNotFound(msg),
+        crate::utils::SharedMemoryError::ConnectionFailed(msg) => RaceRoomError::ConnectionFailed(msg),
+    }
+}
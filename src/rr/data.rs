@@ -0,0 +1,257 @@
+use crate::rr::{DriverInfo, RaceRoomError, Result, Shared};
+use crate::utils::json::{self, Json};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Where Steam installs the content data on Windows.
+const DEFAULT_PATHS: &[&str] = &[
+    r"C:\Program Files (x86)\Steam\steamapps\common\raceroom racing experience\Game\GameData\General\r3e-data.json",
+    r"C:\Program Files\Steam\steamapps\common\raceroom racing experience\Game\GameData\General\r3e-data.json",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CarInfo {
+    pub id: i32,
+    pub name: String,
+    pub class_id: i32,
+    pub manufacturer_id: i32,
+    pub year: Option<i32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassInfo {
+    pub id: i32,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ManufacturerInfo {
+    pub id: i32,
+    pub name: String,
+    pub country: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackInfo {
+    pub id: i32,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutInfo {
+    pub id: i32,
+    pub track_id: i32,
+    pub name: String,
+    /// In metres, when the data lists it.
+    pub length: Option<f32>,
+    pub max_vehicles: Option<i32>,
+}
+
+/// RaceRoom's static content data, resolving the numeric ids of the shared
+/// memory to names.
+///
+/// The game ships it as `Game/GameData/General/r3e-data.json` in its install
+/// folder.
+///
+/// ```no_run
+/// use race_sdk::rr::{RaceRoom, RaceRoomData};
+///
+/// let data = RaceRoomData::open_default().unwrap();
+/// let mut r3e = RaceRoom::new().with_data(data);
+/// r3e.connect().unwrap();
+///
+/// if let Some(snapshot) = r3e.get_snapshot() {
+///     println!("{} ({}) at {}", snapshot.player.car, snapshot.player.class, snapshot.track.layout);
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RaceRoomData {
+    pub cars: HashMap<i32, CarInfo>,
+    pub classes: HashMap<i32, ClassInfo>,
+    pub manufacturers: HashMap<i32, ManufacturerInfo>,
+    pub tracks: HashMap<i32, TrackInfo>,
+    pub layouts: HashMap<i32, LayoutInfo>,
+}
+
+/// Names of a car, empty where the content data does not know its ids.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VehicleNames {
+    pub car: String,
+    pub class: String,
+    pub manufacturer: String,
+}
+
+/// Names of the track, taken from the shared memory where the content data
+/// does not know its ids.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrackNames {
+    pub track: String,
+    pub layout: String,
+    /// In metres.
+    pub length: f32,
+}
+
+/// A [`Shared`] page together with the names of the track and the cars, see
+/// [`RaceRoomClient::get_snapshot`](crate::rr::RaceRoomClient::get_snapshot).
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub shared: Shared,
+    pub track: TrackNames,
+    pub player: VehicleNames,
+    /// Names of the cars in [`Shared::drivers`], in the same order.
+    pub drivers: Vec<VehicleNames>,
+}
+
+impl RaceRoomData {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let text = fs::read_to_string(path.as_ref()).map_err(|err| RaceRoomError::InvalidData(format!("cannot read {}: {}", path.as_ref().display(), err)))?;
+        Self::from_json(&text)
+    }
+
+    /// Opens the content data of a Steam install in its default location.
+    pub fn open_default() -> Result<Self> {
+        let path = Self::default_path().ok_or_else(|| RaceRoomError::InvalidData("r3e-data.json not found".to_string()))?;
+        Self::open(path)
+    }
+
+    pub fn default_path() -> Option<PathBuf> {
+        DEFAULT_PATHS.iter().map(PathBuf::from).find(|path| path.is_file())
+    }
+
+    /// Parses the content of `r3e-data.json`.
+    ///
+    /// Entries without an id or a name are skipped, layouts are read both
+    /// from a top-level `layouts` object and from the `layouts` of each
+    /// track.
+    pub fn from_json(text: &str) -> Result<Self> {
+        let root = json::parse(text.trim_start_matches('\u{feff}')).map_err(|err| RaceRoomError::InvalidData(format!("r3e-data.json: {}", err)))?;
+        if !matches!(root, Json::Object(_)) {
+            return Err(RaceRoomError::InvalidData("r3e-data.json: not an object".to_string()));
+        }
+
+        let mut data = Self::default();
+        for (key, car) in entries(&root, "cars") {
+            if let Some((id, name)) = id_name(key, car) {
+                data.cars.insert(
+                    id,
+                    CarInfo {
+                        id,
+                        name,
+                        class_id: car.get("Class").and_then(Json::as_i32).unwrap_or(-1),
+                        manufacturer_id: car.get("Manufacturer").and_then(Json::as_i32).unwrap_or(-1),
+                        year: car.get("Year").and_then(Json::as_i32),
+                    },
+                );
+            }
+        }
+        for (key, class) in entries(&root, "classes") {
+            if let Some((id, name)) = id_name(key, class) {
+                data.classes.insert(id, ClassInfo { id, name });
+            }
+        }
+        for (key, manufacturer) in entries(&root, "manufacturers") {
+            if let Some((id, name)) = id_name(key, manufacturer) {
+                let country = manufacturer.get("Country").and_then(Json::as_str).map(str::to_string);
+                data.manufacturers.insert(id, ManufacturerInfo { id, name, country });
+            }
+        }
+        for (key, track) in entries(&root, "tracks") {
+            let Some((id, name)) = id_name(key, track) else {
+                continue;
+            };
+            if let Some(Json::Array(layouts)) = track.get("layouts") {
+                for layout in layouts {
+                    data.insert_layout("", layout, id);
+                }
+            }
+            data.tracks.insert(id, TrackInfo { id, name });
+        }
+        for (key, layout) in entries(&root, "layouts") {
+            data.insert_layout(key, layout, -1);
+        }
+        Ok(data)
+    }
+
+    pub fn car(&self, model_id: i32) -> Option<&CarInfo> {
+        self.cars.get(&model_id)
+    }
+
+    pub fn class(&self, class_id: i32) -> Option<&ClassInfo> {
+        self.classes.get(&class_id)
+    }
+
+    pub fn manufacturer(&self, manufacturer_id: i32) -> Option<&ManufacturerInfo> {
+        self.manufacturers.get(&manufacturer_id)
+    }
+
+    pub fn track(&self, track_id: i32) -> Option<&TrackInfo> {
+        self.tracks.get(&track_id)
+    }
+
+    pub fn layout(&self, layout_id: i32) -> Option<&LayoutInfo> {
+        self.layouts.get(&layout_id)
+    }
+
+    /// Resolves the ids of a car, falling back to the class and manufacturer
+    /// of its model when the entry's own ids are unknown.
+    pub fn vehicle_names(&self, info: &DriverInfo) -> VehicleNames {
+        let car = self.car(info.model_id);
+        let class = self.class(info.class_id).or_else(|| self.class(car?.class_id));
+        let manufacturer = self.manufacturer(info.manufacturer_id).or_else(|| self.manufacturer(car?.manufacturer_id));
+        VehicleNames {
+            car: car.map(|car| car.name.clone()).unwrap_or_default(),
+            class: class.map(|class| class.name.clone()).unwrap_or_default(),
+            manufacturer: manufacturer.map(|manufacturer| manufacturer.name.clone()).unwrap_or_default(),
+        }
+    }
+
+    pub fn track_names(&self, shared: &Shared) -> TrackNames {
+        let layout = self.layout(shared.layout_id);
+        TrackNames {
+            track: self.track(shared.track_id).map_or_else(|| shared.track_name_str(), |track| track.name.clone()),
+            layout: layout.map_or_else(|| shared.layout_name_str(), |layout| layout.name.clone()),
+            length: layout.and_then(|layout| layout.length).unwrap_or(shared.layout_length),
+        }
+    }
+
+    /// Resolves all names of a page.
+    pub fn snapshot(&self, shared: Shared) -> Snapshot {
+        Snapshot {
+            track: self.track_names(&shared),
+            player: self.vehicle_names(&shared.vehicle_info),
+            drivers: shared.drivers().iter().map(|driver| self.vehicle_names(&driver.driver_info)).collect(),
+            shared,
+        }
+    }
+
+    fn insert_layout(&mut self, key: &str, layout: &Json, track_id: i32) {
+        let Some((id, name)) = id_name(key, layout) else {
+            return;
+        };
+        let info = LayoutInfo {
+            id,
+            track_id: layout.get("Track").and_then(Json::as_i32).unwrap_or(track_id),
+            name,
+            length: layout.get("Length").and_then(Json::as_f64).map(|length| length as f32),
+            max_vehicles: layout.get("MaxNumberOfVehicles").and_then(Json::as_i32),
+        };
+        self.layouts.entry(id).or_insert(info);
+    }
+}
+
+/// The members of the object `root[name]`.
+fn entries<'a>(root: &'a Json, name: &str) -> impl Iterator<Item = (&'a str, &'a Json)> {
+    let members = match root.get(name) {
+        Some(Json::Object(members)) => members.as_slice(),
+        _ => &[],
+    };
+    members.iter().map(|(key, value)| (key.as_str(), value))
+}
+
+/// The id of an entry, from its `Id` or else its key, and its name.
+fn id_name(key: &str, entry: &Json) -> Option<(i32, String)> {
+    let id = entry.get("Id").and_then(Json::as_i32).or_else(|| key.parse().ok())?;
+    let name = entry.get("Name").and_then(Json::as_str)?;
+    Some((id, name.to_string()))
+}
@@ -0,0 +1,137 @@
+pub mod client;
+pub mod data;
+pub mod types;
+pub use client::*;
+pub use data::*;
+pub use types::*;
+
+use crate::SharedMemory;
+use crate::telemetry::Sampler;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum RaceRoomError {
+    SharedMemoryNotFound(String),
+    ConnectionFailed(String),
+    InvalidData(String),
+    VersionMismatch { expected: (i32, i32), found: (i32, i32) },
+}
+
+impl fmt::Display for RaceRoomError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RaceRoomError::SharedMemoryNotFound(msg) => write!(f, "Shared memory not found: {}", msg),
+            RaceRoomError::ConnectionFailed(msg) => write!(f, "Connection failed: {}", msg),
+            RaceRoomError::InvalidData(msg) => write!(f, "Invalid data format: {}", msg),
+            RaceRoomError::VersionMismatch { expected, found } => write!(
+                f,
+                "Shared memory version {}.{} does not match the supported {}.{}",
+                found.0, found.1, expected.0, expected.1
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RaceRoomError {}
+
+pub type Result<T> = std::result::Result<T, RaceRoomError>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConnectionStatus {
+    Disconnected,
+    Connected,
+}
+
+/// RaceRoom Racing Experience, read through the `$R3E` shared memory.
+///
+/// Unlike the Kunos games RaceRoom publishes a single page holding the
+/// player's car, the session and all other cars, see [`Shared`].
+///
+/// ```no_run
+/// use race_sdk::rr::RaceRoom;
+///
+/// let mut r3e = RaceRoom::new();
+/// r3e.connect().unwrap();
+///
+/// if let Some(shared) = r3e.get_shared() {
+///     println!("{} on {}", shared.player_name_str(), shared.track_name_str());
+///     let fl = shared.tire_temp[0].current_temp;
+///     println!("{:.0} km/h, front left tread {:.0}/{:.0}/{:.0} °C", shared.speed_kmh(), fl[0], fl[1], fl[2]);
+///     for driver in shared.drivers() {
+///         println!("P{} {}", { driver.place }, driver.driver_info.name_str());
+///     }
+/// }
+/// ```
+pub struct RaceRoom {
+    client: RaceRoomClient,
+}
+
+impl RaceRoom {
+    pub fn new() -> Self {
+        Self { client: RaceRoomClient::new() }
+    }
+
+    /// Creates a facade reading from a custom memory source, see
+    /// [`RaceRoomClient::from_shared_memory`].
+    pub fn from_shared_memory(memory: Box<dyn SharedMemory>) -> Self {
+        Self {
+            client: RaceRoomClient::from_shared_memory(memory),
+        }
+    }
+
+    /// Resolves ids to names through `data`, see [`RaceRoom::get_snapshot`].
+    pub fn with_data(mut self, data: RaceRoomData) -> Self {
+        self.client.set_data(data);
+        self
+    }
+
+    pub fn connect(&mut self) -> Result<()> {
+        self.client.connect()
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.client.is_connected()
+    }
+
+    pub fn status(&self) -> ConnectionStatus {
+        if self.is_connected() {
+            ConnectionStatus::Connected
+        } else {
+            ConnectionStatus::Disconnected
+        }
+    }
+
+    pub fn version(&self) -> Option<(i32, i32)> {
+        self.client.version()
+    }
+
+    pub fn get_shared(&self) -> Option<Shared> {
+        self.client.get_shared()
+    }
+
+    pub fn get_player(&self) -> Option<PlayerData> {
+        self.client.get_shared().map(|shared| shared.player)
+    }
+
+    /// The page with the names of the track and the cars, see
+    /// [`RaceRoomClient::get_snapshot`].
+    pub fn get_snapshot(&self) -> Option<Snapshot> {
+        self.client.get_snapshot()
+    }
+
+    /// The cars in the session, ordered as the game lists them.
+    pub fn get_drivers(&self) -> Vec<DriverData> {
+        self.client.get_shared().map(|shared| shared.drivers().to_vec()).unwrap_or_default()
+    }
+
+    /// Returns a sampler that emits the page on every new simulation tick.
+    pub fn sampler(&self) -> Sampler<Shared, impl FnMut() -> Option<Shared> + '_> {
+        Sampler::new(|| self.client.get_shared())
+    }
+}
+
+impl Default for RaceRoom {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,651 @@
+use crate::telemetry::Packet;
+use std::fmt;
+use std::mem;
+use std::ptr;
+
+/// Version of the `$R3E` layout these types describe.
+pub const R3E_VERSION_MAJOR: i32 = 2;
+pub const R3E_VERSION_MINOR: i32 = 11;
+
+/// Maximum number of cars in [`Shared::all_drivers_data`].
+pub const R3E_NUM_DRIVERS_MAX: usize = 128;
+
+/// Number of entries in [`Shared::pit_menu_state`].
+pub const R3E_PIT_MENU_MAX: usize = 11;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(i32)]
+pub enum SessionType {
+    Unknown = -1,
+    Practice = 0,
+    Qualify = 1,
+    Race = 2,
+    Warmup = 3,
+}
+
+impl From<i32> for SessionType {
+    fn from(value: i32) -> Self {
+        match value {
+            0 => SessionType::Practice,
+            1 => SessionType::Qualify,
+            2 => SessionType::Race,
+            3 => SessionType::Warmup,
+            _ => SessionType::Unknown,
+        }
+    }
+}
+
+impl fmt::Display for SessionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SessionType::Unknown => write!(f, "Unknown"),
+            SessionType::Practice => write!(f, "Practice"),
+            SessionType::Qualify => write!(f, "Qualify"),
+            SessionType::Race => write!(f, "Race"),
+            SessionType::Warmup => write!(f, "Warmup"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(i32)]
+pub enum SessionPhase {
+    Unknown = -1,
+    Garage = 1,
+    Gridwalk = 2,
+    Formation = 3,
+    Countdown = 4,
+    Green = 5,
+    Checkered = 6,
+}
+
+impl From<i32> for SessionPhase {
+    fn from(value: i32) -> Self {
+        match value {
+            1 => SessionPhase::Garage,
+            2 => SessionPhase::Gridwalk,
+            3 => SessionPhase::Formation,
+            4 => SessionPhase::Countdown,
+            5 => SessionPhase::Green,
+            6 => SessionPhase::Checkered,
+            _ => SessionPhase::Unknown,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(i32)]
+pub enum FinishStatus {
+    Unknown = -1,
+    None = 0,
+    Finished = 1,
+    Dnf = 2,
+    Dnq = 3,
+    Dns = 4,
+    Dq = 5,
+}
+
+impl From<i32> for FinishStatus {
+    fn from(value: i32) -> Self {
+        match value {
+            0 => FinishStatus::None,
+            1 => FinishStatus::Finished,
+            2 => FinishStatus::Dnf,
+            3 => FinishStatus::Dnq,
+            4 => FinishStatus::Dns,
+            5 => FinishStatus::Dq,
+            _ => FinishStatus::Unknown,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(i32)]
+pub enum PitWindow {
+    Unknown = -1,
+    Disabled = 0,
+    Closed = 1,
+    Open = 2,
+    Stopped = 3,
+    Completed = 4,
+}
+
+impl From<i32> for PitWindow {
+    fn from(value: i32) -> Self {
+        match value {
+            0 => PitWindow::Disabled,
+            1 => PitWindow::Closed,
+            2 => PitWindow::Open,
+            3 => PitWindow::Stopped,
+            4 => PitWindow::Completed,
+            _ => PitWindow::Unknown,
+        }
+    }
+}
+
+/// Indices of per-tyre arrays.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(usize)]
+pub enum Tire {
+    FrontLeft = 0,
+    FrontRight = 1,
+    RearLeft = 2,
+    RearRight = 3,
+}
+
+/// Indices of [`TireTemp::current_temp`], the zones across the tread.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(usize)]
+pub enum TireTread {
+    Left = 0,
+    Center = 1,
+    Right = 2,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+#[repr(C, packed)]
+pub struct Vector3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+#[repr(C, packed)]
+pub struct Vector3F64 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+#[repr(C, packed)]
+pub struct Orientation {
+    pub pitch: f32,
+    pub yaw: f32,
+    pub roll: f32,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+#[repr(C, packed)]
+pub struct SectorStarts {
+    pub sector1: f32,
+    pub sector2: f32,
+    pub sector3: f32,
+}
+
+/// High-detail physics of the player's car. Values are in SI units, local
+/// vectors are relative to the car.
+#[derive(Debug, Clone, Copy, Default)]
+#[repr(C, packed)]
+pub struct PlayerData {
+    /// Physics ticks since the game started, advances at 400 Hz.
+    pub game_simulation_ticks: f64,
+    pub game_simulation_time: f64,
+    pub position: Vector3F64,
+    pub velocity: Vector3F64,
+    pub local_velocity: Vector3F64,
+    pub acceleration: Vector3F64,
+    pub local_acceleration: Vector3F64,
+    pub orientation: Vector3F64,
+    pub rotation: Vector3F64,
+    pub angular_acceleration: Vector3F64,
+    pub angular_velocity: Vector3F64,
+    pub local_angular_velocity: Vector3F64,
+    pub local_g_force: Vector3F64,
+    pub steering_force: f64,
+    pub steering_force_percentage: f64,
+    pub engine_torque: f64,
+    pub current_downforce: f64,
+    pub voltage: f64,
+    pub ers_level: f64,
+    pub power_mgu_h: f64,
+    pub power_mgu_k: f64,
+    pub torque_mgu_k: f64,
+    pub suspension_deflection: [f64; 4],
+    pub suspension_velocity: [f64; 4],
+    pub camber: [f64; 4],
+    pub ride_height: [f64; 4],
+    pub front_wing_height: f64,
+    pub front_roll_angle: f64,
+    pub rear_roll_angle: f64,
+    pub third_spring_suspension_deflection_front: f64,
+    pub third_spring_suspension_velocity_front: f64,
+    pub third_spring_suspension_deflection_rear: f64,
+    pub third_spring_suspension_velocity_rear: f64,
+    pub unused1: f64,
+}
+
+/// Flags shown to the player, `-1` where the session does not use them.
+#[derive(Debug, Clone, Copy, Default)]
+#[repr(C, packed)]
+pub struct Flags {
+    pub yellow: i32,
+    pub yellow_caused_it: i32,
+    pub yellow_overtake: i32,
+    pub yellow_positions_gained: i32,
+    pub sector_yellow: [i32; 3],
+    pub closest_yellow_distance_into_track: f32,
+    pub blue: i32,
+    pub black: i32,
+    pub green: i32,
+    pub checkered: i32,
+    pub white: i32,
+    pub black_and_white: i32,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+#[repr(C, packed)]
+pub struct CutTrackPenalties {
+    pub drive_through: i32,
+    pub stop_and_go: i32,
+    pub pit_stop: i32,
+    pub time_deduction: i32,
+    pub slow_down: i32,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+#[repr(C, packed)]
+pub struct Drs {
+    pub equipped: i32,
+    pub available: i32,
+    pub num_activations_left: i32,
+    pub engaged: i32,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+#[repr(C, packed)]
+pub struct PushToPass {
+    pub available: i32,
+    pub engaged: i32,
+    pub amount_left: i32,
+    pub engaged_time_left: f32,
+    pub wait_time_left: f32,
+}
+
+/// Driving aids, `-1` when unavailable, `0` off, `1` on, `5` currently active.
+#[derive(Debug, Clone, Copy, Default)]
+#[repr(C, packed)]
+pub struct AidSettings {
+    pub abs: i32,
+    pub tc: i32,
+    pub esp: i32,
+    pub countersteer: i32,
+    pub cornering: i32,
+}
+
+/// Temperatures of one tyre in °C.
+#[derive(Debug, Clone, Copy, Default)]
+#[repr(C, packed)]
+pub struct TireTemp {
+    /// Surface temperature across the tread, indexed by [`TireTread`].
+    pub current_temp: [f32; 3],
+    pub optimal_temp: f32,
+    pub cold_temp: f32,
+    pub hot_temp: f32,
+}
+
+/// Temperatures of one brake disc in °C.
+#[derive(Debug, Clone, Copy, Default)]
+#[repr(C, packed)]
+pub struct BrakeTemp {
+    pub current_temp: f32,
+    pub optimal_temp: f32,
+    pub cold_temp: f32,
+    pub hot_temp: f32,
+}
+
+/// Damage from `0.0` (destroyed) to `1.0` (intact), `-1.0` when unavailable.
+#[derive(Debug, Clone, Copy, Default)]
+#[repr(C, packed)]
+pub struct CarDamage {
+    pub engine: f32,
+    pub transmission: f32,
+    pub aerodynamics: f32,
+    pub suspension: f32,
+    pub unused1: f32,
+    pub unused2: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+#[repr(C, packed)]
+pub struct DriverInfo {
+    /// UTF-8, see [`DriverInfo::name_str`].
+    pub name: [u8; 64],
+    pub car_number: i32,
+    pub class_id: i32,
+    pub model_id: i32,
+    pub team_id: i32,
+    pub livery_id: i32,
+    pub manufacturer_id: i32,
+    pub user_id: i32,
+    pub slot_id: i32,
+    pub class_performance_index: i32,
+    pub engine_type: i32,
+    pub car_width: f32,
+    pub car_length: f32,
+    pub unused1: i32,
+    pub unused2: i32,
+}
+
+impl DriverInfo {
+    pub fn name_str(&self) -> String {
+        utf8_to_string(&self.name)
+    }
+}
+
+/// Scoring and position of one car in the session.
+#[derive(Debug, Clone, Copy)]
+#[repr(C, packed)]
+pub struct DriverData {
+    pub driver_info: DriverInfo,
+    pub finish_status: i32,
+    pub place: i32,
+    pub place_class: i32,
+    pub lap_distance: f32,
+    pub position: Vector3,
+    pub track_sector: i32,
+    pub completed_laps: i32,
+    pub current_lap_valid: i32,
+    pub lap_time_current_self: f32,
+    pub sector_time_current_self: [f32; 3],
+    pub sector_time_previous_self: [f32; 3],
+    pub sector_time_best_self: [f32; 3],
+    pub time_delta_front: f32,
+    pub time_delta_behind: f32,
+    pub pitstop_status: i32,
+    pub in_pitlane: i32,
+    pub num_pitstops: i32,
+    pub penalties: CutTrackPenalties,
+    pub car_speed: f32,
+    pub tire_type_front: i32,
+    pub tire_type_rear: i32,
+    pub tire_subtype_front: i32,
+    pub tire_subtype_rear: i32,
+    pub base_penalty_weight: f32,
+    pub aid_penalty_weight: f32,
+    pub drs_state: i32,
+    pub ptp_state: i32,
+    pub penalty_type: i32,
+    pub penalty_reason: i32,
+    pub engine_state: i32,
+    pub orientation: Vector3,
+}
+
+impl DriverData {
+    pub fn finish_status(&self) -> FinishStatus {
+        FinishStatus::from(self.finish_status)
+    }
+}
+
+/// The `$R3E` shared-memory page, updated by the game on every physics tick.
+///
+/// Times are in seconds and `-1.0` when not set, distances in metres and
+/// speeds in m/s unless noted otherwise. Strings are UTF-8.
+#[derive(Debug, Clone, Copy)]
+#[repr(C, packed)]
+pub struct Shared {
+    pub version_major: i32,
+    pub version_minor: i32,
+    /// Byte offset of [`Shared::num_cars`].
+    pub all_drivers_offset: i32,
+    /// Size of one [`DriverData`] entry.
+    pub driver_data_size: i32,
+
+    pub game_paused: i32,
+    pub game_in_menus: i32,
+    pub game_in_replay: i32,
+    pub game_using_vr: i32,
+    pub game_unused1: i32,
+
+    pub player: PlayerData,
+
+    pub track_name: [u8; 64],
+    pub layout_name: [u8; 64],
+    pub track_id: i32,
+    pub layout_id: i32,
+    pub layout_length: f32,
+    pub sector_start_factors: SectorStarts,
+    pub race_session_laps: [i32; 3],
+    pub race_session_minutes: [i32; 3],
+    pub event_index: i32,
+    pub session_type: i32,
+    pub session_iteration: i32,
+    pub session_length_format: i32,
+    /// In m/s.
+    pub session_pit_speed_limit: f32,
+    pub session_phase: i32,
+    pub start_lights: i32,
+    pub tire_wear_active: i32,
+    pub fuel_use_active: i32,
+    pub number_of_laps: i32,
+    pub session_time_duration: f32,
+    pub session_time_remaining: f32,
+    pub max_incident_points: i32,
+    pub event_unused2: f32,
+
+    pub pit_window_status: i32,
+    pub pit_window_start: i32,
+    pub pit_window_end: i32,
+    pub in_pitlane: i32,
+    pub pit_menu_selection: i32,
+    pub pit_menu_state: [i32; R3E_PIT_MENU_MAX],
+    pub pit_state: i32,
+    pub pit_total_duration: f32,
+    pub pit_elapsed_time: f32,
+    pub pit_action: i32,
+    pub num_pitstops_performed: i32,
+    pub pit_min_duration_total: f32,
+    pub pit_min_duration_left: f32,
+
+    pub flags: Flags,
+    pub position: i32,
+    pub position_class: i32,
+    pub finish_status: i32,
+    pub cut_track_warnings: i32,
+    pub penalties: CutTrackPenalties,
+    pub num_penalties: i32,
+    pub completed_laps: i32,
+    pub current_lap_valid: i32,
+    pub track_sector: i32,
+    pub lap_distance: f32,
+    pub lap_distance_fraction: f32,
+    pub lap_time_best_leader: f32,
+    pub lap_time_best_leader_class: f32,
+    pub session_best_lap_sector_times: [f32; 3],
+    pub lap_time_best_self: f32,
+    pub sector_time_best_self: [f32; 3],
+    pub lap_time_previous_self: f32,
+    pub sector_time_previous_self: [f32; 3],
+    pub lap_time_current_self: f32,
+    pub sector_time_current_self: [f32; 3],
+    pub lap_time_delta_leader: f32,
+    pub lap_time_delta_leader_class: f32,
+    pub time_delta_front: f32,
+    pub time_delta_behind: f32,
+    pub time_delta_best_self: f32,
+    pub best_individual_sector_time_self: [f32; 3],
+    pub best_individual_sector_time_leader: [f32; 3],
+    pub best_individual_sector_time_leader_class: [f32; 3],
+    pub incident_points: i32,
+    pub lap_valid_state: i32,
+    pub score_unused1: f32,
+    pub score_unused2: f32,
+
+    pub vehicle_info: DriverInfo,
+    pub player_name: [u8; 64],
+
+    pub control_type: i32,
+    pub car_speed: f32,
+    /// Engine speed in rad/s.
+    pub engine_rps: f32,
+    pub max_engine_rps: f32,
+    pub upshift_rps: f32,
+    /// `-1` reverse, `0` neutral.
+    pub gear: i32,
+    pub num_gears: i32,
+    pub car_cg_location: Vector3,
+    pub car_orientation: Orientation,
+    pub local_acceleration: Vector3,
+    /// In kg.
+    pub total_mass: f32,
+    /// In litres.
+    pub fuel_left: f32,
+    pub fuel_capacity: f32,
+    pub fuel_per_lap: f32,
+    /// In °C.
+    pub engine_water_temp: f32,
+    pub engine_oil_temp: f32,
+    /// In kPa.
+    pub fuel_pressure: f32,
+    pub engine_oil_pressure: f32,
+    /// In bar.
+    pub turbo_pressure: f32,
+    pub throttle: f32,
+    pub throttle_raw: f32,
+    pub brake: f32,
+    pub brake_raw: f32,
+    pub clutch: f32,
+    pub clutch_raw: f32,
+    pub steer_input_raw: f32,
+    pub steer_lock_degrees: i32,
+    pub steer_wheel_range_degrees: i32,
+    pub aid_settings: AidSettings,
+    pub drs: Drs,
+    pub pit_limiter: i32,
+    pub push_to_pass: PushToPass,
+    pub brake_bias: f32,
+    pub drs_num_activations_total: i32,
+    pub ptp_num_activations_total: i32,
+    pub battery_soc: f32,
+    pub water_left: f32,
+    pub abs_setting: i32,
+    pub headlights: i32,
+    pub vehicle_unused1: f32,
+
+    /// Deprecated, see `tire_type_front` and `tire_type_rear`.
+    pub tire_type: i32,
+    /// Per tyre, indexed by [`Tire`]. Rotation speed in rad/s.
+    pub tire_rps: [f32; 4],
+    pub tire_speed: [f32; 4],
+    pub tire_grip: [f32; 4],
+    pub tire_wear: [f32; 4],
+    pub tire_flatspot: [i32; 4],
+    /// In kPa.
+    pub tire_pressure: [f32; 4],
+    pub tire_dirt: [f32; 4],
+    pub tire_temp: [TireTemp; 4],
+    pub tire_type_front: i32,
+    pub tire_type_rear: i32,
+    pub tire_subtype_front: i32,
+    pub tire_subtype_rear: i32,
+    pub brake_temp: [BrakeTemp; 4],
+    pub brake_pressure: [f32; 4],
+
+    pub traction_control_setting: i32,
+    pub engine_map_setting: i32,
+    pub engine_brake_setting: i32,
+    pub traction_control_percent: f32,
+    pub tire_unused1: f32,
+    /// In N.
+    pub tire_load: [f32; 4],
+
+    pub car_damage: CarDamage,
+
+    pub num_cars: i32,
+    pub all_drivers_data: [DriverData; R3E_NUM_DRIVERS_MAX],
+}
+
+impl Shared {
+    /// Reads the page from raw shared memory, `None` if `bytes` is too short.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < mem::size_of::<Self>() {
+            return None;
+        }
+        // Every field is a plain number or byte array, any bit pattern is valid.
+        Some(unsafe { ptr::read_unaligned(bytes.as_ptr() as *const Self) })
+    }
+
+    /// The `(major, minor)` version stored at the start of `bytes`.
+    pub fn version_of(bytes: &[u8]) -> Option<(i32, i32)> {
+        let word = |offset: usize| Some(i32::from_le_bytes(bytes.get(offset..offset + 4)?.try_into().ok()?));
+        Some((word(0)?, word(4)?))
+    }
+
+    pub fn track_name_str(&self) -> String {
+        utf8_to_string(&self.track_name)
+    }
+
+    pub fn layout_name_str(&self) -> String {
+        utf8_to_string(&self.layout_name)
+    }
+
+    pub fn player_name_str(&self) -> String {
+        utf8_to_string(&self.player_name)
+    }
+
+    pub fn session_type(&self) -> SessionType {
+        SessionType::from(self.session_type)
+    }
+
+    pub fn session_phase(&self) -> SessionPhase {
+        SessionPhase::from(self.session_phase)
+    }
+
+    pub fn finish_status(&self) -> FinishStatus {
+        FinishStatus::from(self.finish_status)
+    }
+
+    pub fn pit_window_status(&self) -> PitWindow {
+        PitWindow::from(self.pit_window_status)
+    }
+
+    /// Speed in km/h.
+    pub fn speed_kmh(&self) -> f32 {
+        self.car_speed * 3.6
+    }
+
+    /// Engine speed in revolutions per minute.
+    pub fn rpm(&self) -> f32 {
+        self.engine_rps * 60.0 / std::f32::consts::TAU
+    }
+
+    /// The entries of [`Shared::all_drivers_data`] in use.
+    pub fn drivers(&self) -> &[DriverData] {
+        let count = self.num_cars.clamp(0, R3E_NUM_DRIVERS_MAX as i32) as usize;
+        let drivers = ptr::addr_of!(self.all_drivers_data) as *const DriverData;
+        // `DriverData` is packed, so the array has no alignment requirement.
+        unsafe { std::slice::from_raw_parts(drivers, count) }
+    }
+}
+
+impl Packet for Shared {
+    /// The simulation tick, the page carries no packet counter.
+    fn packet_id(&self) -> i32 {
+        self.player.game_simulation_ticks as i32
+    }
+}
+
+pub fn utf8_to_string(utf8: &[u8]) -> String {
+    let end = utf8.iter().position(|&c| c == 0).unwrap_or(utf8.len());
+    String::from_utf8_lossy(&utf8[..end]).into_owned()
+}
+
+impl Default for DriverInfo {
+    fn default() -> Self {
+        // All fields are numbers, zero is a valid value for each.
+        unsafe { mem::zeroed() }
+    }
+}
+
+impl Default for DriverData {
+    fn default() -> Self {
+        unsafe { mem::zeroed() }
+    }
+}
+
+impl Default for Shared {
+    fn default() -> Self {
+        unsafe { mem::zeroed() }
+    }
+}
@@ -0,0 +1,367 @@
+use crate::recording::Recording;
+use crate::store::{Lap, LapTracker, Result};
+use crate::telemetry::{Game, TelemetrySource};
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{Connection, OptionalExtension, Row, params, params_from_iter};
+use std::io::{Cursor, Read};
+use std::ops::Range;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS laps (
+    id INTEGER PRIMARY KEY,
+    recorded_at INTEGER NOT NULL,
+    game TEXT NOT NULL,
+    car TEXT NOT NULL,
+    track TEXT NOT NULL,
+    track_configuration TEXT NOT NULL,
+    session TEXT NOT NULL,
+    lap INTEGER NOT NULL,
+    lap_time_ms INTEGER NOT NULL,
+    valid INTEGER,
+    tyre_compound TEXT NOT NULL,
+    rain_intensity TEXT,
+    road_temp REAL NOT NULL,
+    air_temp REAL NOT NULL,
+    fuel_used REAL NOT NULL,
+    telemetry BLOB NOT NULL
+);
+CREATE TABLE IF NOT EXISTS sectors (
+    lap_id INTEGER NOT NULL REFERENCES laps(id) ON DELETE CASCADE,
+    sector INTEGER NOT NULL,
+    time_ms INTEGER NOT NULL,
+    PRIMARY KEY (lap_id, sector)
+);
+CREATE INDEX IF NOT EXISTS laps_car_track ON laps (car, track, track_configuration);
+";
+
+const COLUMNS: &str = "id, recorded_at, game, car, track, track_configuration, session, lap, lap_time_ms, valid, \
+                       tyre_compound, rain_intensity, road_temp, air_temp, fuel_used";
+
+/// A lap as stored, see [`LapStore::laps`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredLap {
+    pub id: i64,
+    pub recorded_at: SystemTime,
+    /// The lap without its telemetry.
+    pub lap: Lap,
+}
+
+/// Filters laps by car, track and conditions. Unset criteria match every lap.
+///
+/// ```
+/// use race_sdk::store::LapQuery;
+///
+/// let query = LapQuery::new()
+///     .car("ferrari_296_gt3")
+///     .track("monza")
+///     .rain_intensity("NoRain")
+///     .road_temp(25.0..35.0);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LapQuery {
+    game: Option<Game>,
+    car: Option<String>,
+    track: Option<String>,
+    track_configuration: Option<String>,
+    session: Option<String>,
+    tyre_compound: Option<String>,
+    rain_intensity: Option<String>,
+    road_temp: Option<Range<f32>>,
+    air_temp: Option<Range<f32>>,
+    include_invalid: bool,
+}
+
+impl LapQuery {
+    /// All valid laps, laps of games without validity count as valid.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn game(mut self, game: Game) -> Self {
+        self.game = Some(game);
+        self
+    }
+
+    pub fn car(mut self, car: &str) -> Self {
+        self.car = Some(car.to_string());
+        self
+    }
+
+    pub fn track(mut self, track: &str) -> Self {
+        self.track = Some(track.to_string());
+        self
+    }
+
+    pub fn track_configuration(mut self, track_configuration: &str) -> Self {
+        self.track_configuration = Some(track_configuration.to_string());
+        self
+    }
+
+    pub fn session(mut self, session: &str) -> Self {
+        self.session = Some(session.to_string());
+        self
+    }
+
+    pub fn tyre_compound(mut self, tyre_compound: &str) -> Self {
+        self.tyre_compound = Some(tyre_compound.to_string());
+        self
+    }
+
+    pub fn rain_intensity(mut self, rain_intensity: &str) -> Self {
+        self.rain_intensity = Some(rain_intensity.to_string());
+        self
+    }
+
+    /// Track temperature in °C.
+    pub fn road_temp(mut self, range: Range<f32>) -> Self {
+        self.road_temp = Some(range);
+        self
+    }
+
+    /// Air temperature in °C.
+    pub fn air_temp(mut self, range: Range<f32>) -> Self {
+        self.air_temp = Some(range);
+        self
+    }
+
+    pub fn include_invalid(mut self) -> Self {
+        self.include_invalid = true;
+        self
+    }
+
+    /// The `WHERE` clause and its parameters.
+    fn to_sql(&self) -> (String, Vec<SqlValue>) {
+        let mut conditions = Vec::new();
+        let mut values = Vec::new();
+        let texts = [
+            ("game", self.game.map(|game| game.code().to_string())),
+            ("car", self.car.clone()),
+            ("track", self.track.clone()),
+            ("track_configuration", self.track_configuration.clone()),
+            ("session", self.session.clone()),
+            ("tyre_compound", self.tyre_compound.clone()),
+            ("rain_intensity", self.rain_intensity.clone()),
+        ];
+        for (column, value) in texts {
+            if let Some(value) = value {
+                conditions.push(format!("{} = ?", column));
+                values.push(SqlValue::Text(value));
+            }
+        }
+        for (column, range) in [("road_temp", &self.road_temp), ("air_temp", &self.air_temp)] {
+            if let Some(range) = range {
+                conditions.push(format!("{} >= ? AND {} < ?", column, column));
+                values.push(SqlValue::Real(range.start as f64));
+                values.push(SqlValue::Real(range.end as f64));
+            }
+        }
+        if !self.include_invalid {
+            conditions.push("(valid IS NULL OR valid = 1)".to_string());
+        }
+        if conditions.is_empty() {
+            (String::new(), values)
+        } else {
+            (format!("WHERE {}", conditions.join(" AND ")), values)
+        }
+    }
+}
+
+/// A SQLite database of completed laps.
+///
+/// Every lap is stored with its time, sectors, validity, car, track,
+/// conditions and fuel used, plus its telemetry as a compressed recording.
+///
+/// ```no_run
+/// use race_sdk::acc::AssettoCorsaCompetizione;
+/// use race_sdk::recording::Recording;
+/// use race_sdk::store::{LapQuery, LapStore};
+///
+/// let mut store = LapStore::open("laps.db").unwrap();
+/// store
+///     .import_recording::<AssettoCorsaCompetizione, _>(Recording::open("session.rsdk").unwrap())
+///     .unwrap();
+///
+/// for best in store.personal_bests(&LapQuery::new().track("monza")).unwrap() {
+///     println!("{} {:?} {:?}", best.lap.car, best.lap.rain_intensity, best.lap.lap_time);
+/// }
+/// ```
+pub struct LapStore {
+    connection: Connection,
+}
+
+impl LapStore {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(connection: Connection) -> Result<Self> {
+        connection.execute_batch("PRAGMA foreign_keys = ON;")?;
+        connection.execute_batch(SCHEMA)?;
+        Ok(Self { connection })
+    }
+
+    /// Stores a lap, returns its id.
+    pub fn insert(&mut self, lap: &Lap) -> Result<i64> {
+        let transaction = self.connection.transaction()?;
+        let id = insert(&transaction, lap)?;
+        transaction.commit()?;
+        Ok(id)
+    }
+
+    /// Stores every lap completed in a recording in one transaction, returns
+    /// their ids.
+    pub fn import_recording<S: TelemetrySource, R: Read>(&mut self, recording: Recording<R>) -> Result<Vec<i64>> {
+        let mut tracker = LapTracker::<S>::new();
+        let mut laps = Vec::new();
+        for frame in recording.frames::<S>() {
+            let frame = frame?;
+            laps.extend(tracker.push_frame(frame.time, &frame.frame)?);
+        }
+        let transaction = self.connection.transaction()?;
+        let ids = laps.iter().map(|lap| insert(&transaction, lap)).collect::<Result<Vec<_>>>()?;
+        transaction.commit()?;
+        Ok(ids)
+    }
+
+    pub fn lap(&self, id: i64) -> Result<Option<StoredLap>> {
+        let mut statement = self.connection.prepare(&format!("SELECT {} FROM laps WHERE id = ?1", COLUMNS))?;
+        let lap = statement.query_row([id], read_lap).optional()?;
+        match lap {
+            Some(lap) => Ok(Some(self.with_sectors(lap)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// The laps matching `query`, fastest first.
+    pub fn laps(&self, query: &LapQuery) -> Result<Vec<StoredLap>> {
+        let (filter, values) = query.to_sql();
+        let sql = format!("SELECT {} FROM laps {} ORDER BY lap_time_ms, id", COLUMNS, filter);
+        self.query(&sql, values)
+    }
+
+    /// The fastest lap matching `query`.
+    pub fn personal_best(&self, query: &LapQuery) -> Result<Option<StoredLap>> {
+        Ok(self.laps(query)?.into_iter().next())
+    }
+
+    /// The fastest lap matching `query` for every combination of game, car,
+    /// track, layout and rain intensity.
+    pub fn personal_bests(&self, query: &LapQuery) -> Result<Vec<StoredLap>> {
+        let (filter, values) = query.to_sql();
+        let sql = format!(
+            "SELECT {columns} FROM (
+                SELECT *, ROW_NUMBER() OVER (
+                    PARTITION BY game, car, track, track_configuration, rain_intensity
+                    ORDER BY lap_time_ms, id
+                ) AS rank
+                FROM laps {filter}
+            ) WHERE rank = 1 ORDER BY game, car, track, track_configuration, rain_intensity",
+            columns = COLUMNS,
+            filter = filter
+        );
+        self.query(&sql, values)
+    }
+
+    /// The raw telemetry of a lap, a compressed recording.
+    pub fn telemetry(&self, id: i64) -> Result<Option<Vec<u8>>> {
+        Ok(self
+            .connection
+            .query_row("SELECT telemetry FROM laps WHERE id = ?1", [id], |row| row.get(0))
+            .optional()?)
+    }
+
+    /// The telemetry of a lap opened as a recording.
+    pub fn recording(&self, id: i64) -> Result<Option<Recording<Cursor<Vec<u8>>>>> {
+        match self.telemetry(id)? {
+            Some(bytes) => Ok(Some(Recording::new(Cursor::new(bytes))?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn delete(&self, id: i64) -> Result<bool> {
+        Ok(self.connection.execute("DELETE FROM laps WHERE id = ?1", [id])? > 0)
+    }
+
+    fn query(&self, sql: &str, values: Vec<SqlValue>) -> Result<Vec<StoredLap>> {
+        let mut statement = self.connection.prepare(sql)?;
+        let laps = statement.query_map(params_from_iter(values), read_lap)?.collect::<rusqlite::Result<Vec<_>>>()?;
+        laps.into_iter().map(|lap| self.with_sectors(lap)).collect()
+    }
+
+    fn with_sectors(&self, mut lap: StoredLap) -> Result<StoredLap> {
+        let mut statement = self
+            .connection
+            .prepare_cached("SELECT time_ms FROM sectors WHERE lap_id = ?1 ORDER BY sector")?;
+        lap.lap.sectors = statement
+            .query_map([lap.id], |row| row.get::<_, i64>(0))?
+            .map(|millis| millis.map(|millis| Duration::from_millis(millis as u64)))
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(lap)
+    }
+}
+
+fn insert(connection: &Connection, lap: &Lap) -> Result<i64> {
+    let recorded_at = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs() as i64);
+    connection.execute(
+        "INSERT INTO laps (recorded_at, game, car, track, track_configuration, session, lap, lap_time_ms, valid, \
+         tyre_compound, rain_intensity, road_temp, air_temp, fuel_used, telemetry) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+        params![
+            recorded_at,
+            lap.game.code(),
+            lap.car,
+            lap.track,
+            lap.track_configuration,
+            lap.session,
+            lap.lap,
+            lap.lap_time.as_millis() as i64,
+            lap.valid,
+            lap.tyre_compound,
+            lap.rain_intensity,
+            lap.road_temp,
+            lap.air_temp,
+            lap.fuel_used,
+            lap.telemetry,
+        ],
+    )?;
+    let id = connection.last_insert_rowid();
+    for (sector, time) in lap.sectors.iter().enumerate() {
+        connection.execute(
+            "INSERT INTO sectors (lap_id, sector, time_ms) VALUES (?1, ?2, ?3)",
+            params![id, sector as i64, time.as_millis() as i64],
+        )?;
+    }
+    Ok(id)
+}
+
+fn read_lap(row: &Row) -> rusqlite::Result<StoredLap> {
+    let game: String = row.get(2)?;
+    let game = Game::from_code(&game).ok_or_else(|| rusqlite::Error::InvalidColumnType(2, game, rusqlite::types::Type::Text))?;
+    Ok(StoredLap {
+        id: row.get(0)?,
+        recorded_at: UNIX_EPOCH + Duration::from_secs(row.get::<_, i64>(1)?.max(0) as u64),
+        lap: Lap {
+            game,
+            car: row.get(3)?,
+            track: row.get(4)?,
+            track_configuration: row.get(5)?,
+            session: row.get(6)?,
+            lap: row.get(7)?,
+            lap_time: Duration::from_millis(row.get::<_, i64>(8)?.max(0) as u64),
+            sectors: Vec::new(),
+            valid: row.get(9)?,
+            tyre_compound: row.get(10)?,
+            rain_intensity: row.get(11)?,
+            road_temp: row.get(12)?,
+            air_temp: row.get(13)?,
+            fuel_used: row.get(14)?,
+            telemetry: Vec::new(),
+        },
+    })
+}
@@ -0,0 +1,38 @@
+pub mod laps;
+pub mod tracker;
+pub use laps::*;
+pub use tracker::*;
+
+use crate::recording::RecordingError;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum StoreError {
+    Sqlite(rusqlite::Error),
+    Recording(RecordingError),
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StoreError::Sqlite(err) => write!(f, "SQLite error: {}", err),
+            StoreError::Recording(err) => write!(f, "Recording error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for StoreError {}
+
+impl From<rusqlite::Error> for StoreError {
+    fn from(err: rusqlite::Error) -> Self {
+        StoreError::Sqlite(err)
+    }
+}
+
+impl From<RecordingError> for StoreError {
+    fn from(err: RecordingError) -> Self {
+        StoreError::Recording(err)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, StoreError>;
@@ -0,0 +1,299 @@
+use crate::export::SessionTracker;
+use crate::recording::{DEFAULT_BLOCK_FRAMES, Frame, Recorder};
+use crate::store::Result;
+use crate::telemetry::{Game, Packet, Page, TelemetrySource, Value};
+use std::time::{Duration, Instant};
+
+/// A completed lap with the conditions it was driven in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lap {
+    pub game: Game,
+    pub car: String,
+    pub track: String,
+    pub track_configuration: String,
+    /// Session type, e.g. `Practice` or `Race`.
+    pub session: String,
+    /// Number of laps completed before this one, `0` is the out lap.
+    pub lap: i32,
+    pub lap_time: Duration,
+    /// Sector times in order, the last one derived from the lap time.
+    pub sectors: Vec<Duration>,
+    /// `None` when the game does not report validity.
+    pub valid: Option<bool>,
+    pub tyre_compound: String,
+    pub rain_intensity: Option<String>,
+    /// Track temperature in °C at the end of the lap.
+    pub road_temp: f32,
+    /// Air temperature in °C at the end of the lap.
+    pub air_temp: f32,
+    /// Litres used during the lap.
+    pub fuel_used: f32,
+    /// The lap's frames as a compressed recording, times measured from the
+    /// start of the lap. Empty for laps read back by a query, see
+    /// [`LapStore::telemetry`](crate::store::LapStore::telemetry).
+    pub telemetry: Vec<u8>,
+}
+
+/// Cuts a stream of frames into [`Lap`]s.
+///
+/// A lap ends when `completed_laps` goes up. Laps whose start was not seen,
+/// like the one in progress when recording started, are skipped since their
+/// telemetry would be incomplete. A new session, as told by
+/// [`SessionTracker`], discards the lap in progress and starts the next one.
+///
+/// ```no_run
+/// use race_sdk::acc::AssettoCorsaCompetizione;
+/// use race_sdk::store::{LapStore, LapTracker};
+///
+/// let acc = AssettoCorsaCompetizione::new();
+/// let mut store = LapStore::open("laps.db").unwrap();
+/// let mut tracker = LapTracker::<AssettoCorsaCompetizione>::new();
+/// loop {
+///     if let Some(lap) = tracker.record(&acc).unwrap() {
+///         println!("lap {} in {:?}", lap.lap, lap.lap_time);
+///         store.insert(&lap).unwrap();
+///     }
+///     std::thread::sleep(std::time::Duration::from_millis(2));
+/// }
+/// ```
+pub struct LapTracker<S: TelemetrySource> {
+    static_info: S::StaticInfo,
+    physics: Option<S::Physics>,
+    graphics: Option<S::Graphics>,
+    current: Option<LapState<S>>,
+    sessions: SessionTracker,
+    last_physics: Option<i32>,
+    last_graphics: Option<i32>,
+    epoch: Instant,
+}
+
+struct LapState<S: TelemetrySource> {
+    lap: i32,
+    start: Duration,
+    fuel: Option<f32>,
+    sector: Option<i32>,
+    sectors: Vec<Duration>,
+    valid: Option<bool>,
+    recorder: Recorder<S, Vec<u8>>,
+}
+
+impl<S: TelemetrySource> Default for LapTracker<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: TelemetrySource> LapTracker<S> {
+    pub fn new() -> Self {
+        Self {
+            static_info: S::StaticInfo::default(),
+            physics: None,
+            graphics: None,
+            current: None,
+            sessions: SessionTracker::new(),
+            last_physics: None,
+            last_graphics: None,
+            epoch: Instant::now(),
+        }
+    }
+
+    /// Feeds the next frame, returns the lap it completed if any.
+    pub fn push_frame(&mut self, time: Duration, frame: &Frame<S>) -> Result<Option<Lap>> {
+        match frame {
+            Frame::Physics(physics) => {
+                if let Some(current) = &mut self.current {
+                    current.fuel.get_or_insert_with(|| float(physics, "fuel"));
+                    current.recorder.write_physics(time.saturating_sub(current.start), physics)?;
+                }
+                self.physics = Some(physics.clone());
+                Ok(None)
+            }
+            Frame::Graphics(graphics) => self.push_graphics(time, graphics),
+            Frame::StaticInfo(static_info) => {
+                if let Some(current) = &mut self.current {
+                    current.recorder.write_static_info(time.saturating_sub(current.start), static_info)?;
+                }
+                self.static_info = static_info.clone();
+                Ok(None)
+            }
+        }
+    }
+
+    /// Reads all pages from a live `source`, returns the lap completed by
+    /// the new frames if any. Times are measured from the creation of the
+    /// tracker.
+    pub fn record(&mut self, source: &S) -> Result<Option<Lap>> {
+        let time = self.epoch.elapsed();
+        if let Some(static_info) = source.get_static_info()
+            && static_info.to_bytes() != self.static_info.to_bytes()
+        {
+            self.push_frame(time, &Frame::StaticInfo(static_info))?;
+        }
+        let mut lap = None;
+        if let Some(graphics) = source.get_graphics()
+            && self.last_graphics != Some(graphics.packet_id())
+        {
+            self.last_graphics = Some(graphics.packet_id());
+            lap = self.push_frame(time, &Frame::Graphics(graphics))?;
+        }
+        if let Some(physics) = source.get_physics()
+            && self.last_physics != Some(physics.packet_id())
+        {
+            self.last_physics = Some(physics.packet_id());
+            self.push_frame(time, &Frame::Physics(physics))?;
+        }
+        Ok(lap)
+    }
+
+    fn push_graphics(&mut self, time: Duration, graphics: &S::Graphics) -> Result<Option<Lap>> {
+        let completed = match graphics.get("completed_laps") {
+            Some(Value::Int(completed)) => completed,
+            _ => return Ok(None),
+        };
+
+        let session = self.sessions.session();
+        self.sessions
+            .update_words((int(graphics, "session"), int(graphics, "session_index")), Some(completed));
+
+        let mut lap = None;
+        match &self.current {
+            // The lap in progress ends with its session, unfinished.
+            _ if self.sessions.session() != session => self.current = Some(self.start(time, completed)?),
+            Some(current) if completed == current.lap + 1 => {
+                let current = self.current.take().expect("checked above");
+                lap = Some(self.finish(time, graphics, current)?);
+                self.current = Some(self.start(time, completed)?);
+            }
+            Some(current) if completed == current.lap => {}
+            // A jump, the next lap starts here.
+            Some(_) => self.current = Some(self.start(time, completed)?),
+            None => {
+                if let Some(previous) = self.graphics.as_ref().and_then(|previous| int(previous, "completed_laps"))
+                    && previous != completed
+                {
+                    self.current = Some(self.start(time, completed)?);
+                }
+            }
+        }
+
+        if let Some(current) = &mut self.current {
+            if let Some(sector) = int(graphics, "current_sector_index") {
+                if let Some(previous) = current.sector
+                    && sector == previous + 1
+                    && let Some(millis) = int(graphics, "last_sector_time").filter(|millis| *millis > 0)
+                {
+                    current.sectors.push(Duration::from_millis(millis as u64));
+                }
+                current.sector = Some(sector);
+            }
+            if let Some(valid) = int(graphics, "is_valid_lap") {
+                current.valid = Some(current.valid.unwrap_or(true) && valid != 0);
+            }
+            current.recorder.write_graphics(time.saturating_sub(current.start), graphics)?;
+        }
+        self.graphics = Some(graphics.clone());
+        Ok(lap)
+    }
+
+    fn start(&self, time: Duration, lap: i32) -> Result<LapState<S>> {
+        let mut recorder = Recorder::new(Vec::new(), &self.static_info)?.with_compression(DEFAULT_BLOCK_FRAMES);
+        if let Some(physics) = &self.physics {
+            recorder.write_physics(Duration::ZERO, physics)?;
+        }
+        Ok(LapState {
+            lap,
+            start: time,
+            fuel: self.physics.as_ref().map(|physics| float(physics, "fuel")),
+            sector: None,
+            sectors: Vec::new(),
+            valid: None,
+            recorder,
+        })
+    }
+
+    /// Completes `current` with the first graphics frame of the next lap.
+    fn finish(&self, time: Duration, graphics: &S::Graphics, current: LapState<S>) -> Result<Lap> {
+        let lap_time = match int(graphics, "i_last_time") {
+            Some(millis) if millis > 0 => Duration::from_millis(millis as u64),
+            _ => time.saturating_sub(current.start),
+        };
+        let mut sectors = current.sectors;
+        let driven: Duration = sectors.iter().sum();
+        if driven < lap_time {
+            sectors.push(lap_time - driven);
+        }
+        let previous = self.graphics.as_ref().unwrap_or(graphics);
+        let physics = self.physics.clone().unwrap_or_default();
+        Ok(Lap {
+            game: S::GAME,
+            car: text(&self.static_info, "car_model"),
+            track: text(&self.static_info, "track"),
+            track_configuration: text(&self.static_info, "track_configuration"),
+            session: previous.get("session").map(|session| session.to_string()).unwrap_or_default(),
+            lap: current.lap,
+            lap_time,
+            sectors,
+            valid: current.valid,
+            tyre_compound: text(previous, "tyre_compound"),
+            rain_intensity: previous.get("rain_intensity").map(|rain| rain.to_string()),
+            road_temp: float(&physics, "road_temp"),
+            air_temp: float(&physics, "air_temp"),
+            fuel_used: current.fuel.map_or(0.0, |fuel| fuel - float(&physics, "fuel")),
+            telemetry: current.recorder.finish()?,
+        })
+    }
+}
+
+fn int<P: Page>(page: &P, path: &str) -> Option<i32> {
+    match page.get(path) {
+        Some(Value::Int(value) | Value::Enum(value, _)) => Some(value),
+        _ => None,
+    }
+}
+
+fn float<P: Page>(page: &P, path: &str) -> f32 {
+    match page.get(path) {
+        Some(Value::Float(value)) => value,
+        _ => 0.0,
+    }
+}
+
+fn text<P: Page>(page: &P, path: &str) -> String {
+    match page.get(path) {
+        Some(Value::Text(text)) => text,
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ac::{AcSessionType, AssettoCorsa, Graphics};
+
+    #[test]
+    fn session_change_discards_open_lap() {
+        let mut tracker = LapTracker::<AssettoCorsa>::new();
+        let mut laps = Vec::new();
+        // Lap 1 of the practice starts at 10 s and is still open when the
+        // qualifying starts at 15 s with the same lap count.
+        for i in 0..300 {
+            let (session, completed_laps) = match i {
+                0..100 => (AcSessionType::Practice, 0),
+                100..150 => (AcSessionType::Practice, 1),
+                150..250 => (AcSessionType::Qualify, 1),
+                _ => (AcSessionType::Qualify, 2),
+            };
+            let graphics = Graphics {
+                packet_id: i,
+                session: session.into(),
+                completed_laps,
+                ..Graphics::default()
+            };
+            let time = Duration::from_millis(i as u64 * 100);
+            laps.extend(tracker.push_frame(time, &Frame::Graphics(graphics)).unwrap());
+        }
+
+        let laps: Vec<_> = laps.iter().map(|lap| (lap.session.as_str(), lap.lap, lap.lap_time)).collect();
+        assert_eq!(laps, [("Qualify", 1, Duration::from_secs(10))]);
+    }
+}
@@ -0,0 +1,133 @@
+use crate::telemetry::{LayoutError, Page, Value};
+use std::collections::HashMap;
+use std::fmt;
+
+/// One element that differs between two frames.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    /// Name of the page field, e.g. `tyre_temp_i`.
+    pub field: &'static str,
+    /// Path of the element within the field, e.g. `tyre_temp_i[0]`.
+    pub path: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+impl fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} -> {}", self.path, self.old, self.new)
+    }
+}
+
+/// Compares two frames of the same page, element by element.
+///
+/// Floats are compared exactly unless a tolerance is configured, either for
+/// all floats or per field. Tolerances are keyed by field name and apply to
+/// every element of an array field.
+///
+/// ```no_run
+/// use race_sdk::telemetry::{Differ, apply};
+///
+/// let mut acc = race_sdk::acc::AssettoCorsaCompetizione::new();
+/// acc.connect().unwrap();
+///
+/// let differ = Differ::new().ignore("packet_id").tolerance("session_time_left", 1.0);
+/// let mut reported = acc.get_graphics().unwrap();
+/// // The sampler waits for the game to write a new frame.
+/// for sample in acc.graphics_sampler() {
+///     let changes = differ.diff(&reported, &sample.frame);
+///     for change in &changes {
+///         println!("{}", change);
+///     }
+///     // Compare against the last reported values, so that a slow drift
+///     // still shows once it exceeds the tolerance.
+///     apply(&mut reported, &changes).unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Differ {
+    default_tolerance: f32,
+    tolerances: HashMap<String, f32>,
+    ignored: Vec<String>,
+}
+
+impl Differ {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tolerance for every float field without its own tolerance.
+    pub fn default_tolerance(mut self, tolerance: f32) -> Self {
+        self.default_tolerance = tolerance;
+        self
+    }
+
+    /// Changes of at most `tolerance` in this field are not reported.
+    pub fn tolerance(mut self, field: &str, tolerance: f32) -> Self {
+        self.tolerances.insert(field.to_string(), tolerance);
+        self
+    }
+
+    /// Never report changes of this field.
+    pub fn ignore(mut self, field: &str) -> Self {
+        self.ignored.push(field.to_string());
+        self
+    }
+
+    pub fn diff<T: Page>(&self, prev: &T, next: &T) -> Vec<FieldChange> {
+        let prev_bytes = prev.to_bytes();
+        let next_bytes = next.to_bytes();
+        let mut changes = Vec::new();
+
+        for field in T::fields() {
+            let range = field.offset..field.offset + field.size();
+            if prev_bytes[range.clone()] == next_bytes[range] || self.ignored.iter().any(|name| name == field.name) {
+                continue;
+            }
+
+            let tolerance = self.tolerances.get(field.name).copied().unwrap_or(self.default_tolerance);
+            for i in 0..field.count() {
+                let old = field.read(&prev_bytes, i);
+                let new = field.read(&next_bytes, i);
+                let changed = match (&old, &new) {
+                    (Value::Float(a), Value::Float(b)) => float_changed(*a, *b, tolerance),
+                    _ => old != new,
+                };
+                if changed {
+                    changes.push(FieldChange {
+                        field: field.name,
+                        path: field.element_path(i),
+                        old,
+                        new,
+                    });
+                }
+            }
+        }
+
+        changes
+    }
+}
+
+/// Returns every element that differs between two frames, floats compared exactly.
+pub fn diff<T: Page>(prev: &T, next: &T) -> Vec<FieldChange> {
+    Differ::new().diff(prev, next)
+}
+
+/// Replays a list of changes onto a frame, the inverse of [`diff`].
+///
+/// Together with [`diff`] this allows logging a full frame once and only the
+/// changes afterwards.
+pub fn apply<T: Page>(frame: &mut T, changes: &[FieldChange]) -> Result<(), LayoutError> {
+    for change in changes {
+        frame.set(&change.path, &change.new)?;
+    }
+    Ok(())
+}
+
+fn float_changed(a: f32, b: f32, tolerance: f32) -> bool {
+    if a == b || (a.is_nan() && b.is_nan()) {
+        return false;
+    }
+    let delta = (a - b).abs();
+    delta.is_nan() || delta > tolerance
+}
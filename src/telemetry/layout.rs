@@ -0,0 +1,648 @@
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr;
+
+/// The storage type of a single page field.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldType {
+    I32,
+    F32,
+    /// Null-terminated UTF-16 string, the argument is its length in code units.
+    Text(usize),
+    /// A 32-bit enum together with its known `(value, name)` variants.
+    Enum(&'static [(i32, &'static str)]),
+}
+
+impl FieldType {
+    /// Size of one element in bytes.
+    pub const fn size(&self) -> usize {
+        match self {
+            FieldType::Text(len) => *len * 2,
+            _ => 4,
+        }
+    }
+}
+
+/// One dimension of an array field, used to name the flattened elements.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Dim {
+    /// Plain index, elements are named `0`, `1`, ...
+    Index(usize),
+    /// `x`, `y`, `z`
+    Axis,
+    /// `fl`, `fr`, `rl`, `rr`
+    Wheel,
+    /// `front`, `rear`
+    Axle,
+}
+
+impl Dim {
+    pub const fn count(&self) -> usize {
+        match self {
+            Dim::Index(len) => *len,
+            Dim::Axis => 3,
+            Dim::Wheel => 4,
+            Dim::Axle => 2,
+        }
+    }
+
+    pub fn label(&self, index: usize) -> String {
+        match self {
+            Dim::Index(_) => index.to_string(),
+            Dim::Axis => ["x", "y", "z"][index].to_string(),
+            Dim::Wheel => ["fl", "fr", "rl", "rr"][index].to_string(),
+            Dim::Axle => ["front", "rear"][index].to_string(),
+        }
+    }
+}
+
+/// Describes where a field lives inside a `#[repr(C)]` page and how to read it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FieldDescriptor {
+    pub name: &'static str,
+    pub ty: FieldType,
+    pub offset: usize,
+    pub dims: &'static [Dim],
+    /// Physical unit of the values, e.g. `km/h`, `None` for counters, flags
+    /// and normalized values.
+    pub unit: Option<&'static str>,
+    /// Held instead of interpolated by [`interpolate`], for `I32` fields
+    /// such as gears, counters, flags and settings.
+    pub discrete: bool,
+}
+
+impl FieldDescriptor {
+    /// Number of elements, a text field counts as a single element.
+    pub const fn count(&self) -> usize {
+        let mut len = 1;
+        let mut i = 0;
+        while i < self.dims.len() {
+            len *= self.dims[i].count();
+            i += 1;
+        }
+        len
+    }
+
+    /// Size of the whole field in bytes.
+    pub const fn size(&self) -> usize {
+        self.count() * self.ty.size()
+    }
+
+    pub fn is_array(&self) -> bool {
+        !self.dims.is_empty()
+    }
+
+    /// Flattened element name, e.g. `tyre_temp_i_fl` or `car_coordinates_3_x`.
+    pub fn element_name(&self, index: usize) -> String {
+        let mut name = self.name.to_string();
+        for (dim, i) in self.dims.iter().zip(self.indices(index)) {
+            name.push('_');
+            name.push_str(&dim.label(i));
+        }
+        name
+    }
+
+    /// Indexed element path, e.g. `tyre_temp_i[0]` or `car_coordinates[3][0]`.
+    pub fn element_path(&self, index: usize) -> String {
+        let mut path = self.name.to_string();
+        for i in self.indices(index) {
+            path.push_str(&format!("[{}]", i));
+        }
+        path
+    }
+
+    fn indices(&self, mut index: usize) -> Vec<usize> {
+        let mut indices = vec![0; self.dims.len()];
+        for (slot, dim) in indices.iter_mut().zip(self.dims).rev() {
+            *slot = index % dim.count();
+            index /= dim.count();
+        }
+        indices
+    }
+
+    /// Name of an enum value, `None` for unknown values and non-enum fields.
+    pub fn variant_name(&self, value: i32) -> Option<&'static str> {
+        match self.ty {
+            FieldType::Enum(variants) => variants.iter().find(|(v, _)| *v == value).map(|(_, name)| *name),
+            _ => None,
+        }
+    }
+
+    /// Reads one element from the raw bytes of a page.
+    pub fn read(&self, bytes: &[u8], index: usize) -> Value {
+        let offset = self.offset + index * self.ty.size();
+        match self.ty {
+            FieldType::I32 => Value::Int(read_i32(bytes, offset)),
+            FieldType::F32 => Value::Float(f32::from_bits(read_i32(bytes, offset) as u32)),
+            FieldType::Text(len) => {
+                let utf16: Vec<u16> = (0..len)
+                    .map(|i| u16::from_le_bytes([bytes[offset + i * 2], bytes[offset + i * 2 + 1]]))
+                    .collect();
+                Value::Text(utf16_to_string(&utf16))
+            }
+            FieldType::Enum(_) => {
+                let value = read_i32(bytes, offset);
+                Value::Enum(value, self.variant_name(value))
+            }
+        }
+    }
+
+    /// Writes one element into the raw bytes of a page.
+    ///
+    /// Numbers are converted between `Int` and `Float` and enums accept both
+    /// the name of a known variant and any numeric value. Text longer than
+    /// the field is cut.
+    pub fn write(&self, bytes: &mut [u8], index: usize, value: &Value) -> Result<(), LayoutError> {
+        let offset = self.offset + index * self.ty.size();
+        let mismatch = || LayoutError::TypeMismatch(self.name.to_string());
+        match (self.ty, value) {
+            (FieldType::I32, Value::Int(v)) => write_i32(bytes, offset, *v),
+            (FieldType::I32, Value::Float(v)) => write_i32(bytes, offset, *v as i32),
+            (FieldType::F32, Value::Float(v)) => write_i32(bytes, offset, v.to_bits() as i32),
+            (FieldType::F32, Value::Int(v)) => write_i32(bytes, offset, (*v as f32).to_bits() as i32),
+            (FieldType::Text(len), Value::Text(text)) => {
+                let mut utf16: Vec<u16> = text.encode_utf16().take(len - 1).collect();
+                utf16.resize(len, 0);
+                for (i, c) in utf16.iter().enumerate() {
+                    bytes[offset + i * 2..offset + i * 2 + 2].copy_from_slice(&c.to_le_bytes());
+                }
+            }
+            (FieldType::Enum(_), Value::Enum(v, _) | Value::Int(v)) => write_i32(bytes, offset, *v),
+            (FieldType::Enum(variants), Value::Text(name)) => match variants.iter().find(|(_, known)| known == name) {
+                Some((v, _)) => write_i32(bytes, offset, *v),
+                None => return Err(LayoutError::UnknownVariant(self.name.to_string(), name.clone())),
+            },
+            _ => return Err(mismatch()),
+        }
+        Ok(())
+    }
+}
+
+/// A single element read from a page.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(i32),
+    Float(f32),
+    Text(String),
+    /// The raw value and, when known, the variant name.
+    Enum(i32, Option<&'static str>),
+}
+
+impl Value {
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Int(v) | Value::Enum(v, _) => Some(*v as f64),
+            Value::Float(v) => Some(*v as f64),
+            Value::Text(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Int(v) => write!(f, "{}", v),
+            Value::Float(v) => write!(f, "{}", v),
+            Value::Text(v) => write!(f, "{}", v),
+            Value::Enum(_, Some(name)) => write!(f, "{}", name),
+            Value::Enum(v, None) => write!(f, "{}", v),
+        }
+    }
+}
+
+/// A 32-bit enum as stored in a page.
+///
+/// Keeps the raw value, so that a page still decodes when the game writes a
+/// value `T` does not know, e.g. one added by a newer game version.
+#[repr(transparent)]
+pub struct RawEnum<T> {
+    raw: i32,
+    marker: PhantomData<T>,
+}
+
+impl<T> RawEnum<T> {
+    pub const fn from_raw(raw: i32) -> Self {
+        Self { raw, marker: PhantomData }
+    }
+
+    pub const fn raw(&self) -> i32 {
+        self.raw
+    }
+}
+
+impl<T: TryFrom<i32>> RawEnum<T> {
+    /// The variant, `None` for a value `T` does not know.
+    pub fn get(&self) -> Option<T> {
+        T::try_from(self.raw).ok()
+    }
+}
+
+impl<T: Into<i32>> From<T> for RawEnum<T> {
+    fn from(value: T) -> Self {
+        Self::from_raw(value.into())
+    }
+}
+
+impl<T> Clone for RawEnum<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for RawEnum<T> {}
+
+impl<T> PartialEq for RawEnum<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.raw == other.raw
+    }
+}
+
+impl<T> Eq for RawEnum<T> {}
+
+impl<T: Copy + Into<i32>> PartialEq<T> for RawEnum<T> {
+    fn eq(&self, other: &T) -> bool {
+        self.raw == (*other).into()
+    }
+}
+
+impl<T: TryFrom<i32> + fmt::Debug> fmt::Debug for RawEnum<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.get() {
+            Some(value) => value.fmt(f),
+            None => write!(f, "Unknown({})", self.raw),
+        }
+    }
+}
+
+impl<T: TryFrom<i32> + fmt::Display> fmt::Display for RawEnum<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.get() {
+            Some(value) => value.fmt(f),
+            None => write!(f, "{}", self.raw),
+        }
+    }
+}
+
+/// Implements the conversions [`RawEnum`] needs for a fieldless enum.
+macro_rules! raw_enum {
+    ($ty:ident { $($variant:ident),* $(,)? }) => {
+        impl TryFrom<i32> for $ty {
+            type Error = i32;
+
+            fn try_from(raw: i32) -> Result<Self, i32> {
+                $(if raw == $ty::$variant as i32 {
+                    return Ok($ty::$variant);
+                })*
+                Err(raw)
+            }
+        }
+
+        impl From<$ty> for i32 {
+            fn from(value: $ty) -> i32 {
+                value as i32
+            }
+        }
+    };
+}
+
+pub(crate) use raw_enum;
+
+#[derive(Debug)]
+pub enum LayoutError {
+    UnknownField(String),
+    TypeMismatch(String),
+    UnknownVariant(String, String),
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayoutError::UnknownField(name) => write!(f, "Unknown field: {}", name),
+            LayoutError::TypeMismatch(name) => write!(f, "Type mismatch for field: {}", name),
+            LayoutError::UnknownVariant(name, value) => write!(f, "Unknown variant {} for field: {}", value, name),
+        }
+    }
+}
+
+impl std::error::Error for LayoutError {}
+
+/// A `#[repr(C)]` shared-memory page with a field-level description.
+///
+/// The descriptors let generic code (resampling, diffing, exporters) walk any
+/// page without knowing its concrete type.
+///
+/// # Safety
+///
+/// `fields()` must list every field of the type in declaration order with the
+/// correct offset and storage type. [`page_layout!`] checks offsets and sizes
+/// at compile time.
+pub unsafe trait Page: Clone + Default {
+    fn fields() -> &'static [FieldDescriptor];
+
+    fn field(name: &str) -> Option<&'static FieldDescriptor> {
+        Self::fields().iter().find(|field| field.name == name)
+    }
+
+    /// Serializes the page, padding bytes are written as zeroes.
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![0; mem::size_of::<Self>()];
+        let base = self as *const Self as *const u8;
+        for field in Self::fields() {
+            let source = unsafe { std::slice::from_raw_parts(base.add(field.offset), field.size()) };
+            bytes[field.offset..field.offset + field.size()].copy_from_slice(source);
+        }
+        bytes
+    }
+
+    /// Deserializes a page, returns `None` if the buffer is too short.
+    ///
+    /// Enum fields keep values their type does not know, [`Page::get`] reads
+    /// them as `Value::Enum(raw, None)`.
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < mem::size_of::<Self>() {
+            return None;
+        }
+        Some(unsafe { ptr::read_unaligned(bytes.as_ptr() as *const Self) })
+    }
+
+    /// Reads an element by path, e.g. `speed_kmh`, `tyre_temp_i[0]` or `tyre_temp_i_fl`.
+    fn get(&self, path: &str) -> Option<Value> {
+        let (field, index) = resolve(Self::fields(), path)?;
+        let offset = field.offset + index * field.ty.size();
+        // The element's own bytes, which unlike padding are always initialized.
+        let bytes = unsafe { std::slice::from_raw_parts((self as *const Self as *const u8).add(offset), field.ty.size()) };
+        Some(FieldDescriptor { offset: 0, ..*field }.read(bytes, 0))
+    }
+
+    /// Writes an element by path, see [`Page::get`] for the accepted forms.
+    fn set(&mut self, path: &str, value: &Value) -> Result<(), LayoutError> {
+        let (field, index) = resolve(Self::fields(), path).ok_or_else(|| LayoutError::UnknownField(path.to_string()))?;
+        let offset = field.offset + index * field.ty.size();
+        // `write` only stores values valid for the field's storage type, any
+        // 32-bit value for enums, and leaves the bytes untouched when it fails.
+        let bytes = unsafe { std::slice::from_raw_parts_mut((self as *mut Self as *mut u8).add(offset), field.ty.size()) };
+        FieldDescriptor { offset: 0, ..*field }.write(bytes, 0, value)
+    }
+}
+
+/// Runtime description of a page that has no Rust type, e.g. a mod's mapping
+/// known only from its documentation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageLayout {
+    pub size: usize,
+    pub fields: Vec<FieldDescriptor>,
+}
+
+impl PageLayout {
+    /// Panics if a field does not fit into `size` bytes.
+    pub fn new(size: usize, fields: &[FieldDescriptor]) -> Self {
+        for field in fields {
+            assert!(field.offset + field.size() <= size, "field {} does not fit into {} bytes", field.name, size);
+        }
+        Self { size, fields: fields.to_vec() }
+    }
+
+    /// The layout of a typed page.
+    pub fn of<T: Page>() -> Self {
+        Self::new(mem::size_of::<T>(), T::fields())
+    }
+
+    pub fn field(&self, name: &str) -> Option<&FieldDescriptor> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+
+    /// Reads an element by path from raw page bytes, see [`Page::get`].
+    pub fn get(&self, bytes: &[u8], path: &str) -> Option<Value> {
+        if bytes.len() < self.size {
+            return None;
+        }
+        let (field, index) = resolve(&self.fields, path)?;
+        Some(field.read(bytes, index))
+    }
+}
+
+fn resolve<'a>(fields: &'a [FieldDescriptor], path: &str) -> Option<(&'a FieldDescriptor, usize)> {
+    let name = path.split('[').next().unwrap_or(path);
+    if let Some(field) = fields.iter().find(|field| field.name == name) {
+        if name.len() == path.len() {
+            return (!field.is_array()).then_some((field, 0));
+        }
+        return (0..field.count()).find(|i| field.element_path(*i) == path).map(|i| (field, i));
+    }
+    fields
+        .iter()
+        .filter(|field| field.is_array() && path.starts_with(field.name))
+        .find_map(|field| (0..field.count()).find(|i| field.element_name(*i) == path).map(|i| (field, i)))
+}
+
+/// Linearly interpolates every `F32` and `I32` element between two frames,
+/// integers rounded to the nearest value.
+///
+/// Angles, fields in `rad`, take the shorter way around the circle so that a
+/// heading crossing ±π does not sweep through 0. Discrete fields, enums and
+/// strings are held from `a`.
+pub fn interpolate<T: Page>(a: &T, b: &T, t: f32) -> T {
+    let mut out = a.clone();
+    let a_base = a as *const T as *const u8;
+    let b_base = b as *const T as *const u8;
+    let out_base = &mut out as *mut T as *mut u8;
+    for field in T::fields()
+        .iter()
+        .filter(|field| !field.discrete && matches!(field.ty, FieldType::F32 | FieldType::I32))
+    {
+        for i in 0..field.count() {
+            let offset = field.offset + i * 4;
+            // `page_layout!` checks that `F32` and `I32` elements are `f32` and `i32`.
+            unsafe {
+                match field.ty {
+                    FieldType::F32 => {
+                        let x = ptr::read_unaligned(a_base.add(offset) as *const f32);
+                        let y = ptr::read_unaligned(b_base.add(offset) as *const f32);
+                        let value = if field.unit == Some("rad") { lerp_angle(x, y, t) } else { x + (y - x) * t };
+                        ptr::write_unaligned(out_base.add(offset) as *mut f32, value);
+                    }
+                    FieldType::I32 => {
+                        let x = ptr::read_unaligned(a_base.add(offset) as *const i32) as f64;
+                        let y = ptr::read_unaligned(b_base.add(offset) as *const i32) as f64;
+                        ptr::write_unaligned(out_base.add(offset) as *mut i32, (x + (y - x) * t as f64).round() as i32);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Interpolates between two angles in radians along the shorter arc, the
+/// result wrapped into `[-π, π)`.
+fn lerp_angle(x: f32, y: f32, t: f32) -> f32 {
+    use std::f32::consts::{PI, TAU};
+    let delta = (y - x + PI).rem_euclid(TAU) - PI;
+    (x + delta * t + PI).rem_euclid(TAU) - PI
+}
+
+/// Checks that the descriptors cover a type of `size` bytes with nothing but
+/// alignment padding in between.
+pub const fn check_layout(fields: &[FieldDescriptor], size: usize) -> bool {
+    let mut end = 0;
+    let mut i = 0;
+    while i < fields.len() {
+        let field = &fields[i];
+        if field.offset < end || field.offset - end >= 4 {
+            return false;
+        }
+        end = field.offset + field.size();
+        i += 1;
+    }
+    end <= size && size - end < 4
+}
+
+fn read_i32(bytes: &[u8], offset: usize) -> i32 {
+    i32::from_le_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]])
+}
+
+fn write_i32(bytes: &mut [u8], offset: usize, value: i32) {
+    bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+}
+
+fn utf16_to_string(utf16: &[u16]) -> String {
+    let end = utf16.iter().position(|&c| c == 0).unwrap_or(utf16.len());
+    String::from_utf16_lossy(&utf16[..end])
+}
+
+/// Compile-time checks of the Rust type of a field against its declared
+/// storage type, used by [`page_layout!`].
+#[doc(hidden)]
+pub mod storage {
+    pub struct I32;
+    pub struct F32;
+
+    /// Implemented by the types made only of elements of storage type `S`,
+    /// laid out one after another.
+    ///
+    /// # Safety
+    ///
+    /// The type must be `#[repr(C)]` (or transparent) with nothing but
+    /// fields of storage type `S` and no padding.
+    pub unsafe trait Elements<S> {}
+
+    unsafe impl Elements<I32> for i32 {}
+    unsafe impl Elements<F32> for f32 {}
+    unsafe impl<T> Elements<I32> for super::RawEnum<T> {}
+    unsafe impl<S, T: Elements<S>, const N: usize> Elements<S> for [T; N] {}
+
+    pub fn elements<S, T: Elements<S>>(_: T) {}
+}
+
+/// Implements [`Page`] for a `#[repr(C)]` struct from a list of its fields.
+///
+/// Fields must be listed in declaration order, a mismatch with the actual
+/// layout of the struct fails to compile, as does a field whose Rust type
+/// does not match its storage type: `I32` fields must be `i32` or arrays of
+/// it, `Enum` fields [`RawEnum`], `i32` or arrays of them, `F32` fields
+/// `f32` or arrays of it and `Text(n)` fields `[u16; n]`. The struct must
+/// implement `Clone` and `Default`.
+///
+/// # Safety
+///
+/// The invocation starts with `unsafe`, as it implements the unsafe
+/// [`Page`] trait: [`Page::from_bytes`] relies on every bit pattern of the
+/// listed fields being a valid value.
+///
+/// ```
+/// #[derive(Clone, Default)]
+/// #[repr(C)]
+/// struct PluginData {
+///     version: i32,
+///     lap_delta: f32,
+///     tyre_core: [f32; 4],
+///     position: [[f32; 3]; 2],
+///     driver: [u16; 32],
+/// }
+///
+/// race_sdk::page_layout!(unsafe PluginData {
+///     #[discrete]
+///     version: I32,
+///     lap_delta: F32,
+///     tyre_core: F32[Wheel],
+///     position: F32[Index(2), Axis] in "m",
+///     driver: Text(32),
+/// });
+/// ```
+///
+/// A field whose type does not match is rejected:
+///
+/// ```compile_fail
+/// #[derive(Clone, Default)]
+/// #[repr(C)]
+/// struct PluginData {
+///     version: i32,
+///     flags: [u16; 2],
+/// }
+///
+/// race_sdk::page_layout!(unsafe PluginData {
+///     version: I32,
+///     flags: F32,
+/// });
+/// ```
+///
+/// Enum fields take a `&[(i32, &str)]` table of their variants: `status: Enum(STATUS)`.
+/// A trailing `in "unit"` sets the physical unit of a field, `#[discrete]`
+/// before a field keeps [`interpolate`] from blending it.
+#[macro_export]
+macro_rules! page_layout {
+    (@unit) => {
+        None
+    };
+    (@unit $unit:literal) => {
+        Some($unit)
+    };
+    (@check $value:expr, I32) => {
+        $crate::telemetry::storage::elements::<$crate::telemetry::storage::I32, _>($value)
+    };
+    (@check $value:expr, F32) => {
+        $crate::telemetry::storage::elements::<$crate::telemetry::storage::F32, _>($value)
+    };
+    (@check $value:expr, Text($len:expr)) => {{
+        let _: [u16; $len] = $value;
+    }};
+    (@check $value:expr, Enum($variants:expr)) => {
+        $crate::telemetry::storage::elements::<$crate::telemetry::storage::I32, _>($value)
+    };
+    (@discrete) => {
+        false
+    };
+    (@discrete discrete) => {
+        true
+    };
+    (unsafe $page:ty { $($(#[$marker:ident])* $field:ident : $ty:ident $(($arg:expr))? $([$($dim:ident $(($len:expr))?),*])? $(in $unit:literal)?),* $(,)? }) => {
+        const _: () = {
+            #[allow(dead_code)]
+            fn check_field_types(page: &$page) {
+                $($crate::page_layout!(@check page.$field, $ty $(($arg))?);)*
+            }
+        };
+
+        unsafe impl $crate::telemetry::Page for $page {
+            fn fields() -> &'static [$crate::telemetry::FieldDescriptor] {
+                const FIELDS: &[$crate::telemetry::FieldDescriptor] = &[$(
+                    $crate::telemetry::FieldDescriptor {
+                        name: stringify!($field),
+                        ty: $crate::telemetry::FieldType::$ty $(($arg))?,
+                        offset: ::std::mem::offset_of!($page, $field),
+                        dims: &[$($($crate::telemetry::Dim::$dim $(($len))?),*)?],
+                        unit: $crate::page_layout!(@unit $($unit)?),
+                        discrete: $crate::page_layout!(@discrete $($marker)*),
+                    }
+                ),*];
+                const _: () = assert!(
+                    $crate::telemetry::check_layout(FIELDS, ::std::mem::size_of::<$page>()),
+                    concat!("field list does not match the layout of ", stringify!($page))
+                );
+                FIELDS
+            }
+        }
+    };
+}
@@ -0,0 +1,69 @@
+pub mod diff;
+pub mod layout;
+pub mod resample;
+pub mod sampler;
+pub use diff::*;
+pub use layout::*;
+pub use resample::*;
+pub use sampler::*;
+
+use std::fmt;
+
+/// A shared-memory page that carries a monotonically increasing `packet_id`.
+///
+/// The game bumps the counter every time it writes the page, which makes it
+/// the only reliable way to tell a fresh frame from a stale one.
+pub trait Packet {
+    fn packet_id(&self) -> i32;
+}
+
+/// The Kunos games sharing the physics/graphics/static page model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Game {
+    AssettoCorsa,
+    AssettoCorsaCompetizione,
+    AssettoCorsaEvo,
+}
+
+impl Game {
+    /// Short identifier, also used in file formats.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Game::AssettoCorsa => "ac",
+            Game::AssettoCorsaCompetizione => "acc",
+            Game::AssettoCorsaEvo => "ace",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "ac" => Some(Game::AssettoCorsa),
+            "acc" => Some(Game::AssettoCorsaCompetizione),
+            "ace" => Some(Game::AssettoCorsaEvo),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Game::AssettoCorsa => write!(f, "Assetto Corsa"),
+            Game::AssettoCorsaCompetizione => write!(f, "Assetto Corsa Competizione"),
+            Game::AssettoCorsaEvo => write!(f, "Assetto Corsa EVO"),
+        }
+    }
+}
+
+/// Anything that provides the three pages of a Kunos game, implemented by
+/// the facades and the clients of `ac`, `acc` and `ace`.
+pub trait TelemetrySource {
+    const GAME: Game;
+    type Physics: Page + Packet;
+    type Graphics: Page + Packet;
+    type StaticInfo: Page;
+
+    fn get_physics(&self) -> Option<Self::Physics>;
+    fn get_graphics(&self) -> Option<Self::Graphics>;
+    fn get_static_info(&self) -> Option<Self::StaticInfo>;
+}
@@ -0,0 +1,232 @@
+use crate::telemetry::{Page, Sample, interpolate};
+use std::collections::VecDeque;
+use std::fmt;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResampleError {
+    /// The rate is not a positive number of frames per second.
+    InvalidRate(f64),
+    ZeroPeriod,
+}
+
+impl fmt::Display for ResampleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResampleError::InvalidRate(rate) => write!(f, "Invalid resampling rate: {}", rate),
+            ResampleError::ZeroPeriod => write!(f, "Resampling period must not be zero"),
+        }
+    }
+}
+
+impl std::error::Error for ResampleError {}
+
+/// A frame together with the time it belongs to.
+#[derive(Debug, Clone)]
+pub struct Timestamped<T> {
+    pub time: Duration,
+    pub frame: T,
+}
+
+impl<T> From<Sample<T>> for Timestamped<T> {
+    fn from(sample: Sample<T>) -> Self {
+        Self {
+            time: sample.time,
+            frame: sample.frame,
+        }
+    }
+}
+
+impl<T> From<(Duration, T)> for Timestamped<T> {
+    fn from((time, frame): (Duration, T)) -> Self {
+        Self { time, frame }
+    }
+}
+
+/// Turns an irregular stream of timestamped frames into a fixed-rate one.
+///
+/// Output frames sit on a grid of multiples of the period, measured from the
+/// same origin as the input times. Numeric elements are interpolated between
+/// the two surrounding input frames as [`interpolate`] does, discrete ones
+/// (gear, flags, enums, strings) are held from the earlier one. Because interpolation needs
+/// the frame after a grid point, output lags the input by one frame.
+pub struct Resampler<T: Page> {
+    period: Duration,
+    max_gap: Option<Duration>,
+    next_tick: u64,
+    previous: Option<Timestamped<T>>,
+}
+
+impl<T: Page> Resampler<T> {
+    /// Creates a resampler producing `rate` frames per second, which must
+    /// be finite and positive.
+    pub fn new(rate: f64) -> Result<Self, ResampleError> {
+        if !rate.is_finite() || rate <= 0.0 {
+            return Err(ResampleError::InvalidRate(rate));
+        }
+        Self::with_period(Duration::from_secs_f64(1.0 / rate))
+    }
+
+    pub fn with_period(period: Duration) -> Result<Self, ResampleError> {
+        if period.is_zero() {
+            return Err(ResampleError::ZeroPeriod);
+        }
+        Ok(Self {
+            period,
+            max_gap: Some(Duration::from_secs(1)),
+            next_tick: 0,
+            previous: None,
+        })
+    }
+
+    /// Input gaps longer than this (pauses, menus) produce no output instead
+    /// of being interpolated across. `None` interpolates across any gap.
+    pub fn with_max_gap(mut self, max_gap: Option<Duration>) -> Self {
+        self.max_gap = max_gap;
+        self
+    }
+
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// Feeds one input frame and returns the grid frames it completes.
+    ///
+    /// Frames that are not newer than the previous one are ignored.
+    pub fn push(&mut self, input: impl Into<Timestamped<T>>) -> Vec<Timestamped<T>> {
+        let current = input.into();
+        let mut output = Vec::new();
+
+        let Some(previous) = self.previous.take() else {
+            self.next_tick = self.first_tick_at(current.time);
+            self.previous = Some(current);
+            return output;
+        };
+
+        if current.time <= previous.time {
+            self.previous = Some(previous);
+            return output;
+        }
+
+        let span = current.time - previous.time;
+        if self.max_gap.is_some_and(|max_gap| span > max_gap) {
+            self.next_tick = self.first_tick_at(current.time);
+            self.previous = Some(current);
+            return output;
+        }
+
+        while self.tick_time(self.next_tick) < current.time {
+            let time = self.tick_time(self.next_tick);
+            let t = (time - previous.time).as_secs_f64() / span.as_secs_f64();
+            output.push(Timestamped {
+                time,
+                frame: interpolate(&previous.frame, &current.frame, t as f32),
+            });
+            self.next_tick += 1;
+        }
+
+        self.previous = Some(current);
+        output
+    }
+
+    /// Forgets the previous frame, the next input starts a new stream.
+    pub fn reset(&mut self) {
+        self.previous = None;
+    }
+
+    fn tick_time(&self, tick: u64) -> Duration {
+        Duration::from_nanos((self.period.as_nanos() * tick as u128) as u64)
+    }
+
+    fn first_tick_at(&self, time: Duration) -> u64 {
+        time.as_nanos().div_ceil(self.period.as_nanos()) as u64
+    }
+}
+
+/// A physics and a graphics frame for the same instant.
+#[derive(Debug, Clone)]
+pub struct CombinedFrame<P, G> {
+    pub time: Duration,
+    pub physics: P,
+    pub graphics: G,
+}
+
+/// Resamples the physics and graphics streams onto one grid and pairs them up.
+///
+/// ```no_run
+/// use race_sdk::telemetry::FrameAligner;
+///
+/// let mut acc = race_sdk::acc::AssettoCorsaCompetizione::new();
+/// acc.connect().unwrap();
+///
+/// let mut physics = acc.physics_sampler();
+/// let mut graphics = acc.graphics_sampler().with_epoch(physics.epoch());
+/// let mut aligner = FrameAligner::new(100.0).unwrap();
+///
+/// while let Some(sample) = physics.next_sample() {
+///     if let Some(sample) = graphics.poll() {
+///         aligner.push_graphics(sample);
+///     }
+///     for frame in aligner.push_physics(sample) {
+///         println!("{:?} {:.1} km/h lap {}", frame.time, frame.physics.speed_kmh, frame.graphics.completed_laps);
+///     }
+/// }
+/// ```
+///
+/// Both streams must share a time origin, hence the common sampler epoch.
+pub struct FrameAligner<P: Page, G: Page> {
+    physics: Resampler<P>,
+    graphics: Resampler<G>,
+    pending_physics: VecDeque<Timestamped<P>>,
+    pending_graphics: VecDeque<Timestamped<G>>,
+}
+
+impl<P: Page, G: Page> FrameAligner<P, G> {
+    /// Fails for the rates [`Resampler::new`] rejects.
+    pub fn new(rate: f64) -> Result<Self, ResampleError> {
+        Ok(Self {
+            physics: Resampler::new(rate)?,
+            graphics: Resampler::new(rate)?,
+            pending_physics: VecDeque::new(),
+            pending_graphics: VecDeque::new(),
+        })
+    }
+
+    pub fn with_max_gap(mut self, max_gap: Option<Duration>) -> Self {
+        self.physics = self.physics.with_max_gap(max_gap);
+        self.graphics = self.graphics.with_max_gap(max_gap);
+        self
+    }
+
+    pub fn push_physics(&mut self, input: impl Into<Timestamped<P>>) -> Vec<CombinedFrame<P, G>> {
+        let frames = self.physics.push(input);
+        self.pending_physics.extend(frames);
+        self.drain()
+    }
+
+    pub fn push_graphics(&mut self, input: impl Into<Timestamped<G>>) -> Vec<CombinedFrame<P, G>> {
+        let frames = self.graphics.push(input);
+        self.pending_graphics.extend(frames);
+        self.drain()
+    }
+
+    fn drain(&mut self) -> Vec<CombinedFrame<P, G>> {
+        let mut output = Vec::new();
+        while let (Some(physics), Some(graphics)) = (self.pending_physics.front(), self.pending_graphics.front()) {
+            // Both streams emit increasing grid times, so the earlier front
+            // will never get a partner once the other stream has moved past it.
+            if physics.time < graphics.time {
+                self.pending_physics.pop_front();
+            } else if graphics.time < physics.time {
+                self.pending_graphics.pop_front();
+            } else if let (Some(physics), Some(graphics)) = (self.pending_physics.pop_front(), self.pending_graphics.pop_front()) {
+                output.push(CombinedFrame {
+                    time: physics.time,
+                    physics: physics.frame,
+                    graphics: graphics.frame,
+                });
+            }
+        }
+        output
+    }
+}
@@ -0,0 +1,231 @@
+use crate::telemetry::Packet;
+use std::hint;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How the sampler waits between two reads of the shared memory.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WaitStrategy {
+    /// Busy-wait between reads. Lowest latency, burns a full core.
+    Spin,
+    /// Sleep for a fixed interval between reads.
+    Sleep(Duration),
+    /// Sleep until shortly before the next frame is expected, then spin.
+    ///
+    /// The expected frame interval is learned from the observed update rate,
+    /// `spin_window` is how early the sampler wakes up to start spinning.
+    Adaptive { spin_window: Duration },
+}
+
+impl Default for WaitStrategy {
+    fn default() -> Self {
+        WaitStrategy::Adaptive {
+            spin_window: Duration::from_micros(500),
+        }
+    }
+}
+
+/// A frame captured by a [`Sampler`].
+#[derive(Debug, Clone)]
+pub struct Sample<T> {
+    /// Monotonic capture time, relative to the sampler epoch.
+    pub time: Duration,
+    pub packet_id: i32,
+    /// Number of `packet_id`s skipped since the previous sample.
+    pub dropped: u32,
+    pub frame: T,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SamplerStats {
+    /// Frames emitted so far.
+    pub frames: u64,
+    /// Total `packet_id`s skipped between emitted frames.
+    pub dropped: u64,
+    /// Times the `packet_id` went backwards (session restart, replay seek).
+    pub resets: u64,
+    /// Shared-memory reads performed, including the ones that saw no change.
+    pub reads: u64,
+}
+
+/// Polls a page and emits a [`Sample`] only when its `packet_id` changes.
+///
+/// The sampler takes any closure returning the current page, so it works with
+/// both the facades and the low-level clients:
+///
+/// ```no_run
+/// use race_sdk::telemetry::Sampler;
+///
+/// let mut acc = race_sdk::acc::AssettoCorsaCompetizione::new();
+/// acc.connect().unwrap();
+///
+/// let mut sampler = Sampler::new(|| acc.get_physics());
+/// while let Some(sample) = sampler.next_sample() {
+///     println!("{:?} #{} speed {:.1}", sample.time, sample.packet_id, sample.frame.speed_kmh);
+/// }
+/// ```
+pub struct Sampler<T, F>
+where
+    F: FnMut() -> Option<T>,
+{
+    read: F,
+    strategy: WaitStrategy,
+    timeout: Option<Duration>,
+    epoch: Instant,
+    last_packet_id: Option<i32>,
+    last_change: Option<Instant>,
+    interval: Option<Duration>,
+    stats: SamplerStats,
+}
+
+impl<T, F> Sampler<T, F>
+where
+    T: Packet,
+    F: FnMut() -> Option<T>,
+{
+    pub fn new(read: F) -> Self {
+        Self {
+            read,
+            strategy: WaitStrategy::default(),
+            timeout: None,
+            epoch: Instant::now(),
+            last_packet_id: None,
+            last_change: None,
+            interval: None,
+            stats: SamplerStats::default(),
+        }
+    }
+
+    pub fn with_wait_strategy(mut self, strategy: WaitStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Makes [`Sampler::next_sample`] give up after waiting this long for a new frame.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Measures sample times from `epoch` instead of the sampler creation time,
+    /// so that several samplers share one time base.
+    pub fn with_epoch(mut self, epoch: Instant) -> Self {
+        self.epoch = epoch;
+        self
+    }
+
+    /// The instant all sample times are measured from.
+    pub fn epoch(&self) -> Instant {
+        self.epoch
+    }
+
+    pub fn stats(&self) -> SamplerStats {
+        self.stats
+    }
+
+    /// The update interval learned from the observed frames, if any.
+    pub fn estimated_interval(&self) -> Option<Duration> {
+        self.interval
+    }
+
+    /// Reads the page once and returns it if the `packet_id` changed.
+    pub fn poll(&mut self) -> Option<Sample<T>> {
+        let frame = (self.read)()?;
+        let now = Instant::now();
+        self.stats.reads += 1;
+
+        let packet_id = frame.packet_id();
+        let dropped = match self.last_packet_id {
+            Some(last) if last == packet_id => return None,
+            // In i64, as a negative `last` overflows the i32 difference.
+            Some(last) if packet_id > last => (i64::from(packet_id) - i64::from(last) - 1).min(u32::MAX as i64) as u32,
+            Some(_) => {
+                self.stats.resets += 1;
+                0
+            }
+            None => 0,
+        };
+
+        if let Some(last_change) = self.last_change {
+            self.learn_interval(now - last_change, dropped);
+        }
+
+        self.last_packet_id = Some(packet_id);
+        self.last_change = Some(now);
+        self.stats.frames += 1;
+        self.stats.dropped += dropped as u64;
+
+        Some(Sample {
+            time: now - self.epoch,
+            packet_id,
+            dropped,
+            frame,
+        })
+    }
+
+    /// Waits for the next frame according to the wait strategy.
+    ///
+    /// Returns `None` when the source is disconnected or the timeout expires.
+    pub fn next_sample(&mut self) -> Option<Sample<T>> {
+        let started = Instant::now();
+        loop {
+            let before = self.stats.reads;
+            if let Some(sample) = self.poll() {
+                return Some(sample);
+            }
+            if self.stats.reads == before {
+                return None;
+            }
+            if let Some(timeout) = self.timeout
+                && started.elapsed() >= timeout
+            {
+                return None;
+            }
+            self.wait();
+        }
+    }
+
+    fn wait(&self) {
+        match self.strategy {
+            WaitStrategy::Spin => hint::spin_loop(),
+            WaitStrategy::Sleep(interval) => thread::sleep(interval),
+            WaitStrategy::Adaptive { spin_window } => {
+                let (Some(interval), Some(last_change)) = (self.interval, self.last_change) else {
+                    thread::yield_now();
+                    return;
+                };
+                let wake_at = last_change + interval.saturating_sub(spin_window);
+                let now = Instant::now();
+                if wake_at > now {
+                    thread::sleep(wake_at - now);
+                } else if now - last_change > interval * 2 {
+                    // The game stopped writing (pause, menus), no point in spinning.
+                    thread::sleep(interval / 2);
+                } else {
+                    hint::spin_loop();
+                }
+            }
+        }
+    }
+
+    fn learn_interval(&mut self, elapsed: Duration, dropped: u32) {
+        // A gap covers several game frames, spread it over all of them.
+        let frame = elapsed / dropped.saturating_add(1);
+        self.interval = Some(match self.interval {
+            Some(current) => current.saturating_mul(7).saturating_add(frame) / 8,
+            None => frame,
+        });
+    }
+}
+
+impl<T, F> Iterator for Sampler<T, F>
+where
+    T: Packet,
+    F: FnMut() -> Option<T>,
+{
+    type Item = Sample<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_sample()
+    }
+}
@@ -0,0 +1,92 @@
+use crate::telemetry::{Page, PageLayout, Value};
+use crate::utils::{SharedMemory, open_shared_memory};
+use std::mem;
+
+/// User-registered shared-memory pages read next to a game's built-in ones.
+///
+/// Extra pages are optional: a page that cannot be opened (mod not installed,
+/// plugin not running) stays unavailable without failing the connection.
+pub struct ExtraPages {
+    pages: Vec<ExtraPage>,
+}
+
+struct ExtraPage {
+    name: String,
+    layout: PageLayout,
+    memory: Option<Box<dyn SharedMemory>>,
+}
+
+impl ExtraPages {
+    pub fn new() -> Self {
+        Self { pages: Vec::new() }
+    }
+
+    /// Registers a page, replacing any page registered under the same name.
+    pub fn register(&mut self, name: &str, layout: PageLayout, open: bool) {
+        self.pages.retain(|page| page.name != name);
+        let mut page = ExtraPage {
+            name: name.to_string(),
+            layout,
+            memory: None,
+        };
+        if open {
+            page.open();
+        }
+        self.pages.push(page);
+    }
+
+    pub fn unregister(&mut self, name: &str) {
+        self.pages.retain(|page| page.name != name);
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.pages.iter().map(|page| page.name.as_str()).collect()
+    }
+
+    pub fn open_all(&mut self) {
+        for page in self.pages.iter_mut().filter(|page| page.memory.is_none()) {
+            page.open();
+        }
+    }
+
+    pub fn close_all(&mut self) {
+        for page in self.pages.iter_mut() {
+            page.memory = None;
+        }
+    }
+
+    pub fn is_open(&self, name: &str) -> bool {
+        self.find(name).is_some_and(|page| page.memory.is_some())
+    }
+
+    pub fn bytes(&self, name: &str) -> Option<Vec<u8>> {
+        let page = self.find(name)?;
+        let memory = page.memory.as_ref()?;
+        let mut bytes = vec![0; page.layout.size];
+        (memory.read_into(&mut bytes) == bytes.len()).then_some(bytes)
+    }
+
+    pub fn get<T: Page>(&self, name: &str) -> Option<T> {
+        if self.find(name)?.layout.size < mem::size_of::<T>() {
+            return None;
+        }
+        T::from_bytes(&self.bytes(name)?)
+    }
+
+    pub fn value(&self, name: &str, path: &str) -> Option<Value> {
+        let page = self.find(name)?;
+        page.layout.get(&self.bytes(name)?, path)
+    }
+
+    fn find(&self, name: &str) -> Option<&ExtraPage> {
+        self.pages.iter().find(|page| page.name == name)
+    }
+}
+
+impl ExtraPage {
+    fn open(&mut self) {
+        self.memory = open_shared_memory(&self.name, self.layout.size)
+            .ok()
+            .map(|memory| Box::new(memory) as Box<dyn SharedMemory>);
+    }
+}
@@ -0,0 +1,66 @@
+/// Declares a newtype over an integer whose named constants are single
+/// bits, printed by name in `Debug`.
+macro_rules! bit_flags {
+    (
+        $(#[$meta:meta])*
+        pub struct $name:ident($ty:ty) {
+            $($(#[$flag_meta:meta])* const $flag:ident = $value:expr;)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+        pub struct $name(pub $ty);
+
+        impl $name {
+            $($(#[$flag_meta])* pub const $flag: Self = Self($value);)*
+
+            const NAMED: &'static [(&'static str, $ty)] = &[$((stringify!($flag), $value)),*];
+
+            pub fn bits(self) -> $ty {
+                self.0
+            }
+
+            pub fn is_empty(self) -> bool {
+                self.0 == 0
+            }
+
+            /// Whether all bits of `other` are set.
+            pub fn contains(self, other: Self) -> bool {
+                self.0 & other.0 == other.0
+            }
+
+            pub fn intersects(self, other: Self) -> bool {
+                self.0 & other.0 != 0
+            }
+
+            /// The names of the set bits, unnamed bits are left out.
+            pub fn names(self) -> Vec<&'static str> {
+                Self::NAMED.iter().filter(|(_, bits)| self.0 & bits != 0).map(|(name, _)| *name).collect()
+            }
+        }
+
+        impl std::ops::BitOr for $name {
+            type Output = Self;
+
+            fn bitor(self, rhs: Self) -> Self {
+                Self(self.0 | rhs.0)
+            }
+        }
+
+        impl std::fmt::Debug for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                let unnamed = Self::NAMED.iter().fold(self.0, |rest, (_, bits)| rest & !bits);
+                let mut names = self.names().join(" | ");
+                if unnamed != 0 {
+                    if !names.is_empty() {
+                        names.push_str(" | ");
+                    }
+                    names.push_str(&format!("{:#x}", unnamed));
+                }
+                write!(f, "{}({})", stringify!($name), names)
+            }
+        }
+    };
+}
+
+pub(crate) use bit_flags;
@@ -0,0 +1,232 @@
+//! A small LZ77 byte compressor in the spirit of the LZ4 block format.
+//!
+//! The output is a sequence of `token, literals, offset, match` groups: the
+//! high nibble of the token is the literal length, the low nibble the match
+//! length minus [`MIN_MATCH`], both extended by `255`-bytes when they reach
+//! `15`. Offsets are `u16` little-endian. The last group has literals only.
+
+const MIN_MATCH: usize = 4;
+const MAX_OFFSET: usize = u16::MAX as usize;
+const HASH_BITS: u32 = 16;
+
+pub fn compress(input: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(input.len() / 2 + 16);
+    let mut table = vec![usize::MAX; 1 << HASH_BITS];
+    let mut anchor = 0;
+    let mut pos = 0;
+
+    while pos + MIN_MATCH <= input.len() {
+        let sequence = read_u32(input, pos);
+        let slot = hash(sequence);
+        let candidate = table[slot];
+        table[slot] = pos;
+
+        if candidate == usize::MAX || pos - candidate > MAX_OFFSET || read_u32(input, candidate) != sequence {
+            pos += 1;
+            continue;
+        }
+
+        let (mut start, mut candidate) = (pos, candidate);
+        while start > anchor && candidate > 0 && input[start - 1] == input[candidate - 1] {
+            start -= 1;
+            candidate -= 1;
+        }
+        let mut length = pos - start + MIN_MATCH;
+        while start + length < input.len() && input[candidate + length] == input[start + length] {
+            length += 1;
+        }
+
+        write_sequence(&mut out, &input[anchor..start], Some((start - candidate, length)));
+        pos = start + length;
+        anchor = pos;
+    }
+
+    write_sequence(&mut out, &input[anchor..], None);
+    out
+}
+
+/// Decompresses `input` into exactly `size` bytes, `None` if it is corrupt
+/// or does not end with a literals-only group.
+pub fn decompress(input: &[u8], size: usize) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(size.min(input.len().saturating_mul(256)));
+    let mut pos = 0;
+
+    loop {
+        let token = *input.get(pos)?;
+        pos += 1;
+
+        let literals = read_length(input, &mut pos, (token >> 4) as usize)?;
+        let literal_end = pos.checked_add(literals).filter(|&end| end <= input.len())?;
+        if out.len() + literals > size {
+            return None;
+        }
+        out.extend_from_slice(&input[pos..literal_end]);
+        pos = literal_end;
+        if pos == input.len() {
+            if token & 0x0f != 0 {
+                return None;
+            }
+            break;
+        }
+
+        let offset = u16::from_le_bytes([*input.get(pos)?, *input.get(pos + 1)?]) as usize;
+        pos += 2;
+        let length = read_length(input, &mut pos, (token & 0x0f) as usize)? + MIN_MATCH;
+        if offset == 0 || offset > out.len() || out.len() + length > size {
+            return None;
+        }
+        let start = out.len() - offset;
+        for i in 0..length {
+            out.push(out[start + i]);
+        }
+    }
+
+    (out.len() == size).then_some(out)
+}
+
+fn write_sequence(out: &mut Vec<u8>, literals: &[u8], matched: Option<(usize, usize)>) {
+    let match_length = matched.map_or(0, |(_, length)| length - MIN_MATCH);
+    out.push(((literals.len().min(15) as u8) << 4) | match_length.min(15) as u8);
+    write_length(out, literals.len());
+    out.extend_from_slice(literals);
+    if let Some((offset, _)) = matched {
+        out.extend_from_slice(&(offset as u16).to_le_bytes());
+        write_length(out, match_length);
+    }
+}
+
+fn write_length(out: &mut Vec<u8>, length: usize) {
+    if length < 15 {
+        return;
+    }
+    let mut rest = length - 15;
+    while rest >= 255 {
+        out.push(255);
+        rest -= 255;
+    }
+    out.push(rest as u8);
+}
+
+fn read_length(input: &[u8], pos: &mut usize, nibble: usize) -> Option<usize> {
+    let mut length = nibble;
+    if nibble == 15 {
+        loop {
+            let byte = *input.get(*pos)?;
+            *pos += 1;
+            length = length.checked_add(byte as usize)?;
+            if byte != 255 {
+                break;
+            }
+        }
+    }
+    Some(length)
+}
+
+fn read_u32(input: &[u8], pos: usize) -> u32 {
+    u32::from_le_bytes([input[pos], input[pos + 1], input[pos + 2], input[pos + 3]])
+}
+
+fn hash(sequence: u32) -> usize {
+    (sequence.wrapping_mul(2654435761) >> (32 - HASH_BITS)) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic bytes that do not compress.
+    fn noise(len: usize) -> Vec<u8> {
+        let mut state = 0x2545_f491_u32;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            })
+            .collect()
+    }
+
+    fn round_trip(input: &[u8]) -> Vec<u8> {
+        let compressed = compress(input);
+        assert_eq!(decompress(&compressed, input.len()).as_deref(), Some(input));
+        compressed
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(round_trip(&[]), [0]);
+        assert_eq!(decompress(&[], 0), None);
+        assert_eq!(decompress(&[0], 1), None);
+    }
+
+    #[test]
+    fn incompressible() {
+        for len in [1, 3, 4, 14, 15, 16, 269, 270, 271, 4096] {
+            let input = noise(len);
+            assert!(round_trip(&input).len() <= len + 1 + len / 255 + 1);
+        }
+    }
+
+    #[test]
+    fn long_runs() {
+        let input = vec![7u8; 1 << 20];
+        assert!(round_trip(&input).len() < 5000);
+
+        let mut input = noise(300);
+        input.extend(vec![0u8; 100_000]);
+        input.extend(noise(300));
+        input.extend(vec![0xff; 270]);
+        round_trip(&input);
+    }
+
+    #[test]
+    fn overlapping_matches() {
+        let input: Vec<u8> = b"abc".iter().copied().cycle().take(1000).collect();
+        let compressed = round_trip(&input);
+        assert!(compressed.len() < 20);
+
+        let mut input = noise(MAX_OFFSET + 100);
+        let repeated = input[10..1010].to_vec();
+        input.extend_from_slice(&repeated);
+        input.extend_from_slice(b"xyxyxyxyxyxyxyxyxyxy");
+        round_trip(&input);
+    }
+
+    #[test]
+    fn truncated_input() {
+        let mut input = noise(200);
+        input.extend(vec![1u8; 500]);
+        input.extend_from_slice(b"trailing literals");
+        let compressed = compress(&input);
+        for len in 0..compressed.len() {
+            assert_eq!(decompress(&compressed[..len], input.len()), None, "{} bytes", len);
+        }
+        assert_eq!(decompress(&compressed, input.len() - 1), None);
+        assert_eq!(decompress(&compressed, input.len() + 1), None);
+    }
+
+    #[test]
+    fn garbage_input() {
+        // A match before any output, and one reaching back past it.
+        assert_eq!(decompress(&[0x00, 0x01, 0x00], 4), None);
+        assert_eq!(decompress(&[0x10, b'a', 0x02, 0x00], 5), None);
+        // An offset of 0.
+        assert_eq!(decompress(&[0x10, b'a', 0x00, 0x00], 5), None);
+        // A length extension that never ends.
+        assert_eq!(decompress(&[0xf0, 255, 255], 1000), None);
+        // A match with no literals-only group after it.
+        assert_eq!(decompress(&[0x10, b'a', 0x01, 0x00], 5), None);
+        // Literals past the end of the input.
+        assert_eq!(decompress(&[0x50, 1, 2], 5), None);
+
+        for seed in 0..64 {
+            let garbage: Vec<u8> = noise(64 + seed).into_iter().skip(seed).collect();
+            for size in [0, 1, 64, 1 << 16] {
+                if let Some(out) = decompress(&garbage, size) {
+                    assert_eq!(out.len(), size);
+                }
+            }
+        }
+    }
+}
@@ -1,3 +1,9 @@
+mod extra_pages;
+pub(crate) mod flags;
+pub(crate) mod json;
+pub mod lz;
 mod shared_memory;
 
+pub use extra_pages::ExtraPages;
+pub(crate) use shared_memory::read_page;
 pub use shared_memory::{SharedMemory, SharedMemoryError, open_shared_memory};
@@ -1,7 +1,34 @@
+use crate::telemetry::Page;
 use std::fmt;
+use std::mem;
 
+/// A readable block of memory holding one page, either a named mapping
+/// opened from the game or a custom source such as a recording replay.
 pub trait SharedMemory {
     fn as_slice(&self) -> &[u8];
+
+    /// Copies the start of the page into `buf`, returns how many bytes were
+    /// copied.
+    ///
+    /// Sources whose contents are produced on demand, like a replay, can
+    /// override this to avoid keeping what [`as_slice`](Self::as_slice)
+    /// returned alive.
+    fn read_into(&self, buf: &mut [u8]) -> usize {
+        let bytes = self.as_slice();
+        let len = bytes.len().min(buf.len());
+        buf[..len].copy_from_slice(&bytes[..len]);
+        len
+    }
+}
+
+/// Decodes a page from a copy of `size_of::<T>()` bytes, so that it is
+/// validated and decoded from the same bytes while the game keeps writing.
+pub(crate) fn read_page<T: Page>(memory: &dyn SharedMemory) -> Option<T> {
+    let mut bytes = vec![0; mem::size_of::<T>()];
+    if memory.read_into(&mut bytes) < bytes.len() {
+        return None;
+    }
+    T::from_bytes(&bytes)
 }
 
 #[derive(Debug)]
@@ -104,3 +131,29 @@ mod windows_implemetation {
         WindowsSharedMemory::open(name, size)
     }
 }
+
+#[cfg(not(target_os = "windows"))]
+pub use fallback_implementation::open_shared_memory;
+
+#[cfg(not(target_os = "windows"))]
+mod fallback_implementation {
+    use super::SharedMemory;
+    use super::SharedMemoryError;
+
+    /// Named shared memory only exists on Windows, elsewhere opening always
+    /// fails and only custom sources (e.g. replays) can be used.
+    pub enum UnsupportedSharedMemory {}
+
+    impl SharedMemory for UnsupportedSharedMemory {
+        fn as_slice(&self) -> &[u8] {
+            match *self {}
+        }
+    }
+
+    pub fn open_shared_memory(name: &str, _size: usize) -> Result<UnsupportedSharedMemory, SharedMemoryError> {
+        Err(SharedMemoryError::SharedMemoryNotFound(format!(
+            "{} (shared memory is only available on Windows)",
+            name
+        )))
+    }
+}